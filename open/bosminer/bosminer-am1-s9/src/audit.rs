@@ -0,0 +1,277 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Audit trail of frequency and voltage changes applied to hash chains. Recent changes are kept
+//! in memory (available through API) and optionally appended to a file as JSON lines.
+
+use ii_logging::macros::*;
+
+use crate::power;
+use crate::FrequencySettings;
+
+use serde::Serialize;
+
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::Utc;
+use once_cell::sync::OnceCell;
+
+/// Number of changes kept in memory
+pub const LOG_CAPACITY: usize = 1000;
+
+/// What triggered the change
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    /// Settings applied from configuration during hash chain initialization
+    Config,
+    /// Frequency throttling decided by monitor
+    Throttle,
+    /// Correction of hardware errors
+    HwErrorControl,
+    /// Mismatch of requested and measured voltage
+    VoltageCheck,
+    /// System power limit
+    PowerLimit,
+    /// Hashrate dropped below expected hashrate
+    HashrateCheck,
+    /// Settings applied through `RunningChain` (e.g. by hooks or tuning tools)
+    External,
+    /// Configuration applied to all running hash chains at once
+    ConfigApply,
+}
+
+impl Source {
+    /// Whether the change is decided by automatic control loop (as opposed to configuration or
+    /// explicit request)
+    pub fn is_automatic(&self) -> bool {
+        match self {
+            Self::Throttle
+            | Self::HwErrorControl
+            | Self::VoltageCheck
+            | Self::PowerLimit
+            | Self::HashrateCheck => true,
+            Self::Config | Self::External | Self::ConfigApply => false,
+        }
+    }
+}
+
+impl std::string::ToString for Source {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Config => "config".to_string(),
+            Self::Throttle => "throttle".to_string(),
+            Self::HwErrorControl => "hw_error_control".to_string(),
+            Self::VoltageCheck => "voltage_check".to_string(),
+            Self::PowerLimit => "power_limit".to_string(),
+            Self::HashrateCheck => "hashrate_check".to_string(),
+            Self::External => "external".to_string(),
+            Self::ConfigApply => "config_apply".to_string(),
+        }
+    }
+}
+
+/// Changed hash chain setting
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Setting {
+    /// Average frequency of chips in MHz
+    Frequency,
+    /// Voltage in Volts
+    Voltage,
+}
+
+impl std::string::ToString for Setting {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Frequency => "frequency".to_string(),
+            Self::Voltage => "voltage".to_string(),
+        }
+    }
+}
+
+/// One recorded change
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Entry {
+    /// Unix timestamp in seconds
+    pub timestamp: i64,
+    pub hashboard_idx: usize,
+    pub source: Source,
+    pub setting: Setting,
+    pub old: f64,
+    pub new: f64,
+}
+
+/// Audit log of running miner
+#[derive(Debug)]
+pub struct Log {
+    capacity: usize,
+    path: Option<PathBuf>,
+    entries: Mutex<VecDeque<Entry>>,
+}
+
+impl Log {
+    fn new(capacity: usize, path: Option<PathBuf>) -> Self {
+        Self {
+            capacity,
+            path,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    fn append(path: &Path, entry: &Entry) -> std::io::Result<()> {
+        let line = serde_json::to_string(entry)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// Record the change unless the value is unchanged
+    pub fn record(&self, entry: Entry) {
+        if entry.old == entry.new {
+            return;
+        }
+        if let Some(path) = self.path.as_ref() {
+            if let Err(e) = Self::append(path, &entry) {
+                warn!("Cannot write audit log '{}': {}", path.display(), e);
+            }
+        }
+        let mut entries = self.entries.lock().expect("BUG: cannot lock audit log");
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Recorded changes from the oldest one
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries
+            .lock()
+            .expect("BUG: cannot lock audit log")
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+static LOG: OnceCell<Log> = OnceCell::new();
+
+/// Create audit log of current run, changes are appended to file at `path` when present
+pub fn init(path: Option<PathBuf>) -> &'static Log {
+    LOG.get_or_init(|| Log::new(LOG_CAPACITY, path))
+}
+
+/// Audit log of current run (missing when `init` hasn't been called yet)
+pub fn log() -> Option<&'static Log> {
+    LOG.get()
+}
+
+fn record(hashboard_idx: usize, source: Source, setting: Setting, old: f64, new: f64) {
+    if let Some(log) = log() {
+        log.record(Entry {
+            timestamp: Utc::now().timestamp(),
+            hashboard_idx,
+            source,
+            setting,
+            old,
+            new,
+        });
+    }
+}
+
+/// Record frequency change of hash chain
+pub fn record_frequency(
+    hashboard_idx: usize,
+    source: Source,
+    old: &FrequencySettings,
+    new: &FrequencySettings,
+) {
+    let mhz = |frequency: &FrequencySettings| frequency.avg() as f64 / 1_000_000.0;
+    record(
+        hashboard_idx,
+        source,
+        Setting::Frequency,
+        mhz(old),
+        mhz(new),
+    );
+}
+
+/// Record voltage change of hash chain (`old` is missing when voltage hasn't been set yet)
+pub fn record_voltage(
+    hashboard_idx: usize,
+    source: Source,
+    old: Option<power::Voltage>,
+    new: power::Voltage,
+) {
+    let volts = |voltage: power::Voltage| voltage.as_volts() as f64;
+    record(
+        hashboard_idx,
+        source,
+        Setting::Voltage,
+        old.map_or(0.0, volts),
+        volts(new),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log() {
+        let log = Log::new(2, None);
+        let entry = |old, new| Entry {
+            timestamp: 0,
+            hashboard_idx: 6,
+            source: Source::Throttle,
+            setting: Setting::Frequency,
+            old,
+            new,
+        };
+        log.record(entry(650.0, 625.0));
+        // unchanged value is not recorded
+        log.record(entry(625.0, 625.0));
+        assert_eq!(log.entries(), vec![entry(650.0, 625.0)]);
+
+        // the oldest change is dropped when capacity is exceeded
+        log.record(entry(625.0, 600.0));
+        log.record(entry(600.0, 575.0));
+        assert_eq!(
+            log.entries(),
+            vec![entry(625.0, 600.0), entry(600.0, 575.0)]
+        );
+    }
+
+    /// Test which changes are ignored by frozen hash chains
+    #[test]
+    fn test_automatic_source() {
+        for source in &[Source::Throttle, Source::HwErrorControl, Source::PowerLimit] {
+            assert!(source.is_automatic());
+        }
+        for source in &[Source::Config, Source::External, Source::ConfigApply] {
+            assert!(!source.is_automatic());
+        }
+    }
+}
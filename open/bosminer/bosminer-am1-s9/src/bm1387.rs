@@ -49,6 +49,9 @@ pub const CHIP_OSC_CLK_BASE_BAUD_DIV: usize = 8;
 /// How many cores are on the chip
 pub const NUM_CORES_ON_CHIP: usize = 114;
 
+/// Midstate counts supported by S9 FPGA (all of them are powers of two)
+pub const SUPPORTED_MIDSTATE_COUNTS: &[usize] = &[1, 2, 4];
+
 /// `MidstateCount` represents the number of midstates S9 FPGA sends to chips.
 /// This information needs to be accessible to everyone that processes `work_id`.
 ///
@@ -68,12 +71,20 @@ pub struct MidstateCount {
 impl MidstateCount {
     /// Construct Self, panic if number of midstates is not valid for this hw
     pub fn new(count: usize) -> Self {
-        match count {
-            1 => Self { log2: 0 },
-            2 => Self { log2: 1 },
-            4 => Self { log2: 2 },
-            _ => panic!("Unsupported S9 midstate count {}", count),
+        Self::try_new(count).expect("BUG: invalid midstate count")
+    }
+
+    /// Construct Self, fail if number of midstates is not valid for this hw
+    pub fn try_new(count: usize) -> error::Result<Self> {
+        if !SUPPORTED_MIDSTATE_COUNTS.contains(&count) {
+            Err(ErrorKind::General(format!(
+                "unsupported S9 midstate count {} (supported: {:?})",
+                count, SUPPORTED_MIDSTATE_COUNTS
+            )))?;
         }
+        Ok(Self {
+            log2: count.trailing_zeros() as usize,
+        })
     }
 
     /// Return midstate count encoded for FPGA
@@ -124,6 +135,15 @@ impl ChipAddress {
         }
     }
 
+    /// Return slice of nonce space explored by the chip (see `CoreAddress`) or `None` if it's a
+    /// broadcast
+    pub fn nonce_slice(&self) -> Option<usize> {
+        match self {
+            ChipAddress::All => None,
+            ChipAddress::One(_) => Some(CoreAddress::new(self.to_hw_addr() as u32).chip),
+        }
+    }
+
     /// Return hardware chip address or 0 if it's a broadcast
     fn to_hw_addr(&self) -> u8 {
         match self {
@@ -584,17 +604,17 @@ impl Register for MiscCtrlReg {
 pub struct PllReg {
     /// Range: 60..=320, but in datasheet table: 32..=128
     #[packed_field(bits = "23:16")]
-    fbdiv: u8,
+    pub fbdiv: u8,
     /// Range: 1..=63, but in datasheet always 2
     #[packed_field(bits = "11:8")]
-    refdiv: u8,
+    pub refdiv: u8,
     /// Range: 1..=7
     #[packed_field(bits = "7:4")]
-    postdiv1: u8,
+    pub postdiv1: u8,
     /// Range: 1..=7, but in datasheet always 1
     /// Also must hold: postdiv2 <= postdiv1
     #[packed_field(bits = "3:0")]
-    postdiv2: u8,
+    pub postdiv2: u8,
 }
 
 impl PllReg {
@@ -619,6 +639,27 @@ impl Register for PllReg {
     const REG_NUM: u8 = 0x0c;
 }
 
+/// PLL register as read back from chip. Chip sets the most significant bit of the register when
+/// it is read back. The bit is not documented, it is only assumed to be PLL lock flag.
+#[derive(PackedStruct, Debug, PartialEq, Clone)]
+#[packed_struct(bit_numbering = "lsb0", size_bytes = "4", endian = "msb")]
+pub struct PllStatusReg {
+    #[packed_field(bits = "31")]
+    pub locked: bool,
+    #[packed_field(bits = "23:16")]
+    pub fbdiv: u8,
+    #[packed_field(bits = "11:8")]
+    pub refdiv: u8,
+    #[packed_field(bits = "7:4")]
+    pub postdiv1: u8,
+    #[packed_field(bits = "3:0")]
+    pub postdiv2: u8,
+}
+
+impl Register for PllStatusReg {
+    const REG_NUM: u8 = PllReg::REG_NUM;
+}
+
 // TODO: how to initialize with custom XTAL frequency?
 pub static PRECOMPUTED_PLL: Lazy<Vec<PllFrequency>> =
     Lazy::new(|| PllFrequency::precompute_pll_table(crate::CHIP_OSC_CLK_HZ));
@@ -750,6 +791,15 @@ mod test {
         assert_eq!(one.to_hw_addr(), 0x24);
     }
 
+    /// Test that nonces computed by a chip are attributed back to the same chip
+    #[test]
+    fn test_chip_address_nonce_slice() {
+        assert_eq!(ChipAddress::All.nonce_slice(), None);
+        for chip in 0..crate::MAX_CHIPS_ON_CHAIN {
+            assert_eq!(ChipAddress::One(chip).nonce_slice(), Some(chip));
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_chip_address_too_big() {
@@ -992,6 +1042,17 @@ mod test {
         assert_eq!(pll.to_reg(), reg);
     }
 
+    #[test]
+    fn test_pll_status_reg() {
+        let reg = PllStatusReg::from_reg(0x8068_0221);
+        assert!(reg.locked);
+        assert_eq!(reg.fbdiv, 0x68);
+        assert_eq!(reg.refdiv, 2);
+        assert_eq!(reg.postdiv1, 2);
+        assert_eq!(reg.postdiv2, 1);
+        assert!(!PllStatusReg::from_reg(0x0068_0221).locked);
+    }
+
     #[test]
     fn test_pll_computation() {
         try_one_divider(100_000_000, 0x200241, 0x20, 2, 4, 1);
@@ -1087,6 +1148,21 @@ mod test {
         MidstateCount::new(3);
     }
 
+    #[test]
+    fn test_midstate_count_validation() {
+        for &count in SUPPORTED_MIDSTATE_COUNTS {
+            assert_eq!(
+                MidstateCount::try_new(count)
+                    .expect("BUG: supported midstate count rejected")
+                    .to_count(),
+                count
+            );
+        }
+        assert!(MidstateCount::try_new(0).is_err());
+        assert!(MidstateCount::try_new(3).is_err());
+        assert!(MidstateCount::try_new(8).is_err());
+    }
+
     #[test]
     fn test_midstate_count_conversion() {
         use ii_fpga_io_am1_s9::common::ctrl_reg::MIDSTATE_CNT_A;
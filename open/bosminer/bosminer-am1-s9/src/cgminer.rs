@@ -21,19 +21,77 @@
 // contact us at opensource@braiins.com.
 
 use ii_cgminer_api::command::{DEVDETAILS, FANS, TEMPCTRL, TEMPS};
-use ii_cgminer_api::{command, commands, response};
+use ii_cgminer_api::support::ValueExt as _;
+use ii_cgminer_api::{command, commands, json, response};
+
+use bosminer::client;
+use bosminer::hal;
+use bosminer_config::{ClientDescriptor, PoolConfig};
 
 use serde::Serialize;
 
+use futures::lock::Mutex;
+use ii_async_compat::futures;
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::audit;
+use crate::bm1387::Register as _;
+use crate::config;
+use crate::events;
 use crate::monitor;
+use crate::nonce_partition;
+use crate::power;
+use crate::remote_pools;
+use crate::restart;
+use crate::self_check;
 use crate::sensor;
 
+/// List of S9 specific commands
+const POWER: &str = "power";
+const HWERRORS: &str = "hwerrors";
+const SELFCHECK: &str = "selfcheck";
+const RESETPEAKTEMPS: &str = "resetpeaktemps";
+const RESTARTS: &str = "restarts";
+const HEALTH: &str = "health";
+const FANTEST: &str = "fantest";
+const MAINTENANCE: &str = "maintenance";
+const CONFIGWARNINGS: &str = "configwarnings";
+const NONCEPARTITION: &str = "noncepartition";
+const RELOADPOOLS: &str = "reloadpools";
+const AUDITLOG: &str = "auditlog";
+const THERMALRESET: &str = "thermalreset";
+const WORKQUEUE: &str = "workqueue";
+const EVENTS: &str = "events";
+const PLL: &str = "pll";
+const APPLYCONFIG: &str = "applyconfig";
+const HEALTHCHECK: &str = "healthcheck";
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 #[repr(u32)]
 pub enum StatusCode {
     NotReady = 1,
+    Power = 2,
+    HwErrors = 3,
+    SelfCheck = 4,
+    ResetPeakTemps = 5,
+    Restarts = 6,
+    Health = 7,
+    FanTest = 8,
+    Maintenance = 9,
+    ConfigWarnings = 10,
+    NoncePartition = 11,
+    ReloadPools = 12,
+    AuditLog = 13,
+    ThermalReset = 14,
+    WorkQueue = 15,
+    Events = 16,
+    Pll = 17,
+    ApplyConfig = 18,
+    HealthCheck = 19,
 }
 
 impl From<StatusCode> for u32 {
@@ -44,12 +102,51 @@ impl From<StatusCode> for u32 {
 
 pub enum ErrorCode {
     NotReady,
+    FanTest(String),
+    InvalidMaintenanceDuration,
+    InvalidPools(String),
+    InvalidEventId,
+    InvalidConfig(String),
+    ApplyConfig(String),
+    NotHealthy(String),
 }
 
 impl From<ErrorCode> for response::Error {
     fn from(code: ErrorCode) -> Self {
         let (code, msg) = match code {
             ErrorCode::NotReady => (StatusCode::NotReady, "Not ready".to_string()),
+            ErrorCode::FanTest(reason) => {
+                (StatusCode::FanTest, format!("Fan test failed: {}", reason))
+            }
+            ErrorCode::InvalidMaintenanceDuration => (
+                StatusCode::Maintenance,
+                format!(
+                    "Maintenance duration has to be in range 0..{} seconds",
+                    config::MAINTENANCE_DURATION_MAX
+                ),
+            ),
+            ErrorCode::InvalidPools(reason) => (
+                StatusCode::ReloadPools,
+                format!("Invalid pool list, pools not changed: {}", reason),
+            ),
+            ErrorCode::InvalidEventId => (
+                StatusCode::Events,
+                "Event ID has to be non-negative number".to_string(),
+            ),
+            ErrorCode::InvalidConfig(reason) => (
+                StatusCode::ApplyConfig,
+                format!("Invalid configuration, nothing applied: {}", reason),
+            ),
+            ErrorCode::ApplyConfig(reason) => (
+                StatusCode::ApplyConfig,
+                format!(
+                    "Cannot apply configuration, changes rolled back: {}",
+                    reason
+                ),
+            ),
+            ErrorCode::NotHealthy(reason) => {
+                (StatusCode::HealthCheck, format!("Not ready: {}", reason))
+            }
         };
 
         Self::from_custom_error(code, msg)
@@ -66,6 +163,15 @@ pub struct DevDetailInfo {
     pub chips: u32,
     #[serde(rename = "Cores")]
     pub cores: u32,
+    #[serde(rename = "ASIC Difficulty")]
+    pub asic_difficulty: u32,
+    /// Hash chain is exempt from automatic frequency and voltage adjustments
+    #[serde(rename = "Frozen")]
+    pub frozen: bool,
+    /// Ratio of measured hashrate to hashrate expected from current frequency
+    #[serde(rename = "Hashrate Ratio")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hashrate_ratio: Option<f64>,
 }
 
 #[derive(Serialize, PartialEq, Clone, Debug)]
@@ -74,12 +180,688 @@ pub struct TempInfo {
     pub board: f64,
     #[serde(rename = "Chip")]
     pub chip: f64,
+    /// Highest temperature since start (or since the last reset)
+    #[serde(rename = "Peak")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak: Option<f64>,
+    /// Highest temperature within configured rolling window
+    #[serde(rename = "Window Peak")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_peak: Option<f64>,
+}
+
+/// Uptime and restart telemetry
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct Restarts {
+    /// Uptime in seconds
+    #[serde(rename = "Uptime")]
+    pub uptime: u64,
+    #[serde(rename = "Restart Count")]
+    pub restart_count: u64,
+    #[serde(rename = "Last Restart Reason")]
+    pub last_reason: Option<String>,
+    /// Number of thermal shutdowns within thermal recovery window
+    #[serde(rename = "Thermal Shutdowns")]
+    pub thermal_shutdowns: usize,
+    /// Recovery from thermal shutdown of the previous run (see `restart::Recovery`)
+    #[serde(rename = "Thermal Recovery")]
+    pub thermal_recovery: String,
+    #[serde(rename = "Recovery Attempt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_attempt: Option<usize>,
+    #[serde(rename = "Max Recovery Attempts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_recovery_attempts: Option<usize>,
+}
+
+impl From<Restarts> for response::Dispatch {
+    fn from(restarts: Restarts) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::Restarts,
+            format!("{} restart(s)", restarts.restart_count),
+            Some(response::Body {
+                name: "RESTARTS",
+                list: vec![restarts],
+            }),
+        )
+    }
+}
+
+/// State of maintenance window (see `monitor::Monitor::start_maintenance`)
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct Maintenance {
+    /// Remaining time of maintenance window in seconds (zero when inactive)
+    #[serde(rename = "Remaining")]
+    pub remaining: u64,
+}
+
+impl From<Maintenance> for response::Dispatch {
+    fn from(maintenance: Maintenance) -> Self {
+        let msg = match maintenance.remaining {
+            0 => "Maintenance window inactive".to_string(),
+            remaining => format!("Maintenance window active for {} s", remaining),
+        };
+        response::Dispatch::from_custom_success(
+            StatusCode::Maintenance,
+            msg,
+            Some(response::Body {
+                name: "MAINTENANCE",
+                list: vec![maintenance],
+            }),
+        )
+    }
+}
+
+pub struct ThermalReset;
+
+impl From<ThermalReset> for response::Dispatch {
+    fn from(_: ThermalReset) -> Self {
+        response::Dispatch::from_custom_success::<(), _>(
+            StatusCode::ThermalReset,
+            "Thermal lockout cleared, hash chains start after miner restart".to_string(),
+            None,
+        )
+    }
+}
+
+pub struct ResetPeakTemps;
+
+impl From<ResetPeakTemps> for response::Dispatch {
+    fn from(_: ResetPeakTemps) -> Self {
+        response::Dispatch::from_custom_success::<(), _>(
+            StatusCode::ResetPeakTemps,
+            "Peak temperatures reset".to_string(),
+            None,
+        )
+    }
+}
+
+/// Estimated power consumption of one hash chain
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct ChainPower {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    #[serde(rename = "Frequency")]
+    pub frequency: u32,
+    #[serde(rename = "Voltage")]
+    pub voltage: f64,
+    /// Estimated power in Watts (see `power::estimate_power` for the model used)
+    #[serde(rename = "Estimated Power")]
+    pub estimated_power: f64,
+    /// Voltage read back from voltage controller during the last voltage check
+    #[serde(rename = "Measured Voltage")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub measured_voltage: Option<f64>,
+    #[serde(rename = "Voltage Mismatch")]
+    pub voltage_mismatch: bool,
+}
+
+/// Estimated power consumption of all hash chains
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct Power {
+    #[serde(rename = "Chains")]
+    pub chains: Vec<ChainPower>,
+    #[serde(rename = "Estimated Total Power")]
+    pub estimated_total_power: f64,
+}
+
+impl From<Power> for response::Dispatch {
+    fn from(power: Power) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::Power,
+            "Estimated power consumption".to_string(),
+            Some(response::Body {
+                name: "POWER",
+                list: vec![power],
+            }),
+        )
+    }
+}
+
+/// Hardware error rate of one hash chain
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct HwErrorInfo {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    /// Hardware error rate (in percent) over the last hashrate interval
+    #[serde(rename = "HW Error Rate")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hw_error_rate: Option<f64>,
+    /// Threshold (in percent) above which corrective action is taken
+    #[serde(rename = "Threshold")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// Shares found again after they have been submitted
+    #[serde(rename = "Duplicate Shares")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_shares: Option<u64>,
+    /// Duplicate shares that have not been submitted
+    #[serde(rename = "Suppressed Duplicates")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppressed_duplicates: Option<u64>,
+}
+
+pub struct HwErrors {
+    pub list: Vec<HwErrorInfo>,
+}
+
+impl From<HwErrors> for response::Dispatch {
+    fn from(hw_errors: HwErrors) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::HwErrors,
+            format!("{} hash chain(s)", hw_errors.list.len()),
+            Some(response::Body {
+                name: "HWERRORS",
+                list: hw_errors.list,
+            }),
+        )
+    }
+}
+
+/// Occupancy of FPGA work queue of one hash chain
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct WorkQueueInfo {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    /// Configured queue depth (missing when the whole FIFO is used)
+    #[serde(rename = "Depth")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u32>,
+    /// Number of jobs buffered when hash chain asked for new work the last time (missing when
+    /// hash chain is not running)
+    #[serde(rename = "Queued")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued: Option<u32>,
+}
+
+pub struct WorkQueue {
+    pub list: Vec<WorkQueueInfo>,
+}
+
+impl From<WorkQueue> for response::Dispatch {
+    fn from(work_queue: WorkQueue) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::WorkQueue,
+            format!("{} hash chain(s)", work_queue.list.len()),
+            Some(response::Body {
+                name: "WORKQUEUE",
+                list: work_queue.list,
+            }),
+        )
+    }
+}
+
+/// PLL divider of chips of one hash chain running at the same requested frequency
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct PllInfo {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    #[serde(rename = "Chips")]
+    pub chips: u32,
+    /// Requested frequency in MHz
+    #[serde(rename = "Requested Frequency")]
+    pub requested_frequency: f64,
+    /// Frequency produced by the PLL divider in MHz (missing when requested frequency is out of
+    /// PLL range)
+    #[serde(rename = "Actual Frequency")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_frequency: Option<f64>,
+    /// Value of PLL register in hexadecimal
+    #[serde(rename = "Register")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub register: Option<String>,
+    #[serde(rename = "FBDIV")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fbdiv: Option<u32>,
+    #[serde(rename = "REFDIV")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refdiv: Option<u32>,
+    #[serde(rename = "POSTDIV1")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postdiv1: Option<u32>,
+    #[serde(rename = "POSTDIV2")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postdiv2: Option<u32>,
+    /// Number of chips whose PLL failed to lock (missing when lock status has not been read)
+    #[serde(rename = "Unlocked Chips")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unlocked_chips: Option<u32>,
+}
+
+impl PllInfo {
+    fn new(
+        hashboard_idx: usize,
+        pll_setting: crate::PllSetting,
+        unlocked_chips: Option<usize>,
+    ) -> Self {
+        let pll = pll_setting.pll.as_ref();
+        Self {
+            id: hashboard_idx as i32,
+            chips: pll_setting.chip_count as u32,
+            requested_frequency: pll_setting.frequency as f64 / 1_000_000.0,
+            actual_frequency: pll.map(|pll| pll.frequency as f64 / 1_000_000.0),
+            register: pll.map(|pll| format!("{:#08x}", pll.reg.to_reg())),
+            fbdiv: pll.map(|pll| pll.reg.fbdiv as u32),
+            refdiv: pll.map(|pll| pll.reg.refdiv as u32),
+            postdiv1: pll.map(|pll| pll.reg.postdiv1 as u32),
+            postdiv2: pll.map(|pll| pll.reg.postdiv2 as u32),
+            unlocked_chips: unlocked_chips.map(|unlocked_chips| unlocked_chips as u32),
+        }
+    }
+}
+
+pub struct Pll {
+    pub list: Vec<PllInfo>,
+}
+
+impl From<Pll> for response::Dispatch {
+    fn from(pll: Pll) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::Pll,
+            format!("{} PLL setting(s)", pll.list.len()),
+            Some(response::Body {
+                name: "PLL",
+                list: pll.list,
+            }),
+        )
+    }
+}
+
+/// Hash chain affected by configuration applied at runtime
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct AppliedConfigInfo {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    /// Whether new frequency and voltage have been applied (stopped hash chains are skipped)
+    #[serde(rename = "Applied")]
+    pub applied: bool,
+}
+
+pub struct ApplyConfig {
+    pub list: Vec<AppliedConfigInfo>,
+}
+
+impl From<ApplyConfig> for response::Dispatch {
+    fn from(apply_config: ApplyConfig) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::ApplyConfig,
+            format!("{} hash chain(s)", apply_config.list.len()),
+            Some(response::Body {
+                name: "APPLYCONFIG",
+                list: apply_config.list,
+            }),
+        )
+    }
+}
+
+/// Warning collected while parsing and resolving configuration (see `config::Warnings`)
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct ConfigWarningInfo {
+    #[serde(rename = "Kind")]
+    pub kind: String,
+    /// Identifier used to suppress the warning
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "Message")]
+    pub message: String,
+    /// Configuration field the warning refers to
+    #[serde(rename = "Path")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+pub struct ConfigWarnings {
+    pub list: Vec<ConfigWarningInfo>,
+}
+
+impl From<ConfigWarnings> for response::Dispatch {
+    fn from(config_warnings: ConfigWarnings) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::ConfigWarnings,
+            format!("{} configuration warning(s)", config_warnings.list.len()),
+            Some(response::Body {
+                name: "CONFIGWARNINGS",
+                list: config_warnings.list,
+            }),
+        )
+    }
+}
+
+/// Frequency or voltage change of hash chain (see `audit::Entry`)
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct AuditLogEntry {
+    #[serde(rename = "When")]
+    pub when: i64,
+    #[serde(rename = "ID")]
+    pub id: i32,
+    #[serde(rename = "Source")]
+    pub source: String,
+    /// Frequency (in MHz) or voltage (in Volts)
+    #[serde(rename = "Setting")]
+    pub setting: String,
+    #[serde(rename = "Old")]
+    pub old: f64,
+    #[serde(rename = "New")]
+    pub new: f64,
+}
+
+pub struct AuditLog {
+    pub list: Vec<AuditLogEntry>,
+}
+
+impl From<AuditLog> for response::Dispatch {
+    fn from(audit_log: AuditLog) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::AuditLog,
+            format!("{} change(s)", audit_log.list.len()),
+            Some(response::Body {
+                name: "AUDITLOG",
+                list: audit_log.list,
+            }),
+        )
+    }
+}
+
+/// Monitoring event (see `events::Event`)
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct EventInfo {
+    /// Sequence number of the event
+    #[serde(rename = "Event ID")]
+    pub event_id: u64,
+    #[serde(rename = "When")]
+    pub when: i64,
+    #[serde(rename = "Kind")]
+    pub kind: String,
+    /// Hash chain the event relates to (missing for miner-wide events)
+    #[serde(rename = "ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(rename = "Message")]
+    pub message: String,
+}
+
+pub struct Events {
+    pub list: Vec<EventInfo>,
+}
+
+impl From<Events> for response::Dispatch {
+    fn from(events: Events) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::Events,
+            format!("{} event(s)", events.list.len()),
+            Some(response::Body {
+                name: "EVENTS",
+                list: events.list,
+            }),
+        )
+    }
+}
+
+/// Pool connected after pool list reload
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct ReloadedPool {
+    #[serde(rename = "URL")]
+    pub url: String,
+    #[serde(rename = "User")]
+    pub user: String,
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+}
+
+pub struct ReloadPools {
+    pub list: Vec<ReloadedPool>,
+}
+
+impl From<ReloadPools> for response::Dispatch {
+    fn from(reload_pools: ReloadPools) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::ReloadPools,
+            format!("{} pool(s) reloaded", reload_pools.list.len()),
+            Some(response::Body {
+                name: "RELOADPOOLS",
+                list: reload_pools.list,
+            }),
+        )
+    }
+}
+
+/// Nonce space explored by chips of one running hash chain (see `nonce_partition`)
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct ChainNoncePartition {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    #[serde(rename = "Midstate Count")]
+    pub midstate_count: u32,
+    #[serde(rename = "Chip Count")]
+    pub chip_count: u32,
+    #[serde(rename = "Chip Slices")]
+    pub chip_slices: Vec<u32>,
+    #[serde(rename = "Missing Slices")]
+    pub missing_slices: Vec<u32>,
+    /// Fraction of nonce space explored by the hash chain (in percent)
+    #[serde(rename = "Coverage")]
+    pub coverage: f64,
+}
+
+pub struct NoncePartition {
+    pub list: Vec<ChainNoncePartition>,
+    /// Result of `nonce_partition::Partition::check`
+    pub check: Result<(), String>,
+}
+
+impl From<NoncePartition> for response::Dispatch {
+    fn from(nonce_partition: NoncePartition) -> Self {
+        let msg = match nonce_partition.check {
+            Ok(_) => "Nonce partition is valid".to_string(),
+            Err(e) => format!("Invalid nonce partition: {}", e),
+        };
+        response::Dispatch::from_custom_success(
+            StatusCode::NoncePartition,
+            msg,
+            Some(response::Body {
+                name: "NONCEPARTITION",
+                list: nonce_partition.list,
+            }),
+        )
+    }
+}
+
+/// Health score of one hash chain with its components (see `monitor::ChainHealth`)
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct HealthInfo {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    /// Composite score 0-100
+    #[serde(rename = "Score")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(rename = "HW Errors")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hw_errors: Option<f64>,
+    #[serde(rename = "Temperature")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(rename = "Acceptance")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acceptance: Option<f64>,
+    #[serde(rename = "Frequency")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency: Option<f64>,
+    /// Watchdog state of hash chain (see `monitor::ChainWatchdogState`)
+    #[serde(rename = "State")]
+    pub state: String,
+    /// How long (in seconds) is degraded hash chain unresponsive
+    #[serde(rename = "Unresponsive")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresponsive: Option<f64>,
+}
+
+pub struct Health {
+    pub list: Vec<HealthInfo>,
+}
+
+impl From<Health> for response::Dispatch {
+    fn from(health: Health) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::Health,
+            format!("{} hash chain(s)", health.list.len()),
+            Some(response::Body {
+                name: "HEALTH",
+                list: health.list,
+            }),
+        )
+    }
+}
+
+/// Readiness of the miner for process supervisors and orchestrators. The miner is live whenever
+/// it responds, not ready miner responds with error status instead.
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct HealthCheck {
+    #[serde(rename = "Live")]
+    pub live: bool,
+    #[serde(rename = "Ready")]
+    pub ready: bool,
+    #[serde(rename = "Mining Chains")]
+    pub mining_chains: u32,
+    #[serde(rename = "Min Chains")]
+    pub min_chains: u32,
+    #[serde(rename = "Connected Pools")]
+    pub connected_pools: u32,
+    #[serde(rename = "Min Pools")]
+    pub min_pools: u32,
+}
+
+impl HealthCheck {
+    /// Check `readiness` criteria (all enabled hash chains have to mine by default). Returns
+    /// the reason when the miner is not ready.
+    pub fn evaluate(
+        readiness: &config::ReadinessConfig,
+        enabled_chains: usize,
+        mining_chains: usize,
+        connected_pools: usize,
+    ) -> Result<Self, String> {
+        let min_chains = readiness.min_chains.unwrap_or(enabled_chains);
+        let min_pools = readiness.min_pools;
+
+        if mining_chains < min_chains || connected_pools < min_pools {
+            return Err(format!(
+                "{} of {} hash chain(s) mining, {} of {} pool(s) connected",
+                mining_chains, min_chains, connected_pools, min_pools
+            ));
+        }
+        Ok(Self {
+            live: true,
+            ready: true,
+            mining_chains: mining_chains as u32,
+            min_chains: min_chains as u32,
+            connected_pools: connected_pools as u32,
+            min_pools: min_pools as u32,
+        })
+    }
+}
+
+impl From<HealthCheck> for response::Dispatch {
+    fn from(health_check: HealthCheck) -> Self {
+        response::Dispatch::from_custom_success(
+            StatusCode::HealthCheck,
+            "Ready".to_string(),
+            Some(response::Body {
+                name: "HEALTHCHECK",
+                list: vec![health_check],
+            }),
+        )
+    }
+}
+
+/// Fan test result of one fan
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct FanTestInfo {
+    #[serde(rename = "ID")]
+    pub id: i32,
+    #[serde(rename = "RPM")]
+    pub rpm: u32,
+    /// Fan reached minimal RPM
+    #[serde(rename = "Ok")]
+    pub ok: bool,
+}
+
+/// Result of on-demand fan test (see `monitor::Monitor::fan_test`)
+pub struct FanTest(monitor::FanTestReport);
+
+impl From<FanTest> for response::Dispatch {
+    fn from(fan_test: FanTest) -> Self {
+        let report = fan_test.0;
+        let msg = match report.aborted.as_ref() {
+            Some(reason) => format!("Fan test aborted: {}", reason),
+            None if report.passed() => "Fan test passed".to_string(),
+            None => format!(
+                "Fan test failed: less than {} fan(s) reached {} RPM",
+                report.min_fans, report.min_rpm
+            ),
+        };
+        response::Dispatch::from_custom_success(
+            StatusCode::FanTest,
+            msg,
+            Some(response::Body {
+                name: "FANTEST",
+                list: report
+                    .fans
+                    .iter()
+                    .enumerate()
+                    .map(|(id, fan)| FanTestInfo {
+                        id: id as i32,
+                        rpm: fan.rpm as u32,
+                        ok: fan.ok,
+                    })
+                    .collect(),
+            }),
+        )
+    }
+}
+
+/// Wrapper for startup self-check report
+pub struct SelfCheck(self_check::Report);
+
+impl From<SelfCheck> for response::Dispatch {
+    fn from(self_check: SelfCheck) -> Self {
+        let report = self_check.0;
+        let msg = if report.failed_chains.is_empty() {
+            "Self-check passed".to_string()
+        } else {
+            format!("Failed hash chain(s): {:?}", report.failed_chains)
+        };
+        response::Dispatch::from_custom_success(
+            StatusCode::SelfCheck,
+            msg,
+            Some(response::Body {
+                name: "SELFCHECK",
+                list: vec![report],
+            }),
+        )
+    }
+}
+
+/// Pool clients replaced by pool list reload
+pub struct Clients {
+    pub manager: client::Manager,
+    pub backend_info: Option<hal::BackendInfo>,
+    /// Groups mined by dedicated hash chains which cannot be reloaded
+    pub assigned_pool_groups: BTreeSet<String>,
 }
 
 pub struct Handler {
     model: String,
     managers: Vec<Arc<crate::Manager>>,
     monitor: Arc<monitor::Monitor>,
+    self_check: Arc<Mutex<Option<self_check::Report>>>,
+    /// Unit of reported temperatures
+    temp_unit: config::TempUnit,
+    config_warnings: config::Warnings,
+    clients: Clients,
+    /// Active configuration file applied by `APPLYCONFIG`
+    config_path: PathBuf,
+    /// When is the miner reported as ready by `HEALTHCHECK`
+    readiness: config::ReadinessConfig,
 }
 
 impl Handler {
@@ -87,14 +869,31 @@ impl Handler {
         model: String,
         managers: Vec<Arc<crate::Manager>>,
         monitor: Arc<monitor::Monitor>,
+        self_check: Arc<Mutex<Option<self_check::Report>>>,
+        temp_unit: config::TempUnit,
+        config_warnings: config::Warnings,
+        clients: Clients,
+        config_path: PathBuf,
+        readiness: config::ReadinessConfig,
     ) -> Self {
         Self {
             model,
             managers,
             monitor,
+            self_check,
+            temp_unit,
+            config_warnings,
+            clients,
+            config_path,
+            readiness,
         }
     }
 
+    /// Convert temperature measured in Celsius to configured unit
+    fn temp(&self, temp: f32) -> f32 {
+        self.temp_unit.from_celsius(temp as f64) as f32
+    }
+
     fn get_monitor_status(&self) -> command::Result<monitor::Status> {
         match self.monitor.status_receiver.borrow().clone() {
             Some(status) => Ok(status),
@@ -109,10 +908,12 @@ impl Handler {
             let mut chip_count = 0;
             let mut voltage = 0.0;
             let mut frequency = 0;
+            let mut hashrate_ratio = None;
             if let Some(hash_chain) = inner.hash_chain.as_ref() {
                 chip_count = hash_chain.chip_count;
                 voltage = hash_chain.get_voltage().await.as_volts() as f64;
                 frequency = hash_chain.get_frequency().await.avg() as u32;
+                hashrate_ratio = hash_chain.get_hashrate_ratio().await;
             }
             list.push(response::DevDetail {
                 idx: list.len() as i32,
@@ -127,6 +928,9 @@ impl Handler {
                     frequency,
                     chips: chip_count as u32,
                     cores: (chip_count * crate::bm1387::NUM_CORES_ON_CHIP) as u32,
+                    asic_difficulty: manager.chain_config.asic_difficulty as u32,
+                    frozen: manager.chain_config.frozen,
+                    hashrate_ratio,
                 },
             });
         }
@@ -134,8 +938,500 @@ impl Handler {
         Ok(response::DevDetails { list })
     }
 
+    /// Hash chains that are not running draw no power
+    async fn handle_power(&self) -> command::Result<Power> {
+        let mut chains = vec![];
+        for manager in self.managers.iter() {
+            let inner = manager.inner.lock().await;
+            let mut frequency = 0;
+            let mut voltage = 0.0;
+            let mut estimated_power = 0.0;
+            let mut readback = None;
+            if let Some(hash_chain) = inner.hash_chain.as_ref() {
+                let frequency_settings = hash_chain.get_frequency().await;
+                let voltage_settings = hash_chain.get_voltage().await;
+                frequency = frequency_settings.avg() as u32;
+                voltage = voltage_settings.as_volts() as f64;
+                estimated_power = power::estimate_power(&frequency_settings, voltage_settings);
+                readback = hash_chain.get_voltage_readback().await;
+            }
+            chains.push(ChainPower {
+                id: manager.hashboard_idx as i32,
+                frequency,
+                voltage,
+                estimated_power,
+                measured_voltage: readback.map(|readback| readback.measured.as_volts() as f64),
+                voltage_mismatch: readback.map_or(false, |readback| readback.is_mismatch()),
+            });
+        }
+        let estimated_total_power = chains.iter().map(|chain| chain.estimated_power).sum();
+
+        Ok(Power {
+            chains,
+            estimated_total_power,
+        })
+    }
+
+    async fn handle_hw_errors(&self) -> command::Result<HwErrors> {
+        let mut list = vec![];
+        for manager in self.managers.iter() {
+            let inner = manager.inner.lock().await;
+            let (hw_error_rate, counter) = match inner.hash_chain.as_ref() {
+                Some(hash_chain) => (
+                    hash_chain.get_hw_error_rate().await,
+                    Some(hash_chain.snapshot_counter().await),
+                ),
+                None => (None, None),
+            };
+            list.push(HwErrorInfo {
+                id: manager.hashboard_idx as i32,
+                hw_error_rate: hw_error_rate.map(|rate| rate * 100.0),
+                threshold: manager
+                    .chain_config
+                    .hw_error_control
+                    .as_ref()
+                    .map(|hw_error_control| hw_error_control.threshold * 100.0),
+                duplicate_shares: counter
+                    .as_ref()
+                    .map(|counter| counter.duplicate_shares as u64),
+                suppressed_duplicates: counter
+                    .as_ref()
+                    .map(|counter| counter.suppressed_shares as u64),
+            });
+        }
+
+        Ok(HwErrors { list })
+    }
+
+    async fn handle_work_queue(&self) -> command::Result<WorkQueue> {
+        let mut list = vec![];
+        for manager in self.managers.iter() {
+            let inner = manager.inner.lock().await;
+            let queued = match inner.hash_chain.as_ref() {
+                Some(hash_chain) => Some(hash_chain.get_queued_work().await as u32),
+                None => None,
+            };
+            list.push(WorkQueueInfo {
+                id: manager.hashboard_idx as i32,
+                depth: manager
+                    .chain_config
+                    .work_queue_depth
+                    .map(|depth| depth as u32),
+                queued,
+            });
+        }
+
+        Ok(WorkQueue { list })
+    }
+
+    /// Load active configuration file again and apply frequency and voltage to all running hash
+    /// chains at once (see `Backend::apply_chain_configs`)
+    async fn handle_apply_config(&self) -> command::Result<ApplyConfig> {
+        let config_path = self.config_path.to_string_lossy();
+        let config_wrapper =
+            config::FormatWrapper::<config::Backend>::parse(&config_path, Default::default())
+                .map_err(|e| ErrorCode::InvalidConfig(e.to_string()))?;
+        let mut backend_config = config_wrapper.body;
+        backend_config.warnings = config_wrapper.warnings;
+        let chain_configs = crate::Backend::resolve_chain_configs(&self.managers, &backend_config)
+            .map_err(ErrorCode::InvalidConfig)?;
+        let applied = crate::Backend::apply_chain_configs(&self.managers, &chain_configs)
+            .await
+            .map_err(ErrorCode::ApplyConfig)?;
+
+        Ok(ApplyConfig {
+            list: self
+                .managers
+                .iter()
+                .map(|manager| AppliedConfigInfo {
+                    id: manager.hashboard_idx as i32,
+                    applied: applied.contains(&manager.hashboard_idx),
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_pll(&self) -> command::Result<Pll> {
+        let mut list = vec![];
+        for manager in self.managers.iter() {
+            let inner = manager.inner.lock().await;
+            // stopped hash chain reports configured frequency
+            let (frequency, pll_unlocked_chips) = match inner.hash_chain.as_ref() {
+                Some(hash_chain) => (
+                    hash_chain.get_frequency().await,
+                    hash_chain.get_pll_unlocked_chips().await,
+                ),
+                None => (manager.chain_config.frequency.clone(), None),
+            };
+            list.extend(frequency.pll_settings().into_iter().map(|pll_setting| {
+                // count unlocked chips running at frequency of this PLL setting
+                let unlocked_chips = pll_unlocked_chips.as_ref().map(|unlocked_chips| {
+                    unlocked_chips
+                        .iter()
+                        .filter(|&&chip| frequency.chip[chip] == pll_setting.frequency)
+                        .count()
+                });
+                PllInfo::new(manager.hashboard_idx, pll_setting, unlocked_chips)
+            }));
+        }
+
+        Ok(Pll { list })
+    }
+
+    async fn handle_health(&self) -> command::Result<Health> {
+        let status = self.get_monitor_status()?;
+        let to_f64 = |value: Option<f32>| value.map(|value| value as f64);
+        Ok(Health {
+            list: status
+                .chain_health
+                .iter()
+                .map(|health| {
+                    let state = status.watchdog_state(health.hashboard_idx);
+                    HealthInfo {
+                        id: health.hashboard_idx as i32,
+                        score: to_f64(health.score),
+                        hw_errors: to_f64(health.hw_errors),
+                        temperature: to_f64(health.temperature),
+                        acceptance: to_f64(health.acceptance),
+                        frequency: to_f64(health.frequency),
+                        state: state
+                            .map(|state| state.to_string())
+                            .unwrap_or_else(|| "Unknown".to_string()),
+                        unresponsive: match state {
+                            Some(monitor::ChainWatchdogState::Degraded { unresponsive_for }) => {
+                                Some(unresponsive_for.as_secs_f64())
+                            }
+                            _ => None,
+                        },
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_health_check(&self) -> command::Result<HealthCheck> {
+        let mut enabled_chains = 0;
+        let mut mining_chains = 0;
+        for manager in self.managers.iter() {
+            if manager.chain_config.enabled {
+                enabled_chains += 1;
+            }
+            if manager.inner.lock().await.hash_chain.is_some() {
+                mining_chains += 1;
+            }
+        }
+        let mut connected_pools = 0;
+        for group in self.clients.manager.get_groups().await {
+            for client in group.get_clients().await {
+                if client.is_running() {
+                    connected_pools += 1;
+                }
+            }
+        }
+        HealthCheck::evaluate(
+            &self.readiness,
+            enabled_chains,
+            mining_chains,
+            connected_pools,
+        )
+        .map_err(|reason| ErrorCode::NotHealthy(reason).into())
+    }
+
+    async fn handle_self_check(&self) -> command::Result<SelfCheck> {
+        match self.self_check.lock().await.clone() {
+            Some(report) => Ok(SelfCheck(report)),
+            None => Err(ErrorCode::NotReady.into()),
+        }
+    }
+
+    async fn handle_restarts(&self) -> command::Result<Restarts> {
+        let tracker = restart::tracker().ok_or(ErrorCode::NotReady)?;
+        let recovery = tracker.recovery();
+        let (recovery_attempt, max_recovery_attempts) = match recovery {
+            restart::Recovery::Retry {
+                attempt,
+                max_attempts,
+                ..
+            } => (Some(attempt), Some(max_attempts)),
+            _ => (None, None),
+        };
+        Ok(Restarts {
+            uptime: tracker.uptime().as_secs(),
+            restart_count: tracker.restart_count(),
+            last_reason: tracker.last_reason().map(|reason| reason.to_string()),
+            thermal_shutdowns: tracker.thermal_shutdowns(),
+            thermal_recovery: recovery.to_string(),
+            recovery_attempt,
+            max_recovery_attempts,
+        })
+    }
+
+    async fn handle_thermal_reset(&self) -> command::Result<ThermalReset> {
+        let tracker = restart::tracker().ok_or(ErrorCode::NotReady)?;
+        tracker.clear_thermal_lockout();
+        Ok(ThermalReset)
+    }
+
+    async fn handle_audit_log(&self) -> command::Result<AuditLog> {
+        let log = audit::log().ok_or(ErrorCode::NotReady)?;
+        Ok(AuditLog {
+            list: log
+                .entries()
+                .into_iter()
+                .map(|entry| AuditLogEntry {
+                    when: entry.timestamp,
+                    id: entry.hashboard_idx as i32,
+                    source: entry.source.to_string(),
+                    setting: entry.setting.to_string(),
+                    old: entry.old,
+                    new: entry.new,
+                })
+                .collect(),
+        })
+    }
+
+    /// Optional parameter is ID of the last event client has seen, only newer events are
+    /// returned (clients poll with ID of the last received event to follow the stream)
+    fn check_events(_command: &str, parameter: &Option<&json::Value>) -> command::Result<()> {
+        match parameter.map(|value| value.to_i32()) {
+            None => Ok(()),
+            Some(Some(event_id)) if event_id >= 0 => Ok(()),
+            _ => Err(ErrorCode::InvalidEventId.into()),
+        }
+    }
+
+    async fn handle_events(&self, parameter: Option<&json::Value>) -> command::Result<Events> {
+        let stream = events::stream().ok_or(ErrorCode::NotReady)?;
+        let last_id = parameter
+            .map(|value| value.to_i32().expect("BUG: invalid EVENTS parameter type") as u64);
+        Ok(Events {
+            list: stream
+                .events_since(last_id)
+                .into_iter()
+                .map(|event| EventInfo {
+                    event_id: event.id,
+                    when: event.timestamp,
+                    kind: event.kind.to_string(),
+                    id: event.hashboard_idx.map(|idx| idx as i32),
+                    message: event.message,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_config_warnings(&self) -> command::Result<ConfigWarnings> {
+        Ok(ConfigWarnings {
+            list: self
+                .config_warnings
+                .list()
+                .into_iter()
+                .map(|warning| ConfigWarningInfo {
+                    kind: warning.kind.to_string(),
+                    id: warning.id.to_string(),
+                    message: warning.message,
+                    path: warning.path,
+                })
+                .collect(),
+        })
+    }
+
+    /// Only running hash chains are reported as the partition depends on discovered chips
+    async fn handle_nonce_partition(&self) -> command::Result<NoncePartition> {
+        let mut work_midstate_count = 0;
+        let mut chains = vec![];
+        for manager in self.managers.iter() {
+            work_midstate_count = manager.chain_config.midstate_count.to_count();
+            let inner = manager.inner.lock().await;
+            if let Some(hash_chain) = inner.hash_chain.as_ref() {
+                chains.push(hash_chain.nonce_partition());
+            }
+        }
+        let to_u32 = |slices: &[usize]| slices.iter().map(|&slice| slice as u32).collect();
+        let partition = nonce_partition::Partition::new(work_midstate_count, chains);
+
+        Ok(NoncePartition {
+            list: partition
+                .chains
+                .iter()
+                .map(|chain| ChainNoncePartition {
+                    id: chain.hashboard_idx as i32,
+                    midstate_count: chain.midstate_count as u32,
+                    chip_count: chain.chip_slices.len() as u32,
+                    chip_slices: to_u32(&chain.chip_slices),
+                    missing_slices: to_u32(&chain.missing_slices()),
+                    coverage: chain.coverage() * 100.0,
+                })
+                .collect(),
+            check: partition.check(),
+        })
+    }
+
+    async fn handle_fan_test(&self) -> command::Result<FanTest> {
+        match self.monitor.fan_test().await {
+            Ok(report) => Ok(FanTest(report)),
+            Err(reason) => Err(ErrorCode::FanTest(reason).into()),
+        }
+    }
+
+    /// Optional parameter is duration in seconds (configured duration when missing), zero stops
+    /// maintenance window started from API
+    fn check_maintenance(_command: &str, parameter: &Option<&json::Value>) -> command::Result<()> {
+        match parameter.map(|value| value.to_i32()) {
+            None => Ok(()),
+            Some(Some(duration))
+                if duration >= 0 && duration as u64 <= config::MAINTENANCE_DURATION_MAX =>
+            {
+                Ok(())
+            }
+            _ => Err(ErrorCode::InvalidMaintenanceDuration.into()),
+        }
+    }
+
+    /// Pool list is either JSON array of pools (with the same fields as in configuration file)
+    /// or string containing such array
+    fn parse_pool_list(parameter: &json::Value) -> Result<Vec<PoolConfig>, String> {
+        let pools = match parameter {
+            json::Value::String(pools) => json::from_str(pools),
+            pools => json::from_value(pools.clone()),
+        };
+        pools.map_err(|e| e.to_string())
+    }
+
+    fn check_reload_pools(_command: &str, parameter: &Option<&json::Value>) -> command::Result<()> {
+        match parameter {
+            Some(parameter) => Self::parse_pool_list(parameter)
+                .map(|_| ())
+                .map_err(|e| ErrorCode::InvalidPools(e).into()),
+            // Pools are fetched from provisioning server
+            None => Ok(()),
+        }
+    }
+
+    fn reloaded_pools(descriptors: &[ClientDescriptor]) -> Vec<ReloadedPool> {
+        descriptors
+            .iter()
+            .map(|descriptor| ReloadedPool {
+                url: descriptor.get_url(true, true, false),
+                user: descriptor.user.clone(),
+                enabled: descriptor.enabled,
+            })
+            .collect()
+    }
+
+    async fn replace_clients(&self, group: &client::Group, descriptors: Vec<ClientDescriptor>) {
+        let client_handles = descriptors
+            .into_iter()
+            .map(|descriptor| {
+                client::Handle::new(descriptor, self.clients.backend_info.clone(), None)
+            })
+            .collect();
+        group.replace_clients(client_handles).await;
+    }
+
+    /// Replace pools of the default group without touching hash chains or monitor. The new
+    /// pool list is validated as a whole first so the current pools stay connected when it is
+    /// malformed. Without pool list, pool groups are fetched from provisioning server instead.
+    async fn handle_reload_pools(
+        &self,
+        parameter: Option<&json::Value>,
+    ) -> command::Result<ReloadPools> {
+        let parameter = match parameter {
+            Some(parameter) => parameter,
+            None => return self.reload_remote_pools().await,
+        };
+        let pools = Self::parse_pool_list(parameter).expect("BUG: invalid RELOADPOOLS parameter");
+        let descriptors = config::Backend::parse_pools(&pools).map_err(ErrorCode::InvalidPools)?;
+
+        let list = Self::reloaded_pools(&descriptors);
+        let group = self.clients.manager.create_or_get_default_group().await;
+        self.replace_clients(&group, descriptors).await;
+
+        Ok(ReloadPools { list })
+    }
+
+    /// Fetch pool groups from provisioning server and replace all pool groups with them the same
+    /// way as during startup: fetched groups are validated together with the active configuration
+    /// file and groups missing on the server are removed. Groups assigned to hash chains are left
+    /// untouched. Nothing is replaced when the fetched groups are rejected.
+    async fn reload_remote_pools(&self) -> command::Result<ReloadPools> {
+        let config_path = self.config_path.to_string_lossy();
+        let mut backend_config =
+            config::FormatWrapper::<config::Backend>::parse(&config_path, Default::default())
+                .map_err(|e| ErrorCode::InvalidPools(e.to_string()))?
+                .body;
+        let source = backend_config.remote_pools().ok_or_else(|| {
+            ErrorCode::InvalidPools("missing pool list and no pools URL configured".to_string())
+        })?;
+        remote_pools::fetch_groups(&mut backend_config, &source)
+            .await
+            .map_err(|e| {
+                ErrorCode::InvalidPools(format!("cannot load pools from '{}': {}", source.url, e))
+            })?;
+        backend_config.prune_invalid_pools();
+
+        let group_configs: Vec<_> = backend_config
+            .groups
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|group| {
+                !self
+                    .clients
+                    .assigned_pool_groups
+                    .contains(&group.descriptor.name)
+            })
+            .collect();
+        let mut list = vec![];
+        for group in group_configs.iter() {
+            if let Some(pools) = group.pools.as_ref() {
+                let descriptors =
+                    config::Backend::parse_pools(pools).map_err(ErrorCode::InvalidPools)?;
+                list.extend(Self::reloaded_pools(&descriptors));
+            }
+        }
+        self.clients
+            .manager
+            .replace_groups(
+                group_configs,
+                self.clients.backend_info.as_ref(),
+                config::DEFAULT_POOL_ENABLED,
+            )
+            .await
+            .map_err(|e| ErrorCode::InvalidPools(e.to_string()))?;
+
+        Ok(ReloadPools { list })
+    }
+
+    async fn handle_maintenance(
+        &self,
+        parameter: Option<&json::Value>,
+    ) -> command::Result<Maintenance> {
+        let duration = parameter.map(|value| {
+            value
+                .to_i32()
+                .expect("BUG: invalid MAINTENANCE parameter type") as u64
+        });
+        let remaining = match duration {
+            Some(0) => self.monitor.stop_maintenance().await,
+            duration => {
+                self.monitor
+                    .start_maintenance(duration.map(Duration::from_secs))
+                    .await
+            }
+        };
+        Ok(Maintenance {
+            remaining: remaining.map_or(0, |remaining| remaining.as_secs()),
+        })
+    }
+
+    async fn handle_reset_peak_temps(&self) -> command::Result<ResetPeakTemps> {
+        self.monitor.reset_peak_temperatures().await;
+        Ok(ResetPeakTemps)
+    }
+
     async fn handle_temp_ctrl(&self) -> command::Result<response::ext::TempCtrl> {
-        let config = self.get_monitor_status()?.config;
+        let status = self.get_monitor_status()?;
+        let config = status.config;
 
         let mut mode = response::ext::TempCtrlMode::Disabled;
         let mut target = None;
@@ -144,13 +1440,13 @@ impl Handler {
 
         if let Some(temp_config) = config.temp_config {
             mode = response::ext::TempCtrlMode::Manual;
-            hot.replace(temp_config.hot_temp);
-            dangerous.replace(temp_config.dangerous_temp);
+            hot.replace(self.temp(temp_config.hot_temp));
+            dangerous.replace(self.temp(temp_config.dangerous_temp));
         }
         if let Some(fan_config) = config.fan_config {
             if let monitor::FanControlMode::TargetTemperature(target_temp) = fan_config.mode {
                 mode = response::ext::TempCtrlMode::Automatic;
-                target.replace(target_temp);
+                target.replace(self.temp(target_temp));
             }
         }
 
@@ -159,12 +1455,23 @@ impl Handler {
             target,
             hot,
             dangerous,
+            paused_monitoring: Some(status.mining_paused),
         })
     }
 
     async fn handle_temps(&self) -> command::Result<response::ext::Temps<TempInfo>> {
+        let peak_temperatures = self
+            .monitor
+            .status_receiver
+            .borrow()
+            .as_ref()
+            .map(|status| status.chain_peak_temperatures.clone())
+            .unwrap_or_default();
         let mut list = vec![];
         for manager in self.managers.iter() {
+            let peak_temperature = peak_temperatures
+                .iter()
+                .find(|peak_temperature| peak_temperature.hashboard_idx == manager.hashboard_idx);
             let inner = manager.inner.lock().await;
             if let Some(hash_chain) = inner.hash_chain.as_ref() {
                 if let Some(sensor::Temperature { local, remote }) =
@@ -174,8 +1481,14 @@ impl Handler {
                         idx: list.len() as i32,
                         id: manager.hashboard_idx as i32,
                         info: TempInfo {
-                            board: Option::from(local).unwrap_or(0.0) as f64,
-                            chip: Option::from(remote).unwrap_or(0.0) as f64,
+                            board: self.temp(Option::from(local).unwrap_or(0.0)) as f64,
+                            chip: self.temp(Option::from(remote).unwrap_or(0.0)) as f64,
+                            peak: peak_temperature
+                                .and_then(|peak_temperature| peak_temperature.peak)
+                                .map(|peak| self.temp(peak) as f64),
+                            window_peak: peak_temperature
+                                .and_then(|peak_temperature| peak_temperature.window_peak)
+                                .map(|peak| self.temp(peak) as f64),
                         },
                     });
                 }
@@ -208,15 +1521,70 @@ pub fn create_custom_commands(
     backend: Arc<crate::Backend>,
     managers: Vec<Arc<crate::Manager>>,
     monitor: Arc<monitor::Monitor>,
+    self_check: Arc<Mutex<Option<self_check::Report>>>,
+    temp_unit: config::TempUnit,
+    config_warnings: config::Warnings,
+    clients: Clients,
+    config_path: PathBuf,
+    readiness: config::ReadinessConfig,
 ) -> Option<command::Map> {
-    let handler = Arc::new(Handler::new(backend.to_string(), managers, monitor));
+    let handler = Arc::new(Handler::new(
+        backend.to_string(),
+        managers,
+        monitor,
+        self_check,
+        temp_unit,
+        config_warnings,
+        clients,
+        config_path,
+        readiness,
+    ));
 
-    let custom_commands = commands![
+    let check_maintenance: command::ParameterCheckHandler =
+        Box::new(|command, parameter| Handler::check_maintenance(command, parameter));
+    let check_reload_pools: command::ParameterCheckHandler =
+        Box::new(|command, parameter| Handler::check_reload_pools(command, parameter));
+    let check_events: command::ParameterCheckHandler =
+        Box::new(|command, parameter| Handler::check_events(command, parameter));
+
+    let mut custom_commands = commands![
         (DEVDETAILS: ParameterLess -> handler.handle_dev_details),
         (TEMPCTRL: ParameterLess -> handler.handle_temp_ctrl),
         (TEMPS: ParameterLess -> handler.handle_temps),
-        (FANS: ParameterLess -> handler.handle_fans)
+        (FANS: ParameterLess -> handler.handle_fans),
+        (POWER: ParameterLess -> handler.handle_power),
+        (HWERRORS: ParameterLess -> handler.handle_hw_errors),
+        (SELFCHECK: ParameterLess -> handler.handle_self_check),
+        (RESETPEAKTEMPS: ParameterLess -> handler.handle_reset_peak_temps),
+        (RESTARTS: ParameterLess -> handler.handle_restarts),
+        (HEALTH: ParameterLess -> handler.handle_health),
+        (FANTEST: ParameterLess -> handler.handle_fan_test),
+        (MAINTENANCE: Parameter(check_maintenance) -> handler.handle_maintenance),
+        (CONFIGWARNINGS: ParameterLess -> handler.handle_config_warnings),
+        (NONCEPARTITION: ParameterLess -> handler.handle_nonce_partition),
+        (RELOADPOOLS: Parameter(check_reload_pools) -> handler.handle_reload_pools),
+        (AUDITLOG: ParameterLess -> handler.handle_audit_log),
+        (THERMALRESET: ParameterLess -> handler.handle_thermal_reset),
+        (WORKQUEUE: ParameterLess -> handler.handle_work_queue),
+        (EVENTS: Parameter(check_events) -> handler.handle_events),
+        (PLL: ParameterLess -> handler.handle_pll),
+        (APPLYCONFIG: ParameterLess -> handler.handle_apply_config),
+        (HEALTHCHECK: ParameterLess -> handler.handle_health_check)
     ];
+    // Commands changing miner state are refused by read-only API
+    for name in &[
+        RESETPEAKTEMPS,
+        FANTEST,
+        MAINTENANCE,
+        RELOADPOOLS,
+        THERMALRESET,
+        APPLYCONFIG,
+    ] {
+        custom_commands
+            .get_mut(name)
+            .expect("BUG: missing custom command")
+            .set_mutating();
+    }
 
     Some(custom_commands)
 }
@@ -29,30 +29,40 @@
 
 use ii_logging::macros::*;
 
+mod analysis;
 pub mod api;
+mod document;
 mod metadata;
 pub mod support;
 
-use crate::bm1387::MidstateCount;
+use crate::bm1387::{self, MidstateCount};
+use crate::error::{self, ErrorKind};
 use crate::fan;
 use crate::hooks;
 use crate::monitor;
 use crate::power;
+use crate::remote_pools;
+use crate::restart;
 use crate::FrequencySettings;
 
 use support::OptionDefault;
 
+pub use analysis::{AnalysisError, AnalysisResult, AnalysisStage};
+
 use bosminer::client;
 use bosminer::hal::{self, BackendConfig as _};
 
-use bosminer_config::{ClientDescriptor, ClientUserInfo};
+use bosminer_config::{ClientDescriptor, GroupDescriptor, PoolConfig};
 
+use chrono::NaiveTime;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::OsString;
 use std::fmt;
 use std::fs;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 /// Hardware revision
@@ -77,6 +87,9 @@ pub const DEFAULT_HW_ID_PATH: &'static str = "/tmp/miner_hwid";
 /// Default value for hash chain enabled flag
 pub const DEFAULT_HASH_CHAIN_ENABLED: bool = true;
 
+/// By default hash chains are adjusted by automatic frequency and voltage control
+pub const DEFAULT_FROZEN: bool = false;
+
 /// Default value for pool enabled flag
 pub const DEFAULT_POOL_ENABLED: bool = true;
 
@@ -92,20 +105,186 @@ pub const DEFAULT_FREQUENCY_MHZ: f64 = 650.0;
 /// Default voltage
 pub const DEFAULT_VOLTAGE_V: f64 = 8.8;
 
+/// Default board model (plain S9 uses `DEFAULT_FREQUENCY_MHZ` and `DEFAULT_VOLTAGE_V`)
+pub const DEFAULT_BOARD_MODEL: &'static str = "s9";
+
+/// Frequency and voltage used for hash chains without explicit settings
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardDefaults {
+    /// Frequency in MHz
+    pub frequency: f64,
+    /// Voltage in V
+    pub voltage: f64,
+}
+
+/// Table of supported board models with their default hash chain settings
+pub const BOARD_MODELS: &[(&'static str, BoardDefaults)] = &[
+    (
+        DEFAULT_BOARD_MODEL,
+        BoardDefaults {
+            frequency: DEFAULT_FREQUENCY_MHZ,
+            voltage: DEFAULT_VOLTAGE_V,
+        },
+    ),
+    (
+        "s9i",
+        BoardDefaults {
+            frequency: 625.0,
+            voltage: 8.9,
+        },
+    ),
+    (
+        "s9j",
+        BoardDefaults {
+            frequency: 675.0,
+            voltage: 9.0,
+        },
+    ),
+];
+
+/// Find default hash chain settings for `board_model`
+pub fn board_defaults(board_model: &str) -> Option<BoardDefaults> {
+    BOARD_MODELS
+        .iter()
+        .find(|(name, _)| *name == board_model)
+        .map(|(_, defaults)| *defaults)
+}
+
+/// Default granularity of runtime frequency adjustments (throttling, hardware error correction
+/// and system power limit) in MHz
+pub const DEFAULT_FREQUENCY_STEP_MHZ: f64 = 25.0;
+
+/// Frequency step is rounded to multiple of this value which the chip PLL reaches exactly with
+/// datasheet dividers (25 MHz oscillator, reference divider 2 and post divider 2)
+pub const FREQUENCY_STEP_GRANULARITY_MHZ: f64 = 6.25;
+
 /// Default temperature control mode
 pub const DEFAULT_TEMP_CONTROL_MODE: TempControlMode = TempControlMode::Auto;
 
+/// Default unit of temperatures in configuration and API
+pub const DEFAULT_TEMP_UNIT: TempUnit = TempUnit::Celsius;
+
 /// Default temperatures for temperature control
 pub const DEFAULT_TARGET_TEMP_C: f64 = 89.0;
 pub const DEFAULT_HOT_TEMP_C: f64 = 100.0;
 pub const DEFAULT_DANGEROUS_TEMP_C: f64 = 110.0;
 
+/// By default fans run at full speed above hot temperature even in manual fan mode
+pub const DEFAULT_HOT_TEMP_OVERRIDE: bool = true;
+
+/// Default frequency throttling settings
+pub const DEFAULT_THROTTLE_ENABLED: bool = false;
+pub const DEFAULT_THROTTLE_TEMP_C: f64 = 95.0;
+pub const DEFAULT_THROTTLE_HYSTERESIS_C: f64 = 5.0;
+pub const DEFAULT_THROTTLE_STEP_MHZ: f64 = 25.0;
+pub const DEFAULT_THROTTLE_MIN_FREQUENCY_MHZ: f64 = 400.0;
+pub const DEFAULT_THROTTLE_RECOVERY_DWELL: Duration = Duration::from_secs(300);
+pub const DEFAULT_THROTTLE_WARMUP_PERIOD: Duration = Duration::from_secs(120);
+
+/// Default hardware error control settings
+pub const DEFAULT_HW_ERROR_STRATEGY: HwErrorStrategy = HwErrorStrategy::Voltage;
+pub const DEFAULT_HW_ERROR_VOLTAGE_STEP_V: f64 = 0.05;
+pub const DEFAULT_HW_ERROR_FREQUENCY_STEP_MHZ: f64 = 25.0;
+pub const DEFAULT_HW_ERROR_WARMUP_PERIOD: Duration = Duration::from_secs(120);
+
+/// Default voltage readback check settings
+pub const DEFAULT_VOLTAGE_CHECK_TOLERANCE_V: f64 = 0.1;
+pub const DEFAULT_VOLTAGE_MISMATCH_ACTION: VoltageMismatchAction = VoltageMismatchAction::Warn;
+
+/// Default hashrate drop check settings
+pub const DEFAULT_HASHRATE_CHECK_PERIOD: Duration = Duration::from_secs(600);
+pub const DEFAULT_HASHRATE_DROP_ACTION: HashrateDropAction = HashrateDropAction::Warn;
+pub const DEFAULT_HASHRATE_CHECK_VOLTAGE_STEP_V: f64 = 0.05;
+
+/// Default PLL lock check settings
+/// Lock flag is not documented (see `bm1387::PllStatusReg`) so the default is only to report it
+pub const DEFAULT_PLL_LOCK_FAILURE_ACTION: PllLockFailureAction = PllLockFailureAction::Warn;
+pub const DEFAULT_PLL_LOCK_RETRIES: usize = 3;
+pub const DEFAULT_PLL_LOCK_FALLBACK_STEP_MHZ: f64 = 25.0;
+
+/// Default weight of each hash chain health score component (all are equally important)
+pub const DEFAULT_HEALTH_WEIGHT: f64 = 1.0;
+
 /// Default fan speed for manual target speed
 pub const DEFAULT_FAN_SPEED: usize = 100;
 
+/// Default fan speed while mining is paused (the same as when temperature is unknown)
+pub const DEFAULT_IDLE_FAN_SPEED: usize = 100;
+
 /// Default minimal running fans for monitoring
 pub const DEFAULT_MIN_FANS: usize = 1;
 
+/// Default action taken when less than minimal running fans are spinning
+pub const DEFAULT_ON_FAN_FAILURE: FanFailureAction = FanFailureAction::Shutdown;
+
+/// Default action taken when there are no pools in configuration file nor on command line
+pub const DEFAULT_ON_NO_POOLS: NoPoolsAction = NoPoolsAction::Error;
+
+/// Frequency used by built-in safe mode configuration (see `FormatWrapper::safe_mode`)
+pub const SAFE_MODE_FREQUENCY_MHZ: f64 = 400.0;
+
+/// Frequency step used for trimming hash chains to fit system power limit
+pub const POWER_LIMIT_STEP_MHZ: f64 = 25.0;
+
+/// Default location of file with persistent restart telemetry
+pub const DEFAULT_STATE_FILE_PATH: &'static str = "/tmp/bosminer.state";
+
+/// Default location of configuration that successfully started all hash chains (it is stored next
+/// to the active configuration file, see `Backend::last_good_config_path`)
+pub const DEFAULT_LAST_GOOD_CONFIG_PATH: &'static str = "/etc/bosminer.last_good.toml";
+
+/// Default location of the last pool section fetched from provisioning server
+pub const DEFAULT_POOLS_CACHE_PATH: &'static str = "/etc/bosminer.pools.toml";
+
+/// Default hotplug settings (scanning for inserted/removed hashboards at runtime)
+pub const DEFAULT_HOTPLUG: bool = false;
+pub const DEFAULT_HOTPLUG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// By default the miner refuses to run when some hash chain fails to initialize
+pub const DEFAULT_CONTINUE_ON_CHAIN_FAILURE: bool = false;
+
+/// By default hash chain is declared failed as soon as it stops responding
+pub const DEFAULT_FAILURE_GRACE: Duration = Duration::from_secs(0);
+
+/// By default runtime frequency and voltage changes are not rate limited
+pub const DEFAULT_MIN_CHANGE_INTERVAL: Duration = Duration::from_secs(0);
+
+/// By default all hash chains are powered on at once
+pub const DEFAULT_POWER_ON_INTERVAL_MS: u64 = 0;
+
+/// By default a single malformed pool makes the whole configuration invalid
+pub const DEFAULT_LENIENT_POOLS: bool = false;
+
+/// By default work dispatched to hash chains is not checked for overlapping nonce ranges
+pub const DEFAULT_DEBUG_NONCE_PARTITION: bool = false;
+
+/// By default shares found again after they have been submitted are sent to pool anyway
+pub const DEFAULT_SUPPRESS_DUPLICATE_SHARES: bool = false;
+
+/// By default API commands can change miner state
+pub const DEFAULT_API_READ_ONLY: bool = false;
+
+/// By default API token (when set) is not required for query commands
+pub const DEFAULT_API_PROTECT_QUERIES: bool = false;
+
+/// Default number of connected pools required for the miner to report readiness
+pub const DEFAULT_READY_MIN_POOLS: usize = 1;
+
+/// Default length of maintenance window in seconds
+pub const DEFAULT_MAINTENANCE_DURATION: u64 = 30 * 60;
+/// Maximal length of maintenance window in seconds
+pub const MAINTENANCE_DURATION_MAX: u64 = 24 * 60 * 60;
+
+/// Default recovery from thermal shutdown: hash chains start again after cooldown (in seconds)
+/// unless there are more than `DEFAULT_THERMAL_RECOVERY_MAX_ATTEMPTS` thermal shutdowns within
+/// `DEFAULT_THERMAL_RECOVERY_WINDOW` (in seconds)
+pub const DEFAULT_THERMAL_RECOVERY_MODE: ThermalRecoveryMode = ThermalRecoveryMode::Retry;
+pub const DEFAULT_THERMAL_RECOVERY_COOLDOWN: u64 = 60;
+pub const DEFAULT_THERMAL_RECOVERY_MAX_ATTEMPTS: usize = 3;
+pub const DEFAULT_THERMAL_RECOVERY_WINDOW: u64 = 60 * 60;
+/// Format of daily maintenance window start time
+pub const MAINTENANCE_START_FORMAT: &'static str = "%H:%M";
+
 /// Index of hashboard that is to be instantiated
 pub const S9_HASHBOARD_INDEX: usize = 8;
 
@@ -121,6 +300,31 @@ pub const FREQUENCY_MHZ_MAX: f64 = 900.0;
 pub const VOLTAGE_V_MIN: f64 = 7.95;
 pub const VOLTAGE_V_MAX: f64 = 9.4;
 
+/// Minimal voltage (in V) required for hash chain frequency (in MHz) used by voltage guard.
+/// The entry with the highest frequency not exceeding hash chain frequency applies, there is no
+/// requirement for frequencies below the first entry.
+pub const MIN_VOLTAGE_TABLE: &[(f64, f64)] = &[
+    (500.0, 8.0),
+    (600.0, 8.4),
+    (650.0, 8.7),
+    (700.0, 9.0),
+    (750.0, 9.2),
+    (800.0, VOLTAGE_V_MAX),
+];
+
+/// Look up minimal voltage required for `frequency` (in MHz) in `MIN_VOLTAGE_TABLE`
+pub fn min_voltage_for_frequency(frequency: f64) -> Option<f64> {
+    MIN_VOLTAGE_TABLE
+        .iter()
+        .rev()
+        .find(|(min_frequency, _)| *min_frequency <= frequency)
+        .map(|(_, min_voltage)| *min_voltage)
+}
+
+/// Default voltage guard settings
+pub const DEFAULT_VOLTAGE_GUARD_ENABLED: bool = false;
+pub const DEFAULT_VOLTAGE_GUARD_ACTION: VoltageGuardAction = VoltageGuardAction::RaiseVoltage;
+
 /// Range of monitored temperature
 pub const TEMPERATURE_C_MIN: f64 = 0.0;
 pub const TEMPERATURE_C_MAX: f64 = 200.0;
@@ -135,6 +339,20 @@ pub const FANS_MAX: usize = 4;
 
 /// Default ASIC difficulty
 pub const DEFAULT_ASIC_DIFFICULTY: usize = 64;
+/// Range of ASIC difficulty (it has to be power of 2). Lower difficulty makes chips report more
+/// solutions which increases granularity of share reporting (useful for diagnostics).
+pub const ASIC_DIFFICULTY_MIN: usize = 1;
+pub const ASIC_DIFFICULTY_MAX: usize = 1 << 16;
+
+/// Range of work queue depth (number of jobs buffered in FPGA for one hash chain). Shallow queue
+/// starves chips when new work arrives late (e.g. on pool latency spikes), deep queue makes chips
+/// spend more time on stale jobs after pool switches to new block. The whole FPGA FIFO is used
+/// when depth is not configured.
+pub const WORK_QUEUE_DEPTH_MIN: usize = 1;
+pub const WORK_QUEUE_DEPTH_MAX: usize = 48;
+
+/// Limit of delay (in milliseconds) before hash chain is powered on
+pub const POWER_ON_DELAY_MS_MAX: u64 = 60_000;
 
 /// Default hashrate interval used for statistics in seconds
 pub const DEFAULT_HASHRATE_INTERVAL: Duration = Duration::from_secs(60);
@@ -142,11 +360,216 @@ pub const DEFAULT_HASHRATE_INTERVAL: Duration = Duration::from_secs(60);
 /// Maximum time it takes to compute one job under normal circumstances
 pub const JOB_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Criteria of miner readiness reported by health-check API command
+#[derive(Clone, Copy, Debug)]
+pub struct ReadinessConfig {
+    /// Number of mining hash chains (all enabled hash chains when missing)
+    pub min_chains: Option<usize>,
+    /// Number of connected pools
+    pub min_pools: usize,
+}
+
 pub struct ResolvedChainConfig {
     pub midstate_count: MidstateCount,
     pub frequency: FrequencySettings,
+    /// Frequency (in Hz) at which the hash chain is initialized before it is ramped up to
+    /// `frequency` (the chain is initialized directly at `frequency` when missing)
+    pub init_frequency: Option<usize>,
     pub voltage: power::Voltage,
     pub enabled: bool,
+    /// Granularity of runtime frequency adjustments in Hz
+    pub frequency_step: usize,
+    pub hw_error_control: Option<HwErrorControlConfig>,
+    pub voltage_check: VoltageCheckConfig,
+    pub pll_lock_check: PllLockCheckConfig,
+    /// Minimal voltage check of runtime frequency changes (disabled when missing)
+    pub voltage_guard: Option<VoltageGuardConfig>,
+    /// Pool group assigned to this hash chain (global pools are used when missing)
+    pub pool_group: Option<String>,
+    /// Difficulty of solutions reported by chips
+    pub asic_difficulty: usize,
+    /// Maximal number of jobs buffered in FPGA (whole FIFO is used when missing)
+    pub work_queue_depth: Option<usize>,
+    /// Temperature (in Celsius) at which `ThermalWarn` event is emitted
+    pub alert_temp: Option<f32>,
+    /// Hash chain is exempt from automatic frequency and voltage adjustments
+    pub frozen: bool,
+    /// Minimal time between runtime frequency or voltage changes (changes requested in the
+    /// meantime are coalesced)
+    pub min_change_interval: Duration,
+    /// Delay before the hash chain is powered on to stagger power-up of hash chains
+    pub power_on_delay: Duration,
+}
+
+/// Resolved voltage readback check settings
+#[derive(Clone, Copy, Debug)]
+pub struct VoltageCheckConfig {
+    /// Maximal difference between requested and measured voltage in volts
+    pub tolerance: f32,
+    pub action: VoltageMismatchAction,
+}
+
+/// Resolved voltage guard settings
+#[derive(Clone, Copy, Debug)]
+pub struct VoltageGuardConfig {
+    pub action: VoltageGuardAction,
+}
+
+/// Resolved hashrate drop check settings
+#[derive(Clone, Copy, Debug)]
+pub struct HashrateCheckConfig {
+    /// Minimal ratio of measured hashrate to hashrate expected from frequency
+    pub tolerance: f64,
+    /// How long the hashrate has to stay below tolerance before action is taken
+    pub period: Duration,
+    pub action: HashrateDropAction,
+    /// Voltage step in volts used with `HashrateDropAction::Voltage`
+    pub voltage_step: f32,
+    /// Voltage is never increased above this value (in volts)
+    pub max_voltage: f32,
+}
+
+/// Resolved PLL lock check settings
+#[derive(Clone, Copy, Debug)]
+pub struct PllLockCheckConfig {
+    pub action: PllLockFailureAction,
+    /// How many times PLL of unlocked chips is loaded again
+    pub retries: usize,
+    /// Frequency step in Hz used with `PllLockFailureAction::Fallback`
+    pub fallback_step: usize,
+    /// Frequency is never lowered below this value (in Hz)
+    pub min_frequency: usize,
+}
+
+impl PllLockCheckConfig {
+    /// Frequency lowered by one fallback step (but not below `min_frequency`)
+    pub fn fallback(&self, frequency: usize) -> usize {
+        frequency
+            .saturating_sub(self.fallback_step)
+            .max(self.min_frequency)
+    }
+}
+
+/// Resolved hardware error control settings
+#[derive(Clone, Debug)]
+pub struct HwErrorControlConfig {
+    /// Maximal ratio of hardware errors to all nonces
+    pub threshold: f64,
+    pub strategy: HwErrorStrategy,
+    /// Voltage increment in volts
+    pub voltage_step: f32,
+    /// Voltage is never increased above this value (in volts)
+    pub max_voltage: f32,
+    /// Frequency decrement in Hz
+    pub frequency_step: usize,
+    /// Frequency is never lowered below this value (in Hz)
+    pub min_frequency: usize,
+    /// No corrective action is taken until hash chain runs for this long
+    pub warmup_period: Duration,
+}
+
+/// Corrective action taken when hardware error rate exceeds threshold
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HwErrorStrategy {
+    /// Increase voltage by one step (lower frequency when voltage is at maximum)
+    Voltage,
+    /// Lower frequency by one step
+    Frequency,
+}
+
+impl std::string::ToString for HwErrorStrategy {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Voltage => "voltage".to_string(),
+            Self::Frequency => "frequency".to_string(),
+        }
+    }
+}
+
+/// Action taken when measured voltage differs from requested voltage
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum VoltageMismatchAction {
+    /// Only log the mismatch and report it through API
+    Warn,
+    /// Lower frequency by one step
+    Throttle,
+    /// Shut down the hash chain
+    Shutdown,
+}
+
+impl std::string::ToString for VoltageMismatchAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Warn => "warn".to_string(),
+            Self::Throttle => "throttle".to_string(),
+            Self::Shutdown => "shutdown".to_string(),
+        }
+    }
+}
+
+/// Action taken when frequency is changed at runtime without sufficient voltage
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum VoltageGuardAction {
+    /// Raise voltage to the minimal voltage required for the new frequency
+    RaiseVoltage,
+    /// Refuse the frequency change
+    Reject,
+}
+
+impl std::string::ToString for VoltageGuardAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::RaiseVoltage => "raise_voltage".to_string(),
+            Self::Reject => "reject".to_string(),
+        }
+    }
+}
+
+/// Action taken when hashrate of hash chain stays below expected hashrate
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HashrateDropAction {
+    /// Only log the drop and emit event
+    Warn,
+    /// Stop the hash chain and initialize it again
+    Reinit,
+    /// Increase voltage by one step
+    Voltage,
+}
+
+impl std::string::ToString for HashrateDropAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Warn => "warn".to_string(),
+            Self::Reinit => "reinit".to_string(),
+            Self::Voltage => "voltage".to_string(),
+        }
+    }
+}
+
+/// Action taken when PLL of some chips does not lock after frequency is set
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PllLockFailureAction {
+    /// Only log the failure and report it through API
+    Warn,
+    /// Load PLL of unlocked chips again with the same frequency
+    Retry,
+    /// Load PLL of unlocked chips again with frequency lowered by one step
+    Fallback,
+}
+
+impl std::string::ToString for PllLockFailureAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Warn => "warn".to_string(),
+            Self::Retry => "retry".to_string(),
+            Self::Fallback => "fallback".to_string(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug)]
@@ -167,6 +590,316 @@ impl std::string::ToString for TempControlMode {
     }
 }
 
+#[derive(Serialize, Deserialize, Copy, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ThermalRecoveryMode {
+    Retry,
+    StayOff,
+}
+
+impl std::string::ToString for ThermalRecoveryMode {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Retry => "retry".to_string(),
+            Self::StayOff => "stay_off".to_string(),
+        }
+    }
+}
+
+/// Unit of temperatures in configuration file and API (internally everything is in Celsius)
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum TempUnit {
+    #[serde(rename = "c")]
+    Celsius,
+    #[serde(rename = "f")]
+    Fahrenheit,
+}
+
+impl TempUnit {
+    /// Convert temperature in this unit to Celsius
+    pub fn to_celsius(&self, temp: f64) -> f64 {
+        match self {
+            Self::Celsius => temp,
+            Self::Fahrenheit => (temp - 32.0) * 5.0 / 9.0,
+        }
+    }
+
+    /// Convert temperature in Celsius to this unit
+    pub fn from_celsius(&self, temp: f64) -> f64 {
+        match self {
+            Self::Celsius => temp,
+            Self::Fahrenheit => temp * 9.0 / 5.0 + 32.0,
+        }
+    }
+}
+
+impl std::string::ToString for TempUnit {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Celsius => "c".to_string(),
+            Self::Fahrenheit => "f".to_string(),
+        }
+    }
+}
+
+/// Action taken when less than `min_fans` are running
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FanFailureAction {
+    /// Shutdown the miner immediately
+    Shutdown,
+    /// Throttle hash chains to minimal frequency
+    Throttle,
+    /// Only log a warning (suitable for immersion/passive cooling)
+    Warn,
+}
+
+impl std::string::ToString for FanFailureAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Shutdown => "shutdown".to_string(),
+            Self::Throttle => "throttle".to_string(),
+            Self::Warn => "warn".to_string(),
+        }
+    }
+}
+
+/// Action taken when neither configuration file nor command line specifies any pool
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum NoPoolsAction {
+    /// Refuse to start the miner
+    Error,
+    /// Start the miner without any work source (pools can be added later)
+    Idle,
+}
+
+impl std::string::ToString for NoPoolsAction {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Error => "error".to_string(),
+            Self::Idle => "idle".to_string(),
+        }
+    }
+}
+
+/// Category of configuration warning
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WarningKind {
+    /// Configured value has no effect
+    Unused,
+    /// Configured value has been adjusted to supported one
+    Adjusted,
+    /// Invalid part of configuration has been skipped
+    Skipped,
+}
+
+impl WarningKind {
+    pub const ALL: &'static [Self] = &[Self::Unused, Self::Adjusted, Self::Skipped];
+}
+
+impl std::string::ToString for WarningKind {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Unused => "unused".to_string(),
+            Self::Adjusted => "adjusted".to_string(),
+            Self::Skipped => "skipped".to_string(),
+        }
+    }
+}
+
+/// Stable identifier of configuration warning used to suppress it (see `Miner::suppress_warnings`)
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum WarningId {
+    UnknownField,
+    SkippedPool,
+    SkippedGroup,
+    FrequencyStepRounded,
+    ChipFrequencyClamped,
+    UnusedHotTemp,
+    UnusedDangerousTemp,
+    UnusedHotTempOverride,
+    UnusedFanSpeed,
+    UnusedTargetTemp,
+    UnusedThrottle,
+    UnknownSuppressedWarning,
+    ConflictingAsicBoost,
+    RemotePools,
+    SafeMode,
+}
+
+impl WarningId {
+    pub const ALL: &'static [Self] = &[
+        Self::UnknownField,
+        Self::SkippedPool,
+        Self::SkippedGroup,
+        Self::FrequencyStepRounded,
+        Self::ChipFrequencyClamped,
+        Self::UnusedHotTemp,
+        Self::UnusedDangerousTemp,
+        Self::UnusedHotTempOverride,
+        Self::UnusedFanSpeed,
+        Self::UnusedTargetTemp,
+        Self::UnusedThrottle,
+        Self::UnknownSuppressedWarning,
+        Self::ConflictingAsicBoost,
+        Self::RemotePools,
+        Self::SafeMode,
+    ];
+
+    pub fn kind(&self) -> WarningKind {
+        match self {
+            Self::UnknownField
+            | Self::UnusedHotTemp
+            | Self::UnusedDangerousTemp
+            | Self::UnusedHotTempOverride
+            | Self::UnusedFanSpeed
+            | Self::UnusedTargetTemp
+            | Self::UnusedThrottle
+            | Self::UnknownSuppressedWarning
+            | Self::ConflictingAsicBoost => WarningKind::Unused,
+            Self::FrequencyStepRounded | Self::ChipFrequencyClamped => WarningKind::Adjusted,
+            Self::SkippedPool | Self::SkippedGroup | Self::RemotePools | Self::SafeMode => {
+                WarningKind::Skipped
+            }
+        }
+    }
+}
+
+impl std::string::ToString for WarningId {
+    fn to_string(&self) -> String {
+        match self {
+            Self::UnknownField => "unknown_field".to_string(),
+            Self::SkippedPool => "skipped_pool".to_string(),
+            Self::SkippedGroup => "skipped_group".to_string(),
+            Self::FrequencyStepRounded => "frequency_step_rounded".to_string(),
+            Self::ChipFrequencyClamped => "chip_frequency_clamped".to_string(),
+            Self::UnusedHotTemp => "unused_hot_temp".to_string(),
+            Self::UnusedDangerousTemp => "unused_dangerous_temp".to_string(),
+            Self::UnusedHotTempOverride => "unused_hot_temp_override".to_string(),
+            Self::UnusedFanSpeed => "unused_fan_speed".to_string(),
+            Self::UnusedTargetTemp => "unused_target_temp".to_string(),
+            Self::UnusedThrottle => "unused_throttle".to_string(),
+            Self::UnknownSuppressedWarning => "unknown_suppressed_warning".to_string(),
+            Self::ConflictingAsicBoost => "conflicting_asic_boost".to_string(),
+            Self::RemotePools => "remote_pools".to_string(),
+            Self::SafeMode => "safe_mode".to_string(),
+        }
+    }
+}
+
+/// Problem in configuration that doesn't prevent the miner from running
+#[derive(Clone, PartialEq, Debug)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub id: WarningId,
+    pub message: String,
+    /// Path to configuration field the warning refers to (e.g. `fan_control.speed`) when known
+    pub path: Option<String>,
+}
+
+#[derive(Default, Debug)]
+struct WarningsInner {
+    list: Vec<Warning>,
+    /// Identifiers or kinds of warnings that are neither logged nor remembered
+    suppressed: Vec<String>,
+    /// Warnings are only remembered (configuration is analyzed without running the miner)
+    quiet: bool,
+}
+
+impl WarningsInner {
+    fn is_suppressed(&self, warning: &Warning) -> bool {
+        self.suppressed
+            .iter()
+            .any(|name| *name == warning.id.to_string() || *name == warning.kind.to_string())
+    }
+}
+
+/// Shared list of warnings collected while parsing and resolving configuration so that they
+/// can be queried at runtime
+#[derive(Clone, Default, Debug)]
+pub struct Warnings(Arc<Mutex<WarningsInner>>);
+
+impl Warnings {
+    /// Warnings which are remembered but never logged
+    pub fn quiet() -> Self {
+        let warnings = Self::default();
+        warnings.0.lock().expect("BUG: cannot lock warnings").quiet = true;
+        warnings
+    }
+
+    /// Nothing related to configuration should be logged
+    pub fn is_quiet(&self) -> bool {
+        self.0.lock().expect("BUG: cannot lock warnings").quiet
+    }
+
+    /// Log warning and remember it (the same warning is remembered only once) unless it is
+    /// suppressed
+    pub fn push(&self, id: WarningId, message: String) {
+        self.add(id, None, message);
+    }
+
+    /// Push warning which refers to configuration field `path`
+    pub fn push_field(&self, id: WarningId, path: &str, message: String) {
+        self.add(id, Some(path.to_string()), message);
+    }
+
+    fn add(&self, id: WarningId, path: Option<String>, message: String) {
+        let warning = Warning {
+            kind: id.kind(),
+            id,
+            message,
+            path,
+        };
+        let mut inner = self.0.lock().expect("BUG: cannot lock warnings");
+        if inner.is_suppressed(&warning) {
+            return;
+        }
+        if !inner.quiet {
+            warn!("{}", warning.message);
+        }
+        if !inner.list.contains(&warning) {
+            inner.list.push(warning);
+        }
+    }
+
+    /// Suppress warnings referenced by identifier or kind (already remembered ones are
+    /// forgotten). Returns names that don't refer to any warning.
+    pub fn suppress(&self, names: &[String]) -> Vec<String> {
+        let (known, unknown): (Vec<_>, Vec<_>) = names.iter().cloned().partition(|name| {
+            WarningId::ALL.iter().any(|id| id.to_string() == *name)
+                || WarningKind::ALL
+                    .iter()
+                    .any(|kind| kind.to_string() == *name)
+        });
+        let mut inner = self.0.lock().expect("BUG: cannot lock warnings");
+        inner.suppressed = known;
+        let list = std::mem::replace(&mut inner.list, vec![]);
+        inner.list = list
+            .into_iter()
+            .filter(|warning| !inner.is_suppressed(warning))
+            .collect();
+        unknown
+    }
+
+    /// Forget all warnings before configuration is resolved again (e.g. on reload)
+    pub fn clear(&self) {
+        self.0
+            .lock()
+            .expect("BUG: cannot lock warnings")
+            .list
+            .clear();
+    }
+
+    pub fn list(&self) -> Vec<Warning> {
+        self.0
+            .lock()
+            .expect("BUG: cannot lock warnings")
+            .list
+            .clone()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Format {
     pub version: String,
@@ -193,8 +926,62 @@ pub struct HashChain {
     pub enabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency: Option<f64>,
+    /// Frequency in MHz used during hash chain initialization (the chain is ramped up to
+    /// `frequency` afterwards)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_frequency: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voltage: Option<f64>,
+    /// Granularity of runtime frequency adjustments in MHz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_step: Option<f64>,
+    /// Difficulty of solutions reported by chips (power of 2)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asic_difficulty: Option<usize>,
+    /// Number of jobs buffered in FPGA
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub work_queue_depth: Option<usize>,
+    /// Temperature (in `temp_unit`) at which alert event is emitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert_temp: Option<f64>,
+    /// Exempt hash chain from automatic frequency and voltage adjustments (throttling, hardware
+    /// error control, voltage check and system power limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frozen: Option<bool>,
+    /// AsicBoost override (allowed only in per-chain configuration). All hash chains mine the
+    /// same work so it has to agree with `hash_chain_global.asic_boost`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asic_boost: Option<bool>,
+    /// Frequency offsets (in MHz) of individual chips relative to hash chain frequency
+    /// (chip index is the key, allowed only in per-chain configuration)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chip_frequency_offsets: Option<BTreeMap<String, f64>>,
+    /// Name of pool group the hash chain mines for exclusively instead of the global pools
+    /// (allowed only in per-chain configuration)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_group: Option<String>,
+    /// Delay (in milliseconds) before the hash chain is powered on during initialization
+    /// (allowed only in per-chain configuration, `miner.power_on_interval` is used otherwise)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power_on_delay: Option<u64>,
+}
+
+impl HashChain {
+    fn parse_chip_index(key: &str) -> Result<usize, String> {
+        key.parse::<usize>()
+            .map_err(|_| format!("chip index '{}' is not number", key))
+            .and_then(|idx| {
+                if idx < crate::EXPECTED_CHIPS_ON_CHAIN {
+                    Ok(idx)
+                } else {
+                    Err(format!(
+                        "chip index '{}' is out of range '0..{}'",
+                        idx,
+                        crate::EXPECTED_CHIPS_ON_CHAIN - 1
+                    ))
+                }
+            })
+    }
 }
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
@@ -208,6 +995,15 @@ pub struct TempControl {
     hot_temp: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     dangerous_temp: Option<f64>,
+    /// Run fans at full speed above `hot_temp` in manual mode (overriding fixed fan speed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hot_temp_override: Option<bool>,
+    /// Length of rolling window (in seconds) for peak temperature of hashchains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    peak_window: Option<u64>,
+    /// Unit of `target_temp`, `hot_temp` and `dangerous_temp` and of temperatures reported by API
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temp_unit: Option<TempUnit>,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
@@ -217,53 +1013,331 @@ pub struct FanControl {
     speed: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     min_fans: Option<usize>,
+    /// PWM carrier frequency in Hz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pwm_frequency: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_fan_failure: Option<FanFailureAction>,
+    /// Fan speed used while all hash chains are stopped and there is no temperature to control
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idle_speed: Option<usize>,
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
 #[serde(deny_unknown_fields)]
-pub struct Backend {
-    #[serde(skip)]
-    pub info: hal::BackendInfo,
-    #[serde(skip)]
-    pub client_manager: Option<client::Manager>,
-    // TODO: merge pools and clients
+pub struct Throttle {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub hash_chain_global: Option<HashChainGlobal>,
-    /// We use `BTreeMap` to have alphabetically sorted hash chain indices in persistent
-    /// configuration file (TOML)
-    #[serde(rename = "hash_chain")]
+    enabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    hash_chains: Option<BTreeMap<String, HashChain>>,
+    temp: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    temp_control: Option<TempControl>,
+    hysteresis: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    fan_control: Option<FanControl>,
-    #[serde(rename = "group")]
+    step: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub groups: Option<Vec<bosminer_config::GroupConfig>>,
-    #[serde(skip)]
-    pub hooks: Option<Arc<dyn hooks::Hooks>>,
-    #[serde(skip)]
-    pub fans_on_while_warming_up: Option<bool>,
+    min_frequency: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recovery_temp: Option<f64>,
+    /// Recovery dwell time in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recovery_dwell: Option<u64>,
+    /// Time in seconds after hash chain start during which it is not throttled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warmup_period: Option<u64>,
 }
 
-pub trait ConfigBody
-where
-    Self: Serialize + DeserializeOwned + Default + fmt::Debug,
-{
-    fn model() -> String;
-
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HwErrorControl {
+    /// Threshold in percent, no corrective action is taken when it is missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    strategy: Option<HwErrorStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voltage_step: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_step: Option<f64>,
+    /// Time in seconds after hash chain start during which no corrective action is taken
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warmup_period: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VoltageCheck {
+    /// Tolerance in volts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tolerance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<VoltageMismatchAction>,
+}
+
+/// Check of runtime frequency changes against `MIN_VOLTAGE_TABLE`
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct VoltageGuard {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<VoltageGuardAction>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HashrateCheck {
+    /// Minimal ratio of measured to expected hashrate, no action is taken when it is missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_hashrate_tolerance: Option<f64>,
+    /// Time in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<HashrateDropAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voltage_step: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PllLockCheck {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<PllLockFailureAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<usize>,
+    /// Frequency step in MHz
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fallback_step: Option<f64>,
+}
+
+/// Weights of hash chain health score components
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Health {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hw_errors_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    acceptance_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_weight: Option<f64>,
+}
+
+/// Access policy of miner API
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Api {
+    /// Refuse commands changing miner state (query commands remain available)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    read_only: Option<bool>,
+    /// Token which has to be presented with commands changing miner state
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+    /// Require the token also for query commands
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protect_queries: Option<bool>,
+    /// Number of mining hash chains required for readiness (all enabled hash chains by default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ready_min_chains: Option<usize>,
+    /// Number of connected pools required for readiness
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ready_min_pools: Option<usize>,
+}
+
+/// Maintenance window suppressing protective actions below dangerous temperature
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Maintenance {
+    /// Time of day (UTC) in format "HH:MM" when maintenance window starts every day
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start: Option<String>,
+    /// Length of maintenance window in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u64>,
+}
+
+/// What happens after thermal shutdown (dangerous temperature, failed sensors or fans)
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ThermalRecovery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<ThermalRecoveryMode>,
+    /// How long (in seconds) hash chains stay off after thermal shutdown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cooldown: Option<u64>,
+    /// Number of thermal shutdowns within `window` after which hash chains stay off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_attempts: Option<usize>,
+    /// Length of window (in seconds) in which thermal shutdowns are counted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window: Option<u64>,
+}
+
+impl Maintenance {
+    fn parse_start(start: &str) -> Result<NaiveTime, String> {
+        NaiveTime::parse_from_str(start, MAINTENANCE_START_FORMAT)
+            .map_err(|_| format!("maintenance start '{}' is not in format 'HH:MM'", start))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Miner {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_no_pools: Option<NoPoolsAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    psu_model: Option<String>,
+    /// Board model selecting default frequency and voltage (see `BOARD_MODELS`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hotplug: Option<bool>,
+    /// Hotplug scan interval in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hotplug_interval: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    continue_on_chain_failure: Option<bool>,
+    /// How long (in seconds) can be hash chain unresponsive before it is declared failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure_grace: Option<u64>,
+    /// Minimal time (in seconds) between runtime frequency or voltage changes of hash chain
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_change_interval: Option<u64>,
+    /// Maximal number of hash chains initialized at once (all at once when missing)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    init_concurrency: Option<usize>,
+    /// Delay (in milliseconds) between powering on consecutive hash chains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    power_on_interval: Option<u64>,
+    /// Path to file with persistent restart telemetry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_file: Option<String>,
+    /// Hard limit of estimated power consumption of all hash chains in Watts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_power_limit: Option<f64>,
+    /// Skip malformed pools (with a warning) instead of rejecting the whole configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lenient_pools: Option<bool>,
+    /// Path to copy of configuration that successfully started all hash chains
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_good_config: Option<String>,
+    /// HTTPS URL of provisioning server with pool section replacing local pool groups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pools_url: Option<String>,
+    /// Path to the last pool section fetched from `pools_url`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pools_cache: Option<String>,
+    /// Check at runtime that no nonce range is dispatched to hash chains twice (debugging only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    debug_nonce_partition: Option<bool>,
+    /// Do not submit shares that have already been submitted recently
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suppress_duplicate_shares: Option<bool>,
+    /// Path to file where frequency and voltage changes of hash chains are appended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audit_log: Option<String>,
+    /// Identifiers or kinds of configuration warnings that are not reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suppress_warnings: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Backend {
+    #[serde(skip)]
+    pub info: hal::BackendInfo,
+    #[serde(skip)]
+    pub client_manager: Option<client::Manager>,
+    // TODO: merge pools and clients
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_chain_global: Option<HashChainGlobal>,
+    /// We use `BTreeMap` to have alphabetically sorted hash chain indices in persistent
+    /// configuration file (TOML)
+    #[serde(rename = "hash_chain")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash_chains: Option<BTreeMap<String, HashChain>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temp_control: Option<TempControl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fan_control: Option<FanControl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    throttle: Option<Throttle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hw_error_control: Option<HwErrorControl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voltage_check: Option<VoltageCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pll_lock_check: Option<PllLockCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voltage_guard: Option<VoltageGuard>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hashrate_check: Option<HashrateCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    health: Option<Health>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    miner: Option<Miner>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api: Option<Api>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maintenance: Option<Maintenance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thermal_recovery: Option<ThermalRecovery>,
+    #[serde(rename = "group")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<bosminer_config::GroupConfig>>,
+    #[serde(skip)]
+    pub hooks: Option<Arc<dyn hooks::Hooks>>,
+    #[serde(skip)]
+    pub fans_on_while_warming_up: Option<bool>,
+    /// Serialized configuration persisted as last-known-good after successful start
+    #[serde(skip)]
+    pub config_snapshot: Option<String>,
+    /// Path to configuration file the miner has been started with (`DEFAULT_CONFIG_PATH` when
+    /// missing), see `Backend::active_config_path`
+    #[serde(skip)]
+    pub config_path: Option<String>,
+    /// Warnings collected while parsing and resolving configuration
+    #[serde(skip)]
+    pub warnings: Warnings,
+}
+
+pub trait ConfigBody
+where
+    Self: Serialize + DeserializeOwned + Default + fmt::Debug,
+{
+    fn model() -> String;
+
     fn version() -> String;
 
     fn version_is_supported(version: &str) -> bool;
 
     fn sanity_check(&self) -> Result<(), String>;
 
+    /// Check that overridden settings don't contradict each other. Conflicts are errors in
+    /// strict mode and warnings otherwise.
+    fn check_overrides(&self, mode: ParseMode, warnings: &Warnings) -> Result<(), String>;
+
     fn metadata() -> serde_json::Value;
 
     fn variant() -> String;
 }
 
+/// How strictly is the configuration file parsed
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ParseMode {
+    /// Reject configuration with unknown fields
+    Strict,
+    /// Ignore unknown fields with a warning (e.g. configuration written for a newer version)
+    Lenient,
+}
+
+impl Default for ParseMode {
+    fn default() -> Self {
+        Self::Strict
+    }
+}
+
 #[derive(Debug)]
 pub enum FormatWrapperError<B> {
     ParsingError(String),
@@ -291,6 +1365,9 @@ pub struct FormatWrapper<B> {
     format: Format,
     #[serde(flatten)]
     pub body: B,
+    /// Warnings collected while parsing configuration file
+    #[serde(skip)]
+    pub warnings: Warnings,
 }
 
 impl<B> FormatWrapper<B>
@@ -325,10 +1402,67 @@ where
         B::metadata()
     }
 
-    pub fn parse(config_path: &str) -> Result<Self, FormatWrapperError<B>> {
-        // Parse config file - either user specified or the default one
-        let mut config: Self = bosminer_config::parse(config_path)
+    /// Parse configuration leniently: every unknown field reported by deserializer is removed
+    /// from the configuration (with a warning) and deserialization is attempted again
+    fn parse_lenient(config_path: &str) -> Result<Self, FormatWrapperError<B>> {
+        let value: serde_json::Value = bosminer_config::parse(config_path)
             .map_err(|msg| FormatWrapperError::ParsingError(msg))?;
+        Self::from_value_lenient(value, config_path)
+    }
+
+    fn from_value_lenient(
+        mut value: serde_json::Value,
+        config_path: &str,
+    ) -> Result<Self, FormatWrapperError<B>> {
+        let warnings = Warnings::default();
+        // Unknown top level sections are not reported by deserializer of flattened body at all
+        let mut sections = vec!["format"];
+        sections.extend_from_slice(support::struct_fields::<B>());
+        for section in support::remove_unexpected_fields(&mut value, &sections) {
+            warnings.push_field(
+                WarningId::UnknownField,
+                &section,
+                format!(
+                    "Ignoring unknown section '{}' in configuration file '{}'",
+                    section, config_path
+                ),
+            );
+        }
+        loop {
+            match serde_json::from_value::<Self>(value.clone()) {
+                Ok(mut config) => {
+                    config.warnings = warnings;
+                    return Ok(config);
+                }
+                Err(e) => {
+                    let msg = e.to_string();
+                    match support::remove_unknown_field(&mut value, msg.as_str()) {
+                        Some(path) => warnings.push_field(
+                            WarningId::UnknownField,
+                            &path,
+                            format!(
+                                "Ignoring unknown field '{}' in configuration file '{}'",
+                                path, config_path
+                            ),
+                        ),
+                        None => return Err(FormatWrapperError::ParsingError(msg)),
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn parse(config_path: &str, mode: ParseMode) -> Result<Self, FormatWrapperError<B>> {
+        // Parse config file - either user specified or the default one
+        let mut config: Self = match mode {
+            ParseMode::Strict => bosminer_config::parse(config_path)
+                .map_err(|msg| FormatWrapperError::ParsingError(msg))?,
+            ParseMode::Lenient => Self::parse_lenient(config_path)?,
+        };
+        config
+            .body
+            .check_overrides(mode, &config.warnings)
+            .map_err(|msg| FormatWrapperError::IncorrectBody(msg))?;
 
         match config.sanity_check() {
             Ok(_) => Ok(config),
@@ -340,11 +1474,61 @@ where
     }
 }
 
+impl FormatWrapper<Backend> {
+    /// Built-in conservative configuration used when configuration file cannot be loaded and
+    /// safe mode is enabled: low frequency, default voltage, automatic temperature control and
+    /// no pools (the miner is idle until the configuration file is fixed)
+    pub fn safe_mode(config_path: &str, reason: &str) -> Self {
+        let warnings = Warnings::default();
+        warnings.push(
+            WarningId::SafeMode,
+            format!(
+                "Running in SAFE MODE with built-in configuration, cannot load configuration \
+                 file '{}': {}",
+                config_path, reason
+            ),
+        );
+        Self {
+            format: Format {
+                version: Backend::version(),
+                model: Backend::model(),
+                generator: None,
+                timestamp: None,
+            },
+            body: Backend {
+                hash_chain_global: Some(HashChainGlobal {
+                    asic_boost: None,
+                    overridable: Some(HashChain {
+                        frequency: Some(SAFE_MODE_FREQUENCY_MHZ),
+                        ..Default::default()
+                    }),
+                }),
+                miner: Some(Miner {
+                    on_no_pools: Some(NoPoolsAction::Idle),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            warnings,
+        }
+    }
+}
+
 impl Backend {
     pub fn has_groups(&self) -> bool {
         self.groups.as_ref().map(|v| !v.is_empty()).unwrap_or(false)
     }
 
+    /// Names of pool groups assigned to individual hash chains. These groups are not scheduled
+    /// with the global pools.
+    pub fn assigned_pool_groups(&self) -> BTreeSet<String> {
+        self.hash_chains
+            .iter()
+            .flat_map(|hash_chains| hash_chains.values())
+            .filter_map(|hash_chain| hash_chain.pool_group.clone())
+            .collect()
+    }
+
     pub fn has_pools(&self) -> bool {
         match &self.groups {
             Some(groups) => groups
@@ -354,120 +1538,755 @@ impl Backend {
         }
     }
 
-    pub fn resolve_chain_config(&self, hash_chain_idx: usize) -> ResolvedChainConfig {
-        // Take global hash chain configuration or default value
-        let overridable = self
-            .hash_chain_global
+    /// Skip malformed pools instead of rejecting the whole configuration. At least one valid
+    /// pool has to remain.
+    pub fn lenient_pools(&self) -> bool {
+        self.miner
             .as_ref()
-            .and_then(|v| v.overridable.as_ref());
-        let mut frequency = OptionDefault::new(
-            overridable.as_ref().and_then(|v| v.frequency),
-            DEFAULT_FREQUENCY_MHZ,
-        );
-        let mut voltage = OptionDefault::new(
-            overridable.as_ref().and_then(|v| v.voltage),
-            DEFAULT_VOLTAGE_V,
-        );
-        let mut enabled = DEFAULT_HASH_CHAIN_ENABLED;
+            .and_then(|miner| miner.lenient_pools)
+            .unwrap_or(DEFAULT_LENIENT_POOLS)
+    }
 
-        // If there's a per-chain override then apply it
-        if let Some(hash_chain) = self
-            .hash_chains
+    /// Verify nonce partitioning of work dispatched to hash chains at runtime
+    pub fn debug_nonce_partition(&self) -> bool {
+        self.miner
             .as_ref()
-            .and_then(|m| m.get(&hash_chain_idx.to_string()))
-        {
-            enabled = hash_chain.enabled.unwrap_or(enabled);
-            frequency = hash_chain
-                .frequency
-                .map(|v| OptionDefault::Some(v))
-                .unwrap_or(frequency);
-            voltage = hash_chain
-                .voltage
-                .map(|v| OptionDefault::Some(v))
-                .unwrap_or(voltage);
-        }
+            .and_then(|miner| miner.debug_nonce_partition)
+            .unwrap_or(DEFAULT_DEBUG_NONCE_PARTITION)
+    }
 
-        // Computed s9-specific values
-        ResolvedChainConfig {
-            midstate_count: MidstateCount::new(self.midstate_count()),
-            frequency: FrequencySettings::from_frequency((*frequency * 1_000_000.0) as usize),
-            // TODO: handle config errors
-            voltage: power::Voltage::from_volts(*voltage as f32)
-                .expect("TODO: bad voltage requested"),
-            enabled,
-        }
+    /// Drop locally detected duplicate shares instead of submitting them
+    pub fn suppress_duplicate_shares(&self) -> bool {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.suppress_duplicate_shares)
+            .unwrap_or(DEFAULT_SUPPRESS_DUPLICATE_SHARES)
     }
 
-    pub fn resolve_monitor_config(&self) -> monitor::Config {
-        // Get temperature control settings
-        let mode = OptionDefault::new(
-            self.temp_control.as_ref().and_then(|v| v.mode),
-            DEFAULT_TEMP_CONTROL_MODE,
-        );
-        let target_temp = OptionDefault::new(
-            self.temp_control.as_ref().and_then(|v| v.target_temp),
-            DEFAULT_TARGET_TEMP_C,
-        );
-        let hot_temp = OptionDefault::new(
-            self.temp_control.as_ref().and_then(|v| v.hot_temp),
-            DEFAULT_HOT_TEMP_C,
-        );
-        let dangerous_temp = OptionDefault::new(
-            self.temp_control.as_ref().and_then(|v| v.dangerous_temp),
-            DEFAULT_DANGEROUS_TEMP_C,
-        );
+    fn check_pool(pool: &PoolConfig) -> Result<ClientDescriptor, String> {
+        pool.create_descriptor(DEFAULT_POOL_ENABLED).map_err(|e| {
+            format!(
+                "{} in pool '{}@{}'",
+                e.to_string(),
+                pool.urls().join(","),
+                pool.user
+            )
+        })
+    }
 
-        // Get fan control settings
-        let fan_speed = OptionDefault::new(
-            self.fan_control.as_ref().and_then(|v| v.speed),
-            DEFAULT_FAN_SPEED,
-        );
-        let min_fans = OptionDefault::new(
-            self.fan_control.as_ref().and_then(|v| v.min_fans),
-            DEFAULT_MIN_FANS,
-        );
+    /// Validate pool list replacing pools of running miner. Only pool settings are checked and
+    /// the list is rejected as a whole when any pool is malformed.
+    pub fn parse_pools(pools: &[PoolConfig]) -> Result<Vec<ClientDescriptor>, String> {
+        if pools.is_empty() {
+            Err("no pool specified".to_string())?;
+        }
+        pools.iter().map(Self::check_pool).collect()
+    }
 
-        let temp_config;
-        let fan_config;
+    /// Provisioning server with pool section (when configured)
+    pub fn remote_pools(&self) -> Option<remote_pools::Source> {
+        let miner = self.miner.as_ref()?;
+        Some(remote_pools::Source {
+            url: miner.pools_url.clone()?,
+            cache_path: miner
+                .pools_cache
+                .as_deref()
+                .unwrap_or(DEFAULT_POOLS_CACHE_PATH)
+                .into(),
+        })
+    }
 
-        // Configure temperature controller
-        match *mode {
-            TempControlMode::Auto | TempControlMode::Manual => {
-                temp_config = Some(monitor::TempControlConfig {
-                    dangerous_temp: *dangerous_temp as f32,
-                    hot_temp: *hot_temp as f32,
-                });
-            }
-            TempControlMode::Disabled => {
-                temp_config = None;
-                // do sanity checks
-                if hot_temp.is_some() {
-                    warn!(
-                        "Unused 'hot_temp' ({}) because 'disable' mode is set",
-                        *hot_temp
-                    );
-                }
-                if dangerous_temp.is_some() {
-                    warn!(
-                        "Unused 'dangerous_temp' ({}) because 'disable' mode is set",
-                        *hot_temp
-                    );
+    /// Replace pool groups (e.g. with the ones fetched from provisioning server). The new groups
+    /// are rejected when configuration with them doesn't pass sanity check.
+    pub fn replace_groups(
+        &mut self,
+        groups: Vec<bosminer_config::GroupConfig>,
+    ) -> Result<(), String> {
+        let previous = self.groups.replace(groups);
+        if let Err(e) = self.sanity_check() {
+            self.groups = previous;
+            Err(e)?;
+        }
+        Ok(())
+    }
+
+    /// Remove malformed pools (with a warning) when lenient pool parsing is enabled. Groups left
+    /// without any pool are removed too.
+    pub fn prune_invalid_pools(&mut self) {
+        if !self.lenient_pools() {
+            return;
+        }
+        if let Some(groups) = self.groups.take() {
+            let mut valid_groups = Vec::with_capacity(groups.len());
+            for mut group in groups {
+                if let Some(pools) = group.pools.take() {
+                    let configured = pools.len();
+                    let pools: Vec<_> = pools
+                        .into_iter()
+                        .filter(|pool| match Self::check_pool(pool) {
+                            Ok(_) => true,
+                            Err(e) => {
+                                self.warnings
+                                    .push(WarningId::SkippedPool, format!("Skipping pool: {}", e));
+                                false
+                            }
+                        })
+                        .collect();
+                    if configured > 0 && pools.is_empty() {
+                        self.warnings.push(
+                            WarningId::SkippedGroup,
+                            format!(
+                                "Skipping group '{}' without any valid pool",
+                                group.descriptor.name
+                            ),
+                        );
+                        continue;
+                    }
+                    group.pools = Some(pools);
                 }
+                valid_groups.push(group);
             }
-        };
+            self.groups = Some(valid_groups);
+        }
+    }
 
-        // Configure fan controller
+    /// Unit of configured temperatures and temperatures reported by API
+    pub fn temp_unit(&self) -> TempUnit {
+        self.temp_control
+            .as_ref()
+            .and_then(|temp_control| temp_control.temp_unit)
+            .unwrap_or(DEFAULT_TEMP_UNIT)
+    }
+
+    /// Action taken when there are no pools in configuration file nor on command line
+    pub fn no_pools_action(&self) -> NoPoolsAction {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.on_no_pools)
+            .unwrap_or(DEFAULT_ON_NO_POOLS)
+    }
+
+    /// AsicBoost setting shared by all hash chains (see `HashChain::asic_boost`)
+    fn asic_boost(&self) -> bool {
+        self.hash_chain_global
+            .as_ref()
+            .and_then(|v| v.asic_boost)
+            .unwrap_or(DEFAULT_ASIC_BOOST)
+    }
+
+    /// Keep mining with remaining hash chains when some of them fail to initialize or they are
+    /// declared failed by watchdog
+    pub fn continue_on_chain_failure(&self) -> bool {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.continue_on_chain_failure)
+            .unwrap_or(DEFAULT_CONTINUE_ON_CHAIN_FAILURE)
+    }
+
+    /// How long is unresponsive hash chain reported as degraded before it is declared failed
+    pub fn failure_grace(&self) -> Duration {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.failure_grace)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_FAILURE_GRACE)
+    }
+
+    /// How often can frequency or voltage of one hash chain change at runtime
+    pub fn min_change_interval(&self) -> Duration {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.min_change_interval)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_MIN_CHANGE_INTERVAL)
+    }
+
+    /// Stop reporting configuration warnings listed in `suppress_warnings`. Warnings reported
+    /// while parsing the configuration file have already been logged at this point.
+    pub fn suppress_warnings(&self) {
+        let names = self
+            .miner
+            .as_ref()
+            .and_then(|miner| miner.suppress_warnings.clone())
+            .unwrap_or_default();
+        for name in self.warnings.suppress(&names) {
+            self.warnings.push_field(
+                WarningId::UnknownSuppressedWarning,
+                "miner.suppress_warnings",
+                format!("Unknown warning '{}' in 'suppress_warnings'", name),
+            );
+        }
+    }
+
+    /// Path to file with audit log of frequency and voltage changes (changes are kept only in
+    /// memory when missing)
+    pub fn audit_log_path(&self) -> Option<std::path::PathBuf> {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.audit_log.as_ref())
+            .map(|path| path.into())
+    }
+
+    /// Path to file with persistent restart telemetry
+    pub fn state_file_path(&self) -> std::path::PathBuf {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.state_file.as_ref())
+            .map(|path| path.as_str())
+            .unwrap_or(DEFAULT_STATE_FILE_PATH)
+            .into()
+    }
+
+    /// Access restrictions of miner API
+    pub fn api_access(&self) -> hal::ApiAccess {
+        let api = self.api.as_ref();
+        hal::ApiAccess {
+            read_only: api
+                .and_then(|api| api.read_only)
+                .unwrap_or(DEFAULT_API_READ_ONLY),
+            token: api.and_then(|api| api.token.clone()),
+            protect_queries: api
+                .and_then(|api| api.protect_queries)
+                .unwrap_or(DEFAULT_API_PROTECT_QUERIES),
+        }
+    }
+
+    /// When the miner reports that it is ready to health-check API command
+    pub fn readiness(&self) -> ReadinessConfig {
+        let api = self.api.as_ref();
+        ReadinessConfig {
+            min_chains: api.and_then(|api| api.ready_min_chains),
+            min_pools: api
+                .and_then(|api| api.ready_min_pools)
+                .unwrap_or(DEFAULT_READY_MIN_POOLS),
+        }
+    }
+
+    /// Path to configuration file the miner has been started with
+    pub fn active_config_path(&self) -> PathBuf {
+        self.config_path
+            .as_deref()
+            .unwrap_or(DEFAULT_CONFIG_PATH)
+            .into()
+    }
+
+    /// Path to copy of configuration that successfully started all hash chains. Unless
+    /// configured explicitly, it is stored next to the active configuration file
+    /// (e.g. "/etc/bosminer.toml" -> "/etc/bosminer.last_good.toml").
+    pub fn last_good_config_path(&self) -> PathBuf {
+        match self
+            .miner
+            .as_ref()
+            .and_then(|miner| miner.last_good_config.as_ref())
+        {
+            Some(path) => path.into(),
+            None => {
+                let config_path = self.active_config_path();
+                let mut file_name: OsString = config_path.file_stem().unwrap_or_default().into();
+                file_name.push(".last_good");
+                if let Some(extension) = config_path.extension() {
+                    file_name.push(".");
+                    file_name.push(extension);
+                }
+                config_path.with_file_name(file_name)
+            }
+        }
+    }
+
+    /// Minimal frequency (in Hz) to which hash chains can be lowered to protect the miner. It is
+    /// the same as the minimal frequency of temperature throttling.
+    pub fn min_viable_frequency(&self) -> usize {
+        let min_frequency = self
+            .throttle
+            .as_ref()
+            .and_then(|v| v.min_frequency)
+            .unwrap_or(DEFAULT_THROTTLE_MIN_FREQUENCY_MHZ);
+        (min_frequency * 1_000_000.0) as usize
+    }
+
+    /// Hard limit of estimated power consumption (in Watts) of all hash chains
+    pub fn system_power_limit(&self) -> Option<f64> {
+        self.miner
+            .as_ref()
+            .and_then(|miner| miner.system_power_limit)
+    }
+
+    /// Trim frequency of the fastest enabled hash chains until their estimated power consumption
+    /// fits into `system_power_limit`
+    pub fn apply_power_limit(
+        &self,
+        chain_configs: &mut [(usize, ResolvedChainConfig)],
+    ) -> error::Result<()> {
+        let limit = match self.system_power_limit() {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let mut chains: Vec<_> = chain_configs
+            .iter()
+            .filter(|(_, chain_config)| chain_config.enabled)
+            .map(|(hashboard_idx, chain_config)| power::ChainOperatingPoint {
+                hashboard_idx: *hashboard_idx,
+                frequency: chain_config.frequency.clone(),
+                voltage: chain_config.voltage,
+                frozen: chain_config.frozen,
+            })
+            .collect();
+        power::fit_power_limit(
+            &mut chains,
+            limit,
+            (POWER_LIMIT_STEP_MHZ * 1_000_000.0) as usize,
+            self.min_viable_frequency(),
+        )?;
+        for chain in chains {
+            let (_, chain_config) = chain_configs
+                .iter_mut()
+                .find(|(hashboard_idx, _)| *hashboard_idx == chain.hashboard_idx)
+                .expect("BUG: missing hash chain configuration");
+            if chain.frequency != chain_config.frequency {
+                if !self.warnings.is_quiet() {
+                    info!(
+                        "Chain {} frequency trimmed to {} MHz to fit system power limit {} W",
+                        chain.hashboard_idx,
+                        chain.frequency.max() / 1_000_000,
+                        limit
+                    );
+                }
+                chain_config.frequency = chain.frequency;
+            }
+        }
+        Ok(())
+    }
+
+    /// Maximal number of hash chains initialized at once (`None` means no limit)
+    pub fn init_concurrency(&self) -> Option<usize> {
+        self.miner.as_ref().and_then(|miner| miner.init_concurrency)
+    }
+
+    pub fn power_on_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.miner
+                .as_ref()
+                .and_then(|miner| miner.power_on_interval)
+                .unwrap_or(DEFAULT_POWER_ON_INTERVAL_MS),
+        )
+    }
+
+    /// Interval of periodic scan for inserted/removed hashboards when hotplug is enabled
+    pub fn hotplug_interval(&self) -> Option<Duration> {
+        let miner = self.miner.as_ref();
+        if !miner
+            .and_then(|miner| miner.hotplug)
+            .unwrap_or(DEFAULT_HOTPLUG)
+        {
+            return None;
+        }
+        Some(
+            miner
+                .and_then(|miner| miner.hotplug_interval)
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_HOTPLUG_INTERVAL),
+        )
+    }
+
+    /// Voltage mapping of configured PSU model
+    fn voltage_mapping(&self) -> power::VoltageMapping {
+        let psu_model = self
+            .miner
+            .as_ref()
+            .and_then(|miner| miner.psu_model.as_deref())
+            .unwrap_or(power::DEFAULT_PSU_MODEL);
+        power::voltage_mapping(psu_model).expect("BUG: unsupported PSU model")
+    }
+
+    /// Default frequency and voltage of configured board model
+    pub fn board_defaults(&self) -> BoardDefaults {
+        let board_model = self
+            .miner
+            .as_ref()
+            .and_then(|miner| miner.model.as_deref())
+            .unwrap_or(DEFAULT_BOARD_MODEL);
+        board_defaults(board_model).expect("BUG: unsupported board model")
+    }
+
+    /// Find per-chain override for hash chain `hash_chain_idx`. Keys are compared by their
+    /// numeric value so that e.g. "06" matches hash chain 6.
+    fn get_hash_chain(&self, hash_chain_idx: usize) -> Option<&HashChain> {
+        self.hash_chains.as_ref().and_then(|hash_chains| {
+            hash_chains
+                .iter()
+                .find(|(key, _)| key.parse::<usize>().ok() == Some(hash_chain_idx))
+                .map(|(_, hash_chain)| hash_chain)
+        })
+    }
+
+    pub fn resolve_chain_config(
+        &self,
+        hash_chain_idx: usize,
+    ) -> error::Result<ResolvedChainConfig> {
+        // Take global hash chain configuration or default value
+        let overridable = self
+            .hash_chain_global
+            .as_ref()
+            .and_then(|v| v.overridable.as_ref());
+        let board_defaults = self.board_defaults();
+        let mut frequency = OptionDefault::new(
+            overridable.as_ref().and_then(|v| v.frequency),
+            board_defaults.frequency,
+        );
+        let mut voltage = OptionDefault::new(
+            overridable.as_ref().and_then(|v| v.voltage),
+            board_defaults.voltage,
+        );
+        let mut init_frequency = overridable.as_ref().and_then(|v| v.init_frequency);
+        let mut frequency_step = overridable
+            .as_ref()
+            .and_then(|v| v.frequency_step)
+            .unwrap_or(DEFAULT_FREQUENCY_STEP_MHZ);
+        let mut asic_difficulty = overridable
+            .as_ref()
+            .and_then(|v| v.asic_difficulty)
+            .unwrap_or(DEFAULT_ASIC_DIFFICULTY);
+        let mut work_queue_depth = overridable.as_ref().and_then(|v| v.work_queue_depth);
+        let mut alert_temp = overridable.as_ref().and_then(|v| v.alert_temp);
+        let mut frozen = overridable
+            .as_ref()
+            .and_then(|v| v.frozen)
+            .unwrap_or(DEFAULT_FROZEN);
+        let mut enabled = DEFAULT_HASH_CHAIN_ENABLED;
+        let mut chip_frequency_offsets = None;
+        let mut pool_group = None;
+        // Hash chains are powered on one after another in order of their indices
+        let mut power_on_delay =
+            self.power_on_interval() * hash_chain_idx.saturating_sub(HASH_CHAIN_INDEX_MIN) as u32;
+
+        // If there's a per-chain override then apply it
+        if let Some(hash_chain) = self.get_hash_chain(hash_chain_idx) {
+            enabled = hash_chain.enabled.unwrap_or(enabled);
+            frequency = hash_chain
+                .frequency
+                .map(|v| OptionDefault::Some(v))
+                .unwrap_or(frequency);
+            init_frequency = hash_chain.init_frequency.or(init_frequency);
+            voltage = hash_chain
+                .voltage
+                .map(|v| OptionDefault::Some(v))
+                .unwrap_or(voltage);
+            frequency_step = hash_chain.frequency_step.unwrap_or(frequency_step);
+            asic_difficulty = hash_chain.asic_difficulty.unwrap_or(asic_difficulty);
+            work_queue_depth = hash_chain.work_queue_depth.or(work_queue_depth);
+            alert_temp = hash_chain.alert_temp.or(alert_temp);
+            frozen = hash_chain.frozen.unwrap_or(frozen);
+            chip_frequency_offsets = hash_chain.chip_frequency_offsets.as_ref();
+            pool_group = hash_chain.pool_group.clone();
+            power_on_delay = hash_chain
+                .power_on_delay
+                .map(Duration::from_millis)
+                .unwrap_or(power_on_delay);
+        }
+
+        // Computed s9-specific values
+        let midstate_count = MidstateCount::try_new(self.midstate_count())
+            .map_err(|e| ErrorKind::Hashboard(hash_chain_idx, e.to_string()))?;
+        let voltage =
+            power::Voltage::from_volts_with_mapping(*voltage as f32, self.voltage_mapping())
+                .map_err(|e| ErrorKind::Hashboard(hash_chain_idx, e.to_string()))?;
+        let aligned_frequency_step = align_frequency_step(frequency_step);
+        if aligned_frequency_step != frequency_step {
+            let path = match self
+                .get_hash_chain(hash_chain_idx)
+                .and_then(|hash_chain| hash_chain.frequency_step)
+            {
+                Some(_) => format!("hash_chain.{}.frequency_step", hash_chain_idx),
+                None => "hash_chain_global.frequency_step".to_string(),
+            };
+            self.warnings.push_field(
+                WarningId::FrequencyStepRounded,
+                &path,
+                format!(
+                    "Chain {}: frequency step {} MHz rounded to {} MHz (multiple of {} MHz)",
+                    hash_chain_idx,
+                    frequency_step,
+                    aligned_frequency_step,
+                    FREQUENCY_STEP_GRANULARITY_MHZ
+                ),
+            );
+        }
+
+        let mut frequency = FrequencySettings::from_frequency((*frequency * 1_000_000.0) as usize);
+        if let Some(chip_frequency_offsets) = chip_frequency_offsets {
+            self.apply_chip_frequency_offsets(
+                hash_chain_idx,
+                &mut frequency,
+                chip_frequency_offsets,
+            )?;
+        }
+
+        Ok(ResolvedChainConfig {
+            midstate_count,
+            frequency,
+            init_frequency: init_frequency.map(|v| (v * 1_000_000.0) as usize),
+            voltage,
+            enabled,
+            frequency_step: (aligned_frequency_step * 1_000_000.0) as usize,
+            hw_error_control: self.resolve_hw_error_control(),
+            voltage_check: self.resolve_voltage_check(),
+            pll_lock_check: self.resolve_pll_lock_check(),
+            voltage_guard: self.resolve_voltage_guard(),
+            pool_group,
+            asic_difficulty,
+            work_queue_depth,
+            alert_temp: alert_temp.map(|temp| self.temp_unit().to_celsius(temp) as f32),
+            frozen,
+            min_change_interval: self.min_change_interval(),
+            power_on_delay,
+        })
+    }
+
+    /// Move frequency of individual chips by configured offsets (resulting frequency is clamped
+    /// to supported range)
+    fn apply_chip_frequency_offsets(
+        &self,
+        hash_chain_idx: usize,
+        frequency: &mut FrequencySettings,
+        chip_frequency_offsets: &BTreeMap<String, f64>,
+    ) -> error::Result<()> {
+        for (key, offset) in chip_frequency_offsets {
+            let chip_idx = HashChain::parse_chip_index(key)
+                .map_err(|e| ErrorKind::Hashboard(hash_chain_idx, e))?;
+            let chip_frequency = frequency.chip[chip_idx] as f64 / 1_000_000.0 + offset;
+            let clamped_frequency = chip_frequency.max(FREQUENCY_MHZ_MIN).min(FREQUENCY_MHZ_MAX);
+            if clamped_frequency != chip_frequency {
+                self.warnings.push_field(
+                    WarningId::ChipFrequencyClamped,
+                    &format!(
+                        "hash_chain.{}.chip_frequency_offsets.{}",
+                        hash_chain_idx, key
+                    ),
+                    format!(
+                        "Chain {}: chip {} frequency {} MHz clamped to {} MHz",
+                        hash_chain_idx, chip_idx, chip_frequency, clamped_frequency
+                    ),
+                );
+            }
+            frequency.chip[chip_idx] = (clamped_frequency * 1_000_000.0) as usize;
+        }
+        Ok(())
+    }
+
+    fn resolve_hw_error_control(&self) -> Option<HwErrorControlConfig> {
+        let hw_error_control = self.hw_error_control.as_ref()?;
+        Some(HwErrorControlConfig {
+            threshold: hw_error_control.threshold? / 100.0,
+            strategy: hw_error_control
+                .strategy
+                .unwrap_or(DEFAULT_HW_ERROR_STRATEGY),
+            voltage_step: hw_error_control
+                .voltage_step
+                .unwrap_or(DEFAULT_HW_ERROR_VOLTAGE_STEP_V) as f32,
+            max_voltage: VOLTAGE_V_MAX as f32,
+            frequency_step: (hw_error_control
+                .frequency_step
+                .unwrap_or(DEFAULT_HW_ERROR_FREQUENCY_STEP_MHZ)
+                * 1_000_000.0) as usize,
+            min_frequency: (FREQUENCY_MHZ_MIN * 1_000_000.0) as usize,
+            warmup_period: hw_error_control
+                .warmup_period
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_HW_ERROR_WARMUP_PERIOD),
+        })
+    }
+
+    fn resolve_voltage_check(&self) -> VoltageCheckConfig {
+        let voltage_check = self.voltage_check.as_ref();
+        VoltageCheckConfig {
+            tolerance: voltage_check
+                .and_then(|voltage_check| voltage_check.tolerance)
+                .unwrap_or(DEFAULT_VOLTAGE_CHECK_TOLERANCE_V) as f32,
+            action: voltage_check
+                .and_then(|voltage_check| voltage_check.action)
+                .unwrap_or(DEFAULT_VOLTAGE_MISMATCH_ACTION),
+        }
+    }
+
+    /// Hashrate drop check shared by all hash chains (missing when it is not configured)
+    pub fn resolve_hashrate_check(&self) -> Option<HashrateCheckConfig> {
+        let hashrate_check = self.hashrate_check.as_ref()?;
+        Some(HashrateCheckConfig {
+            tolerance: hashrate_check.expected_hashrate_tolerance?,
+            period: hashrate_check
+                .period
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_HASHRATE_CHECK_PERIOD),
+            action: hashrate_check
+                .action
+                .unwrap_or(DEFAULT_HASHRATE_DROP_ACTION),
+            voltage_step: hashrate_check
+                .voltage_step
+                .unwrap_or(DEFAULT_HASHRATE_CHECK_VOLTAGE_STEP_V) as f32,
+            max_voltage: VOLTAGE_V_MAX as f32,
+        })
+    }
+
+    fn resolve_voltage_guard(&self) -> Option<VoltageGuardConfig> {
+        let voltage_guard = self.voltage_guard.as_ref()?;
+        if !voltage_guard
+            .enabled
+            .unwrap_or(DEFAULT_VOLTAGE_GUARD_ENABLED)
+        {
+            return None;
+        }
+        Some(VoltageGuardConfig {
+            action: voltage_guard.action.unwrap_or(DEFAULT_VOLTAGE_GUARD_ACTION),
+        })
+    }
+
+    fn resolve_pll_lock_check(&self) -> PllLockCheckConfig {
+        let pll_lock_check = self.pll_lock_check.as_ref();
+        PllLockCheckConfig {
+            action: pll_lock_check
+                .and_then(|pll_lock_check| pll_lock_check.action)
+                .unwrap_or(DEFAULT_PLL_LOCK_FAILURE_ACTION),
+            retries: pll_lock_check
+                .and_then(|pll_lock_check| pll_lock_check.retries)
+                .unwrap_or(DEFAULT_PLL_LOCK_RETRIES),
+            fallback_step: (pll_lock_check
+                .and_then(|pll_lock_check| pll_lock_check.fallback_step)
+                .unwrap_or(DEFAULT_PLL_LOCK_FALLBACK_STEP_MHZ)
+                * 1_000_000.0) as usize,
+            min_frequency: (FREQUENCY_MHZ_MIN * 1_000_000.0) as usize,
+        }
+    }
+
+    pub fn resolve_monitor_config(&self) -> monitor::Config {
+        // Get temperature control settings (configured temperatures are converted to Celsius)
+        let temp_unit = self.temp_unit();
+        let mode = OptionDefault::new(
+            self.temp_control.as_ref().and_then(|v| v.mode),
+            DEFAULT_TEMP_CONTROL_MODE,
+        );
+        let target_temp = OptionDefault::new(
+            self.temp_control
+                .as_ref()
+                .and_then(|v| v.target_temp)
+                .map(|temp| temp_unit.to_celsius(temp)),
+            DEFAULT_TARGET_TEMP_C,
+        );
+        let hot_temp = OptionDefault::new(
+            self.temp_control
+                .as_ref()
+                .and_then(|v| v.hot_temp)
+                .map(|temp| temp_unit.to_celsius(temp)),
+            DEFAULT_HOT_TEMP_C,
+        );
+        let dangerous_temp = OptionDefault::new(
+            self.temp_control
+                .as_ref()
+                .and_then(|v| v.dangerous_temp)
+                .map(|temp| temp_unit.to_celsius(temp)),
+            DEFAULT_DANGEROUS_TEMP_C,
+        );
+        let hot_temp_override = OptionDefault::new(
+            self.temp_control.as_ref().and_then(|v| v.hot_temp_override),
+            DEFAULT_HOT_TEMP_OVERRIDE,
+        );
+
+        // Get fan control settings
+        let fan_speed = OptionDefault::new(
+            self.fan_control.as_ref().and_then(|v| v.speed),
+            DEFAULT_FAN_SPEED,
+        );
+        let min_fans = OptionDefault::new(
+            self.fan_control.as_ref().and_then(|v| v.min_fans),
+            DEFAULT_MIN_FANS,
+        );
+
+        let idle_speed = fan::Speed::new(
+            self.fan_control
+                .as_ref()
+                .and_then(|v| v.idle_speed)
+                .unwrap_or(DEFAULT_IDLE_FAN_SPEED),
+        );
+        let pwm_frequency = self.fan_control.as_ref().and_then(|v| v.pwm_frequency);
+        let on_fan_failure = match self
+            .fan_control
+            .as_ref()
+            .and_then(|v| v.on_fan_failure)
+            .unwrap_or(DEFAULT_ON_FAN_FAILURE)
+        {
+            FanFailureAction::Shutdown => monitor::FanFailureAction::Shutdown,
+            FanFailureAction::Throttle => {
+                monitor::FanFailureAction::Throttle(self.min_viable_frequency())
+            }
+            FanFailureAction::Warn => monitor::FanFailureAction::Warn,
+        };
+
+        let temp_config;
+        let fan_config;
+
+        // Configure temperature controller
+        match *mode {
+            TempControlMode::Auto | TempControlMode::Manual => {
+                temp_config = Some(monitor::TempControlConfig {
+                    dangerous_temp: *dangerous_temp as f32,
+                    hot_temp: *hot_temp as f32,
+                    hot_temp_override: *hot_temp_override,
+                });
+            }
+            TempControlMode::Disabled => {
+                temp_config = None;
+                // do sanity checks
+                if hot_temp.is_some() {
+                    self.warnings.push_field(
+                        WarningId::UnusedHotTemp,
+                        "temp_control.hot_temp",
+                        format!(
+                            "Unused 'hot_temp' ({}) because 'disable' mode is set",
+                            *hot_temp
+                        ),
+                    );
+                }
+                if dangerous_temp.is_some() {
+                    self.warnings.push_field(
+                        WarningId::UnusedDangerousTemp,
+                        "temp_control.dangerous_temp",
+                        format!(
+                            "Unused 'dangerous_temp' ({}) because 'disable' mode is set",
+                            *dangerous_temp
+                        ),
+                    );
+                }
+                if hot_temp_override.is_some() {
+                    self.warnings.push_field(
+                        WarningId::UnusedHotTempOverride,
+                        "temp_control.hot_temp_override",
+                        format!(
+                            "Unused 'hot_temp_override' ({}) because 'disable' mode is set",
+                            *hot_temp_override
+                        ),
+                    );
+                }
+            }
+        };
+
+        // Configure fan controller
         match *mode {
             TempControlMode::Auto => {
                 fan_config = Some(monitor::FanControlConfig {
                     mode: monitor::FanControlMode::TargetTemperature(*target_temp as f32),
                     min_fans: *min_fans,
+                    pwm_frequency,
+                    on_fan_failure,
+                    idle_speed,
                 });
                 // do sanity checks
                 if fan_speed.is_some() {
-                    warn!(
-                        "Unused fan 'speed' ({}) because 'auto' mode is set",
-                        *fan_speed
+                    self.warnings.push_field(
+                        WarningId::UnusedFanSpeed,
+                        "fan_control.speed",
+                        format!(
+                            "Unused fan 'speed' ({}) because 'auto' mode is set",
+                            *fan_speed
+                        ),
                     );
                 }
             }
@@ -479,25 +2298,152 @@ impl Backend {
                     Some(monitor::FanControlConfig {
                         mode: monitor::FanControlMode::FixedSpeed(fan::Speed::new(*fan_speed)),
                         min_fans: *min_fans,
+                        pwm_frequency,
+                        on_fan_failure,
+                        idle_speed,
                     })
                 };
                 // do sanity checks
                 if target_temp.is_some() {
-                    warn!(
-                        "Unused 'target_temp' ({}) because 'auto' mode is not set",
-                        *fan_speed
+                    self.warnings.push_field(
+                        WarningId::UnusedTargetTemp,
+                        "temp_control.target_temp",
+                        format!(
+                            "Unused 'target_temp' ({}) because 'auto' mode is not set",
+                            *target_temp
+                        ),
                     );
                 }
             }
         };
 
+        // Configure frequency throttling (it requires temperature control)
+        let throttle_config = match (self.resolve_throttle_config(), temp_config.as_ref()) {
+            (Some(throttle_config), Some(_)) => Some(throttle_config),
+            (Some(_), None) => {
+                self.warnings.push_field(
+                    WarningId::UnusedThrottle,
+                    "throttle",
+                    format!("Unused 'throttle' section because temperature control is disabled"),
+                );
+                None
+            }
+            (None, _) => None,
+        };
+
         monitor::Config {
             temp_config,
             fan_config,
+            throttle_config,
+            peak_temp_window: self
+                .temp_control
+                .as_ref()
+                .and_then(|v| v.peak_window)
+                .map(Duration::from_secs),
+            health_weights: self.resolve_health_weights(),
+            maintenance_config: self.resolve_maintenance_config(),
             fans_on_while_warming_up: self.fans_on_while_warming_up.unwrap_or(true),
+            failure_grace: self.failure_grace(),
+            continue_on_chain_failure: self.continue_on_chain_failure(),
         }
     }
 
+    fn resolve_health_weights(&self) -> monitor::HealthWeights {
+        let health = self.health.as_ref();
+        let weight = |value: Option<f64>| value.unwrap_or(DEFAULT_HEALTH_WEIGHT) as f32;
+        monitor::HealthWeights {
+            hw_errors: weight(health.and_then(|v| v.hw_errors_weight)),
+            temperature: weight(health.and_then(|v| v.temperature_weight)),
+            acceptance: weight(health.and_then(|v| v.acceptance_weight)),
+            frequency: weight(health.and_then(|v| v.frequency_weight)),
+        }
+    }
+
+    fn resolve_maintenance_config(&self) -> monitor::MaintenanceConfig {
+        let maintenance = self.maintenance.as_ref();
+        monitor::MaintenanceConfig {
+            duration: Duration::from_secs(
+                maintenance
+                    .and_then(|v| v.duration)
+                    .unwrap_or(DEFAULT_MAINTENANCE_DURATION),
+            ),
+            daily_start: maintenance.and_then(|v| v.start.as_ref()).map(|start| {
+                Maintenance::parse_start(start).expect("BUG: invalid maintenance start")
+            }),
+        }
+    }
+
+    pub fn resolve_thermal_recovery(&self) -> restart::RecoveryConfig {
+        let thermal_recovery = self.thermal_recovery.as_ref();
+        restart::RecoveryConfig {
+            mode: match thermal_recovery
+                .and_then(|v| v.mode)
+                .unwrap_or(DEFAULT_THERMAL_RECOVERY_MODE)
+            {
+                ThermalRecoveryMode::Retry => restart::RecoveryMode::Retry,
+                ThermalRecoveryMode::StayOff => restart::RecoveryMode::StayOff,
+            },
+            cooldown: Duration::from_secs(
+                thermal_recovery
+                    .and_then(|v| v.cooldown)
+                    .unwrap_or(DEFAULT_THERMAL_RECOVERY_COOLDOWN),
+            ),
+            max_attempts: thermal_recovery
+                .and_then(|v| v.max_attempts)
+                .unwrap_or(DEFAULT_THERMAL_RECOVERY_MAX_ATTEMPTS),
+            window: Duration::from_secs(
+                thermal_recovery
+                    .and_then(|v| v.window)
+                    .unwrap_or(DEFAULT_THERMAL_RECOVERY_WINDOW),
+            ),
+        }
+    }
+
+    fn resolve_throttle_config(&self) -> Option<monitor::ThrottleConfig> {
+        let throttle = self.throttle.as_ref();
+        if !throttle
+            .and_then(|v| v.enabled)
+            .unwrap_or(DEFAULT_THROTTLE_ENABLED)
+        {
+            return None;
+        }
+
+        let throttle_temp = throttle
+            .and_then(|v| v.temp)
+            .unwrap_or(DEFAULT_THROTTLE_TEMP_C);
+        let hysteresis = throttle
+            .and_then(|v| v.hysteresis)
+            .unwrap_or(DEFAULT_THROTTLE_HYSTERESIS_C);
+        let step = throttle
+            .and_then(|v| v.step)
+            .unwrap_or(DEFAULT_THROTTLE_STEP_MHZ);
+        let min_frequency = throttle
+            .and_then(|v| v.min_frequency)
+            .unwrap_or(DEFAULT_THROTTLE_MIN_FREQUENCY_MHZ);
+        // Recovery is by default allowed right below the hysteresis band
+        let recovery_temp = throttle
+            .and_then(|v| v.recovery_temp)
+            .unwrap_or(throttle_temp - hysteresis);
+        let recovery_dwell = throttle
+            .and_then(|v| v.recovery_dwell)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_THROTTLE_RECOVERY_DWELL);
+        let warmup_period = throttle
+            .and_then(|v| v.warmup_period)
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_THROTTLE_WARMUP_PERIOD);
+
+        Some(monitor::ThrottleConfig {
+            throttle_temp: throttle_temp as f32,
+            hysteresis: hysteresis as f32,
+            step: (step * 1_000_000.0) as usize,
+            min_frequency: (min_frequency * 1_000_000.0) as usize,
+            recovery_temp: recovery_temp as f32,
+            recovery_dwell,
+            warmup_period,
+        })
+    }
+
     pub fn fill_info<T>(&mut self) -> Result<(), std::io::Error>
     where
         T: ConfigBody,
@@ -509,6 +2455,37 @@ impl Backend {
     }
 }
 
+/// Check that fan speed (or any other fan duty cycle setting) is percentage that can be passed
+/// to fan controller
+fn check_fan_speed(name: &str, speed: usize) -> Result<(), String> {
+    if !(FAN_SPEED_MIN..=FAN_SPEED_MAX).contains(&speed) {
+        Err(format!(
+            "{} '{}' is out of range '{}..{}' (percent)",
+            name, speed, FAN_SPEED_MIN, FAN_SPEED_MAX
+        ))?;
+    }
+    Ok(())
+}
+
+/// Check that delay (in milliseconds) of hash chain power-on is within supported range
+fn check_power_on_delay(name: &str, delay: u64) -> Result<(), String> {
+    if delay > POWER_ON_DELAY_MS_MAX {
+        Err(format!(
+            "{} '{}' is out of range '0..{}' (ms)",
+            name, delay, POWER_ON_DELAY_MS_MAX
+        ))?;
+    }
+    Ok(())
+}
+
+/// Round frequency step (in MHz) to the nearest multiple of PLL friendly granularity
+fn align_frequency_step(frequency_step: f64) -> f64 {
+    (frequency_step / FREQUENCY_STEP_GRANULARITY_MHZ)
+        .round()
+        .max(1.0)
+        * FREQUENCY_STEP_GRANULARITY_MHZ
+}
+
 impl ConfigBody for Backend {
     fn model() -> String {
         return FORMAT_MODEL.into();
@@ -523,12 +2500,14 @@ impl ConfigBody for Backend {
     }
 
     fn sanity_check(&self) -> Result<(), String> {
-        // Check if all hash chain keys have meaningful name
+        // Check if all hash chain keys have meaningful name and that there are no two keys
+        // referring to the same hash chain (e.g. "6" and "06")
         if let Some(hash_chains) = &self.hash_chains {
-            for idx in hash_chains.keys() {
-                let _ = idx
+            let mut chain_keys: HashMap<usize, &String> = HashMap::with_capacity(hash_chains.len());
+            for key in hash_chains.keys() {
+                let idx = key
                     .parse::<usize>()
-                    .map_err(|_| format!("hash chain index '{}' is not number", idx))
+                    .map_err(|_| format!("hash chain index '{}' is not number", key))
                     .and_then(|idx| {
                         if (HASH_CHAIN_INDEX_MIN..=HASH_CHAIN_INDEX_MAX).contains(&idx) {
                             Ok(idx)
@@ -539,54 +2518,528 @@ impl ConfigBody for Backend {
                             ))
                         }
                     })?;
+                if let Some(other_key) = chain_keys.insert(idx, key) {
+                    Err(format!(
+                        "hash chain keys '{}' and '{}' refer to the same hash chain {}",
+                        other_key, key, idx
+                    ))?;
+                }
             }
         }
 
-        // Analyze group configuration, make sure the groups are unique, and build descriptor
-        // topology out of the configuration data
-        // Don't worry if is this section missing, maybe there are some pools on command line
-        if let Some(groups) = &self.groups {
-            let mut group_names = HashSet::with_capacity(groups.len());
-            for group in groups {
-                if let Some(name) = group_names.replace(&group.descriptor.name) {
-                    Err(format!("group with name '{}' already defined", name))?;
-                }
-                if let Some(pools) = &group.pools {
-                    for pool in pools {
-                        let _ = ClientDescriptor::create(
-                            pool.url.as_str(),
-                            &ClientUserInfo::new(pool.user.as_str(), pool.password.as_deref()),
-                            pool.enabled.unwrap_or(DEFAULT_POOL_ENABLED),
-                        )
-                        .map_err(|e| {
-                            format!("{} in pool '{}@{}'", e.to_string(), pool.url, pool.user)
-                        })?;
-                    }
-                }
+        // Check frequency step of all hash chains (it is rounded to supported value later)
+        let frequency_steps = self
+            .hash_chain_global
+            .as_ref()
+            .and_then(|v| v.overridable.as_ref())
+            .into_iter()
+            .chain(self.hash_chains.iter().flat_map(|v| v.values()))
+            .filter_map(|v| v.frequency_step);
+        for frequency_step in frequency_steps {
+            if !(frequency_step > 0.0) {
+                Err(format!(
+                    "frequency step '{}' is not positive",
+                    frequency_step
+                ))?;
+            }
+            if frequency_step > FREQUENCY_MHZ_MAX - FREQUENCY_MHZ_MIN {
+                Err(format!(
+                    "frequency step '{}' exceeds frequency range '{}..{}'",
+                    frequency_step, FREQUENCY_MHZ_MIN, FREQUENCY_MHZ_MAX
+                ))?;
             }
         }
 
-        Ok(())
-    }
-
-    fn metadata() -> serde_json::Value {
-        metadata::for_backend()
-    }
-
-    fn variant() -> String {
-        bosminer::SIGNATURE.into()
-    }
-}
+        // Check ASIC difficulty of all hash chains
+        let asic_difficulties = self
+            .hash_chain_global
+            .as_ref()
+            .and_then(|v| v.overridable.as_ref())
+            .into_iter()
+            .chain(self.hash_chains.iter().flat_map(|v| v.values()))
+            .filter_map(|v| v.asic_difficulty);
+        for asic_difficulty in asic_difficulties {
+            if !asic_difficulty.is_power_of_two() {
+                Err(format!(
+                    "ASIC difficulty '{}' is not power of 2",
+                    asic_difficulty
+                ))?;
+            }
+            if asic_difficulty < ASIC_DIFFICULTY_MIN || asic_difficulty > ASIC_DIFFICULTY_MAX {
+                Err(format!(
+                    "ASIC difficulty '{}' is out of range '{}..{}'",
+                    asic_difficulty, ASIC_DIFFICULTY_MIN, ASIC_DIFFICULTY_MAX
+                ))?;
+            }
+        }
 
-impl hal::BackendConfig for Backend {
-    #[inline]
-    fn midstate_count(&self) -> usize {
-        if self
+        // Check work queue depth of all hash chains
+        let work_queue_depths = self
             .hash_chain_global
             .as_ref()
-            .and_then(|v| v.asic_boost)
-            .unwrap_or(DEFAULT_ASIC_BOOST)
+            .and_then(|v| v.overridable.as_ref())
+            .into_iter()
+            .chain(self.hash_chains.iter().flat_map(|v| v.values()))
+            .filter_map(|v| v.work_queue_depth);
+        for work_queue_depth in work_queue_depths {
+            if work_queue_depth < WORK_QUEUE_DEPTH_MIN || work_queue_depth > WORK_QUEUE_DEPTH_MAX {
+                Err(format!(
+                    "work queue depth '{}' is out of range '{}..{}'",
+                    work_queue_depth, WORK_QUEUE_DEPTH_MIN, WORK_QUEUE_DEPTH_MAX
+                ))?;
+            }
+        }
+
+        // Chip frequency offsets are tied to particular hashboard
+        if let Some(overridable) = self
+            .hash_chain_global
+            .as_ref()
+            .and_then(|v| v.overridable.as_ref())
+        {
+            if overridable.chip_frequency_offsets.is_some() {
+                Err("chip frequency offsets can be set only for individual hash chains")?;
+            }
+            if overridable.pool_group.is_some() {
+                Err("pool group can be assigned only to individual hash chains")?;
+            }
+            if overridable.power_on_delay.is_some() {
+                Err("power-on delay can be set only for individual hash chains")?;
+            }
+        }
+        for hash_chain in self.hash_chains.iter().flat_map(|v| v.values()) {
+            if let Some(power_on_delay) = hash_chain.power_on_delay {
+                check_power_on_delay("power-on delay", power_on_delay)?;
+            }
+            for (key, offset) in hash_chain.chip_frequency_offsets.iter().flatten() {
+                HashChain::parse_chip_index(key)?;
+                if !offset.is_finite() {
+                    Err(format!("chip frequency offset '{}' is not number", offset))?;
+                }
+            }
+        }
+
+        // Check that PSU model is known, otherwise configured voltages would be meaningless
+        if let Some(psu_model) = self
+            .miner
+            .as_ref()
+            .and_then(|miner| miner.psu_model.as_ref())
+        {
+            if power::voltage_mapping(psu_model).is_none() {
+                Err(format!(
+                    "unknown PSU model '{}' (supported models: {})",
+                    psu_model,
+                    power::PSU_MODELS
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))?;
+            }
+        }
+
+        // Check that board model is known, otherwise there are no defaults for hash chains
+        if let Some(board_model) = self.miner.as_ref().and_then(|miner| miner.model.as_ref()) {
+            if board_defaults(board_model).is_none() {
+                Err(format!(
+                    "unknown board model '{}' (supported models: {})",
+                    board_model,
+                    BOARD_MODELS
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))?;
+            }
+        }
+
+        // Check that hash chains are initialized at supported frequency which doesn't exceed
+        // their target frequency
+        let overridable = self
+            .hash_chain_global
+            .as_ref()
+            .and_then(|v| v.overridable.as_ref());
+        for hash_chain_idx in HASH_CHAIN_INDEX_MIN..=HASH_CHAIN_INDEX_MAX {
+            let hash_chain = self.get_hash_chain(hash_chain_idx);
+            let init_frequency = match hash_chain
+                .and_then(|v| v.init_frequency)
+                .or(overridable.and_then(|v| v.init_frequency))
+            {
+                Some(init_frequency) => init_frequency,
+                None => continue,
+            };
+            if init_frequency < FREQUENCY_MHZ_MIN || init_frequency > FREQUENCY_MHZ_MAX {
+                Err(format!(
+                    "initialization frequency '{}' is out of range '{}..{}'",
+                    init_frequency, FREQUENCY_MHZ_MIN, FREQUENCY_MHZ_MAX
+                ))?;
+            }
+            let frequency = hash_chain
+                .and_then(|v| v.frequency)
+                .or(overridable.and_then(|v| v.frequency))
+                .unwrap_or(self.board_defaults().frequency);
+            if init_frequency > frequency {
+                Err(format!(
+                    "chain {}: initialization frequency '{}' exceeds frequency '{}'",
+                    hash_chain_idx, init_frequency, frequency
+                ))?;
+            }
+        }
+
+        if let Some(min_chains) = self.api.as_ref().and_then(|api| api.ready_min_chains) {
+            let chain_count = HASH_CHAIN_INDEX_MAX - HASH_CHAIN_INDEX_MIN + 1;
+            if min_chains > chain_count {
+                Err(format!(
+                    "minimal number of ready hash chains '{}' exceeds number of hash chains {}",
+                    min_chains, chain_count
+                ))?;
+            }
+        }
+
+        if let Some(hw_error_control) = &self.hw_error_control {
+            if let Some(threshold) = hw_error_control.threshold {
+                if threshold < 0.0 || threshold > 100.0 {
+                    Err(format!(
+                        "hardware error threshold '{}' is out of range '0..100'",
+                        threshold
+                    ))?;
+                }
+            }
+            if let Some(voltage_step) = hw_error_control.voltage_step {
+                if voltage_step <= 0.0 {
+                    Err(format!(
+                        "hardware error voltage step '{}' is not positive",
+                        voltage_step
+                    ))?;
+                }
+            }
+            if let Some(frequency_step) = hw_error_control.frequency_step {
+                if frequency_step <= 0.0 {
+                    Err(format!(
+                        "hardware error frequency step '{}' is not positive",
+                        frequency_step
+                    ))?;
+                }
+            }
+        }
+
+        if let Some(tolerance) = self
+            .voltage_check
+            .as_ref()
+            .and_then(|voltage_check| voltage_check.tolerance)
+        {
+            if tolerance <= 0.0 {
+                Err(format!(
+                    "voltage check tolerance '{}' is not positive",
+                    tolerance
+                ))?;
+            }
+        }
+
+        if let Some(hashrate_check) = &self.hashrate_check {
+            if let Some(tolerance) = hashrate_check.expected_hashrate_tolerance {
+                if tolerance <= 0.0 || tolerance > 1.0 {
+                    Err(format!(
+                        "expected hashrate tolerance '{}' is out of range '0..1'",
+                        tolerance
+                    ))?;
+                }
+            }
+            if let Some(period) = hashrate_check.period {
+                if Duration::from_secs(period) < DEFAULT_HASHRATE_INTERVAL {
+                    Err(format!(
+                        "hashrate check period '{}' is shorter than hashrate interval {} seconds",
+                        period,
+                        DEFAULT_HASHRATE_INTERVAL.as_secs()
+                    ))?;
+                }
+            }
+            if let Some(voltage_step) = hashrate_check.voltage_step {
+                if voltage_step <= 0.0 {
+                    Err(format!(
+                        "hashrate check voltage step '{}' is not positive",
+                        voltage_step
+                    ))?;
+                }
+            }
+        }
+
+        if let Some(fallback_step) = self
+            .pll_lock_check
+            .as_ref()
+            .and_then(|pll_lock_check| pll_lock_check.fallback_step)
         {
+            if fallback_step <= 0.0 {
+                Err(format!(
+                    "PLL lock fallback step '{}' is not positive",
+                    fallback_step
+                ))?;
+            }
+        }
+
+        if let Some(health) = &self.health {
+            let weights = [
+                health.hw_errors_weight,
+                health.temperature_weight,
+                health.acceptance_weight,
+                health.frequency_weight,
+            ];
+            for weight in weights.iter().filter_map(|weight| *weight) {
+                if weight < 0.0 {
+                    Err(format!("health score weight '{}' is negative", weight))?;
+                }
+            }
+            if weights
+                .iter()
+                .all(|weight| weight.unwrap_or(DEFAULT_HEALTH_WEIGHT) == 0.0)
+            {
+                Err("all health score weights are zero".to_string())?;
+            }
+        }
+
+        MidstateCount::try_new(self.midstate_count()).map_err(|e| e.to_string())?;
+
+        if let Some(fan_control) = &self.fan_control {
+            if let Some(speed) = fan_control.speed {
+                check_fan_speed("fan speed", speed)?;
+            }
+            if let Some(idle_speed) = fan_control.idle_speed {
+                check_fan_speed("idle fan speed", idle_speed)?;
+            }
+            if fan_control.pwm_frequency == Some(0) {
+                Err("fan PWM frequency must be positive")?;
+            }
+        }
+
+        if let Some(api) = &self.api {
+            match api.token.as_ref() {
+                Some(token) if token.is_empty() => Err("API token must not be empty")?,
+                None if api.protect_queries == Some(true) => {
+                    Err("API query protection requires API token")?
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(maintenance) = &self.maintenance {
+            if let Some(start) = &maintenance.start {
+                Maintenance::parse_start(start)?;
+            }
+            if let Some(duration) = maintenance.duration {
+                if duration == 0 || duration > MAINTENANCE_DURATION_MAX {
+                    Err(format!(
+                        "maintenance duration '{}' is out of range '1..{}'",
+                        duration, MAINTENANCE_DURATION_MAX
+                    ))?;
+                }
+            }
+        }
+
+        if let Some(thermal_recovery) = &self.thermal_recovery {
+            if thermal_recovery.max_attempts == Some(0) {
+                Err("thermal recovery max attempts must be positive")?;
+            }
+            if thermal_recovery.window == Some(0) {
+                Err("thermal recovery window must be positive")?;
+            }
+        }
+
+        if let Some(miner) = &self.miner {
+            if miner.hotplug_interval == Some(0) {
+                Err("hotplug interval must be positive")?;
+            }
+            if miner.init_concurrency == Some(0) {
+                Err("init concurrency must be positive")?;
+            }
+            if let Some(power_on_interval) = miner.power_on_interval {
+                check_power_on_delay("power-on interval", power_on_interval)?;
+            }
+            if let Some(pools_url) = &miner.pools_url {
+                if !pools_url.starts_with("https://") {
+                    Err(format!("pools URL '{}' is not HTTPS URL", pools_url))?;
+                }
+            }
+            if let Some(system_power_limit) = miner.system_power_limit {
+                if system_power_limit <= 0.0 {
+                    Err(format!(
+                        "system power limit '{}' is not positive",
+                        system_power_limit
+                    ))?;
+                }
+                // Check that the limit can be met with all hash chains running
+                let mut chain_configs = vec![];
+                for hashboard_idx in HASH_CHAIN_INDEX_MIN..=HASH_CHAIN_INDEX_MAX {
+                    let chain_config = self
+                        .resolve_chain_config(hashboard_idx)
+                        .map_err(|e| e.to_string())?;
+                    chain_configs.push((hashboard_idx, chain_config));
+                }
+                self.apply_power_limit(&mut chain_configs)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+
+        // Check that throttling cannot oscillate: recovery has to happen outside of hysteresis
+        if let Some(throttle) = &self.throttle {
+            let throttle_temp = throttle.temp.unwrap_or(DEFAULT_THROTTLE_TEMP_C);
+            let hysteresis = throttle.hysteresis.unwrap_or(DEFAULT_THROTTLE_HYSTERESIS_C);
+            if hysteresis < 0.0 {
+                Err(format!("throttle hysteresis '{}' is negative", hysteresis))?;
+            }
+            if let Some(step) = throttle.step {
+                if step <= 0.0 {
+                    Err(format!("throttle step '{}' is not positive", step))?;
+                }
+            }
+            if let Some(min_frequency) = throttle.min_frequency {
+                if min_frequency < FREQUENCY_MHZ_MIN || min_frequency > FREQUENCY_MHZ_MAX {
+                    Err(format!(
+                        "throttle minimal frequency '{}' is out of range '{}..{}'",
+                        min_frequency, FREQUENCY_MHZ_MIN, FREQUENCY_MHZ_MAX
+                    ))?;
+                }
+            }
+            if let Some(recovery_temp) = throttle.recovery_temp {
+                if recovery_temp > throttle_temp - hysteresis {
+                    Err(format!(
+                        "throttle recovery temperature '{}' has to be at least hysteresis '{}' \
+                         below throttle temperature '{}'",
+                        recovery_temp, hysteresis, throttle_temp
+                    ))?;
+                }
+            }
+        }
+
+        // Analyze group configuration, make sure the groups are unique, and build descriptor
+        // topology out of the configuration data
+        // Don't worry if is this section missing, maybe there are some pools on command line
+        // (missing pools are handled later according to `Backend::no_pools_action`)
+        if let Some(groups) = &self.groups {
+            let mut group_names = HashSet::with_capacity(groups.len());
+            let mut valid_pools = 0;
+            let mut first_error = None;
+            for group in groups {
+                if let Some(name) = group_names.replace(&group.descriptor.name) {
+                    Err(format!("group with name '{}' already defined", name))?;
+                }
+                if let Some(min_accept_rate) = group.descriptor.min_accept_rate() {
+                    if !(0.0..=100.0).contains(&min_accept_rate) {
+                        Err(format!(
+                            "group '{}': minimal accept rate '{}' is out of range '0..100' \
+                             (percent)",
+                            group.descriptor.name, min_accept_rate
+                        ))?;
+                    }
+                }
+                if group.descriptor.accept_rate_window() < GroupDescriptor::MIN_ACCEPT_RATE_WINDOW {
+                    Err(format!(
+                        "group '{}': accept rate window must be at least {} s",
+                        group.descriptor.name,
+                        GroupDescriptor::MIN_ACCEPT_RATE_WINDOW.as_secs()
+                    ))?;
+                }
+                if let Some(pools) = &group.pools {
+                    for pool in pools {
+                        match Self::check_pool(pool) {
+                            Ok(_) => valid_pools += 1,
+                            // Malformed pools are removed later in lenient mode
+                            Err(e) if self.lenient_pools() => {
+                                first_error.get_or_insert(e);
+                            }
+                            Err(e) => Err(e)?,
+                        }
+                    }
+                }
+            }
+            if let Some(e) = first_error {
+                if valid_pools == 0 {
+                    Err(format!("no valid pool remains ({})", e))?;
+                }
+            }
+            // Configured groups have to provide some pool (unless the miner may start idle)
+            let has_pool = groups.iter().any(|group| {
+                group
+                    .pools
+                    .as_ref()
+                    .map_or(false, |pools| !pools.is_empty())
+            });
+            if !groups.is_empty() && !has_pool && self.no_pools_action() == NoPoolsAction::Error {
+                Err("no pool group has any pool")?;
+            }
+        }
+
+        // Hash chain can be assigned only to existing group with some pools
+        for pool_group in self.assigned_pool_groups() {
+            let has_pools = self
+                .groups
+                .iter()
+                .flatten()
+                .find(|group| group.descriptor.name == pool_group)
+                .map(|group| {
+                    group
+                        .pools
+                        .as_ref()
+                        .map_or(false, |pools| !pools.is_empty())
+                });
+            match has_pools {
+                Some(true) => {}
+                Some(false) => Err(format!(
+                    "pool group '{}' assigned to hash chain has no pools",
+                    pool_group
+                ))?,
+                None => Err(format!(
+                    "pool group '{}' assigned to hash chain does not exist",
+                    pool_group
+                ))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_overrides(&self, mode: ParseMode, warnings: &Warnings) -> Result<(), String> {
+        let asic_boost = self.asic_boost();
+        let asic_boost_overrides = self
+            .hash_chains
+            .iter()
+            .flat_map(|hash_chains| hash_chains.iter())
+            .filter_map(|(key, hash_chain)| hash_chain.asic_boost.map(|v| (key, v)));
+        for (key, chain_asic_boost) in asic_boost_overrides {
+            if chain_asic_boost == asic_boost {
+                continue;
+            }
+            let message = format!(
+                "Chain {}: 'asic_boost' ({}) contradicts 'hash_chain_global.asic_boost' ({}), \
+                 all hash chains use {} midstate(s)",
+                key,
+                chain_asic_boost,
+                asic_boost,
+                self.midstate_count()
+            );
+            match mode {
+                ParseMode::Strict => Err(message)?,
+                ParseMode::Lenient => warnings.push_field(
+                    WarningId::ConflictingAsicBoost,
+                    &format!("hash_chain.{}.asic_boost", key),
+                    message,
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    fn metadata() -> serde_json::Value {
+        metadata::for_backend()
+    }
+
+    fn variant() -> String {
+        bosminer::SIGNATURE.into()
+    }
+}
+
+impl hal::BackendConfig for Backend {
+    #[inline]
+    fn midstate_count(&self) -> usize {
+        if self.asic_boost() {
             ASIC_BOOST_MIDSTATE_COUNT
         } else {
             1
@@ -601,3 +3054,1183 @@ impl hal::BackendConfig for Backend {
         Some(self.info.clone())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bosminer_config::{GroupDescriptor, GroupPoolStrategy};
+
+    fn parse_backend(config: &str) -> Backend {
+        toml::from_str(config).expect("BUG: cannot parse test configuration")
+    }
+
+    #[test]
+    fn test_hash_chain_duplicate_keys() {
+        let backend = parse_backend(
+            r#"
+            [hash_chain.6]
+            frequency = 600.0
+
+            [hash_chain.06]
+            frequency = 650.0
+            "#,
+        );
+        let error = backend
+            .sanity_check()
+            .expect_err("duplicate hash chain keys not detected");
+        assert!(error.contains("'06'") && error.contains("'6'"));
+    }
+
+    #[test]
+    fn test_hash_chain_key_lookup() {
+        let backend = parse_backend(
+            r#"
+            [hash_chain.07]
+            enabled = false
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        assert!(!backend.resolve_chain_config(7).unwrap().enabled);
+        assert!(backend.resolve_chain_config(8).unwrap().enabled);
+    }
+
+    #[test]
+    fn test_no_pools_action() {
+        let empty_group = "[[group]]\nname = 'Default'\n";
+        assert_eq!(
+            parse_backend(empty_group).sanity_check(),
+            Err("no pool group has any pool".to_string())
+        );
+        let backend = parse_backend(&format!("[miner]\non_no_pools = 'idle'\n{}", empty_group));
+        assert!(backend.sanity_check().is_ok());
+        assert!(!backend.has_pools());
+    }
+
+    #[test]
+    fn test_lenient_pools() {
+        let pools = r#"
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            url = 'stratum+tcp://pool.example.com:3333'
+            user = 'user.worker'
+
+            [[group.pool]]
+            url = 'invalid'
+            user = 'user.worker'
+            "#;
+        assert!(parse_backend(pools).sanity_check().is_err());
+
+        let mut backend = parse_backend(&format!("[miner]\nlenient_pools = true\n{}", pools));
+        assert!(backend.sanity_check().is_ok());
+        backend.prune_invalid_pools();
+        let groups = backend.groups.as_ref().expect("missing groups");
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].pools.as_ref().map(|v| v.len()), Some(1));
+
+        let backend = parse_backend(
+            r#"
+            [miner]
+            lenient_pools = true
+
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            url = 'invalid'
+            user = 'user.worker'
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_health_weights() {
+        let backend = parse_backend(
+            r#"
+            [health]
+            temperature_weight = -1.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        let backend = parse_backend(
+            r#"
+            [health]
+            hw_errors_weight = 0.0
+            temperature_weight = 0.0
+            acceptance_weight = 0.0
+            frequency_weight = 0.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_temp_unit() {
+        let backend = parse_backend(
+            r#"
+            [temp_control]
+            mode = "manual"
+            hot_temp = 176.0
+            dangerous_temp = 212.0
+            temp_unit = "f"
+            "#,
+        );
+        assert_eq!(backend.temp_unit(), TempUnit::Fahrenheit);
+        let temp_config = backend
+            .resolve_monitor_config()
+            .temp_config
+            .expect("missing temperature control");
+        assert_eq!(temp_config.hot_temp, 80.0);
+        assert_eq!(temp_config.dangerous_temp, 100.0);
+        assert_eq!(TempUnit::Fahrenheit.from_celsius(100.0), 212.0);
+
+        assert_eq!(parse_backend("").temp_unit(), TempUnit::Celsius);
+        assert!(toml::from_str::<Backend>(
+            r#"
+            [temp_control]
+            temp_unit = "k"
+            "#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_pool_urls() {
+        let backend = parse_backend(
+            r#"
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            urls = ['stratum+tcp://pool.example.com:3333', 'stratum+tcp://backup.example.com']
+            user = 'user.worker'
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        let descriptor = backend.groups.as_ref().unwrap()[0].pools.as_ref().unwrap()[0]
+            .create_descriptor(DEFAULT_POOL_ENABLED)
+            .expect("BUG: invalid pool");
+        assert_eq!(
+            descriptor.get_hosts_and_ports(),
+            vec!["pool.example.com:3333", "backup.example.com:3333"]
+        );
+
+        // Every URL is validated
+        let backend = parse_backend(
+            r#"
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            url = 'stratum+tcp://pool.example.com:3333'
+            urls = ['invalid']
+            user = 'user.worker'
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        // At least one URL is required
+        let backend = parse_backend(
+            r#"
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            urls = []
+            user = 'user.worker'
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_throttle_recovery_outside_hysteresis() {
+        let backend = parse_backend(
+            r#"
+            [throttle]
+            enabled = true
+            temp = 90.0
+            hysteresis = 5.0
+            recovery_temp = 87.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        let backend = parse_backend(
+            r#"
+            [throttle]
+            enabled = true
+            temp = 90.0
+            hysteresis = 5.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+    }
+
+    #[test]
+    fn test_psu_model() {
+        let backend = parse_backend(
+            r#"
+            [miner]
+            psu_model = "unknown"
+            "#,
+        );
+        let error = backend
+            .sanity_check()
+            .expect_err("unknown PSU model not detected");
+        assert!(error.contains("unknown") && error.contains(power::DEFAULT_PSU_MODEL));
+
+        let backend = parse_backend(&format!(
+            r#"
+            [miner]
+            psu_model = "{}"
+            "#,
+            power::DEFAULT_PSU_MODEL
+        ));
+        assert!(backend.sanity_check().is_ok());
+    }
+
+    #[test]
+    fn test_remove_unknown_field() {
+        let mut value = serde_json::json!({
+            "temp_control": { "mode": "auto", "new_field": 1 },
+            "group": [{ "name": "default", "pool": [{ "url": "url", "new_field": 2 }] }]
+        });
+        let error = serde_json::from_value::<FanControl>(serde_json::json!({ "new_field": 1 }))
+            .expect_err("BUG: unknown field accepted");
+        assert_eq!(
+            support::remove_unknown_field(&mut value, "unknown field `new_field`, expected `url`"),
+            Some("group[0].pool[0].new_field".to_string())
+        );
+        assert_eq!(
+            support::remove_unknown_field(
+                &mut value,
+                "unknown field `new_field`, expected one of `mode`, `target_temp`"
+            ),
+            Some("temp_control.new_field".to_string())
+        );
+        assert_eq!(
+            support::remove_unknown_field(&mut value, error.to_string().as_str()),
+            None
+        );
+        assert_eq!(
+            support::remove_unknown_field(&mut value, "invalid type: string"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        assert!(support::struct_fields::<Backend>().contains(&"hash_chain"));
+        assert!(!support::struct_fields::<Backend>().contains(&"warnings"));
+
+        // unknown section is not reported by deserializer of flattened body
+        let value = serde_json::json!({
+            "format": { "version": FORMAT_VERSION, "model": FORMAT_MODEL },
+            "new_section": { "enabled": true }
+        });
+        assert!(serde_json::from_value::<FormatWrapper<Backend>>(value).is_ok());
+
+        let value = serde_json::json!({
+            "format": { "version": FORMAT_VERSION, "model": FORMAT_MODEL },
+            "temp_control": { "mode": "auto", "new_field": 1 },
+            "new_section": { "enabled": true }
+        });
+        let config = FormatWrapper::<Backend>::from_value_lenient(value, "bosminer.toml")
+            .expect("BUG: lenient parsing failed");
+        let paths: Vec<_> = config
+            .warnings
+            .list()
+            .into_iter()
+            .map(|warning| warning.path)
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                Some("new_section".to_string()),
+                Some("temp_control.new_field".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hw_error_control() {
+        let backend = parse_backend(
+            r#"
+            [hw_error_control]
+            threshold = 150.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_hotplug_interval() {
+        let backend = parse_backend(
+            r#"
+            [miner]
+            hotplug = true
+            hotplug_interval = 0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_system_power_limit() {
+        let backend = parse_backend(
+            r#"
+            [miner]
+            system_power_limit = 1200.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        let mut chain_configs: Vec<_> = (HASH_CHAIN_INDEX_MIN..=HASH_CHAIN_INDEX_MAX)
+            .map(|idx| (idx, backend.resolve_chain_config(idx).unwrap()))
+            .collect();
+        backend.apply_power_limit(&mut chain_configs).unwrap();
+        let total: f64 = chain_configs
+            .iter()
+            .map(|(_, chain_config)| {
+                power::estimate_power(&chain_config.frequency, chain_config.voltage)
+            })
+            .sum();
+        assert!(total <= 1200.0);
+
+        // limit cannot be met with minimal frequencies
+        let backend = parse_backend(
+            r#"
+            [miner]
+            system_power_limit = 100.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_api_token() {
+        let backend = parse_backend(
+            r#"
+            [api]
+            token = ""
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        let backend = parse_backend(
+            r#"
+            [api]
+            protect_queries = true
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_api_readiness() {
+        let backend = parse_backend(
+            r#"
+            [api]
+            ready_min_chains = 2
+            ready_min_pools = 0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+
+        let backend = parse_backend(
+            r#"
+            [api]
+            ready_min_chains = 10
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_frequency_step() {
+        let backend = parse_backend(
+            r#"
+            [hash_chain.7]
+            frequency_step = 20.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        // step is rounded to multiple of PLL friendly granularity
+        let chain_config = backend.resolve_chain_config(7).unwrap();
+        assert_eq!(chain_config.frequency_step, 18_750_000);
+
+        let backend = parse_backend(
+            r#"
+            [hash_chain.8]
+            frequency_step = 0.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_maintenance() {
+        for maintenance in &[
+            r#"start = "24:00""#,
+            r#"start = "noon""#,
+            "duration = 0",
+            "duration = 86401",
+        ] {
+            let backend = parse_backend(&format!("[maintenance]\n{}", maintenance));
+            assert!(backend.sanity_check().is_err());
+        }
+    }
+
+    #[test]
+    fn test_chip_frequency_offsets() {
+        let mhz = 1_000_000;
+        let backend = parse_backend(
+            r#"
+            [hash_chain_global]
+            frequency = 650.0
+
+            [hash_chain.6.chip_frequency_offsets]
+            0 = -25.0
+            62 = 12.5
+
+            [hash_chain.7]
+            frequency = 880.0
+
+            [hash_chain.7.chip_frequency_offsets]
+            1 = 50.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        let frequency = backend.resolve_chain_config(6).unwrap().frequency;
+        assert_eq!(frequency.chip[0], 625 * mhz);
+        assert_eq!(frequency.chip[1], 650 * mhz);
+        assert_eq!(frequency.chip[62], 662_500_000);
+        // resulting frequency is clamped to supported range
+        let frequency = backend.resolve_chain_config(7).unwrap().frequency;
+        assert_eq!(frequency.chip[0], 880 * mhz);
+        assert_eq!(frequency.chip[1], 900 * mhz);
+        // chains without offsets run at single frequency
+        let frequency = backend.resolve_chain_config(8).unwrap().frequency;
+        assert_eq!(frequency.min(), frequency.max());
+
+        for config in &[
+            "[hash_chain.6.chip_frequency_offsets]\n63 = 10.0",
+            "[hash_chain.6.chip_frequency_offsets]\nfirst = 10.0",
+            "[hash_chain_global.chip_frequency_offsets]\n0 = 10.0",
+        ] {
+            assert!(parse_backend(config).sanity_check().is_err());
+        }
+    }
+
+    #[test]
+    fn test_warnings() {
+        let backend = parse_backend(
+            r#"
+            [temp_control]
+            mode = "disabled"
+            hot_temp = 90.0
+
+            [hash_chain.6]
+            frequency_step = 10.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        assert!(backend.warnings.list().is_empty());
+
+        // warnings are collected only once even when configuration is resolved repeatedly
+        for _ in 0..2 {
+            backend.resolve_monitor_config();
+            backend.resolve_chain_config(6).unwrap();
+        }
+        let warnings = backend.warnings.list();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::Unused);
+        assert_eq!(warnings[0].id, WarningId::UnusedHotTemp);
+        assert_eq!(
+            warnings[0].message,
+            "Unused 'hot_temp' (90) because 'disable' mode is set"
+        );
+        assert_eq!(warnings[1].kind, WarningKind::Adjusted);
+        assert_eq!(warnings[1].id, WarningId::FrequencyStepRounded);
+
+        backend.warnings.clear();
+        assert!(backend.warnings.list().is_empty());
+    }
+
+    #[test]
+    fn test_active_config_path() {
+        let mut backend = parse_backend("");
+        assert_eq!(
+            backend.active_config_path(),
+            PathBuf::from(DEFAULT_CONFIG_PATH)
+        );
+        assert_eq!(
+            backend.last_good_config_path(),
+            PathBuf::from(DEFAULT_LAST_GOOD_CONFIG_PATH)
+        );
+
+        // last-known-good configuration follows the active configuration file
+        backend.config_path = Some("/mnt/config/miner.toml".to_string());
+        assert_eq!(
+            backend.active_config_path(),
+            PathBuf::from("/mnt/config/miner.toml")
+        );
+        assert_eq!(
+            backend.last_good_config_path(),
+            PathBuf::from("/mnt/config/miner.last_good.toml")
+        );
+        backend.config_path = Some("/mnt/config/miner".to_string());
+        assert_eq!(
+            backend.last_good_config_path(),
+            PathBuf::from("/mnt/config/miner.last_good")
+        );
+
+        // explicitly configured path takes precedence
+        let mut backend = parse_backend(
+            r#"
+            [miner]
+            last_good_config = "/var/bosminer.good.toml"
+            "#,
+        );
+        backend.config_path = Some("/mnt/config/miner.toml".to_string());
+        assert_eq!(
+            backend.last_good_config_path(),
+            PathBuf::from("/var/bosminer.good.toml")
+        );
+    }
+
+    #[test]
+    fn test_voltage_check() {
+        let backend = parse_backend(
+            r#"
+            [voltage_check]
+            tolerance = 0.0
+        "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_voltage_guard() {
+        assert_eq!(min_voltage_for_frequency(450.0), None);
+        assert_eq!(min_voltage_for_frequency(650.0), Some(8.7));
+        assert_eq!(min_voltage_for_frequency(675.0), Some(8.7));
+        assert_eq!(min_voltage_for_frequency(900.0), Some(VOLTAGE_V_MAX));
+        // defaults of all board models pass the guard
+        for (_, defaults) in BOARD_MODELS {
+            assert!(min_voltage_for_frequency(defaults.frequency).unwrap() <= defaults.voltage);
+        }
+    }
+
+    #[test]
+    fn test_hashrate_check() {
+        assert!(parse_backend("").resolve_hashrate_check().is_none());
+
+        let backend = parse_backend(
+            r#"
+            [hashrate_check]
+            expected_hashrate_tolerance = 0.9
+            action = "reinit"
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        assert!(backend.resolve_hashrate_check().is_some());
+
+        let backend = parse_backend(
+            r#"
+            [hashrate_check]
+            expected_hashrate_tolerance = 1.5
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        let backend = parse_backend(
+            r#"
+            [hashrate_check]
+            expected_hashrate_tolerance = 0.9
+            period = 10
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_pll_lock_check() {
+        let backend = parse_backend(
+            r#"
+            [pll_lock_check]
+            action = "fallback"
+            fallback_step = 50.0
+        "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        // frequency falls back by the step but never below the minimal one
+        let pll_lock_check = backend.resolve_chain_config(6).unwrap().pll_lock_check;
+        assert_eq!(pll_lock_check.fallback(650_000_000), 600_000_000);
+        assert_eq!(
+            pll_lock_check.fallback(pll_lock_check.min_frequency + 10_000_000),
+            pll_lock_check.min_frequency
+        );
+
+        let backend = parse_backend(
+            r#"
+            [pll_lock_check]
+            fallback_step = 0.0
+        "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_pool_group() {
+        let backend = parse_backend(
+            r#"
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            url = 'stratum+tcp://pool.example.com:3333'
+            user = 'user.worker'
+
+            [[group]]
+            name = 'Split'
+
+            [[group.pool]]
+            url = 'stratum+tcp://split.example.com:3333'
+            user = 'user.split'
+
+            [hash_chain.6]
+            pool_group = 'Split'
+        "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        assert_eq!(
+            backend
+                .assigned_pool_groups()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["Split".to_string()]
+        );
+
+        // Referenced group has to exist
+        let backend = parse_backend(
+            r#"
+            [hash_chain.6]
+            pool_group = 'Missing'
+        "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        // Pool group cannot be assigned to all hash chains at once
+        let backend = parse_backend(
+            r#"
+            [[group]]
+            name = 'Split'
+
+            [[group.pool]]
+            url = 'stratum+tcp://split.example.com:3333'
+            user = 'user.split'
+
+            [hash_chain_global]
+            pool_group = 'Split'
+        "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_asic_difficulty() {
+        assert!(parse_backend("[hash_chain.6]\nasic_difficulty = 1")
+            .sanity_check()
+            .is_ok());
+        for asic_difficulty in &[0, 48, ASIC_DIFFICULTY_MAX * 2] {
+            let backend = parse_backend(&format!(
+                "[hash_chain.6]\nasic_difficulty = {}",
+                asic_difficulty
+            ));
+            assert!(backend.sanity_check().is_err());
+        }
+    }
+
+    #[test]
+    fn test_pool_group_failover() {
+        let backend = parse_backend(
+            r#"
+            [[group]]
+            name = 'Primary'
+            pool_strategy = 'balance'
+
+            [[group.pool]]
+            url = 'stratum+tcp://primary.example.com:3333'
+            user = 'user.worker'
+
+            [[group.pool]]
+            url = 'stratum+tcp://primary2.example.com:3333'
+            user = 'user.worker'
+
+            [[group]]
+            name = 'Backup'
+            priority = 1
+
+            [[group.pool]]
+            url = 'stratum+tcp://backup.example.com:3333'
+            user = 'user.worker'
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+
+        // At least one group has to have some pool
+        let config = r#"
+            [[group]]
+            name = 'Primary'
+
+            [[group]]
+            name = 'Backup'
+            "#;
+        assert!(parse_backend(config).sanity_check().is_err());
+        let backend = parse_backend(&format!("{}\n[miner]\non_no_pools = 'idle'\n", config));
+        assert!(backend.sanity_check().is_ok());
+    }
+
+    #[test]
+    fn test_pool_group_accept_rate() {
+        let parse = |settings: &str| {
+            parse_backend(&format!(
+                r#"
+                [[group]]
+                name = 'Primary'
+                {}
+
+                [[group.pool]]
+                url = 'stratum+tcp://primary.example.com:3333'
+                user = 'user.worker'
+                "#,
+                settings
+            ))
+        };
+        assert!(parse("min_accept_rate = 80.0\naccept_rate_window = 300")
+            .sanity_check()
+            .is_ok());
+        assert!(parse("min_accept_rate = 120.0").sanity_check().is_err());
+        assert!(parse("accept_rate_window = 10").sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_parse_pools() {
+        let pool = |url: &str| PoolConfig {
+            enabled: None,
+            url: Some(url.to_string()),
+            urls: None,
+            user: "user".to_string(),
+            password: None,
+            clean_jobs: None,
+            clean_jobs_delay: None,
+            keepalive_interval: None,
+            submit_batch_window: None,
+        };
+
+        let descriptors = Backend::parse_pools(&[
+            pool("stratum+tcp://primary.example.com:3333"),
+            pool("stratum+tcp://backup.example.com:3333"),
+        ])
+        .expect("BUG: valid pools rejected");
+        assert_eq!(descriptors.len(), 2);
+        assert_eq!(descriptors[1].host, "backup.example.com");
+
+        // One malformed pool rejects the whole list
+        assert!(Backend::parse_pools(&[
+            pool("stratum+tcp://primary.example.com:3333"),
+            pool("invalid://primary.example.com:3333"),
+        ])
+        .is_err());
+        assert!(Backend::parse_pools(&[]).is_err());
+    }
+
+    #[test]
+    fn test_suppress_warnings() {
+        let backend = parse_backend(
+            r#"
+            [miner]
+            suppress_warnings = ['unused_target_temp', 'adjusted', 'unused_temp']
+
+            [temp_control]
+            mode = "manual"
+            target_temp = 80.0
+
+            [hash_chain.6]
+            frequency_step = 10.0
+            "#,
+        );
+        backend.suppress_warnings();
+        backend.resolve_monitor_config();
+        backend.resolve_chain_config(6).unwrap();
+
+        // only the unknown name in the suppress list is reported
+        let warnings = backend.warnings.list();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].id, WarningId::UnknownSuppressedWarning);
+        assert_eq!(
+            warnings[0].message,
+            "Unknown warning 'unused_temp' in 'suppress_warnings'"
+        );
+
+        // already collected warnings are forgotten once suppressed
+        let warnings = Warnings::default();
+        warnings.push(WarningId::UnusedThrottle, "throttle".to_string());
+        warnings.push(WarningId::SkippedPool, "pool".to_string());
+        assert!(warnings.suppress(&["skipped".to_string()]).is_empty());
+        assert_eq!(warnings.list().len(), 1);
+        assert_eq!(warnings.list()[0].id, WarningId::UnusedThrottle);
+    }
+
+    #[test]
+    fn test_asic_boost_overrides() {
+        let backend = parse_backend(
+            r#"
+            [hash_chain_global]
+            asic_boost = false
+
+            [hash_chain.6]
+            asic_boost = false
+
+            [hash_chain.7]
+            asic_boost = true
+            "#,
+        );
+        let warnings = Warnings::default();
+        assert!(backend
+            .check_overrides(ParseMode::Strict, &warnings)
+            .unwrap_err()
+            .starts_with("Chain 7: 'asic_boost' (true) contradicts"));
+
+        assert!(backend
+            .check_overrides(ParseMode::Lenient, &warnings)
+            .is_ok());
+        let warnings = warnings.list();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].id, WarningId::ConflictingAsicBoost);
+
+        // effective midstate count is the same for all hash chains
+        assert_eq!(
+            backend
+                .resolve_chain_config(6)
+                .unwrap()
+                .midstate_count
+                .to_count(),
+            1
+        );
+        assert_eq!(
+            backend
+                .resolve_chain_config(7)
+                .unwrap()
+                .midstate_count
+                .to_count(),
+            1
+        );
+
+        // overrides agreeing with global setting are fine
+        let backend = parse_backend(
+            r#"
+            [hash_chain.6]
+            asic_boost = true
+            "#,
+        );
+        assert!(backend
+            .check_overrides(ParseMode::Strict, &Warnings::default())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_thermal_recovery() {
+        let backend = parse_backend(
+            r#"
+            [thermal_recovery]
+            max_attempts = 0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_remote_pools() {
+        let mut backend = parse_backend(
+            r#"
+            [miner]
+            pools_url = 'https://provisioning.example.com/pools.toml'
+
+            [[group]]
+            name = 'Local'
+
+            [[group.pool]]
+            url = 'stratum+tcp://stratum.slushpool.com:3333'
+            user = 'local.worker'
+            "#,
+        );
+        // fetched groups are validated the same way as local ones
+        let groups = remote_pools::parse(
+            r#"
+            [[group]]
+            name = 'Fleet'
+
+            [[group.pool]]
+            url = 'invalid'
+            user = 'fleet.worker'
+            "#,
+        )
+        .expect("BUG: cannot parse pool section");
+        assert!(backend.replace_groups(groups).is_err());
+        assert_eq!(backend.groups.as_ref().unwrap()[0].descriptor.name, "Local");
+
+        let groups = remote_pools::parse(
+            r#"
+            [[group]]
+            name = 'Fleet'
+
+            [[group.pool]]
+            url = 'stratum+tcp://stratum.slushpool.com:3333'
+            user = 'fleet.worker'
+            "#,
+        )
+        .expect("BUG: cannot parse pool section");
+        assert!(backend.replace_groups(groups).is_ok());
+        assert_eq!(backend.groups.as_ref().unwrap()[0].descriptor.name, "Fleet");
+
+        let backend = parse_backend(
+            r#"
+            [miner]
+            pools_url = 'http://provisioning.example.com/pools.toml'
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_work_queue_depth() {
+        assert!(parse_backend("[hash_chain.6]\nwork_queue_depth = 2")
+            .sanity_check()
+            .is_ok());
+        for work_queue_depth in &[0, WORK_QUEUE_DEPTH_MAX + 1] {
+            let backend = parse_backend(&format!(
+                "[hash_chain.6]\nwork_queue_depth = {}",
+                work_queue_depth
+            ));
+            assert!(backend.sanity_check().is_err());
+        }
+    }
+
+    #[test]
+    fn test_safe_mode() {
+        let config = FormatWrapper::<Backend>::safe_mode("/etc/bosminer.toml", "missing");
+        assert!(config.body.sanity_check().is_ok());
+        assert!(!config.body.has_pools());
+        assert_eq!(config.body.no_pools_action(), NoPoolsAction::Idle);
+        // temperature control is left in default (automatic) mode
+        assert!(config.body.temp_control.is_none());
+        let chain_config = config.body.resolve_chain_config(6).unwrap();
+        assert_eq!(
+            chain_config.frequency.max(),
+            (SAFE_MODE_FREQUENCY_MHZ * 1_000_000.0) as usize
+        );
+
+        let warnings = config.warnings.list();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].id, WarningId::SafeMode);
+    }
+
+    #[test]
+    fn test_alert_temp() {
+        let backend = parse_backend(
+            r#"
+            [temp_control]
+            temp_unit = "f"
+
+            [hash_chain_global]
+            alert_temp = 185.0
+
+            [hash_chain.6]
+            alert_temp = 176.0
+        "#,
+        );
+        // alert temperatures are converted to Celsius
+        assert_eq!(
+            backend.resolve_chain_config(6).unwrap().alert_temp,
+            Some(80.0)
+        );
+        assert_eq!(
+            backend.resolve_chain_config(7).unwrap().alert_temp,
+            Some(85.0)
+        );
+    }
+
+    #[test]
+    fn test_fan_speed_range() {
+        let backend = |speed: usize| {
+            parse_backend(&format!(
+                "[temp_control]\nmode = 'manual'\n\n[fan_control]\nspeed = {}",
+                speed
+            ))
+        };
+        assert!(backend(FAN_SPEED_MIN).sanity_check().is_ok());
+        assert!(backend(FAN_SPEED_MAX).sanity_check().is_ok());
+        assert_eq!(
+            backend(250).sanity_check(),
+            Err("fan speed '250' is out of range '0..100' (percent)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_idle_fan_speed() {
+        assert_eq!(
+            parse_backend("[fan_control]\nidle_speed = 101").sanity_check(),
+            Err("idle fan speed '101' is out of range '0..100' (percent)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_board_model() {
+        let backend = parse_backend("");
+        assert_eq!(
+            backend.board_defaults(),
+            board_defaults(DEFAULT_BOARD_MODEL).expect("BUG: missing default board model")
+        );
+        let chain_config = backend
+            .resolve_chain_config(S9_HASHBOARD_INDEX)
+            .expect("BUG: cannot resolve chain config");
+        assert_eq!(chain_config.frequency.chip[0], 650_000_000);
+        assert_eq!(
+            chain_config.voltage,
+            power::Voltage::from_volts(DEFAULT_VOLTAGE_V as f32).expect("BUG: invalid voltage")
+        );
+
+        let backend = parse_backend(
+            r#"
+            [miner]
+            model = "s9j"
+
+            [hash_chain.7]
+            frequency = 600.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        let s9j = board_defaults("s9j").expect("BUG: missing S9j defaults");
+        let chain_config = backend
+            .resolve_chain_config(S9_HASHBOARD_INDEX)
+            .expect("BUG: cannot resolve chain config");
+        assert_eq!(
+            chain_config.frequency.chip[0],
+            (s9j.frequency * 1_000_000.0) as usize
+        );
+        assert_eq!(
+            chain_config.voltage,
+            power::Voltage::from_volts(s9j.voltage as f32).expect("BUG: invalid voltage")
+        );
+        // explicit settings take precedence over model defaults
+        let chain_config = backend
+            .resolve_chain_config(7)
+            .expect("BUG: cannot resolve chain config");
+        assert_eq!(chain_config.frequency.chip[0], 600_000_000);
+
+        let backend = parse_backend(
+            r#"
+            [miner]
+            model = "s19"
+            "#,
+        );
+        let error = backend
+            .sanity_check()
+            .expect_err("unknown board model not detected");
+        assert!(error.contains("s19") && error.contains("s9j"));
+    }
+
+    #[test]
+    fn test_init_frequency() {
+        let backend = parse_backend(
+            r#"
+            [hash_chain_global]
+            frequency = 650.0
+            init_frequency = 500.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+
+        let backend = parse_backend(
+            r#"
+            [hash_chain_global]
+            init_frequency = 100.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+
+        // initialization frequency exceeds per-chain frequency
+        let backend = parse_backend(
+            r#"
+            [hash_chain_global]
+            init_frequency = 600.0
+
+            [hash_chain.8]
+            frequency = 550.0
+            "#,
+        );
+        assert!(backend.sanity_check().is_err());
+    }
+
+    #[test]
+    fn test_power_on_delay() {
+        let power_on_delay = |backend: &Backend, idx| {
+            backend
+                .resolve_chain_config(idx)
+                .expect("BUG: cannot resolve chain config")
+                .power_on_delay
+        };
+        // power-on of hash chains is staggered by interval unless overridden
+        let backend = parse_backend(
+            r#"
+            [miner]
+            power_on_interval = 500
+
+            [hash_chain.8]
+            power_on_delay = 3000
+            "#,
+        );
+        assert!(backend.sanity_check().is_ok());
+        assert_eq!(power_on_delay(&backend, 6), Duration::from_secs(0));
+        assert_eq!(power_on_delay(&backend, 7), Duration::from_millis(500));
+        assert_eq!(power_on_delay(&backend, 8), Duration::from_millis(3000));
+
+        // power-on delay is tied to particular hashboard
+        let backend = parse_backend("[hash_chain_global]\npower_on_delay = 1000");
+        assert!(backend.sanity_check().is_err());
+
+        let backend = parse_backend("[hash_chain.6]\npower_on_delay = 100000");
+        assert_eq!(
+            backend.sanity_check(),
+            Err("power-on delay '100000' is out of range '0..60000' (ms)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_frozen() {
+        // frozen hash chain is not trimmed to fit system power limit
+        let backend = parse_backend(
+            r#"
+            [miner]
+            system_power_limit = 800.0
+
+            [hash_chain.6]
+            frozen = true
+            "#,
+        );
+        let mut chain_configs: Vec<_> = [6, 7]
+            .iter()
+            .map(|&idx| (idx, backend.resolve_chain_config(idx).unwrap()))
+            .collect();
+        backend
+            .apply_power_limit(&mut chain_configs)
+            .expect("BUG: cannot fit power limit");
+        assert_eq!(chain_configs[0].1.frequency.max(), 650_000_000);
+        assert!(chain_configs[1].1.frequency.max() < 650_000_000);
+    }
+}
@@ -0,0 +1,265 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Analysis of configuration without running the miner (e.g. by provisioning tools). Problems
+//! found while parsing, validating and resolving the configuration are collected into structured
+//! report instead of being logged.
+
+use super::*;
+
+/// Stage of configuration analysis at which an error has been found
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum AnalysisStage {
+    /// Configuration is not valid TOML or it doesn't match structure of configuration
+    Parse,
+    /// Configuration values are invalid or they contradict each other
+    Validation,
+    /// Configuration cannot be resolved to settings of hash chains
+    Resolution,
+}
+
+impl std::string::ToString for AnalysisStage {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Parse => "parse".to_string(),
+            Self::Validation => "validation".to_string(),
+            Self::Resolution => "resolution".to_string(),
+        }
+    }
+}
+
+/// Problem in configuration that prevents the miner from using it
+#[derive(Clone, PartialEq, Debug)]
+pub struct AnalysisError {
+    pub stage: AnalysisStage,
+    pub message: String,
+    /// Path to configuration field the error refers to (e.g. `group[0].pool[1].url`) when known
+    pub path: Option<String>,
+}
+
+impl AnalysisError {
+    fn new(stage: AnalysisStage, message: String, path: Option<String>) -> Self {
+        Self {
+            stage,
+            message,
+            path,
+        }
+    }
+}
+
+/// Structured report of configuration analysis
+#[derive(Debug)]
+pub struct AnalysisResult {
+    /// Parsed configuration (missing when it cannot be parsed at all)
+    pub backend: Option<Backend>,
+    /// Warnings which would be reported by the miner (suppressed warnings are left out)
+    pub warnings: Vec<Warning>,
+    pub errors: Vec<AnalysisError>,
+}
+
+impl AnalysisResult {
+    /// Configuration can be used by the miner (there may still be some warnings)
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Backend {
+    /// Parse, validate and resolve content of configuration file the same way as the miner does
+    /// at startup, but without logging anything, fetching remote pools or touching hardware.
+    /// Configuration is resolved only when it passes validation.
+    pub fn analyze(contents: &str) -> AnalysisResult {
+        let wrapper = match Self::analyze_parse(contents) {
+            Ok(wrapper) => wrapper,
+            Err(error) => {
+                return AnalysisResult {
+                    backend: None,
+                    warnings: vec![],
+                    errors: vec![error],
+                }
+            }
+        };
+        let FormatWrapper {
+            format, mut body, ..
+        } = wrapper;
+        let warnings = Warnings::quiet();
+        body.warnings = warnings.clone();
+
+        let mut errors = Self::analyze_validation(&format, &body);
+        if errors.is_empty() {
+            body.suppress_warnings();
+            body.prune_invalid_pools();
+            errors = body.analyze_resolution();
+        }
+
+        AnalysisResult {
+            backend: Some(body),
+            warnings: warnings.list(),
+            errors,
+        }
+    }
+
+    fn analyze_parse(contents: &str) -> Result<FormatWrapper<Self>, AnalysisError> {
+        let mut value: serde_json::Value = toml::from_str(contents)
+            .map_err(|e| AnalysisError::new(AnalysisStage::Parse, e.to_string(), None))?;
+        serde_json::from_value(value.clone()).map_err(|e| {
+            let message = e.to_string();
+            // Unknown field is removed only to find out its path
+            let path = support::remove_unknown_field(&mut value, message.as_str());
+            AnalysisError::new(AnalysisStage::Parse, message, path)
+        })
+    }
+
+    fn analyze_validation(format: &Format, body: &Self) -> Vec<AnalysisError> {
+        let mut errors = vec![];
+        let mut error = |message: String, path: Option<&str>| {
+            errors.push(AnalysisError::new(
+                AnalysisStage::Validation,
+                message,
+                path.map(|path| path.to_string()),
+            ))
+        };
+        if format.model != Self::model() {
+            error(
+                format!("incompatible format model '{}'", format.model),
+                Some("format.model"),
+            );
+        }
+        if !Self::version_is_supported(&format.version) {
+            error(
+                format!("incompatible format version '{}'", format.version),
+                Some("format.version"),
+            );
+        }
+        if let Err(message) = body.check_overrides(ParseMode::Strict, &body.warnings) {
+            error(message, None);
+        }
+        if let Err(message) = body.sanity_check() {
+            error(message, None);
+        }
+        errors
+    }
+
+    fn analyze_resolution(&self) -> Vec<AnalysisError> {
+        let mut errors = vec![];
+        let mut chain_configs = vec![];
+        for hashboard_idx in HASH_CHAIN_INDEX_MIN..=HASH_CHAIN_INDEX_MAX {
+            match self.resolve_chain_config(hashboard_idx) {
+                Ok(chain_config) => chain_configs.push((hashboard_idx, chain_config)),
+                Err(e) => errors.push(AnalysisError::new(
+                    AnalysisStage::Resolution,
+                    e.to_string(),
+                    None,
+                )),
+            }
+        }
+        if let Err(e) = self.apply_power_limit(&mut chain_configs) {
+            errors.push(AnalysisError::new(
+                AnalysisStage::Resolution,
+                e.to_string(),
+                Some("miner.system_power_limit".to_string()),
+            ));
+        }
+        // The remaining settings cannot fail, they are resolved only to collect their warnings
+        self.resolve_monitor_config();
+        self.resolve_hashrate_check();
+        self.resolve_thermal_recovery();
+        errors
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn contents(body: &str) -> String {
+        format!(
+            "[format]\nversion = '{}'\nmodel = '{}'\n\n{}",
+            FORMAT_VERSION, FORMAT_MODEL, body
+        )
+    }
+
+    #[test]
+    fn test_analyze() {
+        let result = Backend::analyze(&contents(
+            r#"
+            [[group]]
+            name = 'Default'
+
+            [[group.pool]]
+            url = 'stratum+tcp://stratum.slushpool.com:3333'
+            user = 'user.worker'
+            "#,
+        ));
+        assert!(result.is_valid());
+        assert!(result.backend.is_some());
+        assert!(result.warnings.is_empty());
+
+        // parse error refers to unknown field
+        let result = Backend::analyze(&contents("[fan_control]\nspeeed = 50"));
+        assert!(!result.is_valid());
+        assert!(result.backend.is_none());
+        assert_eq!(result.errors[0].stage, AnalysisStage::Parse);
+        assert_eq!(result.errors[0].path.as_deref(), Some("fan_control.speeed"));
+        assert_eq!(
+            Backend::analyze("[format").errors[0].stage,
+            AnalysisStage::Parse
+        );
+
+        // invalid values are reported together with incompatible format
+        let result = Backend::analyze(&format!(
+            "[format]\nversion = '0.0'\nmodel = '{}'\n\n[fan_control]\nspeed = 250",
+            FORMAT_MODEL
+        ));
+        assert!(result.backend.is_some());
+        assert_eq!(result.errors.len(), 2);
+        assert!(result
+            .errors
+            .iter()
+            .all(|error| error.stage == AnalysisStage::Validation));
+        assert_eq!(result.errors[0].path.as_deref(), Some("format.version"));
+
+        // warnings refer to configuration fields (suppressed warnings are left out)
+        let result = Backend::analyze(&contents(
+            r#"
+            [temp_control]
+            mode = 'auto'
+
+            [fan_control]
+            speed = 50
+
+            [hash_chain.8]
+            chip_frequency_offsets = { '0' = 1000.0 }
+
+            [miner]
+            suppress_warnings = ['chip_frequency_clamped']
+            "#,
+        ));
+        assert!(result.is_valid());
+        let paths: Vec<_> = result
+            .warnings
+            .iter()
+            .map(|warning| warning.path.as_deref())
+            .collect();
+        assert_eq!(paths, vec![Some("fan_control.speed")]);
+    }
+}
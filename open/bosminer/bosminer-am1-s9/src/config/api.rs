@@ -207,7 +207,7 @@ impl<'a> Handler<'a> {
     }
 
     pub fn handle_data<B: ConfigBody>(self) {
-        let response = match FormatWrapper::<B>::parse(self.config_path) {
+        let response = match FormatWrapper::<B>::parse(self.config_path, Default::default()) {
             // TODO: Improve error handling
             Ok(config)
             | Err(crate::config::FormatWrapperError::IncompatibleVersion(_, Some(config))) => {
@@ -251,14 +251,20 @@ impl<'a> Handler<'a> {
         let config_path = Path::new(self.config_path);
         let config_tmp_path = config_path.with_extension(Self::CONFIG_TMP_EXTENSION);
 
+        let mut content = toml::to_string_pretty(&config).expect("TODO: toml::to_string_pretty");
+        // Keep comments and ordering of fields of hand-edited configuration file when it can be
+        // parsed (the response is written to stdout so the failure cannot be logged)
+        if let Some(updated) = fs::read_to_string(config_path)
+            .ok()
+            .and_then(|original| document::update(&original, &content).ok())
+        {
+            content = updated;
+        }
+
         let mut file = FileGuard::create(&config_tmp_path).expect("TODO: File::create");
 
-        file.write_all(
-            toml::to_string_pretty(&config)
-                .expect("TODO: toml::to_string_pretty")
-                .as_bytes(),
-        )
-        .expect("TODO: file.write_all");
+        file.write_all(content.as_bytes())
+            .expect("TODO: file.write_all");
 
         file.persist(config_path).expect("TODO: file.persist");
 
@@ -0,0 +1,149 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Format preserving update of configuration file. Freshly serialized configuration is merged
+//! into the original document so that comments, formatting and ordering of fields which are
+//! still present in the new configuration are kept.
+
+use toml_edit::{Document, Item, Table, Value};
+
+/// Merge `content` (freshly serialized configuration) into `original` content of configuration
+/// file and return the resulting document
+pub fn update(original: &str, content: &str) -> Result<String, String> {
+    let mut document = original
+        .parse::<Document>()
+        .map_err(|e| format!("cannot parse original configuration: {}", e))?;
+    let updated = content
+        .parse::<Document>()
+        .map_err(|e| format!("cannot parse new configuration: {}", e))?;
+    merge_table(document.as_table_mut(), updated.as_table());
+    Ok(document.to_string())
+}
+
+fn merge_table(target: &mut Table, source: &Table) {
+    let removed: Vec<_> = target
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !source.contains_key(key))
+        .collect();
+    for key in removed {
+        target.remove(&key);
+    }
+    for (key, item) in source.iter() {
+        merge_item(target.entry(key), item);
+    }
+}
+
+fn merge_item(target: &mut Item, source: &Item) {
+    match (target, source) {
+        (Item::Table(target), Item::Table(source)) => merge_table(target, source),
+        (Item::ArrayOfTables(target), Item::ArrayOfTables(source)) => {
+            while target.len() > source.len() {
+                target.remove(target.len() - 1);
+            }
+            for (i, table) in source.iter().enumerate() {
+                match target.get_mut(i) {
+                    Some(target) => merge_table(target, table),
+                    None => {
+                        target.append(table.clone());
+                    }
+                }
+            }
+        }
+        (Item::Value(target), Item::Value(source)) => {
+            // Keep original value (with its formatting) when it has not been changed
+            if !same_value(target, source) {
+                let decor = target.decor();
+                *target = toml_edit::decorated(source.clone(), decor.prefix(), decor.suffix());
+            }
+        }
+        (target, source) => *target = source.clone(),
+    }
+}
+
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        _ => bare_value(a) == bare_value(b),
+    }
+}
+
+fn bare_value(value: &Value) -> String {
+    toml_edit::decorated(value.clone(), "", "").to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let original = r#"# Hand-maintained configuration of the miner
+
+[format]
+version = '1.0'   # do not touch
+model = 'Antminer S9'
+
+# Pools of the fleet
+[[group]]
+name = 'Fleet'
+
+[[group.pool]]
+url = 'stratum+tcp://stratum.slushpool.com:3333'
+user = 'fleet.worker'
+
+[hash_chain_global]
+frequency = 650.0 # stable
+voltage = 8.8
+"#;
+        let content = r#"[format]
+version = "1.0"
+model = "Antminer S9"
+generator = "bosminer"
+
+[[group]]
+name = "Fleet"
+
+[[group.pool]]
+url = "stratum+tcp://stratum.slushpool.com:3333"
+user = "fleet.worker2"
+
+[hash_chain_global]
+frequency = 600.0
+"#;
+        let updated = update(original, content).expect("BUG: cannot update configuration");
+        assert!(updated.starts_with("# Hand-maintained configuration of the miner\n"));
+        assert!(updated.contains("version = '1.0'   # do not touch"));
+        assert!(updated.contains("generator = \"bosminer\""));
+        assert!(updated.contains("# Pools of the fleet"));
+        assert!(updated.contains("user = \"fleet.worker2\""));
+        assert!(updated.contains("frequency = 600.0 # stable"));
+        assert!(!updated.contains("voltage"));
+        // the original order of sections is kept
+        assert!(updated.find("[[group]]") < updated.find("[hash_chain_global]"));
+
+        assert!(update("[format", content).is_err());
+    }
+}
@@ -24,7 +24,10 @@
 
 use super::*;
 
-use bosminer_config::CLIENT_URL_JAVA_SCRIPT_REGEX;
+use bosminer_config::{
+    ClientCleanJobs, ClientDescriptor, GroupDescriptor, GroupPoolStrategy,
+    CLIENT_URL_JAVA_SCRIPT_REGEX,
+};
 
 const DESCRIPTION_CAUTION_OVERCLOCKING: &'static str =
     "Caution: Overclocking may damage your device. Proceed at your own risk!";
@@ -37,6 +40,25 @@ const DESCRIPTION_NUMBER_OF_FANS: &'static str =
 use serde_json::{self, json};
 
 pub fn for_backend() -> serde_json::Value {
+    let psu_models: Vec<_> = power::PSU_MODELS
+        .iter()
+        .map(|(name, _)| json!({ "key": name, "label": name }))
+        .collect();
+    let board_models: Vec<_> = BOARD_MODELS
+        .iter()
+        .map(|(name, _)| json!({ "key": name, "label": name }))
+        .collect();
+    let min_keepalive_interval = ClientDescriptor::MIN_KEEPALIVE_INTERVAL.as_secs();
+    let max_submit_batch_window = ClientDescriptor::MAX_SUBMIT_BATCH_WINDOW.as_millis() as u64;
+    let min_accept_rate_window = GroupDescriptor::MIN_ACCEPT_RATE_WINDOW.as_secs();
+    let default_accept_rate_window = GroupDescriptor::DEFAULT_ACCEPT_RATE_WINDOW.as_secs();
+    let suppressible_warnings: Vec<_> = WarningKind::ALL
+        .iter()
+        .map(|kind| kind.to_string())
+        .chain(WarningId::ALL.iter().map(|id| id.to_string()))
+        .map(|name| json!({ "key": name, "label": name }))
+        .collect();
+
     json!([
         [
             "format",
@@ -124,6 +146,60 @@ pub fn for_backend() -> serde_json::Value {
                                 "span": 3
                             }
                         ],
+                        [
+                            "priority",
+                            {
+                                "type": "number",
+                                "label": "Priority",
+                                "min": 0,
+                                "default": GroupDescriptor::DEFAULT_PRIORITY,
+                                "span": 6
+                            }
+                        ],
+                        [
+                            "pool_strategy",
+                            {
+                                "type": "enum",
+                                "label": "Pool Strategy",
+                                "values": [
+                                    {
+                                        "key": GroupPoolStrategy::Failover.to_string(),
+                                        "label": "Failover"
+                                    },
+                                    {
+                                        "key": GroupPoolStrategy::Balance.to_string(),
+                                        "label": "Balance"
+                                    }
+                                ],
+                                "default": GroupDescriptor::DEFAULT_POOL_STRATEGY.to_string(),
+                                "span": 6
+                            }
+                        ],
+                        [
+                            "min_accept_rate",
+                            {
+                                "type": "number",
+                                "label": "Minimal Accept Rate",
+                                "unit": "%",
+                                "min": 0.0,
+                                "max": 100.0,
+                                "step": 0.1,
+                                "float": true,
+                                "default": null,
+                                "span": 6
+                            }
+                        ],
+                        [
+                            "accept_rate_window",
+                            {
+                                "type": "number",
+                                "label": "Accept Rate Window",
+                                "unit": "s",
+                                "min": min_accept_rate_window,
+                                "default": default_accept_rate_window,
+                                "span": 6
+                            }
+                        ],
                         [
                             "pool",
                             {
@@ -154,6 +230,20 @@ pub fn for_backend() -> serde_json::Value {
                                                 "span": 11
                                             }
                                         ],
+                                        [
+                                            "urls",
+                                            {
+                                                "type": "array",
+                                                "label": "Backup Pool URLs",
+                                                "add_label": "Add Backup URL",
+                                                "optional": true,
+                                                "item": {
+                                                    "type": "url",
+                                                    "min_length": 1,
+                                                    "match": CLIENT_URL_JAVA_SCRIPT_REGEX
+                                                }
+                                            }
+                                        ],
                                         [
                                             "user",
                                             {
@@ -171,6 +261,64 @@ pub fn for_backend() -> serde_json::Value {
                                                 "default": null,
                                                 "span": 5
                                             }
+                                        ],
+                                        [
+                                            "clean_jobs",
+                                            {
+                                                "type": "enum",
+                                                "label": "Clean Jobs Handling",
+                                                "values": [
+                                                    {
+                                                        "key": "honor",
+                                                        "label": "Honor"
+                                                    },
+                                                    {
+                                                        "key": "ignore",
+                                                        "label": "Ignore"
+                                                    },
+                                                    {
+                                                        "key": "delay",
+                                                        "label": "Delay"
+                                                    }
+                                                ],
+                                                "default": "honor",
+                                                "span": 6
+                                            }
+                                        ],
+                                        [
+                                            "clean_jobs_delay",
+                                            {
+                                                "type": "number",
+                                                "label": "Clean Jobs Delay",
+                                                "unit": "s",
+                                                "min": 0,
+                                                "max": ClientCleanJobs::MAX_DELAY.as_secs(),
+                                                "default": ClientCleanJobs::DEFAULT_DELAY.as_secs(),
+                                                "span": 6
+                                            }
+                                        ],
+                                        [
+                                            "keepalive_interval",
+                                            {
+                                                "type": "number",
+                                                "label": "Keepalive Interval",
+                                                "unit": "s",
+                                                "min": min_keepalive_interval,
+                                                "default": null,
+                                                "span": 6
+                                            }
+                                        ],
+                                        [
+                                            "submit_batch_window",
+                                            {
+                                                "type": "number",
+                                                "label": "Submit Batch Window",
+                                                "unit": "ms",
+                                                "min": 0,
+                                                "max": max_submit_batch_window,
+                                                "default": 0,
+                                                "span": 6
+                                            }
                                         ]
                                     ]
                                 }
@@ -192,7 +340,8 @@ pub fn for_backend() -> serde_json::Value {
                         {
                             "type": "bool",
                             "label": "AsicBoost",
-                            "default": DEFAULT_ASIC_BOOST
+                            "default": DEFAULT_ASIC_BOOST,
+                            "midstate_counts": bm1387::SUPPORTED_MIDSTATE_COUNTS
                         }
                     ],
                     [
@@ -207,6 +356,17 @@ pub fn for_backend() -> serde_json::Value {
                             "default": DEFAULT_FREQUENCY_MHZ
                         }
                     ],
+                    [
+                        "init_frequency",
+                        {
+                            "type": "number",
+                            "label": "Initialization Frequency",
+                            "unit": "MHz",
+                            "min": FREQUENCY_MHZ_MIN,
+                            "max": FREQUENCY_MHZ_MAX,
+                            "float": true
+                        }
+                    ],
                     [
                         "voltage",
                         {
@@ -218,6 +378,56 @@ pub fn for_backend() -> serde_json::Value {
                             "float": true,
                             "default": DEFAULT_VOLTAGE_V
                         }
+                    ],
+                    [
+                        "frequency_step",
+                        {
+                            "type": "number",
+                            "label": "Frequency Step",
+                            "unit": "MHz",
+                            "min": FREQUENCY_STEP_GRANULARITY_MHZ,
+                            "float": true,
+                            "default": DEFAULT_FREQUENCY_STEP_MHZ
+                        }
+                    ],
+                    [
+                        "asic_difficulty",
+                        {
+                            "type": "number",
+                            "label": "ASIC Difficulty",
+                            "min": ASIC_DIFFICULTY_MIN,
+                            "max": ASIC_DIFFICULTY_MAX,
+                            "default": DEFAULT_ASIC_DIFFICULTY
+                        }
+                    ],
+                    [
+                        "work_queue_depth",
+                        {
+                            "type": "number",
+                            "label": "Work Queue Depth",
+                            "min": WORK_QUEUE_DEPTH_MIN,
+                            "max": WORK_QUEUE_DEPTH_MAX
+                        }
+                    ],
+                    [
+                        "alert_temp",
+                        {
+                            "type": "number",
+                            "label": "Alert Temperature",
+                            "unit": "°C",
+                            "min": TEMPERATURE_C_MIN,
+                            "max": TEMPERATURE_C_MAX,
+                            "step": 0.1,
+                            "float": true
+                        }
+                    ],
+                    [
+                        "frozen",
+                        {
+                            "type": "bool",
+                            "label": "Frozen (No Automatic Tuning)",
+                            "default": DEFAULT_FROZEN
+                        }
                     ]
                 ]
             }
@@ -253,7 +463,31 @@ pub fn for_backend() -> serde_json::Value {
                                 "max": FREQUENCY_MHZ_MAX,
                                 "float": true,
                                 "default": ["$get", "hash_chain_global", "frequency"],
-                                "span": 6
+                                "span": 4
+                            }
+                        ],
+                        [
+                            "init_frequency",
+                            {
+                                "type": "number",
+                                "label": "Initialization Frequency",
+                                "unit": "MHz",
+                                "min": FREQUENCY_MHZ_MIN,
+                                "max": FREQUENCY_MHZ_MAX,
+                                "float": true,
+                                "default": ["$get", "hash_chain_global", "init_frequency"]
+                            }
+                        ],
+                        [
+                            "power_on_delay",
+                            {
+                                "type": "number",
+                                "label": "Power-on Delay",
+                                "unit": "ms",
+                                "min": 0,
+                                "max": POWER_ON_DELAY_MS_MAX,
+                                "step": 1,
+                                "default": null
                             }
                         ],
                         [
@@ -266,7 +500,95 @@ pub fn for_backend() -> serde_json::Value {
                                 "max": VOLTAGE_V_MAX,
                                 "float": true,
                                 "default": ["$get", "hash_chain_global", "voltage"],
-                                "span": 5
+                                "span": 4
+                            }
+                        ],
+                        [
+                            "frequency_step",
+                            {
+                                "type": "number",
+                                "label": "Frequency Step",
+                                "unit": "MHz",
+                                "min": FREQUENCY_STEP_GRANULARITY_MHZ,
+                                "float": true,
+                                "default": ["$get", "hash_chain_global", "frequency_step"],
+                                "span": 3
+                            }
+                        ],
+                        [
+                            "asic_difficulty",
+                            {
+                                "type": "number",
+                                "label": "ASIC Difficulty",
+                                "min": ASIC_DIFFICULTY_MIN,
+                                "max": ASIC_DIFFICULTY_MAX,
+                                "default": ["$get", "hash_chain_global", "asic_difficulty"]
+                            }
+                        ],
+                        [
+                            "work_queue_depth",
+                            {
+                                "type": "number",
+                                "label": "Work Queue Depth",
+                                "min": WORK_QUEUE_DEPTH_MIN,
+                                "max": WORK_QUEUE_DEPTH_MAX,
+                                "default": ["$get", "hash_chain_global", "work_queue_depth"]
+                            }
+                        ],
+                        [
+                            "alert_temp",
+                            {
+                                "type": "number",
+                                "label": "Alert Temperature",
+                                "unit": "°C",
+                                "min": TEMPERATURE_C_MIN,
+                                "max": TEMPERATURE_C_MAX,
+                                "step": 0.1,
+                                "float": true,
+                                "default": ["$get", "hash_chain_global", "alert_temp"]
+                            }
+                        ],
+                        [
+                            "frozen",
+                            {
+                                "type": "bool",
+                                "label": "Frozen (No Automatic Tuning)",
+                                "default": ["$get", "hash_chain_global", "frozen"]
+                            }
+                        ],
+                        [
+                            "asic_boost",
+                            {
+                                "type": "bool",
+                                "label": "AsicBoost",
+                                "default": ["$get", "hash_chain_global", "asic_boost"]
+                            }
+                        ],
+                        [
+                            "chip_frequency_offsets",
+                            {
+                                "type": "dict",
+                                "label": "Chip Frequency Offsets",
+                                "description": DESCRIPTION_CAUTION_OVERCLOCKING,
+                                "key": {
+                                    "min": 0,
+                                    "max": crate::EXPECTED_CHIPS_ON_CHAIN - 1
+                                },
+                                "value": {
+                                    "type": "number",
+                                    "unit": "MHz",
+                                    "min": FREQUENCY_MHZ_MIN - FREQUENCY_MHZ_MAX,
+                                    "max": FREQUENCY_MHZ_MAX - FREQUENCY_MHZ_MIN,
+                                    "float": true
+                                }
+                            }
+                        ],
+                        [
+                            "pool_group",
+                            {
+                                "type": "string",
+                                "label": "Pool Group",
+                                "default": null
                             }
                         ]
                     ]
@@ -347,6 +669,44 @@ pub fn for_backend() -> serde_json::Value {
                             "disabled": ["$eq", ["$get", "temp_control", "mode"], "disabled"],
                             "span": 4
                         }
+                    ],
+                    [
+                        "hot_temp_override",
+                        {
+                            "type": "bool",
+                            "label": "Full Fan Speed Above Hot Temperature",
+                            "default": DEFAULT_HOT_TEMP_OVERRIDE,
+                            "disabled": ["$neq", ["$get", "temp_control", "mode"], "manual"]
+                        }
+                    ],
+                    [
+                        "peak_window",
+                        {
+                            "type": "number",
+                            "label": "Peak Temperature Window",
+                            "unit": "s",
+                            "min": 1,
+                            "step": 1,
+                            "default": null
+                        }
+                    ],
+                    [
+                        "temp_unit",
+                        {
+                            "type": "enum",
+                            "label": "Temperature Unit",
+                            "values": [
+                                {
+                                    "key": TempUnit::Celsius.to_string(),
+                                    "label": "Celsius"
+                                },
+                                {
+                                    "key": TempUnit::Fahrenheit.to_string(),
+                                    "label": "Fahrenheit"
+                                }
+                            ],
+                            "default": DEFAULT_TEMP_UNIT.to_string()
+                        }
                     ]
                 ]
             }
@@ -381,6 +741,862 @@ pub fn for_backend() -> serde_json::Value {
                             "step": 1,
                             "default": DEFAULT_MIN_FANS
                         }
+                    ],
+                    [
+                        "pwm_frequency",
+                        {
+                            "type": "number",
+                            "label": "PWM Frequency",
+                            "unit": "Hz",
+                            "min": 1,
+                            "step": 1,
+                            "default": null
+                        }
+                    ],
+                    [
+                        "on_fan_failure",
+                        {
+                            "type": "enum",
+                            "label": "Action on Fan Failure",
+                            "values": [
+                                {
+                                    "key": FanFailureAction::Shutdown.to_string(),
+                                    "label": "Shutdown"
+                                },
+                                {
+                                    "key": FanFailureAction::Throttle.to_string(),
+                                    "label": "Throttle to Minimum Frequency",
+                                    "alert": DESCRIPTION_CAUTION_CHANGING_DEFAULT
+                                },
+                                {
+                                    "key": FanFailureAction::Warn.to_string(),
+                                    "label": "Warn Only",
+                                    "alert": DESCRIPTION_CAUTION_CHANGING_DEFAULT
+                                }
+                            ],
+                            "default": DEFAULT_ON_FAN_FAILURE.to_string()
+                        }
+                    ],
+                    [
+                        "idle_speed",
+                        {
+                            "type": "number",
+                            "label": "Idle Speed",
+                            "unit": "%",
+                            "min": FAN_SPEED_MIN,
+                            "max": FAN_SPEED_MAX,
+                            "step": 1,
+                            "default": DEFAULT_IDLE_FAN_SPEED
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "throttle",
+            {
+                "type": "object",
+                "label": "Frequency Throttling",
+                "fields": [
+                    [
+                        "enabled",
+                        {
+                            "type": "bool",
+                            "label": "Enabled",
+                            "default": DEFAULT_THROTTLE_ENABLED
+                        }
+                    ],
+                    [
+                        "temp",
+                        {
+                            "type": "number",
+                            "label": "Throttle Temperature",
+                            "unit": "°C",
+                            "min": TEMPERATURE_C_MIN,
+                            "max": TEMPERATURE_C_MAX,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_THROTTLE_TEMP_C,
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "hysteresis",
+                        {
+                            "type": "number",
+                            "label": "Hysteresis",
+                            "unit": "°C",
+                            "min": 0.0,
+                            "max": TEMPERATURE_C_MAX,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_THROTTLE_HYSTERESIS_C,
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "recovery_temp",
+                        {
+                            "type": "number",
+                            "label": "Recovery Temperature",
+                            "unit": "°C",
+                            "min": TEMPERATURE_C_MIN,
+                            "max": TEMPERATURE_C_MAX,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_THROTTLE_TEMP_C - DEFAULT_THROTTLE_HYSTERESIS_C,
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "step",
+                        {
+                            "type": "number",
+                            "label": "Frequency Step",
+                            "unit": "MHz",
+                            "min": 0.0,
+                            "max": FREQUENCY_MHZ_MAX,
+                            "float": true,
+                            "default": DEFAULT_THROTTLE_STEP_MHZ,
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "min_frequency",
+                        {
+                            "type": "number",
+                            "label": "Minimal Frequency",
+                            "unit": "MHz",
+                            "min": FREQUENCY_MHZ_MIN,
+                            "max": FREQUENCY_MHZ_MAX,
+                            "float": true,
+                            "default": DEFAULT_THROTTLE_MIN_FREQUENCY_MHZ,
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "recovery_dwell",
+                        {
+                            "type": "number",
+                            "label": "Recovery Dwell Time",
+                            "unit": "s",
+                            "min": 0,
+                            "step": 1,
+                            "default": DEFAULT_THROTTLE_RECOVERY_DWELL.as_secs(),
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "warmup_period",
+                        {
+                            "type": "number",
+                            "label": "Warm-Up Period",
+                            "unit": "s",
+                            "min": 0,
+                            "step": 1,
+                            "default": DEFAULT_THROTTLE_WARMUP_PERIOD.as_secs(),
+                            "span": 4
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "hw_error_control",
+            {
+                "type": "object",
+                "label": "Hardware Error Control",
+                "fields": [
+                    [
+                        "threshold",
+                        {
+                            "type": "number",
+                            "label": "Hardware Error Threshold",
+                            "unit": "%",
+                            "min": 0.0,
+                            "max": 100.0,
+                            "step": 0.1,
+                            "float": true,
+                            "default": null,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "strategy",
+                        {
+                            "type": "enum",
+                            "label": "Corrective Action",
+                            "values": [
+                                {
+                                    "key": HwErrorStrategy::Voltage.to_string(),
+                                    "label": "Increase Voltage",
+                                    "alert": DESCRIPTION_CAUTION_OVERCLOCKING
+                                },
+                                {
+                                    "key": HwErrorStrategy::Frequency.to_string(),
+                                    "label": "Lower Frequency"
+                                }
+                            ],
+                            "default": DEFAULT_HW_ERROR_STRATEGY.to_string(),
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "voltage_step",
+                        {
+                            "type": "number",
+                            "label": "Voltage Step",
+                            "unit": "V",
+                            "min": 0.0,
+                            "max": VOLTAGE_V_MAX - VOLTAGE_V_MIN,
+                            "step": 0.01,
+                            "float": true,
+                            "default": DEFAULT_HW_ERROR_VOLTAGE_STEP_V,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "frequency_step",
+                        {
+                            "type": "number",
+                            "label": "Frequency Step",
+                            "unit": "MHz",
+                            "min": 0.0,
+                            "max": FREQUENCY_MHZ_MAX - FREQUENCY_MHZ_MIN,
+                            "float": true,
+                            "default": DEFAULT_HW_ERROR_FREQUENCY_STEP_MHZ,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "warmup_period",
+                        {
+                            "type": "number",
+                            "label": "Warm-Up Period",
+                            "unit": "s",
+                            "min": 0,
+                            "step": 1,
+                            "default": DEFAULT_HW_ERROR_WARMUP_PERIOD.as_secs(),
+                            "span": 6
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "voltage_check",
+            {
+                "type": "object",
+                "label": "Voltage Check",
+                "fields": [
+                    [
+                        "tolerance",
+                        {
+                            "type": "number",
+                            "label": "Tolerance",
+                            "unit": "V",
+                            "min": 0.0,
+                            "max": VOLTAGE_V_MAX - VOLTAGE_V_MIN,
+                            "step": 0.01,
+                            "float": true,
+                            "default": DEFAULT_VOLTAGE_CHECK_TOLERANCE_V,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "action",
+                        {
+                            "type": "enum",
+                            "label": "Action on Mismatch",
+                            "values": [
+                                {
+                                    "key": VoltageMismatchAction::Warn.to_string(),
+                                    "label": "Warn Only"
+                                },
+                                {
+                                    "key": VoltageMismatchAction::Throttle.to_string(),
+                                    "label": "Lower Frequency"
+                                },
+                                {
+                                    "key": VoltageMismatchAction::Shutdown.to_string(),
+                                    "label": "Shut Down"
+                                }
+                            ],
+                            "default": DEFAULT_VOLTAGE_MISMATCH_ACTION.to_string(),
+                            "span": 6
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "voltage_guard",
+            {
+                "type": "object",
+                "label": "Voltage Guard",
+                "fields": [
+                    [
+                        "enabled",
+                        {
+                            "type": "bool",
+                            "label": "Enabled",
+                            "default": DEFAULT_VOLTAGE_GUARD_ENABLED,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "action",
+                        {
+                            "type": "enum",
+                            "label": "Action on Insufficient Voltage",
+                            "values": [
+                                {
+                                    "key": VoltageGuardAction::RaiseVoltage.to_string(),
+                                    "label": "Raise Voltage"
+                                },
+                                {
+                                    "key": VoltageGuardAction::Reject.to_string(),
+                                    "label": "Reject Frequency Change"
+                                }
+                            ],
+                            "default": DEFAULT_VOLTAGE_GUARD_ACTION.to_string(),
+                            "span": 6
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "hashrate_check",
+            {
+                "type": "object",
+                "label": "Hashrate Check",
+                "fields": [
+                    [
+                        "expected_hashrate_tolerance",
+                        {
+                            "type": "number",
+                            "label": "Expected Hashrate Tolerance",
+                            "min": 0.0,
+                            "max": 1.0,
+                            "step": 0.01,
+                            "float": true,
+                            "default": null,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "period",
+                        {
+                            "type": "number",
+                            "label": "Period",
+                            "unit": "s",
+                            "min": DEFAULT_HASHRATE_INTERVAL.as_secs(),
+                            "default": DEFAULT_HASHRATE_CHECK_PERIOD.as_secs(),
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "action",
+                        {
+                            "type": "enum",
+                            "label": "Action on Drop",
+                            "values": [
+                                {
+                                    "key": HashrateDropAction::Warn.to_string(),
+                                    "label": "Warn Only"
+                                },
+                                {
+                                    "key": HashrateDropAction::Reinit.to_string(),
+                                    "label": "Reinitialize Chain"
+                                },
+                                {
+                                    "key": HashrateDropAction::Voltage.to_string(),
+                                    "label": "Increase Voltage"
+                                }
+                            ],
+                            "default": DEFAULT_HASHRATE_DROP_ACTION.to_string(),
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "voltage_step",
+                        {
+                            "type": "number",
+                            "label": "Voltage Step",
+                            "unit": "V",
+                            "min": 0.0,
+                            "max": VOLTAGE_V_MAX - VOLTAGE_V_MIN,
+                            "step": 0.01,
+                            "float": true,
+                            "default": DEFAULT_HASHRATE_CHECK_VOLTAGE_STEP_V,
+                            "span": 6
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "pll_lock_check",
+            {
+                "type": "object",
+                "label": "PLL Lock Check",
+                "fields": [
+                    [
+                        "action",
+                        {
+                            "type": "enum",
+                            "label": "Action on Lock Failure",
+                            "values": [
+                                {
+                                    "key": PllLockFailureAction::Warn.to_string(),
+                                    "label": "Warn Only"
+                                },
+                                {
+                                    "key": PllLockFailureAction::Retry.to_string(),
+                                    "label": "Retry"
+                                },
+                                {
+                                    "key": PllLockFailureAction::Fallback.to_string(),
+                                    "label": "Lower Frequency"
+                                }
+                            ],
+                            "default": DEFAULT_PLL_LOCK_FAILURE_ACTION.to_string(),
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "retries",
+                        {
+                            "type": "number",
+                            "label": "Retries",
+                            "min": 0,
+                            "default": DEFAULT_PLL_LOCK_RETRIES,
+                            "span": 4
+                        }
+                    ],
+                    [
+                        "fallback_step",
+                        {
+                            "type": "number",
+                            "label": "Fallback Step",
+                            "unit": "MHz",
+                            "min": 0.0,
+                            "max": FREQUENCY_MHZ_MAX - FREQUENCY_MHZ_MIN,
+                            "float": true,
+                            "default": DEFAULT_PLL_LOCK_FALLBACK_STEP_MHZ,
+                            "span": 4
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "health",
+            {
+                "type": "object",
+                "label": "Health Score",
+                "fields": [
+                    [
+                        "hw_errors_weight",
+                        {
+                            "type": "number",
+                            "label": "Hardware Errors Weight",
+                            "min": 0.0,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_HEALTH_WEIGHT,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "temperature_weight",
+                        {
+                            "type": "number",
+                            "label": "Temperature Weight",
+                            "min": 0.0,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_HEALTH_WEIGHT,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "acceptance_weight",
+                        {
+                            "type": "number",
+                            "label": "Share Acceptance Weight",
+                            "min": 0.0,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_HEALTH_WEIGHT,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "frequency_weight",
+                        {
+                            "type": "number",
+                            "label": "Frequency Weight",
+                            "min": 0.0,
+                            "step": 0.1,
+                            "float": true,
+                            "default": DEFAULT_HEALTH_WEIGHT,
+                            "span": 6
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "miner",
+            {
+                "type": "object",
+                "label": "Miner",
+                "fields": [
+                    [
+                        "on_no_pools",
+                        {
+                            "type": "enum",
+                            "label": "Action Without Pools",
+                            "values": [
+                                {
+                                    "key": NoPoolsAction::Error.to_string(),
+                                    "label": "Refuse to Start"
+                                },
+                                {
+                                    "key": NoPoolsAction::Idle.to_string(),
+                                    "label": "Start Idle"
+                                }
+                            ],
+                            "default": DEFAULT_ON_NO_POOLS.to_string()
+                        }
+                    ],
+                    [
+                        "psu_model",
+                        {
+                            "type": "enum",
+                            "label": "PSU Model",
+                            "values": psu_models,
+                            "default": power::DEFAULT_PSU_MODEL
+                        }
+                    ],
+                    [
+                        "model",
+                        {
+                            "type": "enum",
+                            "label": "Board Model",
+                            "values": board_models,
+                            "default": DEFAULT_BOARD_MODEL
+                        }
+                    ],
+                    [
+                        "hotplug",
+                        {
+                            "type": "bool",
+                            "label": "Hashboard Hotplug",
+                            "default": DEFAULT_HOTPLUG,
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "hotplug_interval",
+                        {
+                            "type": "number",
+                            "label": "Hotplug Scan Interval",
+                            "unit": "s",
+                            "min": 1,
+                            "step": 1,
+                            "default": DEFAULT_HOTPLUG_INTERVAL.as_secs(),
+                            "span": 6
+                        }
+                    ],
+                    [
+                        "continue_on_chain_failure",
+                        {
+                            "type": "bool",
+                            "label": "Continue on Hash Chain Failure",
+                            "default": DEFAULT_CONTINUE_ON_CHAIN_FAILURE
+                        }
+                    ],
+                    [
+                        "failure_grace",
+                        {
+                            "type": "number",
+                            "label": "Hash Chain Failure Grace Period",
+                            "unit": "s",
+                            "min": 0,
+                            "step": 1,
+                            "default": DEFAULT_FAILURE_GRACE.as_secs()
+                        }
+                    ],
+                    [
+                        "min_change_interval",
+                        {
+                            "type": "number",
+                            "label": "Minimal Interval between Frequency and Voltage Changes",
+                            "unit": "s",
+                            "min": 0,
+                            "step": 1,
+                            "default": DEFAULT_MIN_CHANGE_INTERVAL.as_secs()
+                        }
+                    ],
+                    [
+                        "init_concurrency",
+                        {
+                            "type": "number",
+                            "label": "Concurrent Hash Chain Initialization",
+                            "min": 1,
+                            "max": HASH_CHAIN_INDEX_MAX - HASH_CHAIN_INDEX_MIN + 1,
+                            "step": 1,
+                            "default": null
+                        }
+                    ],
+                    [
+                        "power_on_interval",
+                        {
+                            "type": "number",
+                            "label": "Power-on Interval",
+                            "unit": "ms",
+                            "min": 0,
+                            "max": POWER_ON_DELAY_MS_MAX,
+                            "step": 1,
+                            "default": DEFAULT_POWER_ON_INTERVAL_MS
+                        }
+                    ],
+                    [
+                        "state_file",
+                        {
+                            "type": "string",
+                            "label": "State File",
+                            "default": DEFAULT_STATE_FILE_PATH
+                        }
+                    ],
+                    [
+                        "system_power_limit",
+                        {
+                            "type": "number",
+                            "label": "System Power Limit",
+                            "unit": "W",
+                            "min": 1,
+                            "step": 1,
+                            "float": true,
+                            "default": null
+                        }
+                    ],
+                    [
+                        "lenient_pools",
+                        {
+                            "type": "bool",
+                            "label": "Skip Invalid Pools",
+                            "default": DEFAULT_LENIENT_POOLS
+                        }
+                    ],
+                    [
+                        "last_good_config",
+                        {
+                            "type": "string",
+                            "label": "Last-Known-Good Configuration",
+                            "default": DEFAULT_LAST_GOOD_CONFIG_PATH
+                        }
+                    ],
+                    [
+                        "pools_url",
+                        {
+                            "type": "string",
+                            "label": "Provisioning Server Pools URL",
+                            "match": "^https://",
+                            "default": null
+                        }
+                    ],
+                    [
+                        "pools_cache",
+                        {
+                            "type": "string",
+                            "label": "Cached Pools",
+                            "default": DEFAULT_POOLS_CACHE_PATH
+                        }
+                    ],
+                    [
+                        "debug_nonce_partition",
+                        {
+                            "type": "bool",
+                            "label": "Debug Nonce Partition",
+                            "default": DEFAULT_DEBUG_NONCE_PARTITION
+                        }
+                    ],
+                    [
+                        "suppress_duplicate_shares",
+                        {
+                            "type": "bool",
+                            "label": "Suppress Duplicate Shares",
+                            "default": DEFAULT_SUPPRESS_DUPLICATE_SHARES
+                        }
+                    ],
+                    [
+                        "audit_log",
+                        {
+                            "type": "string",
+                            "label": "Frequency and Voltage Audit Log",
+                            "default": null
+                        }
+                    ],
+                    [
+                        "suppress_warnings",
+                        {
+                            "type": "array",
+                            "label": "Suppressed Configuration Warnings",
+                            "add_label": "Add Warning",
+                            "optional": true,
+                            "item": {
+                                "type": "enum",
+                                "values": suppressible_warnings
+                            }
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "api",
+            {
+                "type": "object",
+                "label": "API Settings",
+                "fields": [
+                    [
+                        "read_only",
+                        {
+                            "type": "bool",
+                            "label": "Read-Only",
+                            "default": DEFAULT_API_READ_ONLY
+                        }
+                    ],
+                    [
+                        "token",
+                        {
+                            "type": "password",
+                            "label": "Token",
+                            "default": null
+                        }
+                    ],
+                    [
+                        "protect_queries",
+                        {
+                            "type": "bool",
+                            "label": "Require Token for Queries",
+                            "default": DEFAULT_API_PROTECT_QUERIES
+                        }
+                    ],
+                    [
+                        "ready_min_chains",
+                        {
+                            "type": "number",
+                            "label": "Hash Chains Required for Readiness",
+                            "min": 0,
+                            "max": HASH_CHAIN_INDEX_MAX - HASH_CHAIN_INDEX_MIN + 1,
+                            "default": null
+                        }
+                    ],
+                    [
+                        "ready_min_pools",
+                        {
+                            "type": "number",
+                            "label": "Pools Required for Readiness",
+                            "min": 0,
+                            "default": DEFAULT_READY_MIN_POOLS
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "maintenance",
+            {
+                "type": "object",
+                "label": "Maintenance Window",
+                "fields": [
+                    [
+                        "start",
+                        {
+                            "type": "string",
+                            "label": "Daily Start (UTC)",
+                            "match": "^([01][0-9]|2[0-3]):[0-5][0-9]$",
+                            "default": null
+                        }
+                    ],
+                    [
+                        "duration",
+                        {
+                            "type": "number",
+                            "label": "Duration",
+                            "unit": "s",
+                            "min": 1,
+                            "max": MAINTENANCE_DURATION_MAX,
+                            "step": 1,
+                            "default": DEFAULT_MAINTENANCE_DURATION
+                        }
+                    ]
+                ]
+            }
+        ],
+        [
+            "thermal_recovery",
+            {
+                "type": "object",
+                "label": "Thermal Shutdown Recovery",
+                "fields": [
+                    [
+                        "mode",
+                        {
+                            "type": "enum",
+                            "label": "Mode",
+                            "values": [
+                                {
+                                    "key": ThermalRecoveryMode::Retry.to_string(),
+                                    "label": "Retry after Cooldown"
+                                },
+                                {
+                                    "key": ThermalRecoveryMode::StayOff.to_string(),
+                                    "label": "Stay Off"
+                                }
+                            ],
+                            "default": DEFAULT_THERMAL_RECOVERY_MODE.to_string()
+                        }
+                    ],
+                    [
+                        "cooldown",
+                        {
+                            "type": "number",
+                            "label": "Cooldown",
+                            "unit": "s",
+                            "min": 0,
+                            "step": 1,
+                            "default": DEFAULT_THERMAL_RECOVERY_COOLDOWN,
+                            "disabled": ["$neq", ["$get", "thermal_recovery", "mode"], "retry"]
+                        }
+                    ],
+                    [
+                        "max_attempts",
+                        {
+                            "type": "number",
+                            "label": "Max Attempts",
+                            "min": 1,
+                            "step": 1,
+                            "default": DEFAULT_THERMAL_RECOVERY_MAX_ATTEMPTS,
+                            "disabled": ["$neq", ["$get", "thermal_recovery", "mode"], "retry"]
+                        }
+                    ],
+                    [
+                        "window",
+                        {
+                            "type": "number",
+                            "label": "Attempt Window",
+                            "unit": "s",
+                            "min": 1,
+                            "step": 1,
+                            "default": DEFAULT_THERMAL_RECOVERY_WINDOW,
+                            "disabled": ["$neq", ["$get", "thermal_recovery", "mode"], "retry"]
+                        }
                     ]
                 ]
             }
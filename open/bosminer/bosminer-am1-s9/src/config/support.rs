@@ -20,6 +20,8 @@
 // of such proprietary license or if you have any other questions, please
 // contact us at opensource@braiins.com.
 
+use serde::de::{self, DeserializeOwned, Visitor};
+
 use std::ops::Deref;
 
 /// This enum allows distinguishing between a configuration set by the user (e.g. in a
@@ -65,3 +67,111 @@ impl<T> Deref for OptionDefault<T> {
         }
     }
 }
+
+/// Remove a field reported as unknown in deserialization error message `error` from `value`.
+/// The field is removed only from an object where all the other fields are expected by the
+/// deserializer so that a known field with the same name elsewhere is left intact. Unknown fields
+/// at top level of configuration are never reported (see `remove_unexpected_fields`).
+///
+/// Returns path to the removed field or `None` when `error` doesn't report an unknown field or
+/// the field cannot be found.
+pub fn remove_unknown_field(value: &mut serde_json::Value, error: &str) -> Option<String> {
+    // Serde reports unknown fields as: "unknown field `a`, expected one of `b`, `c`"
+    let error = &error[error.find("unknown field `")?..];
+    let mut names = error.split('`').skip(1).step_by(2);
+    let field = names.next()?;
+    let expected: Vec<_> = names.collect();
+
+    remove_field(value, field, &expected, String::new())
+}
+
+fn remove_field(
+    value: &mut serde_json::Value,
+    field: &str,
+    expected: &[&str],
+    path: String,
+) -> Option<String> {
+    let join = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", path, name)
+        }
+    };
+    match value {
+        serde_json::Value::Object(map) => {
+            if map.contains_key(field)
+                && map
+                    .keys()
+                    .all(|key| key == field || expected.contains(&key.as_str()))
+            {
+                map.remove(field);
+                return Some(join(field));
+            }
+            map.iter_mut()
+                .find_map(|(key, value)| remove_field(value, field, expected, join(key)))
+        }
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .enumerate()
+            .find_map(|(i, item)| remove_field(item, field, expected, format!("{}[{}]", path, i))),
+        _ => None,
+    }
+}
+
+/// Deserializer which only records names of fields expected by struct deserializer
+struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+impl<'de, 'a> de::Deserializer<'de> for FieldsDeserializer<'a> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields collected"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// Names of fields expected by deserializer of struct `T` (skipped fields are missing and renamed
+/// ones use the new name). Flattened structure is deserialized as a map so `T` cannot contain
+/// flattened fields.
+pub fn struct_fields<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldsDeserializer(&mut fields));
+    fields
+}
+
+/// Remove all fields of object `value` which are not listed in `expected`. This is necessary for
+/// top level of configuration where unknown fields are silently ignored by deserializer of
+/// flattened configuration body.
+///
+/// Returns names of removed fields.
+pub fn remove_unexpected_fields(value: &mut serde_json::Value, expected: &[&str]) -> Vec<String> {
+    let map = match value {
+        serde_json::Value::Object(map) => map,
+        _ => return vec![],
+    };
+    let unexpected: Vec<_> = map
+        .keys()
+        .filter(|key| !expected.contains(&key.as_str()))
+        .cloned()
+        .collect();
+    for key in unexpected.iter() {
+        map.remove(key);
+    }
+    unexpected
+}
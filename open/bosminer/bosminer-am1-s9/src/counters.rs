@@ -25,6 +25,7 @@
 //! Note: `valid` counter is in shares, `errors` are in error event instances (not in shares)
 
 use crate::bm1387;
+use crate::duplicate_shares;
 
 use std::time::{Duration, Instant};
 
@@ -82,6 +83,10 @@ pub struct HashChain {
     pub started: Instant,
     pub stopped: Option<Instant>,
     pub asic_difficulty: usize,
+    /// Shares found again after they have been submitted
+    pub duplicate_shares: usize,
+    /// Duplicate shares that have not been submitted
+    pub suppressed_shares: usize,
 }
 
 impl HashChain {
@@ -93,12 +98,16 @@ impl HashChain {
             stopped: None,
             chip: vec![Chip::new(); chip_count],
             asic_difficulty,
+            duplicate_shares: 0,
+            suppressed_shares: 0,
         }
     }
 
     pub fn reset(&mut self) {
         self.valid = 0;
         self.errors = 0;
+        self.duplicate_shares = 0;
+        self.suppressed_shares = 0;
         for chip in self.chip.iter_mut() {
             chip.reset();
         }
@@ -142,6 +151,35 @@ impl HashChain {
         self.chip[addr.chip].core[addr.core].errors += 1;
     }
 
+    pub fn add_duplicate_share(&mut self, verdict: duplicate_shares::Verdict) {
+        self.duplicate_shares += 1;
+        if !verdict.should_submit() {
+            self.suppressed_shares += 1;
+        }
+    }
+
+    /// Ratio of hardware errors to all nonces received since `previous` snapshot.
+    /// Returns `None` if there were no nonces or the counters have been reset in the meantime.
+    pub fn error_rate_since(&self, previous: &Self) -> Option<f64> {
+        let valid = self.valid.checked_sub(previous.valid)? / self.asic_difficulty;
+        let errors = self.errors.checked_sub(previous.errors)?;
+        if self.started != previous.started || valid + errors == 0 {
+            return None;
+        }
+        Some(errors as f64 / (valid + errors) as f64)
+    }
+
+    /// Hashrate (in hashes per second) of valid nonces received since `previous` snapshot.
+    /// Returns `None` if the counters have been reset in the meantime.
+    pub fn hashrate_since(&self, previous: &Self) -> Option<f64> {
+        let valid = self.valid.checked_sub(previous.valid)?;
+        let elapsed = self.stopped?.checked_duration_since(previous.stopped?)?;
+        if self.started != previous.started || elapsed.as_secs_f64() == 0.0 {
+            return None;
+        }
+        Some(valid as f64 * 2f64.powi(32) / elapsed.as_secs_f64())
+    }
+
     pub fn set_chip_count(&mut self, chip_count: usize) {
         self.chip.resize(chip_count, Chip::new());
     }
@@ -0,0 +1,125 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Detection of shares that have already been submitted. Work registry of each hash chain drops
+//! solutions repeated within one work item only, the same share may still be found again when
+//! work is re-dispatched (e.g. after hash chain restart). Shares are identified by their block
+//! header hash which covers job, version, ntime and nonce.
+
+use ii_bitcoin::DHash;
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex as StdMutex;
+
+/// Number of recently submitted shares remembered by `Filter`
+pub const FILTER_CAPACITY: usize = 4096;
+
+/// Outcome of share check
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// Share has not been submitted recently
+    Unique,
+    /// Share has been submitted already and it should be sent anyway
+    Duplicate,
+    /// Share has been submitted already and it must not be sent again
+    Suppressed,
+}
+
+impl Verdict {
+    pub fn should_submit(&self) -> bool {
+        *self != Verdict::Suppressed
+    }
+}
+
+/// Filter of recently submitted shares shared by all hash chains
+#[derive(Debug)]
+pub struct Filter {
+    suppress: bool,
+    capacity: usize,
+    inner: StdMutex<FilterInner>,
+}
+
+#[derive(Debug, Default)]
+struct FilterInner {
+    submitted: HashSet<DHash>,
+    order: VecDeque<DHash>,
+}
+
+impl Filter {
+    pub fn new(suppress: bool) -> Self {
+        Self::with_capacity(suppress, FILTER_CAPACITY)
+    }
+
+    fn with_capacity(suppress: bool, capacity: usize) -> Self {
+        Self {
+            suppress,
+            capacity,
+            inner: Default::default(),
+        }
+    }
+
+    /// Record share with block header `hash` about to be submitted
+    pub fn check(&self, hash: &DHash) -> Verdict {
+        let mut inner = self.inner.lock().expect("BUG: failed to lock mutex");
+        if inner.submitted.contains(hash) {
+            return if self.suppress {
+                Verdict::Suppressed
+            } else {
+                Verdict::Duplicate
+            };
+        }
+        inner.submitted.insert(*hash);
+        inner.order.push_back(*hash);
+        if inner.order.len() > self.capacity {
+            let oldest = inner.order.pop_front().expect("BUG: empty queue");
+            inner.submitted.remove(&oldest);
+        }
+        Verdict::Unique
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ii_bitcoin::HashTrait;
+
+    fn share(n: u8) -> DHash {
+        DHash::hash(&[n])
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter::with_capacity(false, 2);
+        assert_eq!(filter.check(&share(0)), Verdict::Unique);
+        assert_eq!(filter.check(&share(0)), Verdict::Duplicate);
+        assert!(filter.check(&share(0)).should_submit());
+
+        let filter = Filter::with_capacity(true, 2);
+        assert_eq!(filter.check(&share(0)), Verdict::Unique);
+        assert_eq!(filter.check(&share(1)), Verdict::Unique);
+        assert_eq!(filter.check(&share(1)), Verdict::Suppressed);
+        assert!(!filter.check(&share(1)).should_submit());
+        // the oldest share is forgotten when capacity is exceeded
+        assert_eq!(filter.check(&share(2)), Verdict::Unique);
+        assert_eq!(filter.check(&share(0)), Verdict::Unique);
+    }
+}
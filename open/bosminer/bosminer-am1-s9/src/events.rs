@@ -0,0 +1,207 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+//! Stream of discrete monitoring events (temperature alerts, throttling, fan and hash chain
+//! failures, pool switches). Recent events are kept in bounded memory and are available through
+//! API (clients poll with ID of the last event they have seen) or to in-process subscribers.
+//! Neither way can back up the miner: the oldest events are dropped when the buffer is full and
+//! subscribers that don't keep up miss events.
+
+use ii_logging::macros::*;
+
+use serde::Serialize;
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use ii_async_compat::tokio;
+use once_cell::sync::OnceCell;
+use tokio::sync::broadcast;
+
+/// Number of events kept in memory (and queued for each subscriber)
+pub const STREAM_CAPACITY: usize = 256;
+
+/// What happened
+#[derive(Serialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// Hash chain temperature reached its alert threshold
+    ThermalWarn,
+    /// Hash chain frequency throttling has changed
+    ThermalThrottle,
+    /// Not enough fans are running
+    FanFailure,
+    /// Hash chain has been declared broken
+    ChainFailed,
+    /// Unresponsive hash chain has recovered within failure grace period
+    ChainRecovered,
+    /// Pool group started mining for a different pool
+    PoolSwitched,
+    /// Hash chain hashrate stayed below expected hashrate
+    HashrateDrop,
+}
+
+impl std::string::ToString for Kind {
+    fn to_string(&self) -> String {
+        match self {
+            Self::ThermalWarn => "thermal_warn".to_string(),
+            Self::ThermalThrottle => "thermal_throttle".to_string(),
+            Self::FanFailure => "fan_failure".to_string(),
+            Self::ChainFailed => "chain_failed".to_string(),
+            Self::ChainRecovered => "chain_recovered".to_string(),
+            Self::PoolSwitched => "pool_switched".to_string(),
+            Self::HashrateDrop => "hashrate_drop".to_string(),
+        }
+    }
+}
+
+/// One emitted event
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Event {
+    /// Sequence number of the event (starting from 1)
+    pub id: u64,
+    /// Unix timestamp in seconds
+    pub timestamp: i64,
+    pub kind: Kind,
+    /// Hash chain the event relates to (missing for miner-wide events)
+    pub hashboard_idx: Option<usize>,
+    /// Human readable context of the event
+    pub message: String,
+}
+
+#[derive(Debug)]
+struct StreamInner {
+    next_id: u64,
+    events: VecDeque<Event>,
+}
+
+/// Event stream of running miner
+#[derive(Debug)]
+pub struct Stream {
+    capacity: usize,
+    inner: Mutex<StreamInner>,
+    sender: broadcast::Sender<Event>,
+}
+
+impl Stream {
+    fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            capacity,
+            inner: Mutex::new(StreamInner {
+                next_id: 1,
+                events: VecDeque::with_capacity(capacity),
+            }),
+            sender,
+        }
+    }
+
+    /// Record event and pass it to subscribers
+    pub fn emit(&self, kind: Kind, hashboard_idx: Option<usize>, message: String) {
+        let mut inner = self.inner.lock().expect("BUG: cannot lock event stream");
+        let event = Event {
+            id: inner.next_id,
+            timestamp: Utc::now().timestamp(),
+            kind,
+            hashboard_idx,
+            message,
+        };
+        info!("Event {}: {}", event.kind.to_string(), event.message);
+        inner.next_id += 1;
+        if inner.events.len() >= self.capacity {
+            inner.events.pop_front();
+        }
+        inner.events.push_back(event.clone());
+        // There may be no subscribers at all
+        let _ = self.sender.send(event);
+    }
+
+    /// Recorded events newer than event `last_id` (all recorded events when missing) from the
+    /// oldest one
+    pub fn events_since(&self, last_id: Option<u64>) -> Vec<Event> {
+        let last_id = last_id.unwrap_or(0);
+        self.inner
+            .lock()
+            .expect("BUG: cannot lock event stream")
+            .events
+            .iter()
+            .filter(|event| event.id > last_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Receive events emitted from now on. Subscriber that falls more than `STREAM_CAPACITY`
+    /// events behind skips the oldest ones.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+static STREAM: OnceCell<Stream> = OnceCell::new();
+
+/// Create event stream of current run
+pub fn init() -> &'static Stream {
+    STREAM.get_or_init(|| Stream::new(STREAM_CAPACITY))
+}
+
+/// Event stream of current run (missing when `init` hasn't been called yet)
+pub fn stream() -> Option<&'static Stream> {
+    STREAM.get()
+}
+
+/// Emit event to stream of current run
+pub fn emit(kind: Kind, hashboard_idx: Option<usize>, message: String) {
+    if let Some(stream) = stream() {
+        stream.emit(kind, hashboard_idx, message);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stream() {
+        let stream = Stream::new(2);
+        let mut receiver = stream.subscribe();
+        stream.emit(Kind::FanFailure, None, "fans".to_string());
+        stream.emit(Kind::ChainFailed, Some(6), "chain".to_string());
+        let ids = |events: Vec<Event>| events.iter().map(|event| event.id).collect::<Vec<_>>();
+        assert_eq!(ids(stream.events_since(None)), vec![1, 2]);
+        assert_eq!(ids(stream.events_since(Some(1))), vec![2]);
+        assert!(stream.events_since(Some(2)).is_empty());
+        assert_eq!(
+            receiver.try_recv().expect("BUG: missing event").kind,
+            Kind::FanFailure
+        );
+
+        // the oldest event is dropped when capacity is exceeded
+        stream.emit(Kind::ChainRecovered, Some(6), "chain".to_string());
+        assert_eq!(ids(stream.events_since(None)), vec![2, 3]);
+
+        // slow subscriber skips events instead of blocking the stream
+        stream.emit(Kind::PoolSwitched, None, "pool".to_string());
+        stream.emit(Kind::PoolSwitched, None, "pool".to_string());
+        assert!(receiver.try_recv().is_err());
+        assert!(receiver.try_recv().is_ok());
+    }
+}
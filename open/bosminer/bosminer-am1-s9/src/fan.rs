@@ -48,6 +48,22 @@ impl Speed {
     }
 }
 
+/// PWM carrier frequencies (in Hz) that can be programmed into the fan controller. The FPGA fan
+/// controller of S9 generates PWM signal with fixed carrier frequency, so there is nothing to
+/// choose from.
+pub const SUPPORTED_PWM_FREQUENCIES: &[usize] = &[];
+
+/// Check that PWM carrier frequency (in Hz) is one of the `supported` ones
+fn check_pwm_frequency(frequency: usize, supported: &[usize]) -> error::Result<()> {
+    if !supported.contains(&frequency) {
+        Err(ErrorKind::General(format!(
+            "fan PWM frequency {} Hz is not supported (supported: {:?})",
+            frequency, supported
+        )))?;
+    }
+    Ok(())
+}
+
 /// Speed of fans read from feedback pins
 #[derive(Debug, Clone)]
 pub struct Feedback {
@@ -92,6 +108,17 @@ impl Control {
         }
     }
 
+    /// PWM carrier frequencies (in Hz) supported by detected fan controller
+    pub fn supported_pwm_frequencies(&self) -> &'static [usize] {
+        SUPPORTED_PWM_FREQUENCIES
+    }
+
+    /// Set PWM carrier frequency (in Hz). Fails when the frequency is not supported by the
+    /// hardware in which case the carrier frequency is left untouched.
+    pub fn set_pwm_frequency(&self, frequency: usize) -> error::Result<()> {
+        check_pwm_frequency(frequency, self.supported_pwm_frequencies())
+    }
+
     /// Set PWM for fans in percent (0 means fans stopped, 100 means fans on full)
     pub fn set_speed(&self, speed: Speed) {
         // Only lower 8 bits of FAN_PWM register are considered, so writing 256 would stop fans,
@@ -138,4 +165,12 @@ mod test {
         );
         assert_eq!(Feedback { rpm: Vec::new() }.num_fans_running(), 0);
     }
+
+    #[test]
+    fn test_pwm_frequency() {
+        assert!(check_pwm_frequency(25_000, &[1_000, 25_000]).is_ok());
+        assert!(check_pwm_frequency(10_000, &[1_000, 25_000]).is_err());
+        // S9 fan controller has fixed carrier frequency so any configured one falls back to it
+        assert!(check_pwm_frequency(25_000, SUPPORTED_PWM_FREQUENCIES).is_err());
+    }
 }
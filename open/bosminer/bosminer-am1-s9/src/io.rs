@@ -208,7 +208,6 @@ impl WorkTxFifo {
 
     /// Return the value of last work ID send to ASICs
     #[inline]
-    #[allow(dead_code)]
     pub fn get_last_work_id(&mut self) -> u32 {
         self.regs.work_tx_last_id.read().bits()
     }
@@ -225,6 +224,14 @@ impl WorkTxFifo {
         Ok(())
     }
 
+    /// Set number of entries in FIFO queue under which we consider FIFO to have room for one
+    /// more work (it is capped so that the biggest work always fits)
+    pub fn set_threshold(&mut self, threshold: u32) {
+        self.regs
+            .work_tx_irq_thr
+            .write(|w| unsafe { w.bits(threshold.min(Self::FIFO_THRESHOLD)) });
+    }
+
     /// Wait for output FIFO to make room for one work
     pub async fn async_wait_for_room(&self) -> error::Result<()> {
         let cond = || self.has_space_for_one_job();
@@ -419,9 +426,52 @@ impl WorkRx {
 pub struct WorkTx {
     fifo: WorkTxFifo,
     midstate_count: MidstateCount,
+    /// `work_id` of the last work written to FIFO
+    last_work_id: Option<usize>,
 }
 
 impl WorkTx {
+    /// Size of one work in FIFO (in u32 words): header with `work_id`, nbits, ntime and merkle
+    /// root tail followed by midstates
+    fn work_size(midstate_count: MidstateCount) -> u32 {
+        (4 + 8 * midstate_count.to_count()) as u32
+    }
+
+    /// FIFO threshold (in u32 words) under which FIFO contains less than `depth` works
+    fn queue_depth_threshold(midstate_count: MidstateCount, depth: usize) -> u32 {
+        assert!(depth > 0);
+        (depth as u32 - 1) * Self::work_size(midstate_count) + 1
+    }
+
+    /// Number of works written after the work with `sent_work_id` up to the one with
+    /// `last_work_id` (work IDs wrap around)
+    fn work_id_distance(
+        midstate_count: MidstateCount,
+        last_work_id: usize,
+        sent_work_id: usize,
+    ) -> usize {
+        let work_id_count = ExtWorkId::get_work_id_count(midstate_count);
+        (last_work_id + work_id_count - sent_work_id) % work_id_count
+    }
+
+    /// Limit number of works buffered in FIFO to `depth`. New work is requested only when FIFO
+    /// contains less than `depth` works.
+    pub fn set_queue_depth(&mut self, depth: usize) {
+        let threshold = Self::queue_depth_threshold(self.midstate_count, depth);
+        self.fifo.set_threshold(threshold);
+    }
+
+    /// Number of works written to FIFO that haven't been sent to ASICs yet
+    pub fn queued_work(&mut self) -> usize {
+        let last_work_id = match self.last_work_id {
+            Some(last_work_id) => last_work_id,
+            None => return 0,
+        };
+        let sent_work_id =
+            ExtWorkId::from_hw(self.midstate_count, self.fifo.get_last_work_id() & 0xffff).work_id;
+        Self::work_id_distance(self.midstate_count, last_work_id, sent_work_id)
+    }
+
     pub async fn wait_for_room(&self) -> error::Result<()> {
         self.fifo.async_wait_for_room().await
     }
@@ -455,6 +505,7 @@ impl WorkTx {
                 self.fifo.write(midstate_word.to_be())?;
             }
         }
+        self.last_work_id = Some(work_id);
         Ok(())
     }
 
@@ -465,6 +516,7 @@ impl WorkTx {
     }
 
     fn init(&mut self) -> error::Result<()> {
+        self.last_work_id = None;
         self.fifo.init()
     }
 
@@ -472,6 +524,7 @@ impl WorkTx {
         Ok(Self {
             fifo: WorkTxFifo::new(hashboard_idx)?,
             midstate_count,
+            last_work_id: None,
         })
     }
 }
@@ -787,6 +840,25 @@ mod test {
         }
     }
 
+    /// Test limiting number of works buffered in FIFO
+    #[test]
+    fn test_work_queue_depth() {
+        // FIFO has to be empty to request new work when only one work is queued
+        assert_eq!(WorkTx::queue_depth_threshold(MidstateCount::new(1), 1), 1);
+        assert_eq!(WorkTx::queue_depth_threshold(MidstateCount::new(1), 2), 13);
+        assert_eq!(WorkTx::queue_depth_threshold(MidstateCount::new(4), 3), 73);
+
+        let midstate_count = MidstateCount::new(2);
+        let work_id_count = ExtWorkId::get_work_id_count(midstate_count);
+        assert_eq!(WorkTx::work_id_distance(midstate_count, 5, 5), 0);
+        assert_eq!(WorkTx::work_id_distance(midstate_count, 5, 3), 2);
+        // work ID wraps around
+        assert_eq!(
+            WorkTx::work_id_distance(midstate_count, 1, work_id_count - 1),
+            2
+        );
+    }
+
     #[test]
     fn test_version_display() {
         let version = Version {
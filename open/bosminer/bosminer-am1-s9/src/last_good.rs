@@ -0,0 +1,111 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Last-known-good configuration. Configuration that successfully started all hash chains is
+//! persisted so that the miner can roll back to it when a changed configuration prevents hash
+//! chains from starting.
+
+use ii_logging::macros::*;
+
+use crate::config;
+use crate::error;
+use crate::restart;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Serialized configuration waiting for successful start of hash chains
+#[derive(Debug)]
+pub struct Snapshot {
+    path: PathBuf,
+    content: String,
+}
+
+impl Snapshot {
+    pub fn new(path: PathBuf, content: String) -> Self {
+        Self { path, content }
+    }
+
+    /// Serialize parsed configuration
+    pub fn serialize(config: &config::FormatWrapper<config::Backend>) -> Option<String> {
+        toml::to_string_pretty(config)
+            .map_err(|e| warn!("Cannot serialize configuration: {}", e))
+            .ok()
+    }
+
+    /// Persist the snapshot as last-known-good configuration
+    pub fn store(&self) {
+        match Self::write(&self.path, &self.content) {
+            Ok(_) => info!(
+                "Configuration stored as last-known-good to '{}'",
+                self.path.display()
+            ),
+            Err(e) => warn!(
+                "Cannot store last-known-good configuration '{}': {}",
+                self.path.display(),
+                e
+            ),
+        }
+    }
+
+    fn write(path: &Path, content: &str) -> error::Result<()> {
+        // Replace the file atomically so that the last-known-good configuration is never lost
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Roll back only when hash chains failed to start during previous run and the miner is not
+/// allowed to continue without them
+pub fn should_roll_back(
+    last_reason: Option<restart::Reason>,
+    continue_on_chain_failure: bool,
+) -> bool {
+    !continue_on_chain_failure && last_reason == Some(restart::Reason::ChainFailure)
+}
+
+/// Load last-known-good configuration from `path`
+pub fn load(path: &Path) -> Result<config::FormatWrapper<config::Backend>, String> {
+    let config_path = path.to_str().ok_or("invalid path".to_string())?;
+    match config::FormatWrapper::parse(config_path, config::ParseMode::Strict) {
+        Ok(config) | Err(config::FormatWrapperError::IncompatibleVersion(_, Some(config))) => {
+            Ok(config)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_should_roll_back() {
+        assert!(should_roll_back(Some(restart::Reason::ChainFailure), false));
+        // Miner keeps running with remaining hash chains so there's nothing to recover from
+        assert!(!should_roll_back(Some(restart::Reason::ChainFailure), true));
+        assert!(!should_roll_back(Some(restart::Reason::Thermal), false));
+        assert!(!should_roll_back(None, false));
+    }
+}
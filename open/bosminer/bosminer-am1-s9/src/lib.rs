@@ -22,22 +22,30 @@
 #![recursion_limit = "256"]
 
 mod async_i2c;
+pub mod audit;
 pub mod bm1387;
 mod cgminer;
 pub mod command;
 pub mod config;
 pub mod counters;
+pub mod duplicate_shares;
 pub mod error;
+pub mod events;
 pub mod fan;
 pub mod gpio;
 pub mod halt;
 pub mod hooks;
 pub mod i2c;
 pub mod io;
+pub mod last_good;
 pub mod monitor;
+pub mod nonce_partition;
 pub mod null_work;
 pub mod power;
 pub mod registry;
+pub mod remote_pools;
+pub mod restart;
+pub mod self_check;
 pub mod sensor;
 pub mod utils;
 
@@ -47,6 +55,7 @@ pub mod test;
 use ii_logging::macros::*;
 
 use bosminer::async_trait;
+use bosminer::client;
 use bosminer::hal::{self, BackendConfig as _};
 use bosminer::node;
 use bosminer::stats;
@@ -54,6 +63,7 @@ use bosminer::work;
 
 use bosminer_macros::WorkSolverNode;
 
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant};
@@ -66,7 +76,7 @@ use futures::lock::{Mutex, MutexGuard};
 use futures::stream::StreamExt;
 use ii_async_compat::futures;
 
-use bm1387::{ChipAddress, MidstateCount};
+use bm1387::{ChipAddress, MidstateCount, Register};
 use command::Interface;
 
 use packed_struct::PackedStruct;
@@ -109,6 +119,24 @@ const TEMP_CHIP: ChipAddress = ChipAddress::One(61);
 /// Timeout for completion of haschain halt
 const HALT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// How often is estimated power consumption checked against system power limit
+const POWER_LIMIT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often is voltage read back from voltage controller and compared with requested voltage
+const VOLTAGE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait for PLL to lock after its register is loaded
+const PLL_LOCK_TIME: Duration = Duration::from_millis(10);
+
+/// How often is frequency of hash chain initialized at lower frequency raised by one step
+const FREQUENCY_RAMP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often is checked whether postponed frequency and voltage changes can be applied
+const PENDING_CHANGE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Range of hashboard indices that are probed for presence
+const HASHBOARD_INDEX_RANGE: std::ops::RangeInclusive<usize> = 1..=8;
+
 /// Core address space size (it should be 114, but the addresses are non-consecutive)
 const CORE_ADR_SPACE_SIZE: usize = 128;
 
@@ -210,6 +238,43 @@ pub struct HashChain {
     halt_receiver: halt::Receiver,
     /// Current hashchain settings
     frequency: Mutex<FrequencySettings>,
+    /// Requested hashchain settings from which the current settings are derived
+    frequency_control: Mutex<FrequencyControl>,
+    /// Granularity of runtime frequency adjustments in Hz
+    frequency_step: usize,
+    /// Hardware error rate measured over the last hashrate interval
+    hw_error_rate: Mutex<Option<f64>>,
+    /// Ratio of hashrate measured over the last hashrate interval to hashrate expected from
+    /// current frequency
+    hashrate_ratio: Mutex<Option<f64>>,
+    /// What to do when measured voltage differs from requested voltage
+    voltage_check: config::VoltageCheckConfig,
+    /// Result of the last voltage readback
+    voltage_readback: Mutex<Option<power::VoltageReadback>>,
+    /// What to do when PLL of some chips does not lock
+    pll_lock_check: config::PllLockCheckConfig,
+    /// Chips whose PLL failed to lock after the last frequency change (missing until checked)
+    pll_unlocked_chips: Mutex<Option<Vec<usize>>>,
+    /// What to do when frequency is changed at runtime without sufficient voltage
+    voltage_guard: Option<config::VoltageGuardConfig>,
+    /// Runtime check of nonce partitioning shared by all hash chains (debugging only)
+    overlap_detector: Option<Arc<nonce_partition::OverlapDetector>>,
+    /// Filter of recently submitted shares shared by all hash chains
+    duplicate_filter: Option<Arc<duplicate_shares::Filter>>,
+    /// Maximal number of jobs buffered in FPGA (whole FIFO is used when missing)
+    work_queue_depth: Option<usize>,
+    /// Number of jobs buffered in FPGA when hash chain asked for new work the last time
+    queued_work: Mutex<usize>,
+    /// Minimal time between runtime frequency or voltage changes
+    min_change_interval: Duration,
+    /// When runtime frequency or voltage changed the last time
+    last_change: Mutex<Option<Instant>>,
+    /// Changes requested before `min_change_interval` elapsed
+    pending_change: Mutex<PendingChange>,
+    /// Automatic frequency and voltage adjustments are ignored
+    frozen: bool,
+    /// Delay before the hash board is powered on (staggers power-up of hash chains)
+    power_on_delay: Duration,
 }
 
 impl HashChain {
@@ -270,6 +335,34 @@ impl HashChain {
             halt_sender,
             halt_receiver,
             frequency: Mutex::new(FrequencySettings::from_frequency(0)),
+            frequency_control: Mutex::new(FrequencyControl::new(
+                FrequencySettings::from_frequency(0),
+            )),
+            frequency_step: (config::DEFAULT_FREQUENCY_STEP_MHZ * 1_000_000.0) as usize,
+            hw_error_rate: Mutex::new(None),
+            hashrate_ratio: Mutex::new(None),
+            voltage_check: config::VoltageCheckConfig {
+                tolerance: config::DEFAULT_VOLTAGE_CHECK_TOLERANCE_V as f32,
+                action: config::DEFAULT_VOLTAGE_MISMATCH_ACTION,
+            },
+            voltage_readback: Mutex::new(None),
+            pll_lock_check: config::PllLockCheckConfig {
+                action: config::DEFAULT_PLL_LOCK_FAILURE_ACTION,
+                retries: config::DEFAULT_PLL_LOCK_RETRIES,
+                fallback_step: (config::DEFAULT_PLL_LOCK_FALLBACK_STEP_MHZ * 1_000_000.0) as usize,
+                min_frequency: (config::FREQUENCY_MHZ_MIN * 1_000_000.0) as usize,
+            },
+            pll_unlocked_chips: Mutex::new(None),
+            voltage_guard: None,
+            overlap_detector: None,
+            duplicate_filter: None,
+            work_queue_depth: None,
+            queued_work: Mutex::new(0),
+            min_change_interval: config::DEFAULT_MIN_CHANGE_INTERVAL,
+            last_change: Mutex::new(None),
+            pending_change: Mutex::new(Default::default()),
+            frozen: config::DEFAULT_FROZEN,
+            power_on_delay: Duration::from_secs(0),
         })
     }
 
@@ -365,6 +458,15 @@ impl HashChain {
         self.enter_reset()?;
         self.voltage_ctrl.disable_voltage().await?;
         delay_for(INIT_DELAY).await;
+        if self.power_on_delay > Duration::from_secs(0) {
+            info!(
+                "Chain {}: delaying power-on by {} ms",
+                self.hashboard_idx,
+                self.power_on_delay.as_millis()
+            );
+            delay_for(self.power_on_delay).await;
+        }
+        info!("Chain {}: powering on hash board", self.hashboard_idx);
         self.voltage_ctrl.enable_voltage().await?;
         delay_for(INIT_DELAY * 2).await;
         self.exit_reset()?;
@@ -389,7 +491,9 @@ impl HashChain {
         }
 
         // set PLL
-        self.set_pll(initial_frequency).await?;
+        *self.frequency_control.lock().await = FrequencyControl::new(initial_frequency.clone());
+        self.set_pll(initial_frequency, audit::Source::Config)
+            .await?;
 
         // configure the hashing chain to operate at desired baud rate. Note that gate block is
         // enabled to allow continuous start of chips in the chain
@@ -446,10 +550,12 @@ impl HashChain {
         }
 
         // lower voltage to working level
-        self.voltage_ctrl
-            .set_voltage(initial_voltage)
+        self.set_voltage(initial_voltage, audit::Source::Config)
             .await
             .expect("lowering voltage failed");
+        if let Err(e) = self.check_voltage().await {
+            error!("Chain {}: voltage check failed: {}", self.hashboard_idx, e);
+        }
 
         // return work registry we created
         Ok(work_registry)
@@ -528,7 +634,7 @@ impl HashChain {
         );
 
         // NOTE: When PLL register is read back, it is or-ed with 0x8000_0000, not sure why.
-        //  Avoid reading it back to prevent disappointment.
+        //  Avoid reading it back to prevent disappointment, lock is verified separately.
         self.command_context
             .write_register(chip_addr, &pll.reg)
             .await?;
@@ -536,40 +642,367 @@ impl HashChain {
         Ok(())
     }
 
-    /// Load PLL register of all chips
+    /// Indices of chips whose PLL is not locked
+    ///
+    /// Chips are read one by one because BM1387 responses don't carry chip address, so a chip
+    /// that doesn't respond to broadcast would shift indices of all following chips.
+    async fn read_unlocked_chips(&self) -> error::Result<Vec<usize>> {
+        let mut unlocked_chips = Vec::new();
+        for chip in 0..self.chip_count {
+            match self
+                .command_context
+                .read_one_register::<bm1387::PllStatusReg>(ChipAddress::One(chip))
+                .await
+            {
+                Ok(pll_status) => {
+                    if !pll_status.locked {
+                        unlocked_chips.push(chip);
+                    }
+                }
+                // Chip that doesn't respond cannot confirm the lock
+                Err(e) => match e.kind() {
+                    ErrorKind::Hashchip(_) => {
+                        debug!("Chain {}: chip {}: {}", self.hashboard_idx, chip, e);
+                        unlocked_chips.push(chip);
+                    }
+                    _ => Err(e)?,
+                },
+            }
+        }
+        Ok(unlocked_chips)
+    }
+
+    /// Verify that PLL of all chips locked after loading `frequency` and handle chips that
+    /// failed to lock as configured in `pll_lock_check`. Frequency of chips lowered by fallback
+    /// is updated in `frequency` and in base of `frequency_control` so that the next frequency
+    /// change doesn't load the failing frequency again.
+    async fn verify_pll_lock(&self, frequency: &mut FrequencySettings) -> error::Result<()> {
+        let mut attempt = 0;
+        let unlocked_chips = loop {
+            delay_for(PLL_LOCK_TIME).await;
+            let unlocked_chips = match self.read_unlocked_chips().await {
+                Ok(unlocked_chips) => unlocked_chips,
+                Err(e) => {
+                    warn!(
+                        "Chain {}: cannot read PLL lock status: {}",
+                        self.hashboard_idx, e
+                    );
+                    return Ok(());
+                }
+            };
+            if unlocked_chips.is_empty()
+                || attempt >= self.pll_lock_check.retries
+                || self.pll_lock_check.action == config::PllLockFailureAction::Warn
+            {
+                break unlocked_chips;
+            }
+            attempt += 1;
+            warn!(
+                "Chain {}: PLL of chips {:?} not locked, loading it again (attempt {}/{})",
+                self.hashboard_idx, unlocked_chips, attempt, self.pll_lock_check.retries
+            );
+            for &chip in unlocked_chips.iter() {
+                if self.pll_lock_check.action == config::PllLockFailureAction::Fallback {
+                    frequency.chip[chip] = self.pll_lock_check.fallback(frequency.chip[chip]);
+                    let mut frequency_control = self.frequency_control.lock().await;
+                    let mut base = frequency_control.base.clone();
+                    base.chip[chip] = self.pll_lock_check.fallback(base.chip[chip]);
+                    frequency_control.lower_base(base);
+                }
+                self.set_chip_pll(ChipAddress::One(chip), frequency.chip[chip])
+                    .await?;
+            }
+        };
+        if !unlocked_chips.is_empty() {
+            warn!(
+                "Chain {}: PLL of chips {:?} failed to lock",
+                self.hashboard_idx, unlocked_chips
+            );
+        }
+        self.pll_unlocked_chips.lock().await.replace(unlocked_chips);
+        Ok(())
+    }
+
+    /// Chips whose PLL failed to lock after the last frequency change
+    pub async fn get_pll_unlocked_chips(&self) -> Option<Vec<usize>> {
+        self.pll_unlocked_chips.lock().await.clone()
+    }
+
+    /// Load PLL register of all chips and verify that PLLs locked (frequency of chips may end up
+    /// lower than requested when `pll_lock_check` falls back)
     ///
-    /// Takes care of adjusting `work_time`
-    pub async fn set_pll(&self, frequency: &FrequencySettings) -> error::Result<()> {
+    /// Takes care of adjusting `work_time`. The change is recorded in audit log as triggered by
+    /// `source`.
+    pub async fn set_pll(
+        &self,
+        frequency: &FrequencySettings,
+        source: audit::Source,
+    ) -> error::Result<()> {
         // TODO: find a better way - how to communicate with frequency setter how many chips we have?
         assert!(frequency.chip.len() >= self.chip_count);
+        let mut frequency = frequency.clone();
 
-        // Check if the frequencies are identical
-        if frequency.min() == frequency.max() {
-            // Update them in one go
-            self.set_chip_pll(ChipAddress::All, frequency.chip[0])
-                .await?;
-        } else {
-            // Update chips one-by-one
-            for i in 0..self.chip_count {
-                let new_freq = self.frequency.lock().await.chip[i];
-                if new_freq != frequency.chip[i] {
-                    self.set_chip_pll(ChipAddress::One(i), new_freq).await?;
-                }
-            }
+        let pll_updates = frequency.pll_updates(&*self.frequency.lock().await, self.chip_count);
+        for (chip_addr, new_freq) in pll_updates {
+            self.set_chip_pll(chip_addr, new_freq).await?;
         }
 
+        self.verify_pll_lock(&mut frequency).await?;
+
         // Update worktime
         self.set_work_time(frequency.max()).await;
 
         // Remember what frequencies are set
         let mut cur_frequency = self.frequency.lock().await;
+        let old_frequency = cur_frequency.clone();
         for i in 0..self.chip_count {
             cur_frequency.chip[i] = frequency.chip[i];
         }
+        audit::record_frequency(self.hashboard_idx, source, &old_frequency, &cur_frequency);
+
+        Ok(())
+    }
+
+    /// Set voltage of the hash chain and record the change in audit log as triggered by `source`
+    pub async fn set_voltage(
+        &self,
+        voltage: power::Voltage,
+        source: audit::Source,
+    ) -> error::Result<()> {
+        let old_voltage = self.voltage_ctrl.get_current_voltage().await;
+        self.voltage_ctrl.set_voltage(voltage).await?;
+        audit::record_voltage(self.hashboard_idx, source, old_voltage, voltage);
+        Ok(())
+    }
+
+    /// Frozen hash chain ignores changes decided by automatic control loops
+    fn ignores(&self, source: audit::Source) -> bool {
+        if self.frozen && source.is_automatic() {
+            debug!(
+                "Chain {}: frozen, ignoring {} adjustment",
+                self.hashboard_idx,
+                source.to_string()
+            );
+            return true;
+        }
+        false
+    }
+
+    /// Time left until runtime frequency or voltage can be changed again
+    async fn change_cooldown(&self) -> Option<Duration> {
+        let last_change = (*self.last_change.lock().await)?;
+        remaining_change_cooldown(self.min_change_interval, last_change, Instant::now())
+    }
+
+    /// Set voltage requested at runtime. When the last change happened less than
+    /// `min_change_interval` ago, the voltage is applied after the interval elapses (unless it is
+    /// requested again in the meantime).
+    async fn request_voltage(
+        &self,
+        voltage: power::Voltage,
+        source: audit::Source,
+    ) -> error::Result<()> {
+        if self.ignores(source) {
+            return Ok(());
+        }
+        if let Some(cooldown) = self.change_cooldown().await {
+            info!(
+                "Chain {}: postponing voltage change to {} by {:?}",
+                self.hashboard_idx, voltage, cooldown
+            );
+            self.pending_change.lock().await.voltage = Some((voltage, source));
+            return Ok(());
+        }
+        self.set_voltage(voltage, source).await?;
+        self.last_change.lock().await.replace(Instant::now());
+        Ok(())
+    }
+
+    /// Derive frequency from `frequency_control` and load it to chips if it differs from the
+    /// current one. This is the only place where runtime frequency adjustments are applied.
+    /// When the last change happened less than `min_change_interval` ago, the frequency is
+    /// derived again (from the latest requests) after the interval elapses.
+    async fn apply_frequency_control(&self, source: audit::Source) -> error::Result<()> {
+        if let Some(cooldown) = self.change_cooldown().await {
+            info!(
+                "Chain {}: postponing frequency change by {:?}",
+                self.hashboard_idx, cooldown
+            );
+            self.pending_change.lock().await.frequency = Some(source);
+            return Ok(());
+        }
+        if self.load_frequency_control(source).await? {
+            self.last_change.lock().await.replace(Instant::now());
+        }
+        Ok(())
+    }
+
+    /// Load frequency derived from `frequency_control` to chips. Returns false if it is the same
+    /// as the current one.
+    async fn load_frequency_control(&self, source: audit::Source) -> error::Result<bool> {
+        let mut frequency = self.frequency_control.lock().await.resolve();
+        frequency.set_chip_count(self.chip_count);
+        if frequency.chip == self.frequency.lock().await.chip {
+            return Ok(false);
+        }
+        info!(
+            "Chain {}: applying frequency {}",
+            self.hashboard_idx, frequency
+        );
+        self.set_pll(&frequency, source).await?;
+        Ok(true)
+    }
+
+    /// Apply changes postponed due to `min_change_interval` (all at once)
+    async fn apply_pending_change(&self) -> error::Result<()> {
+        let pending_change = std::mem::take(&mut *self.pending_change.lock().await);
+        let mut changed = false;
+        if let Some((voltage, source)) = pending_change.voltage {
+            self.set_voltage(voltage, source).await?;
+            changed = true;
+        }
+        if let Some(source) = pending_change.frequency {
+            changed |= self.load_frequency_control(source).await?;
+        }
+        if changed {
+            self.last_change.lock().await.replace(Instant::now());
+        }
+        Ok(())
+    }
 
+    /// Replace configured frequency and voltage immediately (regardless of
+    /// `min_change_interval`), postponed changes are discarded
+    pub async fn apply_settings(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+        source: audit::Source,
+    ) -> error::Result<()> {
+        *self.pending_change.lock().await = Default::default();
+        // Chips must never run at frequency that is too high for the voltage: voltage is raised
+        // before frequency is changed and it is lowered afterwards
+        let raise_voltage = match self.voltage_ctrl.get_current_voltage().await {
+            Some(current_voltage) => voltage.as_volts() >= current_voltage.as_volts(),
+            None => true,
+        };
+        if raise_voltage {
+            self.set_voltage(voltage, source).await?;
+        }
+        self.frequency_control.lock().await.base = frequency.clone();
+        self.load_frequency_control(source).await?;
+        if !raise_voltage {
+            self.set_voltage(voltage, source).await?;
+        }
+        self.last_change.lock().await.replace(Instant::now());
         Ok(())
     }
 
+    /// Check `voltage` against minimal voltage required for `frequency` when voltage guard is
+    /// enabled (see `required_voltage`)
+    pub fn guard_voltage(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+    ) -> error::Result<Option<power::Voltage>> {
+        let raised_voltage = match self.voltage_guard {
+            Some(voltage_guard) => required_voltage(&voltage_guard, frequency, voltage)?,
+            None => None,
+        };
+        if let Some(raised_voltage) = raised_voltage {
+            info!(
+                "Chain {}: raising voltage from {} to {} required for frequency {} MHz",
+                self.hashboard_idx,
+                voltage,
+                raised_voltage,
+                frequency.max() as f64 / 1_000_000.0
+            );
+        }
+        Ok(raised_voltage)
+    }
+
+    /// Configured frequency before runtime adjustments
+    pub async fn get_base_frequency(&self) -> FrequencySettings {
+        self.frequency_control.lock().await.base.clone()
+    }
+
+    /// Replace configured frequency (runtime adjustments like throttling still apply). Ramp up
+    /// of frequency in progress is cancelled.
+    pub async fn set_base_frequency(
+        &self,
+        frequency: &FrequencySettings,
+        source: audit::Source,
+    ) -> error::Result<()> {
+        {
+            let mut frequency_control = self.frequency_control.lock().await;
+            frequency_control.base = frequency.clone();
+            frequency_control.ramp_target = None;
+        }
+        self.apply_frequency_control(source).await
+    }
+
+    /// Start raising base frequency step by step up to `target` (see `ramp_up_base_frequency`)
+    pub async fn start_frequency_ramp(&self, target: &FrequencySettings) {
+        self.frequency_control.lock().await.ramp_target = Some(target.clone());
+    }
+
+    /// Raise base frequency by one frequency step towards ramp target. Returns false when the
+    /// ramp is over (target is reached, lowered to the base or the ramp is cancelled).
+    pub async fn ramp_up_base_frequency(&self) -> error::Result<bool> {
+        if !self
+            .frequency_control
+            .lock()
+            .await
+            .ramp_up(self.frequency_step)
+        {
+            return Ok(false);
+        }
+        self.apply_frequency_control(audit::Source::Config).await?;
+        Ok(true)
+    }
+
+    /// Lower frequency by `throttled_by` aligned to frequency step (but not below
+    /// `min_frequency`)
+    pub async fn set_throttle(
+        &self,
+        throttled_by: usize,
+        min_frequency: usize,
+    ) -> error::Result<()> {
+        if self.ignores(audit::Source::Throttle) {
+            return Ok(());
+        }
+        {
+            let mut frequency_control = self.frequency_control.lock().await;
+            frequency_control.throttled_by =
+                FrequencyControl::align(throttled_by, self.frequency_step);
+            frequency_control.throttle_min_frequency = min_frequency;
+        }
+        self.apply_frequency_control(audit::Source::Throttle).await
+    }
+
+    /// Permanently lower frequency of all chips by `step` aligned to frequency step (but not
+    /// below `min_frequency`). Returns false if the frequency cannot be lowered anymore.
+    pub async fn lower_base_frequency(
+        &self,
+        step: usize,
+        min_frequency: usize,
+        source: audit::Source,
+    ) -> error::Result<bool> {
+        if self.ignores(source) {
+            return Ok(false);
+        }
+        {
+            let mut frequency_control = self.frequency_control.lock().await;
+            let step = FrequencyControl::align(step, self.frequency_step);
+            let base = frequency_control.base.throttle(step, min_frequency);
+            if base.chip == frequency_control.base.chip {
+                return Ok(false);
+            }
+            frequency_control.lower_base(base);
+        }
+        self.apply_frequency_control(source).await?;
+        Ok(true)
+    }
+
     /// Configure all chips in the hash chain
     ///
     /// This method programs the MiscCtrl register of each chip in the hash chain.
@@ -628,6 +1061,15 @@ impl HashChain {
         self.chip_count
     }
 
+    /// Return how nonce space of work is split among discovered chips
+    pub fn nonce_partition(&self) -> nonce_partition::ChainPartition {
+        nonce_partition::ChainPartition::new(
+            self.hashboard_idx,
+            self.midstate_count.to_count(),
+            self.chip_count,
+        )
+    }
+
     /// Initialize cores by sending open-core work with correct nbits to each core
     async fn send_init_work(&mut self, work_registry: Arc<Mutex<registry::WorkRegistry>>) {
         // Each core gets one work
@@ -655,16 +1097,25 @@ impl HashChain {
     /// generator.
     /// It exits when generator returns `None`.
     async fn work_tx_task(
+        self: Arc<Self>,
         work_registry: Arc<Mutex<registry::WorkRegistry>>,
         mut tx_fifo: io::WorkTx,
         mut work_generator: work::Generator,
     ) {
+        if let Some(work_queue_depth) = self.work_queue_depth {
+            tx_fifo.set_queue_depth(work_queue_depth);
+        }
         loop {
             tx_fifo.wait_for_room().await.expect("wait for tx room");
+            // Zero means that chips may be already starving
+            *self.queued_work.lock().await = tx_fifo.queued_work();
             let work = work_generator.generate().await;
             match work {
                 None => return,
                 Some(work) => {
+                    if let Some(overlap_detector) = self.overlap_detector.as_ref() {
+                        overlap_detector.check_work(self.hashboard_idx, &work);
+                    }
                     // assign `work_id` to `work`
                     let work_id = work_registry.lock().await.store_work(work.clone(), false);
                     // send work is synchronous
@@ -717,7 +1168,26 @@ impl HashChain {
                             } else {
                                 counter.lock().await.add_valid(core_addr);
                             }
-                            solution_sender.send(unique_solution);
+                            let verdict = match self.duplicate_filter.as_ref() {
+                                Some(duplicate_filter) => duplicate_filter.check(hash),
+                                None => duplicate_shares::Verdict::Unique,
+                            };
+                            if verdict != duplicate_shares::Verdict::Unique {
+                                warn!(
+                                    "Chain {}: share {} has already been submitted{}",
+                                    self.hashboard_idx,
+                                    hash,
+                                    if verdict.should_submit() {
+                                        ""
+                                    } else {
+                                        ", suppressing it"
+                                    }
+                                );
+                                counter.lock().await.add_duplicate_share(verdict);
+                            }
+                            if verdict.should_submit() {
+                                solution_sender.send(unique_solution);
+                            }
                         }
                     }
                     if status.duplicate {
@@ -845,6 +1315,23 @@ impl HashChain {
         }
     }
 
+    /// Apply frequency and voltage changes postponed due to `min_change_interval` once the
+    /// interval elapses
+    async fn pending_change_task(self: Arc<Self>) {
+        loop {
+            delay_for(PENDING_CHANGE_CHECK_INTERVAL).await;
+            if self.change_cooldown().await.is_some() {
+                continue;
+            }
+            if let Err(e) = self.apply_pending_change().await {
+                error!(
+                    "Chain {}: failed to apply postponed change: {}",
+                    self.hashboard_idx, e
+                );
+            }
+        }
+    }
+
     /// Hashrate monitor task
     /// Fetch perodically information about hashrate
     #[allow(dead_code)]
@@ -884,6 +1371,7 @@ impl HashChain {
             .register_client("work-tx".into())
             .await
             .spawn(Self::work_tx_task(
+                self.clone(),
                 work_registry.clone(),
                 tx_fifo,
                 work_generator,
@@ -916,6 +1404,14 @@ impl HashChain {
             .register_client("temperature monitor".into())
             .await
             .spawn(Self::monitor_watchdog_temp_task(self.clone()));
+
+        // spawn task applying postponed frequency and voltage changes
+        if self.min_change_interval > Duration::from_secs(0) {
+            self.halt_receiver
+                .register_client("pending change".into())
+                .await
+                .spawn(Self::pending_change_task(self.clone()));
+        }
     }
 
     pub async fn reset_counter(&self) {
@@ -936,6 +1432,132 @@ impl HashChain {
             .await
             .expect("BUG: no voltage on hashchain")
     }
+
+    pub async fn get_hw_error_rate(&self) -> Option<f64> {
+        *self.hw_error_rate.lock().await
+    }
+
+    pub async fn get_hashrate_ratio(&self) -> Option<f64> {
+        *self.hashrate_ratio.lock().await
+    }
+
+    pub fn get_work_queue_depth(&self) -> Option<usize> {
+        self.work_queue_depth
+    }
+
+    pub async fn get_queued_work(&self) -> usize {
+        *self.queued_work.lock().await
+    }
+
+    pub async fn get_voltage_readback(&self) -> Option<power::VoltageReadback> {
+        *self.voltage_readback.lock().await
+    }
+
+    /// Read voltage back from voltage controller and take configured action when it doesn't
+    /// match the requested voltage
+    pub async fn check_voltage(&self) -> error::Result<()> {
+        let readback = self
+            .voltage_ctrl
+            .read_back_voltage(self.voltage_check.tolerance)
+            .await?;
+        *self.voltage_readback.lock().await = readback;
+        let readback = match readback {
+            Some(readback) if readback.is_mismatch() => readback,
+            _ => return Ok(()),
+        };
+        warn!(
+            "Chain {}: measured voltage {} differs from requested voltage {} by more than {:.02} V",
+            self.hashboard_idx, readback.measured, readback.requested, readback.tolerance
+        );
+        match self.voltage_check.action {
+            config::VoltageMismatchAction::Warn => {}
+            config::VoltageMismatchAction::Throttle => {
+                if self
+                    .lower_base_frequency(
+                        self.frequency_step,
+                        (config::FREQUENCY_MHZ_MIN * 1_000_000.0) as usize,
+                        audit::Source::VoltageCheck,
+                    )
+                    .await?
+                {
+                    info!(
+                        "Chain {}: lowered frequency by {} MHz due to voltage mismatch",
+                        self.hashboard_idx,
+                        self.frequency_step / 1_000_000
+                    );
+                }
+            }
+            config::VoltageMismatchAction::Shutdown => {
+                self.monitor_tx
+                    .unbounded_send(monitor::Message::Failed("voltage mismatch"))
+                    .expect("BUG: send failed");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Settings of running hash chain replaced at once by `Backend::apply_chain_configs`
+#[async_trait]
+pub trait ChainSettings: Send + Sync {
+    fn hashboard_idx(&self) -> usize;
+
+    fn chip_count(&self) -> usize;
+
+    /// See `HashChain::guard_voltage`
+    fn guard_voltage(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+    ) -> error::Result<Option<power::Voltage>>;
+
+    async fn get_base_frequency(&self) -> FrequencySettings;
+
+    async fn get_voltage(&self) -> power::Voltage;
+
+    /// See `HashChain::apply_settings`
+    async fn apply_settings(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+        source: audit::Source,
+    ) -> error::Result<()>;
+}
+
+#[async_trait]
+impl ChainSettings for HashChain {
+    fn hashboard_idx(&self) -> usize {
+        self.hashboard_idx
+    }
+
+    fn chip_count(&self) -> usize {
+        self.chip_count
+    }
+
+    fn guard_voltage(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+    ) -> error::Result<Option<power::Voltage>> {
+        HashChain::guard_voltage(self, frequency, voltage)
+    }
+
+    async fn get_base_frequency(&self) -> FrequencySettings {
+        HashChain::get_base_frequency(self).await
+    }
+
+    async fn get_voltage(&self) -> power::Voltage {
+        HashChain::get_voltage(self).await
+    }
+
+    async fn apply_settings(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+        source: audit::Source,
+    ) -> error::Result<()> {
+        HashChain::apply_settings(self, frequency, voltage, source).await
+    }
 }
 
 impl fmt::Debug for HashChain {
@@ -952,7 +1574,88 @@ impl fmt::Display for HashChain {
 
 type Frequency = usize;
 
-#[derive(Clone)]
+/// Runtime frequency and voltage changes postponed due to `min_change_interval`. Repeated
+/// requests are coalesced, only the latest one is applied.
+#[derive(Default)]
+struct PendingChange {
+    /// Frequency is derived from `FrequencyControl` when the change is applied
+    frequency: Option<audit::Source>,
+    voltage: Option<(power::Voltage, audit::Source)>,
+}
+
+/// Sources of runtime frequency adjustments. Frequency loaded to chips is always derived from
+/// all of them so that e.g. recovery from throttling returns to the corrected base frequency.
+struct FrequencyControl {
+    /// Configured frequency adjusted by corrective actions (e.g. due to hardware errors)
+    base: FrequencySettings,
+    /// Frequency the base is raised to step by step when the hash chain is initialized at lower
+    /// frequency. It is lowered together with the base so that the ramp doesn't undo corrective
+    /// actions.
+    ramp_target: Option<FrequencySettings>,
+    /// Throttling (in Hz) requested by monitor
+    throttled_by: usize,
+    /// Frequency below which the throttling doesn't lower chips
+    throttle_min_frequency: usize,
+}
+
+impl FrequencyControl {
+    fn new(base: FrequencySettings) -> Self {
+        Self {
+            base,
+            ramp_target: None,
+            throttled_by: 0,
+            throttle_min_frequency: 0,
+        }
+    }
+
+    /// Lower base frequency to `base`. Ramp target of chips that have been lowered is capped by
+    /// their new frequency.
+    fn lower_base(&mut self, base: FrequencySettings) {
+        if let Some(ramp_target) = self.ramp_target.as_mut() {
+            for ((target, &frequency), &old_frequency) in ramp_target
+                .chip
+                .iter_mut()
+                .zip(base.chip.iter())
+                .zip(self.base.chip.iter())
+            {
+                if frequency < old_frequency {
+                    *target = (*target).min(frequency);
+                }
+            }
+        }
+        self.base = base;
+    }
+
+    /// Raise base frequency by `step` towards ramp target. Returns false when the ramp is over.
+    fn ramp_up(&mut self, step: usize) -> bool {
+        let base = match self.ramp_target.as_ref() {
+            Some(ramp_target) => self.base.ramp_up(ramp_target, step),
+            None => return false,
+        };
+        if base.chip == self.base.chip {
+            self.ramp_target = None;
+            return false;
+        }
+        self.base = base;
+        true
+    }
+
+    fn resolve(&self) -> FrequencySettings {
+        self.base
+            .throttle(self.throttled_by, self.throttle_min_frequency)
+    }
+
+    /// Round frequency `adjustment` to the nearest multiple of `step` (non-zero adjustment is
+    /// always at least one step so that it is not lost)
+    fn align(adjustment: usize, step: usize) -> usize {
+        if adjustment == 0 || step == 0 {
+            return adjustment;
+        }
+        ((adjustment + step / 2) / step).max(1) * step
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct FrequencySettings {
     pub chip: Vec<Frequency>,
 }
@@ -970,6 +1673,26 @@ impl FrequencySettings {
         self.chip.resize(chip_count, 0);
     }
 
+    /// PLL writes needed to get first `chip_count` chips from `current` frequency to this one.
+    /// Identical frequencies are loaded in one go, otherwise only chips whose frequency differs
+    /// are updated one-by-one.
+    pub fn pll_updates(
+        &self,
+        current: &FrequencySettings,
+        chip_count: usize,
+    ) -> Vec<(ChipAddress, usize)> {
+        // Check if the frequencies are identical
+        if self.min() == self.max() {
+            // Update them in one go
+            return vec![(ChipAddress::All, self.chip[0])];
+        }
+        // Update chips one-by-one
+        (0..chip_count)
+            .filter(|&i| current.chip[i] != self.chip[i])
+            .map(|i| (ChipAddress::One(i), self.chip[i]))
+            .collect()
+    }
+
     pub fn total(&self) -> u64 {
         self.chip.iter().fold(0, |total_f, &f| total_f + f as u64)
     }
@@ -984,15 +1707,106 @@ impl FrequencySettings {
         *self.chip.iter().max().expect("BUG: no chips on chain")
     }
 
+    /// Build frequency settings with every chip lowered by `throttled_by` but not below
+    /// `min_frequency` (chips that are already slower are left untouched)
+    pub fn throttle(&self, throttled_by: usize, min_frequency: usize) -> Self {
+        Self {
+            chip: self
+                .chip
+                .iter()
+                .map(|&frequency| {
+                    frequency
+                        .saturating_sub(throttled_by)
+                        .max(min_frequency.min(frequency))
+                })
+                .collect(),
+        }
+    }
+
+    /// Build frequency settings with every chip limited to `max_frequency`
+    pub fn cap(&self, max_frequency: usize) -> Self {
+        Self {
+            chip: self
+                .chip
+                .iter()
+                .map(|&frequency| frequency.min(max_frequency))
+                .collect(),
+        }
+    }
+
+    /// Build frequency settings with every chip raised by `step` towards its frequency in
+    /// `target` (chips never exceed the target frequency)
+    pub fn ramp_up(&self, target: &Self, step: usize) -> Self {
+        Self {
+            chip: self
+                .chip
+                .iter()
+                .zip(target.chip.iter())
+                .map(|(&frequency, &target)| (frequency + step).min(target.max(frequency)))
+                .collect(),
+        }
+    }
+
     pub fn avg(&self) -> usize {
         assert!(self.chip.len() > 0, "BUG: no chips on chain");
         let sum: u64 = self.chip.iter().map(|frequency| *frequency as u64).sum();
         (sum / self.chip.len() as u64) as usize
     }
 
-    fn pretty_frequency(freq: usize) -> String {
-        format!("{:.01} MHz", (freq as f32) / 1_000_000.0)
-    }
+    /// Group chips by requested frequency and look up PLL divider chosen for each group (the
+    /// divider frequency differs from the requested one due to rounding)
+    pub fn pll_settings(&self) -> Vec<PllSetting> {
+        let mut chip_counts = BTreeMap::new();
+        for &frequency in self.chip.iter() {
+            *chip_counts.entry(frequency).or_insert(0) += 1;
+        }
+        chip_counts
+            .into_iter()
+            .map(|(frequency, chip_count)| PllSetting {
+                frequency,
+                chip_count,
+                pll: bm1387::PllFrequency::lookup_freq(frequency).ok(),
+            })
+            .collect()
+    }
+
+    fn pretty_frequency(freq: usize) -> String {
+        format!("{:.01} MHz", (freq as f32) / 1_000_000.0)
+    }
+}
+
+/// PLL divider of chips with the same requested frequency
+#[derive(Clone, Debug)]
+pub struct PllSetting {
+    /// Requested frequency in Hz
+    pub frequency: Frequency,
+    pub chip_count: usize,
+    /// Divider closest to the requested frequency (`None` when the frequency is out of range)
+    pub pll: Option<bm1387::PllFrequency>,
+}
+
+impl fmt::Display for PllSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {} chip(s) ",
+            FrequencySettings::pretty_frequency(self.frequency),
+            self.chip_count
+        )?;
+        match self.pll.as_ref() {
+            Some(pll) => write!(
+                f,
+                "-> PLL {} (reg={:#08x}, fbdiv={}, refdiv={}, postdiv1={}, postdiv2={})",
+                FrequencySettings::pretty_frequency(pll.frequency),
+                pll.reg.to_reg(),
+                pll.reg.fbdiv,
+                pll.reg.refdiv,
+                pll.reg.postdiv1,
+                pll.reg.postdiv2
+            ),
+            None => write!(f, "-> out of PLL range"),
+        }
+    }
 }
 
 impl fmt::Display for FrequencySettings {
@@ -1155,11 +1969,19 @@ impl RunningChain {
 
     pub async fn set_frequency(&self, frequency: &FrequencySettings) -> error::Result<()> {
         let inner = self.manager.inner.lock().await;
-        inner
+        let hash_chain = inner
             .hash_chain
             .as_ref()
-            .expect("BUG: hashchain is not running")
-            .set_pll(frequency)
+            .expect("BUG: hashchain is not running");
+        // voltage has to be raised before the frequency
+        let voltage = hash_chain.get_voltage().await;
+        if let Some(voltage) = hash_chain.guard_voltage(frequency, voltage)? {
+            hash_chain
+                .set_voltage(voltage, audit::Source::External)
+                .await?;
+        }
+        hash_chain
+            .set_base_frequency(frequency, audit::Source::External)
             .await
     }
 
@@ -1169,8 +1991,7 @@ impl RunningChain {
             .hash_chain
             .as_ref()
             .expect("BUG: hashchain is not running")
-            .voltage_ctrl
-            .set_voltage(voltage)
+            .request_voltage(voltage, audit::Source::External)
             .await
     }
 
@@ -1287,6 +2108,8 @@ pub struct ManagerInner {
     pub hash_chain: Option<Arc<HashChain>>,
     /// Each (attempted) hashchain start increments this counter by 1
     pub start_count: usize,
+    /// Reason of the last failed hashchain start
+    pub start_failure: Option<String>,
 }
 
 /// Hashchain manager that can start and stop instances of hashchain
@@ -1310,6 +2133,10 @@ pub struct Manager {
     owned_by: StdMutex<Option<&'static str>>,
     pub inner: Mutex<ManagerInner>,
     pub chain_config: config::ResolvedChainConfig,
+    /// Client of the pool group assigned to this hash chain (work is drawn from it exclusively)
+    pub pool_client: Option<Arc<client::Handle>>,
+    overlap_detector: Option<Arc<nonce_partition::OverlapDetector>>,
+    duplicate_filter: Arc<duplicate_shares::Filter>,
 }
 
 impl Manager {
@@ -1370,10 +2197,32 @@ impl Manager {
             self.monitor_tx.clone(),
         )
         .expect("BUG: hashchain instantiation failed");
+        hash_chain.frequency_step = self.chain_config.frequency_step;
+        hash_chain.voltage_check = self.chain_config.voltage_check;
+        hash_chain.pll_lock_check = self.chain_config.pll_lock_check;
+        hash_chain.voltage_guard = self.chain_config.voltage_guard;
+        hash_chain.overlap_detector = self.overlap_detector.clone();
+        hash_chain.duplicate_filter = Some(self.duplicate_filter.clone());
+        hash_chain.work_queue_depth = self.chain_config.work_queue_depth;
+        hash_chain.min_change_interval = self.chain_config.min_change_interval;
+        hash_chain.frozen = self.chain_config.frozen;
+        hash_chain.power_on_delay = self.chain_config.power_on_delay;
+
+        // chains which don't come up reliably at the target frequency are initialized at lower
+        // frequency and ramped up afterwards
+        let init_frequency = self
+            .chain_config
+            .init_frequency
+            .map(|init_frequency| initial_frequency.cap(init_frequency))
+            .filter(|init_frequency| init_frequency.chip != initial_frequency.chip);
 
         // initialize it
         let work_registry = match hash_chain
-            .init(initial_frequency, initial_voltage, accept_less_chips)
+            .init(
+                init_frequency.as_ref().unwrap_or(initial_frequency),
+                initial_voltage,
+                accept_less_chips,
+            )
             .await
         {
             Err(e) => {
@@ -1388,6 +2237,20 @@ impl Manager {
             }
             Ok(a) => a,
         };
+        if init_frequency.is_some() {
+            hash_chain.start_frequency_ramp(initial_frequency).await;
+        }
+
+        let partition = hash_chain.nonce_partition();
+        if let Err(e) = partition.check() {
+            error!("Invalid nonce partition: {}", e);
+        } else if !partition.missing_slices().is_empty() {
+            warn!(
+                "Chain {}: nonce slices {:?} are not explored by any chip",
+                self.hashboard_idx,
+                partition.missing_slices()
+            );
+        }
 
         // spawn worker tasks for hash chain and start mining
         let hash_chain = Arc::new(hash_chain);
@@ -1400,12 +2263,228 @@ impl Manager {
             )
             .await;
 
+        // follow frequency throttling decided by monitor
+        hash_chain
+            .halt_receiver
+            .register_client("throttle".into())
+            .await
+            .spawn(Self::throttle_task(
+                hash_chain.clone(),
+                self.status_receiver.clone(),
+            ));
+
+        // measure hardware errors and correct them
+        hash_chain
+            .halt_receiver
+            .register_client("hw errors".into())
+            .await
+            .spawn(Self::hw_error_task(
+                hash_chain.clone(),
+                self.chain_config.hw_error_control.clone(),
+            ));
+
+        // periodically verify voltage applied by voltage controller
+        hash_chain
+            .halt_receiver
+            .register_client("voltage check".into())
+            .await
+            .spawn(Self::voltage_check_task(hash_chain.clone()));
+
+        // raise frequency step by step to the target frequency
+        if init_frequency.is_some() {
+            hash_chain
+                .halt_receiver
+                .register_client("frequency ramp".into())
+                .await
+                .spawn(Self::frequency_ramp_task(hash_chain.clone()));
+        }
+
         // remember we started
         inner.hash_chain.replace(hash_chain);
 
         Ok(())
     }
 
+    /// Task that raises frequency of hashchain initialized at lower frequency by one frequency
+    /// step at a time until it reaches ramp target (see `HashChain::start_frequency_ramp`)
+    async fn frequency_ramp_task(hash_chain: Arc<HashChain>) {
+        loop {
+            delay_for(FREQUENCY_RAMP_INTERVAL).await;
+            match hash_chain.ramp_up_base_frequency().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    info!(
+                        "Chain {}: ramped up to frequency {}",
+                        hash_chain.hashboard_idx,
+                        hash_chain.get_base_frequency().await
+                    );
+                    break;
+                }
+                Err(e) => {
+                    error!(
+                        "Chain {}: failed to ramp up frequency: {}",
+                        hash_chain.hashboard_idx, e
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Task that applies frequency throttling of this hashchain as decided by monitor
+    async fn throttle_task(
+        hash_chain: Arc<HashChain>,
+        mut status_receiver: watch::Receiver<Option<monitor::Status>>,
+    ) {
+        let mut current_throttled_by = 0;
+        while let Some(status) = status_receiver.next().await {
+            let status = match status {
+                Some(status) => status,
+                None => continue,
+            };
+            let throttled_by = status.throttled_by(hash_chain.hashboard_idx);
+            if throttled_by == current_throttled_by {
+                continue;
+            }
+            let min_frequency = status
+                .config
+                .throttle_config
+                .as_ref()
+                .map(|throttle_config| throttle_config.min_frequency)
+                .unwrap_or(0);
+            match hash_chain.set_throttle(throttled_by, min_frequency).await {
+                Ok(_) => current_throttled_by = throttled_by,
+                Err(e) => error!(
+                    "Chain {}: failed to apply throttled frequency: {}",
+                    hash_chain.hashboard_idx, e
+                ),
+            }
+        }
+    }
+
+    /// Task that measures hardware error rate (and hashrate ratio) of this hashchain over the
+    /// hashrate interval and takes corrective action when the rate exceeds configured threshold
+    async fn hw_error_task(
+        hash_chain: Arc<HashChain>,
+        hw_error_control: Option<config::HwErrorControlConfig>,
+    ) {
+        let started = Instant::now();
+        let mut previous = hash_chain.snapshot_counter().await;
+        loop {
+            delay_for(config::DEFAULT_HASHRATE_INTERVAL).await;
+            let current = hash_chain.snapshot_counter().await;
+            let hw_error_rate = current.error_rate_since(&previous);
+            *hash_chain.hw_error_rate.lock().await = hw_error_rate;
+            let expected_hashrate = (hash_chain.frequency.lock().await.total()
+                * bm1387::NUM_CORES_ON_CHIP as u64) as f64;
+            *hash_chain.hashrate_ratio.lock().await = current
+                .hashrate_since(&previous)
+                .filter(|_| expected_hashrate > 0.0)
+                .map(|hashrate| hashrate / expected_hashrate);
+            previous = current;
+
+            let (hw_error_rate, hw_error_control) = match (hw_error_rate, &hw_error_control) {
+                (Some(hw_error_rate), Some(hw_error_control))
+                    if hw_error_rate > hw_error_control.threshold =>
+                {
+                    (hw_error_rate, hw_error_control)
+                }
+                _ => continue,
+            };
+            if hash_chain.frozen {
+                info!(
+                    "Chain {}: hardware error rate {:.2}% exceeds threshold {:.2}% but the chain \
+                     is frozen, no corrective action taken",
+                    hash_chain.hashboard_idx,
+                    hw_error_rate * 100.0,
+                    hw_error_control.threshold * 100.0
+                );
+                continue;
+            }
+            if started.elapsed() < hw_error_control.warmup_period {
+                info!(
+                    "Chain {}: hardware error rate {:.2}% exceeds threshold {:.2}% while warming \
+                     up, no corrective action taken",
+                    hash_chain.hashboard_idx,
+                    hw_error_rate * 100.0,
+                    hw_error_control.threshold * 100.0
+                );
+                continue;
+            }
+            warn!(
+                "Chain {}: hardware error rate {:.2}% exceeds threshold {:.2}%",
+                hash_chain.hashboard_idx,
+                hw_error_rate * 100.0,
+                hw_error_control.threshold * 100.0
+            );
+            if let Err(e) = Self::correct_hw_errors(&hash_chain, hw_error_control).await {
+                error!(
+                    "Chain {}: failed to correct hardware errors: {}",
+                    hash_chain.hashboard_idx, e
+                );
+            }
+        }
+    }
+
+    /// Task that periodically reads voltage back from voltage controller
+    async fn voltage_check_task(hash_chain: Arc<HashChain>) {
+        loop {
+            delay_for(VOLTAGE_CHECK_INTERVAL).await;
+            if let Err(e) = hash_chain.check_voltage().await {
+                error!(
+                    "Chain {}: voltage check failed: {}",
+                    hash_chain.hashboard_idx, e
+                );
+            }
+        }
+    }
+
+    /// Bump voltage or lower frequency by one step according to the configured strategy. When
+    /// voltage is already at its maximum, the frequency is lowered instead.
+    async fn correct_hw_errors(
+        hash_chain: &HashChain,
+        hw_error_control: &config::HwErrorControlConfig,
+    ) -> error::Result<()> {
+        if hw_error_control.strategy == config::HwErrorStrategy::Voltage {
+            let voltage = hash_chain.get_voltage().await;
+            let new_voltage = voltage.offset_volts(hw_error_control.voltage_step)?;
+            if new_voltage.as_volts() <= hw_error_control.max_voltage {
+                info!(
+                    "Chain {}: increasing voltage from {} to {} due to hardware errors",
+                    hash_chain.hashboard_idx, voltage, new_voltage
+                );
+                hash_chain
+                    .request_voltage(new_voltage, audit::Source::HwErrorControl)
+                    .await?;
+                return hash_chain.check_voltage().await;
+            }
+            warn!(
+                "Chain {}: voltage {} cannot be increased anymore, lowering frequency instead",
+                hash_chain.hashboard_idx, voltage
+            );
+        }
+        if hash_chain
+            .lower_base_frequency(
+                hw_error_control.frequency_step,
+                hw_error_control.min_frequency,
+                audit::Source::HwErrorControl,
+            )
+            .await?
+        {
+            info!(
+                "Chain {}: lowered frequency by {} MHz due to hardware errors",
+                hash_chain.hashboard_idx,
+                hw_error_control.frequency_step / 1_000_000
+            );
+        } else {
+            warn!(
+                "Chain {}: frequency cannot be lowered anymore, no corrective action taken",
+                hash_chain.hashboard_idx
+            );
+        }
+        Ok(())
+    }
+
     /// TODO: this function is private and should be called only from `RunningChain`
     async fn stop_chain(&self, its_ok_if_its_missing: bool) {
         // lock inner to guarantee atomicity of hashchain stop
@@ -1508,6 +2587,57 @@ impl hal::BackendSolution for Solution {
     }
 }
 
+/// Change of hashboard presence found by hotplug scan
+#[derive(Debug, PartialEq)]
+pub enum HotplugEvent {
+    Inserted,
+    Removed,
+}
+
+impl HotplugEvent {
+    /// Compare presence of hashboard with the previous scan which is updated accordingly
+    pub fn detect(was_present: &mut bool, is_present: bool) -> Option<Self> {
+        if is_present == *was_present {
+            return None;
+        }
+        *was_present = is_present;
+        Some(if is_present {
+            Self::Inserted
+        } else {
+            Self::Removed
+        })
+    }
+}
+
+/// Limits number of hash chains initialized at once (all of them when there is no limit)
+#[derive(Clone)]
+pub struct InitSlots {
+    semaphore: Arc<tokio::sync::Semaphore>,
+    limit: Option<usize>,
+}
+
+impl InitSlots {
+    pub fn new(limit: Option<usize>, chain_count: usize) -> Self {
+        Self {
+            semaphore: Arc::new(tokio::sync::Semaphore::new(limit.unwrap_or(chain_count))),
+            limit,
+        }
+    }
+
+    /// Run initialization of hash chain `hashboard_idx` once there is a free slot
+    pub async fn run<F: std::future::Future>(&self, hashboard_idx: usize, init: F) -> F::Output {
+        if let Some(limit) = self.limit {
+            info!(
+                "Chain {} waiting for initialization slot ({} at once)",
+                hashboard_idx, limit
+            );
+        }
+        let _init_slot = self.semaphore.acquire().await;
+        info!("Chain {} initialization started", hashboard_idx);
+        init.await
+    }
+}
+
 #[derive(Debug, WorkSolverNode)]
 pub struct Backend {
     #[member_work_solver_stats]
@@ -1524,8 +2654,7 @@ impl Backend {
     /// Enumerate present hashboards by querying the plug pin
     pub fn detect_hashboards(gpio_mgr: &gpio::ControlPinManager) -> error::Result<Vec<usize>> {
         let mut detected = vec![];
-        // TODO: configure this range somewhere
-        for hashboard_idx in 1..=8 {
+        for hashboard_idx in HASHBOARD_INDEX_RANGE {
             let plug_pin = PlugPin::open(gpio_mgr, hashboard_idx)?;
             if plug_pin.hashboard_present()? {
                 detected.push(hashboard_idx);
@@ -1534,12 +2663,572 @@ impl Backend {
         Ok(detected)
     }
 
+    /// Resolve configuration of all managed hash chains (including system power limit). Fails
+    /// when configuration of any of them is invalid.
+    pub fn resolve_chain_configs(
+        managers: &[Arc<Manager>],
+        backend_config: &config::Backend,
+    ) -> Result<Vec<(usize, config::ResolvedChainConfig)>, String> {
+        let mut chain_configs = Vec::new();
+        for manager in managers.iter() {
+            let chain_config = backend_config
+                .resolve_chain_config(manager.hashboard_idx)
+                .map_err(|e| e.to_string())?;
+            chain_configs.push((manager.hashboard_idx, chain_config));
+        }
+        backend_config
+            .apply_power_limit(&mut chain_configs)
+            .map_err(|e| e.to_string())?;
+        Ok(chain_configs)
+    }
+
+    /// Apply frequency and voltage of resolved `chain_configs` (in the same order as `managers`)
+    /// to all running hash chains at once. When applying fails on some hash chain, the chains
+    /// changed so far are rolled back to their previous settings. Stopped hash chains keep
+    /// configuration resolved on startup. Returns indexes of hash chains that have been changed.
+    pub async fn apply_chain_configs(
+        managers: &[Arc<Manager>],
+        chain_configs: &[(usize, config::ResolvedChainConfig)],
+    ) -> Result<Vec<usize>, String> {
+        // hold all managers so that no hash chain is started or stopped in the meantime
+        let mut inners = Vec::new();
+        for manager in managers.iter() {
+            inners.push(manager.inner.lock().await);
+        }
+        let chain_settings: Vec<_> = inners
+            .iter()
+            .zip(chain_configs.iter())
+            .filter_map(
+                |(inner, (_, chain_config))| match inner.hash_chain.as_ref() {
+                    Some(hash_chain) if chain_config.enabled => Some((
+                        hash_chain.clone(),
+                        chain_config.frequency.clone(),
+                        chain_config.voltage,
+                    )),
+                    _ => None,
+                },
+            )
+            .collect();
+        Self::apply_chain_settings(&chain_settings).await
+    }
+
+    /// Apply frequency and voltage to each hash chain in `chain_settings` or to none of them
+    /// (see `apply_chain_configs`)
+    async fn apply_chain_settings<T: ChainSettings>(
+        chain_settings: &[(Arc<T>, FrequencySettings, power::Voltage)],
+    ) -> Result<Vec<usize>, String> {
+        let mut applied: Vec<(Arc<T>, FrequencySettings, power::Voltage)> = Vec::new();
+        for (hash_chain, frequency, voltage) in chain_settings.iter() {
+            let previous_frequency = hash_chain.get_base_frequency().await;
+            let previous_voltage = hash_chain.get_voltage().await;
+            let mut frequency = frequency.clone();
+            frequency.set_chip_count(hash_chain.chip_count());
+            let result = match hash_chain.guard_voltage(&frequency, *voltage) {
+                Ok(raised_voltage) => {
+                    hash_chain
+                        .apply_settings(
+                            &frequency,
+                            raised_voltage.unwrap_or(*voltage),
+                            audit::Source::ConfigApply,
+                        )
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                error!(
+                    "Chain {}: failed to apply configuration, rolling back {} chain(s): {}",
+                    hash_chain.hashboard_idx(),
+                    applied.len(),
+                    e
+                );
+                // the failed chain may have been changed partially
+                applied.push((hash_chain.clone(), previous_frequency, previous_voltage));
+                for (hash_chain, frequency, voltage) in applied.iter().rev() {
+                    if let Err(e) = hash_chain
+                        .apply_settings(frequency, *voltage, audit::Source::ConfigApply)
+                        .await
+                    {
+                        error!(
+                            "Chain {}: failed to roll back configuration: {}",
+                            hash_chain.hashboard_idx(),
+                            e
+                        );
+                    }
+                }
+                return Err(format!("chain {}: {}", hash_chain.hashboard_idx(), e));
+            }
+            applied.push((hash_chain.clone(), previous_frequency, previous_voltage));
+        }
+        Ok(applied
+            .iter()
+            .map(|(hash_chain, _, _)| hash_chain.hashboard_idx())
+            .collect())
+    }
+
+    /// Periodically scan plug pins of all managed hashboards. Newly inserted hashboard is started
+    /// with its resolved configuration and removed hashboard is stopped.
+    async fn hotplug_task(
+        managers: Vec<Arc<Manager>>,
+        hooks: Arc<dyn hooks::Hooks>,
+        interval: Duration,
+    ) {
+        let mut present: Vec<_> = managers
+            .iter()
+            .map(|manager| manager.plug_pin.hashboard_present().unwrap_or(false))
+            .collect();
+        loop {
+            delay_for(interval).await;
+            for (manager, was_present) in managers.iter().zip(present.iter_mut()) {
+                let is_present = match manager.plug_pin.hashboard_present() {
+                    Ok(is_present) => is_present,
+                    Err(e) => {
+                        warn!(
+                            "Hotplug: chain {} scan failed: {}",
+                            manager.hashboard_idx, e
+                        );
+                        continue;
+                    }
+                };
+                match HotplugEvent::detect(was_present, is_present) {
+                    Some(HotplugEvent::Inserted) => {
+                        info!("Hotplug: hashboard {} inserted", manager.hashboard_idx);
+                        if hooks.can_start_chain(manager.clone()).await {
+                            tokio::spawn(Self::hotplug_start_chain(manager.clone()));
+                        }
+                    }
+                    Some(HotplugEvent::Removed) => {
+                        info!("Hotplug: hashboard {} removed", manager.hashboard_idx);
+                        match manager.clone().acquire("hotplug").await {
+                            Ok(ChainStatus::Running(running_chain)) => {
+                                running_chain.stop().await;
+                            }
+                            Ok(ChainStatus::Stopped(_)) => (),
+                            Err(owner) => warn!(
+                                "Hotplug: cannot stop chain {} owned by {}",
+                                manager.hashboard_idx, owner
+                            ),
+                        }
+                    }
+                    None => (),
+                }
+            }
+        }
+    }
+
+    /// Stop hash chains that are declared failed by monitor watchdog so that the miner keeps
+    /// running with the remaining ones
+    async fn chain_failure_task(
+        managers: Vec<Arc<Manager>>,
+        mut status_receiver: watch::Receiver<Option<monitor::Status>>,
+    ) {
+        while let Some(status) = status_receiver.next().await {
+            let status = match status {
+                Some(status) => status,
+                None => continue,
+            };
+            for manager in managers.iter() {
+                let reason = match status.watchdog_state(manager.hashboard_idx) {
+                    Some(monitor::ChainWatchdogState::Failed(reason)) => *reason,
+                    _ => continue,
+                };
+                match manager.clone().acquire("watchdog").await {
+                    Ok(ChainStatus::Running(running_chain)) => {
+                        error!(
+                            "Continuing without chain {}: {}",
+                            manager.hashboard_idx, reason
+                        );
+                        running_chain.stop().await;
+                        manager.inner.lock().await.start_failure = Some(reason.to_string());
+                    }
+                    Ok(ChainStatus::Stopped(_)) => (),
+                    Err(owner) => warn!(
+                        "Watchdog: cannot stop chain {} owned by {}",
+                        manager.hashboard_idx, owner
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Start newly inserted hashboard
+    async fn hotplug_start_chain(manager: Arc<Manager>) {
+        if restart::tracker().map(|tracker| tracker.recovery()) == Some(restart::Recovery::StayOff)
+        {
+            warn!(
+                "Hotplug: chain {} is kept off because of thermal lockout",
+                manager.hashboard_idx
+            );
+            return;
+        }
+        let stopped_chain = match manager.clone().acquire("hotplug").await {
+            Ok(ChainStatus::Stopped(stopped_chain)) => stopped_chain,
+            Ok(ChainStatus::Running(_)) => return,
+            Err(owner) => {
+                warn!(
+                    "Hotplug: cannot start chain {} owned by {}",
+                    manager.hashboard_idx, owner
+                );
+                return;
+            }
+        };
+        if let Err((_, e)) = stopped_chain
+            .start(
+                &manager.chain_config.frequency,
+                manager.chain_config.voltage,
+                manager.chain_config.asic_difficulty,
+            )
+            .await
+        {
+            error!(
+                "Hotplug: failed to start chain {}: {}",
+                manager.hashboard_idx, e
+            );
+            manager.inner.lock().await.start_failure = Some(e.to_string());
+        }
+    }
+
+    /// Periodically compare measured hashrate of hash chains with hashrate expected from their
+    /// frequency and take configured action when it stays below tolerance for the whole period
+    async fn hashrate_check_task(
+        managers: Vec<Arc<Manager>>,
+        hashrate_check: config::HashrateCheckConfig,
+    ) {
+        let mut low_since: Vec<Option<Instant>> = vec![None; managers.len()];
+        loop {
+            delay_for(config::DEFAULT_HASHRATE_INTERVAL).await;
+            for (manager, low_since) in managers.iter().zip(low_since.iter_mut()) {
+                let hash_chain = match manager.inner.lock().await.hash_chain.as_ref() {
+                    Some(hash_chain) => hash_chain.clone(),
+                    None => {
+                        *low_since = None;
+                        continue;
+                    }
+                };
+                let hashrate_ratio = match detect_hashrate_drop(
+                    low_since,
+                    hash_chain.get_hashrate_ratio().await,
+                    &hashrate_check,
+                    Instant::now(),
+                ) {
+                    Some(hashrate_ratio) => hashrate_ratio,
+                    None => continue,
+                };
+
+                let message = format!(
+                    "hashrate is at {:.1}% of expected hashrate for {} seconds",
+                    hashrate_ratio * 100.0,
+                    hashrate_check.period.as_secs()
+                );
+                warn!("Chain {}: {}", manager.hashboard_idx, message);
+                events::emit(
+                    events::Kind::HashrateDrop,
+                    Some(manager.hashboard_idx),
+                    message,
+                );
+                if hash_chain.frozen && hashrate_check.action != config::HashrateDropAction::Warn {
+                    info!(
+                        "Chain {}: chain is frozen, no corrective action taken",
+                        manager.hashboard_idx
+                    );
+                    continue;
+                }
+                if let Err(e) = Self::handle_hashrate_drop(manager.clone(), &hashrate_check).await {
+                    error!(
+                        "Chain {}: failed to handle hashrate drop: {}",
+                        manager.hashboard_idx, e
+                    );
+                }
+            }
+        }
+    }
+
+    async fn handle_hashrate_drop(
+        manager: Arc<Manager>,
+        hashrate_check: &config::HashrateCheckConfig,
+    ) -> error::Result<()> {
+        match hashrate_check.action {
+            config::HashrateDropAction::Warn => {}
+            config::HashrateDropAction::Voltage => {
+                let hash_chain = match manager.inner.lock().await.hash_chain.as_ref() {
+                    Some(hash_chain) => hash_chain.clone(),
+                    None => return Ok(()),
+                };
+                let voltage = hash_chain.get_voltage().await;
+                let new_voltage = voltage.offset_volts(hashrate_check.voltage_step)?;
+                if new_voltage.as_volts() > hashrate_check.max_voltage {
+                    warn!(
+                        "Chain {}: voltage {} cannot be increased anymore",
+                        manager.hashboard_idx, voltage
+                    );
+                    return Ok(());
+                }
+                info!(
+                    "Chain {}: increasing voltage from {} to {} due to hashrate drop",
+                    manager.hashboard_idx, voltage, new_voltage
+                );
+                hash_chain
+                    .request_voltage(new_voltage, audit::Source::HashrateCheck)
+                    .await?;
+                hash_chain.check_voltage().await?;
+            }
+            config::HashrateDropAction::Reinit => {
+                let running_chain = match manager.clone().acquire("hashrate check").await {
+                    Ok(ChainStatus::Running(running_chain)) => running_chain,
+                    Ok(ChainStatus::Stopped(_)) => return Ok(()),
+                    Err(owner) => Err(ErrorKind::General(format!(
+                        "cannot reinitialize chain owned by {}",
+                        owner
+                    )))?,
+                };
+                info!(
+                    "Chain {}: reinitializing due to hashrate drop",
+                    manager.hashboard_idx
+                );
+                if let Err((_, e)) = running_chain
+                    .stop()
+                    .await
+                    .start(
+                        &manager.chain_config.frequency,
+                        manager.chain_config.voltage,
+                        manager.chain_config.asic_difficulty,
+                    )
+                    .await
+                {
+                    manager.inner.lock().await.start_failure = Some(e.to_string());
+                    Err(e)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Periodically re-evaluate estimated power consumption of running hash chains (it changes
+    /// with voltage and frequency adjustments at runtime) and lower frequency of the fastest one
+    /// when the total exceeds `limit`.
+    ///
+    /// Frequency is only ever lowered. It is not restored when the estimate falls back under the
+    /// limit because lowered base frequency cannot be told apart from corrective actions of other
+    /// sources (e.g. hardware errors). Configured frequency is restored by restart.
+    async fn power_limit_task(managers: Vec<Arc<Manager>>, limit: f64, min_frequency: usize) {
+        let step = (config::POWER_LIMIT_STEP_MHZ * 1_000_000.0) as usize;
+        loop {
+            delay_for(POWER_LIMIT_CHECK_INTERVAL).await;
+
+            let mut chains = Vec::new();
+            for manager in managers.iter() {
+                if let Some(hash_chain) = manager.inner.lock().await.hash_chain.as_ref() {
+                    chains.push((
+                        hash_chain.clone(),
+                        power::ChainOperatingPoint {
+                            hashboard_idx: manager.hashboard_idx,
+                            frequency: hash_chain.get_frequency().await,
+                            voltage: hash_chain.get_voltage().await,
+                            frozen: hash_chain.frozen,
+                        },
+                    ));
+                }
+            }
+            let mut operating_points: Vec<_> =
+                chains.iter().map(|(_, point)| point.clone()).collect();
+            if let Err(e) =
+                power::fit_power_limit(&mut operating_points, limit, step, min_frequency)
+            {
+                warn!("System power limit: {}", e);
+            }
+            for ((hash_chain, current), trimmed) in chains.iter().zip(operating_points.iter()) {
+                let lowered_by = current
+                    .frequency
+                    .max()
+                    .saturating_sub(trimmed.frequency.max());
+                if lowered_by == 0 {
+                    continue;
+                }
+                info!(
+                    "System power limit: lowering chain {} frequency by {} MHz",
+                    current.hashboard_idx,
+                    lowered_by / 1_000_000
+                );
+                if let Err(e) = hash_chain
+                    .lower_base_frequency(lowered_by, min_frequency, audit::Source::PowerLimit)
+                    .await
+                {
+                    error!(
+                        "System power limit: chain {} frequency change failed: {}",
+                        current.hashboard_idx, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Task that periodically feeds monitor with hardware error rates of hash chains and ratio
+    /// of shares accepted by pools which are used for hash chain health score. It also reports
+    /// whether there's any work from pools at all.
+    async fn health_task(
+        managers: Vec<Arc<Manager>>,
+        monitor: Arc<monitor::Monitor>,
+        client_manager: client::Manager,
+    ) {
+        let mut previous_shares = (0.0, 0.0);
+        loop {
+            delay_for(config::DEFAULT_HASHRATE_INTERVAL).await;
+            for manager in managers.iter() {
+                let hw_error_rate = match manager.inner.lock().await.hash_chain.as_ref() {
+                    Some(hash_chain) => hash_chain.get_hw_error_rate().await,
+                    None => None,
+                };
+                monitor
+                    .set_hw_error_rate(manager.hashboard_idx, hw_error_rate)
+                    .await;
+            }
+
+            // Pools don't know which hash chain found the share so the ratio is shared by all
+            let (mut accepted, mut total) = (0.0, 0.0);
+            let mut work_paused = true;
+            for group in client_manager.get_groups().await {
+                for client in group.get_clients().await {
+                    if client.is_running() {
+                        work_paused = false;
+                    }
+                    let client_stats = client.stats();
+                    let accepted_shares = client_stats
+                        .accepted()
+                        .take_snapshot()
+                        .await
+                        .shares
+                        .as_f64();
+                    let rejected_shares = client_stats
+                        .rejected()
+                        .take_snapshot()
+                        .await
+                        .shares
+                        .as_f64();
+                    let stale_shares = client_stats.stale().take_snapshot().await.shares.as_f64();
+                    accepted += accepted_shares;
+                    total += accepted_shares + rejected_shares + stale_shares;
+                }
+            }
+            let (previous_accepted, previous_total) = previous_shares;
+            let acceptance_rate = if total > previous_total {
+                Some((accepted - previous_accepted) / (total - previous_total))
+            } else {
+                None
+            };
+            previous_shares = (accepted, total);
+            monitor.set_acceptance_rate(acceptance_rate).await;
+            monitor.set_work_paused(work_paused).await;
+        }
+    }
+
+    /// Emit event whenever pool group switches to a different pool. The first running pool of
+    /// the group is considered to be the one the group is mining for.
+    async fn pool_switch_task(client_manager: client::Manager) {
+        let mut status_changes = client_manager.subscribe_to_clients_status_changes();
+        let mut active_pools = HashMap::new();
+        while status_changes.wait_for_event().await.is_ok() {
+            for group in client_manager.get_groups().await {
+                let mut active_pool = None;
+                for client in group.get_clients().await {
+                    if client.is_running() {
+                        active_pool = Some(client.descriptor().await.get_url(true, true, false));
+                        break;
+                    }
+                }
+                // Pool group without running pool keeps its last active pool
+                let active_pool = match active_pool {
+                    Some(active_pool) => active_pool,
+                    None => continue,
+                };
+                let previous_pool =
+                    active_pools.insert(group.descriptor.name.clone(), active_pool.clone());
+                match previous_pool {
+                    Some(previous_pool) if previous_pool != active_pool => events::emit(
+                        events::Kind::PoolSwitched,
+                        None,
+                        format!(
+                            "Group '{}' switched from pool '{}' to '{}'",
+                            group.descriptor.name, previous_pool, active_pool
+                        ),
+                    ),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Wait for initialization of all hash chains and then collect and log self-check report.
+    /// Configuration that started all hash chains is persisted as last-known-good.
+    async fn self_check_task(
+        chain_starts: Vec<tokio::task::JoinHandle<()>>,
+        managers: Vec<Arc<Manager>>,
+        monitor: Arc<monitor::Monitor>,
+        client_manager: client::Manager,
+        report: Arc<Mutex<Option<self_check::Report>>>,
+        last_good_config: Option<last_good::Snapshot>,
+    ) {
+        for chain_start in chain_starts {
+            // Panicking start is reported by the panic itself
+            let _ = chain_start.await;
+        }
+        let self_check_report =
+            self_check::Report::collect(&managers, &monitor, &client_manager).await;
+        self_check_report.log();
+        if let Some(last_good_config) = last_good_config {
+            if self_check_report.all_chains_started() {
+                last_good_config.store();
+            }
+        }
+        report.lock().await.replace(self_check_report);
+    }
+
     /// Miner termination handler called when app is shutdown.
     /// Just propagate the shutdown to all hashchain managers
     async fn termination_handler(halt_sender: Arc<halt::Sender>) {
         halt_sender.send_halt().await;
     }
 
+    /// Create client for each pool group assigned to individual hash chains. Pools of such group
+    /// are not scheduled, the first enabled one is used.
+    fn create_dedicated_clients(
+        client_manager: &client::Manager,
+        group_configs: Vec<bosminer_config::GroupConfig>,
+        backend_info: Option<&hal::BackendInfo>,
+    ) -> bosminer::Result<HashMap<String, client::DedicatedClient>> {
+        let mut dedicated_clients = HashMap::new();
+        for group_config in group_configs {
+            let mut descriptors = Vec::new();
+            for pool_config in group_config.pools.iter().flatten() {
+                descriptors.push(
+                    pool_config
+                        .create_descriptor(config::DEFAULT_POOL_ENABLED)
+                        .map_err(|e| e.to_string())?,
+                );
+            }
+            let name = group_config.descriptor.name;
+            match descriptors
+                .into_iter()
+                .find(|descriptor| descriptor.enabled)
+            {
+                Some(descriptor) => {
+                    info!(
+                        "Pool group '{}' assigned to hash chains uses pool {}",
+                        name,
+                        descriptor.get_full_url()
+                    );
+                    let dedicated_client =
+                        client_manager.create_dedicated_client(descriptor, backend_info);
+                    dedicated_clients.insert(name, dedicated_client);
+                }
+                None => warn!(
+                    "Pool group '{}' has no enabled pool, assigned hash chains use global pools",
+                    name
+                ),
+            }
+        }
+        Ok(dedicated_clients)
+    }
+
     /// Start miner
     /// TODO: maybe think about having a `Result` error value here?
     async fn start_miner(
@@ -1549,7 +3238,15 @@ impl Backend {
         backend_config: config::Backend,
         app_halt_receiver: halt::Receiver,
         app_halt_sender: Arc<halt::Sender>,
-    ) -> (Vec<Arc<Manager>>, Arc<monitor::Monitor>) {
+        dedicated_clients: HashMap<String, client::DedicatedClient>,
+    ) -> (
+        Vec<Arc<Manager>>,
+        Arc<monitor::Monitor>,
+        Vec<tokio::task::JoinHandle<()>>,
+    ) {
+        let hotplug_interval = backend_config.hotplug_interval();
+        let continue_on_chain_failure = backend_config.continue_on_chain_failure();
+        let init_concurrency = backend_config.init_concurrency();
         // Create hooks
         let hooks = match backend_config.hooks.as_ref() {
             Some(hooks) => hooks.clone(),
@@ -1583,24 +3280,79 @@ impl Backend {
         hooks.monitor_started(monitor.clone()).await;
 
         let voltage_ctrl_backend = Arc::new(power::I2cBackend::new(0));
+        let overlap_detector = if backend_config.debug_nonce_partition() {
+            warn!("Nonce partition debugging is enabled, work dispatch will be slower");
+            Some(Arc::new(nonce_partition::OverlapDetector::new(
+                MidstateCount::new(backend_config.midstate_count()),
+            )))
+        } else {
+            None
+        };
+        let duplicate_filter = Arc::new(duplicate_shares::Filter::new(
+            backend_config.suppress_duplicate_shares(),
+        ));
         let mut managers = Vec::new();
         info!(
             "Initializing miner, enabled_chains={:?}, midstate_count={}",
             enabled_chains,
             backend_config.midstate_count(),
         );
-        // build all hash chain managers and register ourselves with frontend
+        // resolve configuration of all hash chains
+        let mut chain_configs = Vec::new();
         for hashboard_idx in enabled_chains {
+            match backend_config.resolve_chain_config(hashboard_idx) {
+                Ok(chain_config) => chain_configs.push((hashboard_idx, chain_config)),
+                Err(e) => error!("Skipping chain with invalid configuration: {}", e),
+            }
+        }
+        // the limit has been checked for all hash chains during configuration sanity check
+        if let Err(e) = backend_config.apply_power_limit(&mut chain_configs) {
+            error!("Cannot fit system power limit: {}", e);
+        }
+
+        // build all hash chain managers and register ourselves with frontend
+        for (hashboard_idx, chain_config) in chain_configs {
+            info!(
+                "Chain {}: resolved frequency={}, voltage={}, asic_difficulty={}, \
+                 midstate_count={}",
+                hashboard_idx,
+                chain_config.frequency,
+                chain_config.voltage,
+                chain_config.asic_difficulty,
+                chain_config.midstate_count.to_count()
+            );
+            for pll_setting in chain_config.frequency.pll_settings() {
+                info!("Chain {}: resolved PLL {}", hashboard_idx, pll_setting);
+            }
             // register monitor for this haschain
-            let monitor_tx = monitor.register_hashchain(hashboard_idx).await;
-            // make pins
-            let chain_config = backend_config.resolve_chain_config(hashboard_idx);
+            let monitor_tx = monitor
+                .register_hashchain(
+                    hashboard_idx,
+                    chain_config.frequency.max(),
+                    chain_config.alert_temp,
+                )
+                .await;
 
             let status_receiver = monitor.status_receiver.clone();
 
+            let dedicated_client = chain_config
+                .pool_group
+                .as_ref()
+                .and_then(|pool_group| dedicated_clients.get(pool_group))
+                .cloned();
+
             // build hashchain_node for statistics and static parameters
             let manager = work_hub
                 .create_work_solver(|work_generator, solution_sender| {
+                    // Hash chain with assigned pool group mines for its client only
+                    let (work_generator, solution_sender, pool_client) = match dedicated_client {
+                        Some(dedicated_client) => (
+                            work_generator.with_engine_receiver(dedicated_client.engine_receiver),
+                            dedicated_client.solution_sender,
+                            Some(dedicated_client.handle),
+                        ),
+                        None => (work_generator, solution_sender, None),
+                    };
                     Manager {
                         // TODO: create a new substructure of the miner that will hold all gpio and
                         // "physical-insertion" detection data. This structure will be persistent in
@@ -1622,15 +3374,30 @@ impl Backend {
                         inner: Mutex::new(ManagerInner {
                             hash_chain: None,
                             start_count: 0,
+                            start_failure: None,
                         }),
                         chain_config,
+                        pool_client,
+                        overlap_detector: overlap_detector.clone(),
+                        duplicate_filter: duplicate_filter.clone(),
                     }
                 })
                 .await;
             managers.push(manager);
         }
 
-        // start everything
+        // start everything (limit number of hash chains initialized at once)
+        let init_slots = InitSlots::new(init_concurrency, managers.len());
+        let mut chain_starts = Vec::new();
+        // Hash chains are kept off or started after cooldown when recovering from thermal
+        // shutdown of the previous run
+        let thermal_recovery = restart::tracker()
+            .map(|tracker| tracker.recovery())
+            .unwrap_or(restart::Recovery::None);
+        let thermal_cooldown = match thermal_recovery {
+            restart::Recovery::Retry { cooldown, .. } => cooldown,
+            _ => Duration::from_secs(0),
+        };
         for manager in managers.iter() {
             let halt_receiver = halt_receiver.clone();
             let manager = manager.clone();
@@ -1645,27 +3412,98 @@ impl Backend {
                 .await
                 .spawn_halt_handler(Manager::termination_handler(manager.clone()));
 
+            // With hotplug enabled all hashboards are managed, but only the present ones are
+            // started right away
+            if hotplug_interval.is_some() && !manager.plug_pin.hashboard_present().unwrap_or(false)
+            {
+                info!("Hotplug: hashboard {} not present", manager.hashboard_idx);
+                continue;
+            }
+
+            if thermal_recovery == restart::Recovery::StayOff {
+                warn!(
+                    "Chain {} is kept off because of thermal lockout",
+                    manager.hashboard_idx
+                );
+                manager.inner.lock().await.start_failure = Some("thermal lockout".to_string());
+                continue;
+            }
+
             // Suppress haschain start if chain is either not enabled or haschain hook doesn't
             // want us to start it (default `NoHooks` has all chains enabled).
             if hooks.can_start_chain(manager.clone()).await {
-                tokio::spawn(async move {
-                    manager
-                        .acquire("main")
-                        .await
-                        .expect("BUG: failed to acquire hashchain")
-                        .expect_stopped()
-                        .start(
-                            &initial_frequency,
-                            initial_voltage,
-                            config::DEFAULT_ASIC_DIFFICULTY,
-                        )
-                        .await
-                        .expect("BUG: failed to start hashchain");
-                });
+                let init_slots = init_slots.clone();
+                chain_starts.push(tokio::spawn(async move {
+                    if thermal_cooldown > Duration::from_secs(0) {
+                        info!(
+                            "Chain {} cooling down for {}s after thermal shutdown",
+                            manager.hashboard_idx,
+                            thermal_cooldown.as_secs()
+                        );
+                        delay_for(thermal_cooldown).await;
+                    }
+                    let start = async {
+                        manager
+                            .clone()
+                            .acquire("main")
+                            .await
+                            .expect("BUG: failed to acquire hashchain")
+                            .expect_stopped()
+                            .start(
+                                &initial_frequency,
+                                initial_voltage,
+                                manager.chain_config.asic_difficulty,
+                            )
+                            .await
+                    };
+                    if let Err((_, e)) = init_slots.run(manager.hashboard_idx, start).await {
+                        if !continue_on_chain_failure {
+                            restart::record_exit(restart::Reason::ChainFailure);
+                            panic!("BUG: failed to start hashchain: {}", e);
+                        }
+                        error!("Continuing without chain {}: {}", manager.hashboard_idx, e);
+                        manager.inner.lock().await.start_failure = Some(e.to_string());
+                    }
+                }));
             }
         }
+        if let Some(limit) = backend_config.system_power_limit() {
+            halt_receiver
+                .register_client("power limit".into())
+                .await
+                .spawn(Self::power_limit_task(
+                    managers.clone(),
+                    limit,
+                    backend_config.min_viable_frequency(),
+                ));
+        }
+        if let Some(hashrate_check) = backend_config.resolve_hashrate_check() {
+            halt_receiver
+                .register_client("hashrate check".into())
+                .await
+                .spawn(Self::hashrate_check_task(managers.clone(), hashrate_check));
+        }
+        if continue_on_chain_failure {
+            halt_receiver
+                .register_client("chain failure".into())
+                .await
+                .spawn(Self::chain_failure_task(
+                    managers.clone(),
+                    monitor.status_receiver.clone(),
+                ));
+        }
+        if let Some(interval) = hotplug_interval {
+            halt_receiver
+                .register_client("hotplug".into())
+                .await
+                .spawn(Self::hotplug_task(
+                    managers.clone(),
+                    hooks.clone(),
+                    interval,
+                ));
+        }
         hooks.miner_started().await;
-        (managers, monitor)
+        (managers, monitor, chain_starts)
     }
 }
 
@@ -1686,30 +3524,63 @@ impl hal::Backend for Backend {
         work_hub: work::SolverBuilder<Self>,
     ) -> bosminer::Result<hal::FrontendConfig> {
         let hooks = backend_config.hooks.clone();
+        // Account this start in persistent restart telemetry
+        restart::init(
+            &backend_config.state_file_path(),
+            &backend_config.resolve_thermal_recovery(),
+        );
+        audit::init(backend_config.audit_log_path());
+        events::init();
         // Prepare data for pool configuration after successful start of backend
         let client_manager = backend_config
             .client_manager
             .take()
             .expect("BUG: missing client manager");
-        let group_configs = backend_config.groups.take();
+        let assigned_pool_groups = backend_config.assigned_pool_groups();
+        let (assigned_group_configs, group_configs): (Vec<_>, Vec<_>) = backend_config
+            .groups
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|group| assigned_pool_groups.contains(&group.descriptor.name));
         let backend_info = backend_config.info();
+        let dedicated_clients = Self::create_dedicated_clients(
+            &client_manager,
+            assigned_group_configs,
+            backend_info.as_ref(),
+        )?;
+        let temp_unit = backend_config.temp_unit();
+        let api_access = backend_config.api_access();
+        let readiness = backend_config.readiness();
+        let config_warnings = backend_config.warnings.clone();
+        let config_path = backend_config.active_config_path();
+        let last_good_config = backend_config.config_snapshot.take().map(|content| {
+            last_good::Snapshot::new(backend_config.last_good_config_path(), content)
+        });
 
         let backend = work_hub.to_node().clone();
         let gpio_mgr = gpio::ControlPinManager::new();
         let (app_halt_sender, app_halt_receiver) = halt::make_pair(HALT_TIMEOUT);
-        let (managers, monitor) = Self::start_miner(
+        // With hotplug enabled all hashboards are managed so that they can be inserted later
+        let chains = match backend_config.hotplug_interval() {
+            Some(_) => HASHBOARD_INDEX_RANGE.collect(),
+            None => Self::detect_hashboards(&gpio_mgr).expect("failed detecting hashboards"),
+        };
+        let (managers, monitor, chain_starts) = Self::start_miner(
             &gpio_mgr,
-            Self::detect_hashboards(&gpio_mgr).expect("failed detecting hashboards"),
+            chains,
             work_hub,
             backend_config,
-            app_halt_receiver,
+            app_halt_receiver.clone(),
             app_halt_sender.clone(),
+            dedicated_clients,
         )
         .await;
 
         // On miner exit, halt the whole program
         app_halt_sender
             .add_exit_hook(async {
+                restart::record_exit(restart::Reason::CleanShutdown);
                 println!("Exiting.");
                 std::process::exit(0);
             })
@@ -1725,13 +3596,51 @@ impl hal::Backend for Backend {
                 config::DEFAULT_POOL_ENABLED,
             )
             .await?;
+        // Run self-check as soon as all hash chains finish their initialization
+        let self_check_report = Arc::new(Mutex::new(None));
+        tokio::spawn(Self::self_check_task(
+            chain_starts,
+            managers.clone(),
+            monitor.clone(),
+            client_manager.clone(),
+            self_check_report.clone(),
+            last_good_config,
+        ));
+        app_halt_receiver
+            .register_client("health".into())
+            .await
+            .spawn(Self::health_task(
+                managers.clone(),
+                monitor.clone(),
+                client_manager.clone(),
+            ));
+        app_halt_receiver
+            .register_client("pool switch".into())
+            .await
+            .spawn(Self::pool_switch_task(client_manager.clone()));
+
         if let Some(hooks) = hooks {
             // Pass the client manager to hook for further processing
-            hooks.clients_loaded(client_manager).await;
+            hooks.clients_loaded(client_manager.clone()).await;
         }
 
         Ok(hal::FrontendConfig {
-            cgminer_custom_commands: cgminer::create_custom_commands(backend, managers, monitor),
+            cgminer_custom_commands: cgminer::create_custom_commands(
+                backend,
+                managers,
+                monitor,
+                self_check_report,
+                temp_unit,
+                config_warnings,
+                cgminer::Clients {
+                    manager: client_manager,
+                    backend_info,
+                    assigned_pool_groups,
+                },
+                config_path,
+                readiness,
+            ),
+            api_access,
         })
     }
 
@@ -1833,3 +3742,63 @@ fn calculate_work_delay_for_pll(n_midstates: usize, pll_frequency: usize) -> f64
 fn secs_to_fpga_ticks(secs: f64) -> u32 {
     (secs * io::F_CLK_SPEED_HZ as f64) as u32
 }
+
+/// Time left at `now` until `min_change_interval` elapses after `last_change` of runtime
+/// frequency or voltage
+fn remaining_change_cooldown(
+    min_change_interval: Duration,
+    last_change: Instant,
+    now: Instant,
+) -> Option<Duration> {
+    min_change_interval
+        .checked_sub(now.duration_since(last_change))
+        .filter(|cooldown| *cooldown > Duration::from_secs(0))
+}
+
+/// Check `voltage` against minimal voltage required for `frequency` (see
+/// `config::MIN_VOLTAGE_TABLE`). Returns voltage the hash chain has to be raised to or error when
+/// insufficient voltage is rejected by `voltage_guard`.
+fn required_voltage(
+    voltage_guard: &config::VoltageGuardConfig,
+    frequency: &FrequencySettings,
+    voltage: power::Voltage,
+) -> error::Result<Option<power::Voltage>> {
+    let frequency_mhz = frequency.max() as f64 / 1_000_000.0;
+    let min_voltage = match config::min_voltage_for_frequency(frequency_mhz) {
+        Some(min_voltage) if min_voltage as f32 > voltage.as_volts() => min_voltage as f32,
+        _ => return Ok(None),
+    };
+    match voltage_guard.action {
+        config::VoltageGuardAction::Reject => Err(ErrorKind::General(format!(
+            "frequency {} MHz requires at least {} V but voltage is {}, raise voltage first",
+            frequency_mhz, min_voltage, voltage
+        )))?,
+        config::VoltageGuardAction::RaiseVoltage => Ok(Some(
+            voltage.offset_volts(min_voltage - voltage.as_volts())?,
+        )),
+    }
+}
+
+/// Track `hashrate_ratio` of hash chain measured at `now` (`low_since` holds when it fell below
+/// tolerance). Returns the ratio when it stayed below tolerance for the whole period of
+/// `hashrate_check` and the period starts over.
+fn detect_hashrate_drop(
+    low_since: &mut Option<Instant>,
+    hashrate_ratio: Option<f64>,
+    hashrate_check: &config::HashrateCheckConfig,
+    now: Instant,
+) -> Option<f64> {
+    let hashrate_ratio = match hashrate_ratio {
+        Some(hashrate_ratio) if hashrate_ratio < hashrate_check.tolerance => hashrate_ratio,
+        _ => {
+            *low_since = None;
+            return None;
+        }
+    };
+    let since = *low_since.get_or_insert(now);
+    if now.duration_since(since) < hashrate_check.period {
+        return None;
+    }
+    *low_since = None;
+    Some(hashrate_ratio)
+}
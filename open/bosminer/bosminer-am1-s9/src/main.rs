@@ -23,6 +23,9 @@
 use ii_logging::macros::*;
 
 use bosminer_am1_s9::config;
+use bosminer_am1_s9::last_good;
+use bosminer_am1_s9::remote_pools;
+use bosminer_am1_s9::restart;
 
 use bosminer_config::clap;
 use bosminer_config::{ClientDescriptor, ClientUserInfo, GroupConfig, PoolConfig};
@@ -40,6 +43,18 @@ async fn main() {
                 .required(false)
                 .takes_value(true),
         )
+        .arg(
+            clap::Arg::with_name("lenient-config")
+                .long("lenient-config")
+                .help("Ignore unknown fields in config file (with a warning)")
+                .required(false),
+        )
+        .arg(
+            clap::Arg::with_name("safe-mode")
+                .long("safe-mode")
+                .help("Start with built-in safe configuration when config file cannot be loaded")
+                .required(false),
+        )
         .arg(
             clap::Arg::with_name("pool")
                 .short("p")
@@ -133,22 +148,81 @@ async fn main() {
         return;
     }
 
-    let mut backend_config: config::Backend = match config::FormatWrapper::parse(config_path) {
+    let parse_mode = if matches.is_present("lenient-config") {
+        config::ParseMode::Lenient
+    } else {
+        config::ParseMode::Strict
+    };
+
+    let mut safe_mode = false;
+    let mut config_wrapper = match config::FormatWrapper::parse(config_path, parse_mode) {
         Err(config::FormatWrapperError::IncompatibleVersion(version, Some(v))) => {
             warn!(
                 "Incompatible format version '{}', but continuing anyway",
                 version
             );
-            v.body
+            v
         }
         Err(e) => {
             error!("Cannot load configuration file \"{}\"", config_path);
             error!("Reason: {}", e);
-            return;
+            if !matches.is_present("safe-mode") {
+                return;
+            }
+            error!("!!! Starting in SAFE MODE with built-in configuration !!!");
+            safe_mode = true;
+            config::FormatWrapper::safe_mode(config_path, &e.to_string())
         }
-        Ok(v) => v.body,
+        Ok(v) => v,
+    };
+    // Reload and last-known-good persistence are tied to the active configuration file
+    config_wrapper.body.config_path = Some(config_path.to_string());
+    // Built-in safe mode configuration must never become last-known-good
+    let mut config_snapshot = if safe_mode {
+        None
+    } else {
+        last_good::Snapshot::serialize(&config_wrapper)
     };
 
+    // Roll back to last-known-good configuration when hash chains failed to start with the
+    // current one during previous run
+    if last_good::should_roll_back(
+        restart::previous_reason(&config_wrapper.body.state_file_path()),
+        config_wrapper.body.continue_on_chain_failure(),
+    ) {
+        let last_good_path = config_wrapper.body.last_good_config_path();
+        match last_good::load(&last_good_path) {
+            Ok(mut last_good_wrapper) => {
+                last_good_wrapper.body.config_path = Some(config_path.to_string());
+                let last_good_snapshot = last_good::Snapshot::serialize(&last_good_wrapper);
+                if last_good_snapshot != config_snapshot {
+                    warn!("!!! Hash chains failed to start during previous run !!!");
+                    warn!(
+                        "!!! Rolling back configuration '{}' to last-known-good '{}' !!!",
+                        config_path,
+                        last_good_path.display()
+                    );
+                    config_wrapper = last_good_wrapper;
+                    config_snapshot = last_good_snapshot;
+                }
+            }
+            Err(e) => warn!(
+                "Cannot roll back to last-known-good configuration '{}': {}",
+                last_good_path.display(),
+                e
+            ),
+        }
+    }
+
+    let mut backend_config: config::Backend = config_wrapper.body;
+    backend_config.warnings = config_wrapper.warnings;
+    backend_config.suppress_warnings();
+    backend_config.config_snapshot = config_snapshot;
+    // Replace pools with the ones served by provisioning server (when configured)
+    remote_pools::load(&mut backend_config).await;
+    // Drop malformed pools when lenient pool parsing is enabled
+    backend_config.prune_invalid_pools();
+
     // Add pools from command line
     if let Some(url) = matches.value_of("pool") {
         let user_info = matches
@@ -167,9 +241,14 @@ async fn main() {
             descriptor: Default::default(),
             pools: Some(vec![PoolConfig {
                 enabled: Default::default(),
-                url: url.to_string(),
+                url: Some(url.to_string()),
+                urls: None,
                 user: user_info.user.to_string(),
                 password: user_info.password.map(|v| v.to_string()),
+                clean_jobs: None,
+                clean_jobs_delay: None,
+                keepalive_interval: None,
+                submit_batch_window: None,
             }]),
         };
 
@@ -182,15 +261,32 @@ async fn main() {
 
     // Check if there's enough pools
     if !backend_config.has_pools() {
-        error!("No pools specified!");
-        info!("Use cli arguments:");
-        info!("    bosminer --pool <HOSTNAME:PORT> --user <USERNAME.WORKERNAME[:PASSWORD]>");
-        info!(
-            "Or specify pool(s) in configuration file '{}':",
-            config_path
-        );
-        info!("    in [[group.pool]] section");
-        return;
+        match backend_config.no_pools_action() {
+            config::NoPoolsAction::Error => {
+                error!(
+                    "No pools specified in configuration file '{}' nor on command line!",
+                    config_path
+                );
+                info!("Use cli arguments:");
+                info!(
+                    "    bosminer --pool <HOSTNAME:PORT> --user <USERNAME.WORKERNAME[:PASSWORD]>"
+                );
+                info!(
+                    "Or specify pool(s) in configuration file '{}':",
+                    config_path
+                );
+                info!("    in [[group.pool]] section");
+                info!("Or set 'on_no_pools = \"idle\"' in [miner] section to start without pools");
+                return;
+            }
+            config::NoPoolsAction::Idle => {
+                warn!(
+                    "No pools specified in configuration file '{}' nor on command line, \
+                     miner is starting idle",
+                    config_path
+                );
+            }
+        }
     }
 
     // Set just 1 midstate if user requested disabling asicboost
@@ -25,13 +25,17 @@
 
 use ii_logging::macros::*;
 
+use crate::events;
 use crate::fan;
 use crate::halt;
+use crate::restart;
 use crate::sensor::{self, Measurement};
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use chrono::{NaiveTime, Utc};
 use futures::channel::mpsc;
 use futures::lock::Mutex;
 use futures::stream::StreamExt;
@@ -50,6 +54,16 @@ const RUN_UPDATE_TIMEOUT: Duration = Duration::from_secs(10);
 const TICK_LENGTH: Duration = Duration::from_secs(5);
 /// How long does it take until miner warm up? We won't let it tu turn fans off until then...
 const WARM_UP_PERIOD: Duration = Duration::from_secs(90);
+/// Hardware error rate (ratio) at which the hardware error component of health score drops to zero
+const HEALTH_HW_ERROR_RATE_CEILING: f64 = 0.05;
+/// How long fans are given to ramp up during fan test
+const FAN_TEST_RAMP_TIME: Duration = Duration::from_secs(10);
+/// How often temperature is checked during fan test
+const FAN_TEST_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Minimal speed of a fan that passes fan test
+pub const FAN_TEST_MIN_RPM: usize = 1000;
+/// Used for wrapping daily maintenance schedule over midnight
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
 
 /// A message from hashchain
 ///
@@ -59,11 +73,16 @@ const WARM_UP_PERIOD: Duration = Duration::from_secs(90);
 /// - duration between `On` and first `Running` must be less than START_TIMEOUT
 /// - duration between `Running` measurement and the next one must be less than
 ///   RUN_UPDATE_INTERVAL (ideally set periodic update to half of this interval)
+/// - `Failed` may be sent in any state when hashchain detects unrecoverable fault
+///
+/// Hashchain that misses `RUN_UPDATE_TIMEOUT` is considered degraded for the configured
+/// failure grace period and it is marked as broken only when it doesn't recover within it.
 #[derive(Debug, Clone)]
 pub enum Message {
     On,
     Running(sensor::Temperature),
     Off,
+    Failed(&'static str),
 }
 
 /// Interpreted hashchain temperature
@@ -111,6 +130,11 @@ enum ChainState {
         last_heartbeat: Instant,
         temperature: sensor::Temperature,
     },
+    /// Running hashchain that stopped sending heartbeats and is within failure grace period
+    Degraded {
+        started: Instant,
+        last_heartbeat: Instant,
+    },
     Off,
     Broken(&'static str),
 }
@@ -133,7 +157,9 @@ impl ChainState {
                 _ => self.bad_transition(),
             },
             Message::Running(temperature) => match *self {
-                ChainState::Running { started, .. } | ChainState::On(started) => {
+                ChainState::Running { started, .. }
+                | ChainState::Degraded { started, .. }
+                | ChainState::On(started) => {
                     *self = ChainState::Running {
                         started,
                         last_heartbeat: now,
@@ -143,31 +169,68 @@ impl ChainState {
                 _ => self.bad_transition(),
             },
             Message::Off => match *self {
-                ChainState::On(_) | ChainState::Running { .. } => *self = ChainState::Off,
+                ChainState::On(_) | ChainState::Running { .. } | ChainState::Degraded { .. } => {
+                    *self = ChainState::Off
+                }
                 _ => self.bad_transition(),
             },
+            Message::Failed(reason) => *self = ChainState::Broken(reason),
         }
     }
 
     /// Do a timer tick: check all timeouts and do appropriate state transitions.
     /// If miner is starting, check it starts in `START_TIMEOUT`, if its running, check
-    /// it's sending "heartbeats" often enought.
-    fn tick(&mut self, now: Instant) {
+    /// it's sending "heartbeats" often enought. Hashchain that is late is degraded until
+    /// `failure_grace` passes.
+    fn tick(&mut self, now: Instant, failure_grace: Duration) {
         match *self {
             ChainState::On(started) => {
                 if now.duration_since(started) >= START_TIMEOUT {
                     *self = ChainState::Broken("took too long to start");
                 }
             }
-            ChainState::Running { last_heartbeat, .. } => {
-                if now.duration_since(last_heartbeat) >= RUN_UPDATE_TIMEOUT {
-                    *self = ChainState::Broken("failed to set update in time");
+            ChainState::Running {
+                started,
+                last_heartbeat,
+                ..
+            }
+            | ChainState::Degraded {
+                started,
+                last_heartbeat,
+            } => {
+                let late_by = now
+                    .duration_since(last_heartbeat)
+                    .checked_sub(RUN_UPDATE_TIMEOUT);
+                match late_by {
+                    Some(late_by) if late_by >= failure_grace => {
+                        *self = ChainState::Broken("failed to set update in time");
+                    }
+                    Some(_) => {
+                        *self = ChainState::Degraded {
+                            started,
+                            last_heartbeat,
+                        }
+                    }
+                    None => {}
                 }
             }
             _ => {}
         }
     }
 
+    /// Return hashchain state as reported to others
+    fn watchdog_state(&self, now: Instant) -> ChainWatchdogState {
+        match self {
+            ChainState::On(_) => ChainWatchdogState::Starting,
+            ChainState::Running { .. } => ChainWatchdogState::Running,
+            ChainState::Degraded { last_heartbeat, .. } => ChainWatchdogState::Degraded {
+                unresponsive_for: now.duration_since(*last_heartbeat),
+            },
+            ChainState::Off => ChainWatchdogState::Off,
+            ChainState::Broken(reason) => ChainWatchdogState::Failed(*reason),
+        }
+    }
+
     /// Return hashchain temperature as seen from our point of view. For example,
     /// `Broken` miner doesn't have a valid temperature reading even though it sent
     /// some numbers a while ago.
@@ -175,6 +238,7 @@ impl ChainState {
         match self {
             ChainState::On(_) => ChainTemperature::Unknown,
             ChainState::Off => ChainTemperature::Unknown,
+            ChainState::Degraded { .. } => ChainTemperature::Unknown,
             ChainState::Broken(_) => ChainTemperature::Failed,
             ChainState::Running { temperature, .. } => {
                 ChainTemperature::from_s9_sensor(temperature.clone())
@@ -182,28 +246,217 @@ impl ChainState {
         }
     }
 
+    /// Temperature used for temperature control. Broken hashchain doesn't take part in it when
+    /// it is pulled from mining or ignored during maintenance window.
+    fn control_temperature(&self, config: &Config, maintenance: bool) -> ChainTemperature {
+        match self {
+            ChainState::Broken(_) if maintenance || config.continue_on_chain_failure => {
+                ChainTemperature::Unknown
+            }
+            _ => self.get_temperature(),
+        }
+    }
+
     /// Is hashchain warming up?
     fn is_warming_up(&self, now: Instant) -> bool {
+        self.is_warming_up_for(now, WARM_UP_PERIOD)
+    }
+
+    /// Is hashchain running shorter than `warm_up_period`?
+    fn is_warming_up_for(&self, now: Instant, warm_up_period: Duration) -> bool {
         match self {
             // chain state stays in "warming up" state until it sends heartbeat
             ChainState::On(_) => true,
-            ChainState::Running { started, .. } => now.duration_since(*started) <= WARM_UP_PERIOD,
+            ChainState::Running { started, .. } | ChainState::Degraded { started, .. } => {
+                now.duration_since(*started) <= warm_up_period
+            }
             _ => false,
         }
     }
 }
 
+/// Frequency throttling state of one hashchain
+#[derive(Debug, Clone, PartialEq)]
+struct ThrottleState {
+    /// Configured frequency (in Hz) of the fastest chip on hashchain
+    nominal_frequency: usize,
+    /// How much (in Hz) is the hashchain frequency lowered
+    throttled_by: usize,
+    /// Since when has the temperature continuously stayed below recovery threshold
+    cool_since: Option<Instant>,
+}
+
+impl ThrottleState {
+    fn new(nominal_frequency: usize) -> Self {
+        Self {
+            nominal_frequency,
+            throttled_by: 0,
+            cool_since: None,
+        }
+    }
+
+    /// Update throttling according to hashchain temperature. Each call lowers the frequency by
+    /// one step while the temperature is at or above `throttle_temp`. Frequency is raised back
+    /// by one step only after the temperature stays below recovery threshold (which never
+    /// reaches into hysteresis band) for the whole `recovery_dwell` period. Every recovery step
+    /// restarts the dwell period so the frequency climbs back gradually.
+    ///
+    /// Returns true when throttling has changed.
+    fn update(&mut self, config: &ThrottleConfig, temp: ChainTemperature, now: Instant) -> bool {
+        let temp = match temp {
+            ChainTemperature::Ok(temp) => temp,
+            // Failed or unknown temperatures are handled by the main decision logic
+            _ => {
+                self.cool_since = None;
+                return false;
+            }
+        };
+
+        if temp >= config.throttle_temp {
+            self.cool_since = None;
+            let max_throttle = self.nominal_frequency.saturating_sub(config.min_frequency);
+            let throttled_by = (self.throttled_by + config.step).min(max_throttle);
+            let changed = throttled_by != self.throttled_by;
+            self.throttled_by = throttled_by;
+            return changed;
+        }
+        if self.throttled_by == 0 || temp >= config.recovery_threshold() {
+            self.cool_since = None;
+            return false;
+        }
+        let cool_since = *self.cool_since.get_or_insert(now);
+        if now.duration_since(cool_since) < config.recovery_dwell {
+            return false;
+        }
+        self.throttled_by = self.throttled_by.saturating_sub(config.step);
+        self.cool_since = Some(now);
+        true
+    }
+}
+
+/// Peak temperature of one hashchain
+#[derive(Debug, Clone, Default)]
+struct PeakTemperature {
+    /// Highest temperature since start (or since the last reset)
+    peak: Option<f32>,
+    /// Measurements within rolling window kept in decreasing order of temperature (the older
+    /// measurement is dropped as soon as a hotter one arrives) so the front is the window peak
+    window: VecDeque<(Instant, f32)>,
+}
+
+impl PeakTemperature {
+    fn update(&mut self, temp: ChainTemperature, now: Instant, window: Option<Duration>) {
+        if let ChainTemperature::Ok(temp) = temp {
+            self.peak = Some(self.peak.map_or(temp, |peak| peak.max(temp)));
+            if window.is_some() {
+                while let Some((_, window_temp)) = self.window.back() {
+                    if *window_temp > temp {
+                        break;
+                    }
+                    self.window.pop_back();
+                }
+                self.window.push_back((now, temp));
+            }
+        }
+        let window = match window {
+            Some(window) => window,
+            None => {
+                self.window.clear();
+                return;
+            }
+        };
+        while let Some((time, _)) = self.window.front() {
+            if now.duration_since(*time) <= window {
+                break;
+            }
+            self.window.pop_front();
+        }
+    }
+
+    fn window_peak(&self) -> Option<f32> {
+        self.window.front().map(|(_, temp)| *temp)
+    }
+}
+
 /// Represent hashchains as registered within Monitor
 struct Chain {
     state: ChainState,
     hashboard_idx: usize,
+    throttle: ThrottleState,
+    peak_temperature: PeakTemperature,
+    /// Hardware error rate (ratio) over the last hashrate interval
+    hw_error_rate: Option<f64>,
+    /// Temperature (in Celsius) at which `ThermalWarn` event is emitted
+    alert_temp: Option<f32>,
+    /// Temperature has been at or above `alert_temp` during the last tick
+    alerted: bool,
+    /// Hashchain has been degraded during the last tick
+    degraded: bool,
+    /// `ChainFailed` event has been emitted for broken hashchain
+    failure_reported: bool,
 }
 
 impl Chain {
-    fn new(hashboard_idx: usize) -> Self {
+    fn new(hashboard_idx: usize, nominal_frequency: usize, alert_temp: Option<f32>) -> Self {
         Self {
             state: ChainState::Off,
             hashboard_idx,
+            throttle: ThrottleState::new(nominal_frequency),
+            peak_temperature: Default::default(),
+            hw_error_rate: None,
+            alert_temp,
+            alerted: false,
+            degraded: false,
+            failure_reported: false,
+        }
+    }
+
+    /// Event about hashchain failure or recovery since the last tick
+    fn watchdog_event(&mut self) -> Option<(events::Kind, String)> {
+        let was_degraded = self.degraded;
+        self.degraded = false;
+        match self.state {
+            ChainState::Broken(reason) if !self.failure_reported => {
+                self.failure_reported = true;
+                Some((
+                    events::Kind::ChainFailed,
+                    format!("Chain {} is broken: {}", self.hashboard_idx, reason),
+                ))
+            }
+            ChainState::Degraded { .. } => {
+                self.degraded = true;
+                None
+            }
+            ChainState::Running { .. } if was_degraded => Some((
+                events::Kind::ChainRecovered,
+                format!(
+                    "Chain {} is responding again within failure grace period",
+                    self.hashboard_idx
+                ),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Event about temperature reaching alert threshold (emitted again only after the
+    /// temperature drops below the threshold)
+    fn alert_event(&mut self, temp: ChainTemperature) -> Option<(events::Kind, String)> {
+        let (alert_temp, temp) = match (self.alert_temp, temp) {
+            (Some(alert_temp), ChainTemperature::Ok(temp)) => (alert_temp, temp),
+            _ => return None,
+        };
+        let was_alerted = self.alerted;
+        self.alerted = temp >= alert_temp;
+        if self.alerted && !was_alerted {
+            Some((
+                events::Kind::ThermalWarn,
+                format!(
+                    "Chain {} temperature {} reached alert threshold {}",
+                    self.hashboard_idx, temp, alert_temp
+                ),
+            ))
+        } else {
+            None
         }
     }
 }
@@ -215,6 +468,17 @@ pub enum FanControlMode {
     TargetTemperature(f32),
 }
 
+/// What to do when less than `min_fans` fans are running
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FanFailureAction {
+    /// Shutdown the miner
+    Shutdown,
+    /// Throttle all hashchains to this frequency (in Hz)
+    Throttle(usize),
+    /// Only log a warning (dangerous temperature is still checked)
+    Warn,
+}
+
 /// Fan configuration
 #[derive(Debug, Clone)]
 pub struct FanControlConfig {
@@ -222,6 +486,12 @@ pub struct FanControlConfig {
     /// Minimal number of fans - miner will refuse to work until at least
     /// this number of fans is spinning.
     pub min_fans: usize,
+    /// PWM carrier frequency in Hz (hardware default when missing)
+    pub pwm_frequency: Option<usize>,
+    pub on_fan_failure: FanFailureAction,
+    /// Fan speed used while mining is paused (all hash chains are stopped) and there's no
+    /// temperature to control
+    pub idle_speed: fan::Speed,
 }
 
 /// Temperature limit configuration
@@ -229,6 +499,90 @@ pub struct FanControlConfig {
 pub struct TempControlConfig {
     pub dangerous_temp: f32,
     pub hot_temp: f32,
+    /// Run fans at full speed above `hot_temp` even when fixed fan speed is configured
+    pub hot_temp_override: bool,
+}
+
+/// Frequency throttling configuration (all frequencies are in Hz)
+#[derive(Debug, Clone)]
+pub struct ThrottleConfig {
+    /// Hashchain frequency is lowered when its temperature reaches this value
+    pub throttle_temp: f32,
+    /// Width of temperature band below `throttle_temp` where throttling is left unchanged
+    pub hysteresis: f32,
+    /// How much is frequency lowered or raised in one step
+    pub step: usize,
+    /// Hashchain is never throttled below this frequency
+    pub min_frequency: usize,
+    /// Throttled hashchain recovers when its temperature stays below this value...
+    pub recovery_temp: f32,
+    /// ...for this long
+    pub recovery_dwell: Duration,
+    /// Hashchain is not throttled until it runs for this long (dangerous temperature is still
+    /// checked)
+    pub warmup_period: Duration,
+}
+
+impl ThrottleConfig {
+    /// Temperature below which throttled hashchain starts to recover
+    fn recovery_threshold(&self) -> f32 {
+        self.recovery_temp.min(self.throttle_temp - self.hysteresis)
+    }
+}
+
+/// Relative weights of components of hashchain health score
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthWeights {
+    pub hw_errors: f32,
+    pub temperature: f32,
+    pub acceptance: f32,
+    pub frequency: f32,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            hw_errors: 1.0,
+            temperature: 1.0,
+            acceptance: 1.0,
+            frequency: 1.0,
+        }
+    }
+}
+
+/// Maintenance window configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceConfig {
+    /// Length of maintenance window (started from API or by schedule)
+    pub duration: Duration,
+    /// Time of day (UTC) when maintenance window is started every day (disabled when missing)
+    pub daily_start: Option<NaiveTime>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(30 * 60),
+            daily_start: None,
+        }
+    }
+}
+
+impl MaintenanceConfig {
+    /// Return how long scheduled maintenance window lasts from `now` (time of day in UTC).
+    /// Windows reaching over midnight are handled as well.
+    fn scheduled_remaining(&self, now: NaiveTime) -> Option<Duration> {
+        let daily_start = self.daily_start?;
+        let since_start = (now - daily_start)
+            .num_seconds()
+            .rem_euclid(SECONDS_PER_DAY) as u64;
+        let duration = self.duration.as_secs();
+        if since_start < duration {
+            Some(Duration::from_secs(duration - since_start))
+        } else {
+            None
+        }
+    }
 }
 
 /// Overall configuration
@@ -237,9 +591,20 @@ pub struct TempControlConfig {
 pub struct Config {
     pub fan_config: Option<FanControlConfig>,
     pub temp_config: Option<TempControlConfig>,
+    pub throttle_config: Option<ThrottleConfig>,
+    /// Length of rolling window for hashchain peak temperature (disabled when missing)
+    pub peak_temp_window: Option<Duration>,
+    /// Weights of hashchain health score components
+    pub health_weights: HealthWeights,
+    /// Maintenance window suppressing protective actions below dangerous temperature
+    pub maintenance_config: MaintenanceConfig,
     /// If true, then do not let fans bellow predefined limit while miner is warming up.
     /// TODO: this is not particularly nice, it should be done per-chain and run-time.
     pub fans_on_while_warming_up: bool,
+    /// How long can be hashchain unresponsive before it is declared broken
+    pub failure_grace: Duration,
+    /// Keep the miner running without broken hashchains instead of shutting it down
+    pub continue_on_chain_failure: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -276,6 +641,14 @@ impl ControlDecision {
         }
         match &fan_config.mode {
             FanControlMode::FixedSpeed(pwm) => {
+                if let ChainTemperature::Ok(input_temp) = temp {
+                    if temp_config.hot_temp_override && input_temp >= temp_config.hot_temp {
+                        return ControlDecisionExplained {
+                            decision: Self::UseFixedSpeed(fan::Speed::FULL_SPEED),
+                            reason: "temperature above HOT overrides user defined fan speed",
+                        };
+                    }
+                }
                 return ControlDecisionExplained {
                     decision: Self::UseFixedSpeed(*pwm),
                     reason: "user defined fan speed",
@@ -324,6 +697,66 @@ impl ControlDecision {
         }
     }
 
+    /// Check that `min_fans` are spinning unless fans are configured to be stopped
+    fn fans_failed(
+        fan_config: &FanControlConfig,
+        num_fans_running: usize,
+        decision: &ControlDecision,
+    ) -> bool {
+        *decision != Self::UseFixedSpeed(fan::Speed::STOPPED)
+            && num_fans_running < fan_config.min_fans
+    }
+
+    /// Check whether temperature is a valid reading below `dangerous_temp`. Failed or unknown
+    /// temperature is never considered safe because `dangerous_temp` cannot be checked.
+    fn temp_safe(config: &Config, temp: ChainTemperature) -> bool {
+        match (config.temp_config.as_ref(), temp) {
+            (Some(temp_config), ChainTemperature::Ok(input_temp)) => {
+                input_temp < temp_config.dangerous_temp
+            }
+            _ => false,
+        }
+    }
+
+    /// Adjust decision during maintenance window: shutdown is replaced with full fan speed
+    /// only when the temperature is known to be below `dangerous_temp`.
+    fn decide_maintenance(
+        config: &Config,
+        temp: ChainTemperature,
+        decision_explained: ControlDecisionExplained,
+    ) -> ControlDecisionExplained {
+        if decision_explained.decision == Self::Shutdown && Self::temp_safe(config, temp) {
+            return ControlDecisionExplained {
+                decision: Self::UseFixedSpeed(fan::Speed::FULL_SPEED),
+                reason: "shutdown suppressed by maintenance window",
+            };
+        }
+        decision_explained
+    }
+
+    /// Decide what to do in one monitor tick with aggregated temperature: mining may be paused
+    /// and shutdown may be suppressed by maintenance window.
+    fn decide_tick(
+        config: &Config,
+        maintenance: bool,
+        chains_stopped: bool,
+        num_fans_running: usize,
+        temp: ChainTemperature,
+    ) -> ControlDecisionExplained {
+        let decision_explained = if chains_stopped {
+            Self::decide_paused(config, num_fans_running, temp)
+        } else {
+            Self::decide(config, num_fans_running, temp)
+        };
+        info!("Monitor: {:?}", decision_explained);
+        if maintenance && decision_explained.decision == Self::Shutdown {
+            let decision_explained = Self::decide_maintenance(config, temp, decision_explained);
+            warn!("Monitor: maintenance window: {:?}", decision_explained);
+            return decision_explained;
+        }
+        decision_explained
+    }
+
     /// Decide what to do depending on temperature/fan feedback.
     /// This function has been factored out of the main control code to facilitate testing.
     fn decide(
@@ -359,23 +792,7 @@ impl ControlDecision {
             } else {
                 Self::decide_fan_control_notemp(fan_config)
             };
-            // This section is labeled `FAN_DANGER` in the diagram
-            //
-            // Check `min_fans` are spinning _unless_ we have been explicitly configured to
-            // turn them off.
-            //
-            // XXX: There's a problem however: if we are configured for stopped fans and then
-            // the configuration changes at runtime to non-stopped fans, the delay of fans
-            // taking some time to spin up will cause this check to fire off!
-            if decision_explained.decision != Self::UseFixedSpeed(fan::Speed::STOPPED) {
-                if num_fans_running < fan_config.min_fans {
-                    return ControlDecisionExplained {
-                        decision: Self::Shutdown,
-                        reason: "not enough fans",
-                    };
-                }
-            }
-            decision_explained
+            Self::check_fans(fan_config, num_fans_running, decision_explained)
         } else {
             // This is only valid if `FanControl` is turned off
             ControlDecisionExplained {
@@ -384,6 +801,58 @@ impl ControlDecision {
             }
         }
     }
+
+    /// Replace `decision_explained` with shutdown when not enough fans are spinning
+    fn check_fans(
+        fan_config: &FanControlConfig,
+        num_fans_running: usize,
+        decision_explained: ControlDecisionExplained,
+    ) -> ControlDecisionExplained {
+        // This section is labeled `FAN_DANGER` in the diagram
+        //
+        // Check `min_fans` are spinning _unless_ we have been explicitly configured to
+        // turn them off.
+        //
+        // XXX: There's a problem however: if we are configured for stopped fans and then
+        // the configuration changes at runtime to non-stopped fans, the delay of fans
+        // taking some time to spin up will cause this check to fire off!
+        //
+        // Other actions than shutdown are taken by caller (see `fans_failed`).
+        if fan_config.on_fan_failure == FanFailureAction::Shutdown
+            && Self::fans_failed(fan_config, num_fans_running, &decision_explained.decision)
+        {
+            return ControlDecisionExplained {
+                decision: Self::Shutdown,
+                reason: "not enough fans",
+            };
+        }
+        decision_explained
+    }
+
+    /// Decide what to do while mining is paused (all hash chains are stopped). Fans are set to
+    /// `idle_speed` instead of full speed when there's no temperature to control, otherwise
+    /// the decision is the same as when mining (`dangerous_temp` is still honored).
+    fn decide_paused(
+        config: &Config,
+        num_fans_running: usize,
+        temp: ChainTemperature,
+    ) -> ControlDecisionExplained {
+        match (
+            config.fan_config.as_ref(),
+            config.temp_config.as_ref(),
+            temp,
+        ) {
+            (Some(fan_config), Some(_), ChainTemperature::Unknown) => Self::check_fans(
+                fan_config,
+                num_fans_running,
+                ControlDecisionExplained {
+                    decision: Self::UseFixedSpeed(fan_config.idle_speed),
+                    reason: "mining paused",
+                },
+            ),
+            _ => Self::decide(config, num_fans_running, temp),
+        }
+    }
 }
 
 /// This structure abstracts the process of "making one aggregate temperature out of
@@ -429,6 +898,192 @@ impl TemperatureAccumulator {
     }
 }
 
+/// Frequency throttling of one hashchain
+#[derive(Debug, Clone)]
+pub struct ChainThrottle {
+    pub hashboard_idx: usize,
+    /// How much (in Hz) should be the hashchain frequency lowered
+    pub throttled_by: usize,
+}
+
+/// Peak temperatures of one hashchain
+#[derive(Debug, Clone)]
+pub struct ChainPeakTemperature {
+    pub hashboard_idx: usize,
+    /// Highest temperature since start (or since the last reset)
+    pub peak: Option<f32>,
+    /// Highest temperature within rolling window
+    pub window_peak: Option<f32>,
+}
+
+/// State of one hashchain as seen by watchdog
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChainWatchdogState {
+    Off,
+    Starting,
+    Running,
+    /// Hashchain stopped responding and it will be declared failed unless it recovers within
+    /// failure grace period
+    Degraded {
+        unresponsive_for: Duration,
+    },
+    Failed(&'static str),
+}
+
+impl std::string::ToString for ChainWatchdogState {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Off => "Off".to_string(),
+            Self::Starting => "Starting".to_string(),
+            Self::Running => "Running".to_string(),
+            Self::Degraded { .. } => "Degraded".to_string(),
+            Self::Failed(_) => "Failed".to_string(),
+        }
+    }
+}
+
+/// Watchdog state of one hashchain
+#[derive(Debug, Clone)]
+pub struct ChainWatchdog {
+    pub hashboard_idx: usize,
+    pub state: ChainWatchdogState,
+}
+
+/// Health of one hashchain
+///
+/// Each component is a factor in range 0.0 (worst) to 1.0 (best):
+///
+/// * `hw_errors` - `1 - hw_error_rate / HEALTH_HW_ERROR_RATE_CEILING` (zero at or above ceiling)
+/// * `temperature` - 1.0 up to `hot_temp`, then falling linearly to zero at `dangerous_temp`
+/// * `acceptance` - ratio of shares accepted by pools (it is shared by all hashchains because
+///   pools don't know which hashchain found the share)
+/// * `frequency` - ratio of throttled frequency to configured frequency
+///
+/// The score is weighted average of known components scaled to 0-100. Components that are
+/// unknown (no measurement yet, temperature control disabled...) are left out of the average.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainHealth {
+    pub hashboard_idx: usize,
+    pub score: Option<f32>,
+    pub hw_errors: Option<f32>,
+    pub temperature: Option<f32>,
+    pub acceptance: Option<f32>,
+    pub frequency: Option<f32>,
+}
+
+impl ChainHealth {
+    fn new(
+        hashboard_idx: usize,
+        config: &Config,
+        temp: ChainTemperature,
+        hw_error_rate: Option<f64>,
+        acceptance_rate: Option<f64>,
+        nominal_frequency: usize,
+        throttled_by: usize,
+    ) -> Self {
+        let clamp = |factor: f32| factor.max(0.0).min(1.0);
+
+        let hw_errors =
+            hw_error_rate.map(|rate| clamp(1.0 - (rate / HEALTH_HW_ERROR_RATE_CEILING) as f32));
+        let temperature = match (config.temp_config.as_ref(), temp) {
+            (Some(temp_config), ChainTemperature::Ok(temp)) => {
+                let band = temp_config.dangerous_temp - temp_config.hot_temp;
+                if temp <= temp_config.hot_temp {
+                    Some(1.0)
+                } else if band <= 0.0 {
+                    Some(0.0)
+                } else {
+                    Some(clamp((temp_config.dangerous_temp - temp) / band))
+                }
+            }
+            (Some(_), ChainTemperature::Failed) => Some(0.0),
+            _ => None,
+        };
+        let acceptance = acceptance_rate.map(|rate| clamp(rate as f32));
+        let frequency = if nominal_frequency > 0 {
+            Some(clamp(
+                nominal_frequency.saturating_sub(throttled_by) as f32 / nominal_frequency as f32,
+            ))
+        } else {
+            None
+        };
+
+        let weights = &config.health_weights;
+        let (weighted_sum, total_weight) = [
+            (hw_errors, weights.hw_errors),
+            (temperature, weights.temperature),
+            (acceptance, weights.acceptance),
+            (frequency, weights.frequency),
+        ]
+        .iter()
+        .filter_map(|(factor, weight)| factor.map(|factor| (factor * weight, *weight)))
+        .fold((0.0, 0.0), |(sum, total), (value, weight)| {
+            (sum + value, total + weight)
+        });
+        let score = if total_weight > 0.0 {
+            Some(100.0 * weighted_sum / total_weight)
+        } else {
+            None
+        };
+
+        Self {
+            hashboard_idx,
+            score,
+            hw_errors,
+            temperature,
+            acceptance,
+            frequency,
+        }
+    }
+}
+
+/// Result of fan test for a single fan
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanTestResult {
+    pub rpm: usize,
+    pub ok: bool,
+}
+
+/// Result of on-demand fan test
+#[derive(Debug, Clone, PartialEq)]
+pub struct FanTestReport {
+    pub fans: Vec<FanTestResult>,
+    /// Minimal speed of a fan that passes the test
+    pub min_rpm: usize,
+    /// Minimal number of fans that have to pass the test (unused fan connectors always fail)
+    pub min_fans: usize,
+    /// Reason why the test has been aborted before fans finished ramping up
+    pub aborted: Option<String>,
+}
+
+impl FanTestReport {
+    fn evaluate(
+        fan_feedback: &fan::Feedback,
+        min_rpm: usize,
+        min_fans: usize,
+        aborted: Option<String>,
+    ) -> Self {
+        Self {
+            fans: fan_feedback
+                .rpm
+                .iter()
+                .map(|rpm| FanTestResult {
+                    rpm: *rpm,
+                    ok: *rpm >= min_rpm,
+                })
+                .collect(),
+            min_rpm,
+            min_fans,
+            aborted,
+        }
+    }
+
+    /// The test finished and enough fans are spinning fast enough
+    pub fn passed(&self) -> bool {
+        self.aborted.is_none() && self.fans.iter().filter(|fan| fan.ok).count() >= self.min_fans
+    }
+}
+
 /// Status of `Monitor` for others to observe
 #[derive(Debug, Clone)]
 pub struct Status {
@@ -438,6 +1093,34 @@ pub struct Status {
     pub input_temperature: ChainTemperature,
     pub temperature_accumulator: TemperatureAccumulator,
     pub decision_explained: ControlDecisionExplained,
+    pub chain_throttles: Vec<ChainThrottle>,
+    pub chain_peak_temperatures: Vec<ChainPeakTemperature>,
+    pub chain_health: Vec<ChainHealth>,
+    pub chain_watchdog: Vec<ChainWatchdog>,
+    /// Remaining time of maintenance window (when active)
+    pub maintenance_remaining: Option<Duration>,
+    /// Mining is paused (no hash chain is running or there's no work from pools) while
+    /// temperature and fans are still being monitored
+    pub mining_paused: bool,
+}
+
+impl Status {
+    /// Return how much (in Hz) should be frequency of hashchain `hashboard_idx` lowered
+    pub fn throttled_by(&self, hashboard_idx: usize) -> usize {
+        self.chain_throttles
+            .iter()
+            .find(|throttle| throttle.hashboard_idx == hashboard_idx)
+            .map(|throttle| throttle.throttled_by)
+            .unwrap_or(0)
+    }
+
+    /// Return watchdog state of hashchain `hashboard_idx` (when it is registered)
+    pub fn watchdog_state(&self, hashboard_idx: usize) -> Option<&ChainWatchdogState> {
+        self.chain_watchdog
+            .iter()
+            .find(|watchdog| watchdog.hashboard_idx == hashboard_idx)
+            .map(|watchdog| &watchdog.state)
+    }
 }
 
 /// Monitor - it holds states of all Chains and everything related to fan control
@@ -455,6 +1138,52 @@ pub struct MonitorInner {
     /// Flag whether miner is in failure state - temperature critical, hashboards not responding,
     /// fans gone missing...
     failure_state: bool,
+    /// Ratio of shares accepted by pools over the last hashrate interval
+    acceptance_rate: Option<f64>,
+    /// Fan speed is not controlled while fan test is in progress
+    fan_test_running: bool,
+    /// End of maintenance window started from API
+    maintenance_until: Option<Instant>,
+    /// Whether maintenance window (started from API or by schedule) was active during last check
+    maintenance_active: bool,
+    /// Not enough fans were running during last check
+    fans_failed: bool,
+    /// There's no work from pools (no pool is connected)
+    work_paused: bool,
+}
+
+impl MonitorInner {
+    /// Remaining time of maintenance window started from API or by schedule
+    fn maintenance_remaining(&self, now: Instant, time_of_day: NaiveTime) -> Option<Duration> {
+        let requested = self
+            .maintenance_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|remaining| *remaining > Duration::from_secs(0));
+        let scheduled = self
+            .config
+            .maintenance_config
+            .scheduled_remaining(time_of_day);
+        requested.max(scheduled)
+    }
+
+    /// Check maintenance window expiration and log its entry/exit
+    fn update_maintenance(&mut self) -> Option<Duration> {
+        let remaining = self.maintenance_remaining(Instant::now(), Utc::now().time());
+        match (remaining, self.maintenance_active) {
+            (Some(remaining), false) => warn!(
+                "Monitor: entering maintenance window for {} s, protective actions below \
+                 DANGEROUS temperature are suppressed",
+                remaining.as_secs()
+            ),
+            (None, true) => info!("Monitor: maintenance window is over"),
+            _ => {}
+        }
+        if remaining.is_none() {
+            self.maintenance_until = None;
+        }
+        self.maintenance_active = remaining.is_some();
+        remaining
+    }
 }
 
 /// Wrapper around `MonitorInner` with immutable fields
@@ -482,13 +1211,30 @@ impl Monitor {
     ) -> Arc<Self> {
         let (status_sender, status_receiver) = watch::channel(None);
 
+        let fan_control = fan::Control::new().expect("failed initializing fan controller");
+        if let Some(pwm_frequency) = config
+            .fan_config
+            .as_ref()
+            .and_then(|fan_config| fan_config.pwm_frequency)
+        {
+            if let Err(e) = fan_control.set_pwm_frequency(pwm_frequency) {
+                warn!("{}, using default fan PWM frequency", e);
+            }
+        }
+
         let inner = MonitorInner {
             chains: Vec::new(),
             config,
-            fan_control: fan::Control::new().expect("failed initializing fan controller"),
+            fan_control,
             pid: fan::pid::TempControl::new(),
             failure_state: false,
             current_fan_speed: None,
+            acceptance_rate: None,
+            fan_test_running: false,
+            maintenance_until: None,
+            maintenance_active: false,
+            fans_failed: false,
+            work_paused: false,
         };
 
         let monitor = Arc::new(Monitor {
@@ -515,6 +1261,8 @@ impl Monitor {
     /// Just stops the fans (depending on whether it's in failure state).
     async fn termination_handler(self: Arc<Self>) {
         let mut inner = self.inner.lock().await;
+        // Fan test cannot prevent stopping fans
+        inner.fan_test_running = false;
         // Decide whether to leave fans on (depending on whether we are in failure state or not)
         if inner.failure_state {
             self.set_fan_speed(&mut inner, fan::Speed::FULL_SPEED);
@@ -524,14 +1272,19 @@ impl Monitor {
     }
 
     /// Shutdown miner
-    async fn shutdown(&self, inner: &mut MonitorInner, reason: String) {
+    async fn shutdown(&self, inner: &mut MonitorInner, cause: restart::Reason, reason: String) {
         error!("Monitor task declared miner shutdown: {}", reason);
+        restart::record_exit(cause);
         inner.failure_state = true;
         self.miner_shutdown.clone().send_halt().await;
     }
 
     /// Set fan speed
     fn set_fan_speed(&self, inner: &mut MonitorInner, fan_speed: fan::Speed) {
+        if inner.fan_test_running {
+            info!("Monitor: fan test in progress, keeping fan speed");
+            return;
+        }
         info!("Monitor: setting fan to {:?}", fan_speed);
         inner.fan_control.set_speed(fan_speed);
         inner.current_fan_speed = Some(fan_speed);
@@ -544,24 +1297,110 @@ impl Monitor {
     async fn do_tick(&self) {
         // decide hashchain state and collect temperatures
         let mut inner = self.inner.lock().await;
+        let maintenance_remaining = inner.update_maintenance();
+        let maintenance = maintenance_remaining.is_some();
         let mut temperature_accumulator = TemperatureAccumulator::new();
         let mut miner_warming_up = false;
+        let mut chain_throttles = Vec::with_capacity(inner.chains.len());
+        let mut chain_peak_temperatures = Vec::with_capacity(inner.chains.len());
+        let mut chain_health_inputs = Vec::with_capacity(inner.chains.len());
+        let mut chain_watchdog = Vec::with_capacity(inner.chains.len());
+        let mut chains_stopped = true;
         for chain in inner.chains.iter() {
             let mut chain = chain.lock().await;
-            chain.state.tick(Instant::now());
+            chain.state.tick(Instant::now(), inner.config.failure_grace);
+            if let ChainState::On(_) | ChainState::Running { .. } | ChainState::Degraded { .. } =
+                chain.state
+            {
+                chains_stopped = false;
+            }
+            if let Some((kind, message)) = chain.watchdog_event() {
+                events::emit(kind, Some(chain.hashboard_idx), message);
+            }
 
+            if let ChainState::Degraded { last_heartbeat, .. } = chain.state {
+                warn!(
+                    "Monitor: chain {} is not responding for {:?} (failure grace period is {:?})",
+                    chain.hashboard_idx,
+                    last_heartbeat.elapsed(),
+                    inner.config.failure_grace
+                );
+            }
             if let ChainState::Broken(reason) = chain.state {
                 // TODO: here comes "Shutdown"
                 let reason = format!("Chain {} is broken: {}", chain.hashboard_idx, reason);
-                // drop `chain` here to drop iterator which holds immutable reference
-                // to `monitor`
-                drop(chain);
-                self.shutdown(&mut inner, reason).await;
-                return;
+                if maintenance {
+                    warn!("Monitor: {} (ignored during maintenance window)", reason);
+                } else if inner.config.continue_on_chain_failure {
+                    warn!("Monitor: {} (continuing without it)", reason);
+                } else {
+                    // drop `chain` here to drop iterator which holds immutable reference
+                    // to `monitor`
+                    drop(chain);
+                    self.shutdown(&mut inner, restart::Reason::Watchdog, reason)
+                        .await;
+                    return;
+                }
             }
             info!("chain {}: {:?}", chain.hashboard_idx, chain.state);
-            temperature_accumulator.add_chain_temp(chain.state.get_temperature());
+            chain_watchdog.push(ChainWatchdog {
+                hashboard_idx: chain.hashboard_idx,
+                state: chain.state.watchdog_state(Instant::now()),
+            });
+            let chain_temperature = chain.state.control_temperature(&inner.config, maintenance);
+            temperature_accumulator.add_chain_temp(chain_temperature);
+            if let Some((kind, message)) = chain.alert_event(chain_temperature) {
+                events::emit(kind, Some(chain.hashboard_idx), message);
+            }
             miner_warming_up |= chain.state.is_warming_up(Instant::now());
+
+            // Adjust hashchain frequency throttling (current throttling is kept during maintenance)
+            if let Some(throttle_config) = inner.config.throttle_config.as_ref() {
+                let previous_throttled_by = chain.throttle.throttled_by;
+                if !maintenance
+                    && !chain
+                        .state
+                        .is_warming_up_for(Instant::now(), throttle_config.warmup_period)
+                    && chain
+                        .throttle
+                        .update(throttle_config, chain_temperature, Instant::now())
+                {
+                    let message = format!(
+                        "Chain {} throttled by {} MHz (previously {} MHz)",
+                        chain.hashboard_idx,
+                        chain.throttle.throttled_by / 1_000_000,
+                        previous_throttled_by / 1_000_000
+                    );
+                    info!("Monitor: {}", message);
+                    events::emit(
+                        events::Kind::ThermalThrottle,
+                        Some(chain.hashboard_idx),
+                        message,
+                    );
+                }
+            } else {
+                chain.throttle = ThrottleState::new(chain.throttle.nominal_frequency);
+            }
+            chain_throttles.push(ChainThrottle {
+                hashboard_idx: chain.hashboard_idx,
+                throttled_by: chain.throttle.throttled_by,
+            });
+
+            chain.peak_temperature.update(
+                chain_temperature,
+                Instant::now(),
+                inner.config.peak_temp_window,
+            );
+            chain_peak_temperatures.push(ChainPeakTemperature {
+                hashboard_idx: chain.hashboard_idx,
+                peak: chain.peak_temperature.peak,
+                window_peak: chain.peak_temperature.window_peak(),
+            });
+            chain_health_inputs.push((
+                chain_temperature,
+                chain.hw_error_rate,
+                chain.throttle.nominal_frequency,
+            ));
         }
         let input_temperature = temperature_accumulator.calc_result();
 
@@ -574,13 +1413,77 @@ impl Monitor {
         );
 
         // all right, temperature has been aggregated, decide what to do
-        let decision_explained =
-            ControlDecision::decide(&inner.config, num_fans_running, input_temperature);
-        info!("Monitor: {:?}", decision_explained);
+        let decision_explained = ControlDecision::decide_tick(
+            &inner.config,
+            maintenance,
+            chains_stopped,
+            num_fans_running,
+            input_temperature,
+        );
+
+        // Fan failure that doesn't shutdown the miner
+        let mut fans_failed = false;
+        if let Some(fan_config) = inner.config.fan_config.as_ref() {
+            if ControlDecision::fans_failed(
+                fan_config,
+                num_fans_running,
+                &decision_explained.decision,
+            ) {
+                fans_failed = true;
+                if !inner.fans_failed {
+                    events::emit(
+                        events::Kind::FanFailure,
+                        None,
+                        format!(
+                            "Not enough fans ({} < {})",
+                            num_fans_running, fan_config.min_fans
+                        ),
+                    );
+                }
+                match fan_config.on_fan_failure {
+                    FanFailureAction::Shutdown => {}
+                    FanFailureAction::Throttle(_) if maintenance => {
+                        warn!(
+                            "Monitor: not enough fans ({} < {}), throttling suppressed by \
+                             maintenance window",
+                            num_fans_running, fan_config.min_fans
+                        );
+                    }
+                    FanFailureAction::Throttle(min_frequency) => {
+                        warn!(
+                            "Monitor: not enough fans ({} < {}), throttling to {} MHz",
+                            num_fans_running,
+                            fan_config.min_fans,
+                            min_frequency / 1_000_000
+                        );
+                        for (chain, chain_throttle) in
+                            inner.chains.iter().zip(chain_throttles.iter_mut())
+                        {
+                            let nominal_frequency = chain.lock().await.throttle.nominal_frequency;
+                            chain_throttle.throttled_by = chain_throttle
+                                .throttled_by
+                                .max(nominal_frequency.saturating_sub(min_frequency));
+                        }
+                    }
+                    FanFailureAction::Warn => {
+                        warn!(
+                            "Monitor: not enough fans ({} < {})",
+                            num_fans_running, fan_config.min_fans
+                        );
+                    }
+                }
+            }
+        }
+        inner.fans_failed = fans_failed;
+
         match decision_explained.decision {
             ControlDecision::Shutdown => {
-                self.shutdown(&mut inner, decision_explained.reason.into())
-                    .await;
+                self.shutdown(
+                    &mut inner,
+                    restart::Reason::Thermal,
+                    decision_explained.reason.into(),
+                )
+                .await;
             }
             ControlDecision::UseFixedSpeed(fan_speed) => {
                 self.set_fan_speed(&mut inner, fan_speed);
@@ -605,6 +1508,25 @@ impl Monitor {
             ControlDecision::Nothing => {}
         }
 
+        // Health is evaluated with final throttling (including fan failure)
+        let chain_health = chain_health_inputs
+            .into_iter()
+            .zip(chain_throttles.iter())
+            .map(
+                |((temperature, hw_error_rate, nominal_frequency), chain_throttle)| {
+                    ChainHealth::new(
+                        chain_throttle.hashboard_idx,
+                        &inner.config,
+                        temperature,
+                        hw_error_rate,
+                        inner.acceptance_rate,
+                        nominal_frequency,
+                        chain_throttle.throttled_by,
+                    )
+                },
+            )
+            .collect();
+
         // Broadcast `Status`
         let monitor_status = Status {
             fan_feedback,
@@ -612,6 +1534,12 @@ impl Monitor {
             input_temperature,
             temperature_accumulator,
             decision_explained,
+            chain_throttles,
+            chain_peak_temperatures,
+            chain_health,
+            chain_watchdog,
+            maintenance_remaining,
+            mining_paused: chains_stopped || inner.work_paused,
             config: inner.config.clone(),
         };
         self.status_sender
@@ -637,10 +1565,21 @@ impl Monitor {
     }
 
     /// Registers hashchain within monitor
-    /// The `hashboard_idx` parameter is for debugging purposes
-    pub async fn register_hashchain(&self, hashboard_idx: usize) -> mpsc::UnboundedSender<Message> {
+    /// The `hashboard_idx` parameter identifies hashchain in throttling status and
+    /// `nominal_frequency` (in Hz) is the configured frequency from which hashchain is throttled.
+    /// `ThermalWarn` event is emitted when hashchain temperature reaches `alert_temp` (in Celsius).
+    pub async fn register_hashchain(
+        &self,
+        hashboard_idx: usize,
+        nominal_frequency: usize,
+        alert_temp: Option<f32>,
+    ) -> mpsc::UnboundedSender<Message> {
         let (tx, rx) = mpsc::unbounded();
-        let chain = Arc::new(Mutex::new(Chain::new(hashboard_idx)));
+        let chain = Arc::new(Mutex::new(Chain::new(
+            hashboard_idx,
+            nominal_frequency,
+            alert_temp,
+        )));
         {
             let mut inner = self.inner.lock().await;
             inner.chains.push(chain.clone());
@@ -649,6 +1588,119 @@ impl Monitor {
         tx
     }
 
+    /// Forget peak temperatures of all hashchains (they will be reported with the next tick)
+    pub async fn reset_peak_temperatures(&self) {
+        let inner = self.inner.lock().await;
+        for chain in inner.chains.iter() {
+            chain.lock().await.peak_temperature = Default::default();
+        }
+    }
+
+    /// Update hardware error rate of hashchain `hashboard_idx` (used for its health score)
+    pub async fn set_hw_error_rate(&self, hashboard_idx: usize, hw_error_rate: Option<f64>) {
+        let inner = self.inner.lock().await;
+        for chain in inner.chains.iter() {
+            let mut chain = chain.lock().await;
+            if chain.hashboard_idx == hashboard_idx {
+                chain.hw_error_rate = hw_error_rate;
+            }
+        }
+    }
+
+    /// Update ratio of shares accepted by pools (used for health score of all hashchains)
+    pub async fn set_acceptance_rate(&self, acceptance_rate: Option<f64>) {
+        self.inner.lock().await.acceptance_rate = acceptance_rate;
+    }
+
+    /// Update whether there's work from pools (it's reported in `Status` only, monitoring
+    /// continues regardless of work)
+    pub async fn set_work_paused(&self, work_paused: bool) {
+        self.inner.lock().await.work_paused = work_paused;
+    }
+
+    /// Ramp fans up to full speed, report speed of each fan and restore the previous fan speed.
+    /// The test is aborted as soon as temperature reaches `hot_temp`.
+    pub async fn fan_test(&self) -> Result<FanTestReport, String> {
+        let (min_fans, hot_temp) = {
+            let mut inner = self.inner.lock().await;
+            let min_fans = match inner.config.fan_config.as_ref() {
+                Some(fan_config) => fan_config.min_fans,
+                None => Err("fan control is disabled")?,
+            };
+            if inner.fan_test_running {
+                Err("fan test is already in progress")?;
+            }
+            info!("Monitor: starting fan test");
+            inner.fan_control.set_speed(fan::Speed::FULL_SPEED);
+            inner.fan_test_running = true;
+            let hot_temp = inner
+                .config
+                .temp_config
+                .as_ref()
+                .map(|temp_config| temp_config.hot_temp);
+            (min_fans, hot_temp)
+        };
+
+        let started = Instant::now();
+        let mut aborted = None;
+        while started.elapsed() < FAN_TEST_RAMP_TIME {
+            delay_for(FAN_TEST_POLL_INTERVAL).await;
+            let input_temperature = self
+                .status_receiver
+                .borrow()
+                .as_ref()
+                .map(|status| status.input_temperature);
+            match (input_temperature, hot_temp) {
+                (Some(ChainTemperature::Ok(temp)), Some(hot_temp)) if temp >= hot_temp => {
+                    aborted = Some(format!("temperature {} above HOT {}", temp, hot_temp));
+                }
+                (Some(ChainTemperature::Failed), _) => {
+                    aborted = Some("temperature readout FAILED".to_string());
+                }
+                _ => {}
+            }
+            if aborted.is_some() {
+                break;
+            }
+        }
+
+        let mut inner = self.inner.lock().await;
+        let fan_feedback = inner.fan_control.read_feedback();
+        inner.fan_test_running = false;
+        // Restore the fan speed set by fan control before the test
+        if let Some(fan_speed) = inner.current_fan_speed {
+            self.set_fan_speed(&mut inner, fan_speed);
+        }
+        let report = FanTestReport::evaluate(&fan_feedback, FAN_TEST_MIN_RPM, min_fans, aborted);
+        match report.aborted.as_ref() {
+            Some(reason) => warn!("Monitor: fan test aborted: {}", reason),
+            None => info!("Monitor: fan test finished: {:?}", report),
+        }
+        Ok(report)
+    }
+
+    /// Start maintenance window lasting `duration` (configured duration when missing).
+    /// Returns remaining time of maintenance window.
+    pub async fn start_maintenance(&self, duration: Option<Duration>) -> Option<Duration> {
+        let mut inner = self.inner.lock().await;
+        let duration = duration.unwrap_or(inner.config.maintenance_config.duration);
+        info!(
+            "Monitor: maintenance window requested for {} s",
+            duration.as_secs()
+        );
+        inner.maintenance_until = Some(Instant::now() + duration);
+        inner.update_maintenance()
+    }
+
+    /// Stop maintenance window started from API (scheduled window cannot be stopped).
+    /// Returns remaining time of maintenance window.
+    pub async fn stop_maintenance(&self) -> Option<Duration> {
+        let mut inner = self.inner.lock().await;
+        info!("Monitor: maintenance window stop requested");
+        inner.maintenance_until = None;
+        inner.update_maintenance()
+    }
+
     pub async fn with_configuration<F, R>(&self, f: F) -> R
     where
         F: FnOnce(&mut Config) -> R,
@@ -768,6 +1820,14 @@ mod test {
             send(running_state.clone(), later, Message::Off),
             ChainState::Off
         );
+        assert_variant!(
+            send(running_state.clone(), later, Message::Failed("test")),
+            ChainState::Broken("test")
+        );
+        assert_variant!(
+            send(ChainState::Off, later, Message::Failed("test")),
+            ChainState::Broken(_)
+        );
     }
 
     /// Test "warm up" period
@@ -792,10 +1852,24 @@ mod test {
         assert_eq!(running_state.clone().is_warming_up(now), true);
         assert_eq!(running_state.clone().is_warming_up(later), true);
         assert_eq!(running_state.clone().is_warming_up(warmed_time), false);
+
+        // custom warm up period (e.g. for throttling)
+        let period = Duration::from_secs(300);
+        assert_eq!(ChainState::Off.is_warming_up_for(now, period), false);
+        assert_eq!(ChainState::On(now).is_warming_up_for(now, period), true);
+        assert_eq!(running_state.is_warming_up_for(warmed_time, period), true);
+        assert_eq!(
+            running_state.is_warming_up_for(now + Duration::from_secs(301), period),
+            false
+        );
+        assert_eq!(
+            running_state.is_warming_up_for(later, Duration::from_secs(0)),
+            false
+        );
     }
 
     fn tick(mut state: ChainState, later: Instant) -> ChainState {
-        state.tick(later);
+        state.tick(later, Duration::from_secs(0));
         state
     }
 
@@ -839,6 +1913,209 @@ mod test {
         );
     }
 
+    /// Test that unresponsive hashchain is degraded during failure grace period
+    #[test]
+    fn test_monitor_failure_grace() {
+        let temp = sensor::Temperature {
+            local: sensor::Measurement::Ok(10.0),
+            remote: sensor::Measurement::Ok(22.0),
+        };
+        let now = Instant::now();
+        let grace = Duration::from_secs(30);
+        let running_state = ChainState::Running {
+            started: now,
+            last_heartbeat: now,
+            temperature: temp.clone(),
+        };
+        let tick = |mut state: ChainState, later: Instant| {
+            state.tick(later, grace);
+            state
+        };
+
+        assert_variant!(
+            tick(running_state.clone(), now + Duration::from_secs(2)),
+            ChainState::Running { .. }
+        );
+        let degraded_state = tick(running_state.clone(), now + Duration::from_secs(20));
+        assert_variant!(degraded_state.clone(), ChainState::Degraded { .. });
+        assert_eq!(
+            degraded_state.watchdog_state(now + Duration::from_secs(20)),
+            ChainWatchdogState::Degraded {
+                unresponsive_for: Duration::from_secs(20)
+            }
+        );
+        assert_eq!(degraded_state.get_temperature(), ChainTemperature::Unknown);
+        assert_variant!(
+            tick(degraded_state.clone(), now + Duration::from_secs(39)),
+            ChainState::Degraded { .. }
+        );
+        assert_variant!(
+            tick(degraded_state.clone(), now + Duration::from_secs(40)),
+            ChainState::Broken(_)
+        );
+
+        // hashchain recovers when it sends heartbeat again
+        assert_variant!(
+            send(
+                degraded_state.clone(),
+                now + Duration::from_secs(25),
+                Message::Running(temp.clone())
+            ),
+            ChainState::Running { .. }
+        );
+        assert_variant!(
+            send(degraded_state, now + Duration::from_secs(25), Message::Off),
+            ChainState::Off
+        );
+    }
+
+    fn tick_config(continue_on_chain_failure: bool) -> Config {
+        Config {
+            fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure,
+            fan_config: Some(FanControlConfig {
+                mode: FanControlMode::TargetTemperature(75.0),
+                min_fans: 2,
+                pwm_frequency: None,
+                on_fan_failure: FanFailureAction::Shutdown,
+                idle_speed: fan::Speed::FULL_SPEED,
+            }),
+            temp_config: Some(TempControlConfig {
+                dangerous_temp: 100.0,
+                hot_temp: 80.0,
+                hot_temp_override: true,
+            }),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
+        }
+    }
+
+    fn running_state(temp: f32) -> ChainState {
+        ChainState::Running {
+            started: Instant::now(),
+            last_heartbeat: Instant::now(),
+            temperature: sensor::Temperature {
+                local: sensor::Measurement::Ok(10.0),
+                remote: sensor::Measurement::Ok(temp),
+            },
+        }
+    }
+
+    /// Test that broken hashchain fails temperature control unless mining continues without it
+    /// or maintenance window is active
+    #[test]
+    fn test_broken_chain_temperature() {
+        let broken_state = ChainState::Broken("took too long to start");
+        assert_eq!(
+            broken_state.control_temperature(&tick_config(false), false),
+            ChainTemperature::Failed
+        );
+        assert_eq!(
+            broken_state.control_temperature(&tick_config(true), false),
+            ChainTemperature::Unknown
+        );
+        assert_eq!(
+            broken_state.control_temperature(&tick_config(false), true),
+            ChainTemperature::Unknown
+        );
+
+        let running_state = running_state(22.0);
+        assert_eq!(
+            running_state.control_temperature(&tick_config(true), true),
+            running_state.get_temperature()
+        );
+    }
+
+    /// Test one tick of temperature control with broken hashchain. Only shutdown decision
+    /// records thermal termination reason.
+    #[test]
+    fn test_tick_broken_chain() {
+        let config = tick_config(false);
+        let states = [running_state(70.0), ChainState::Broken("not responding")];
+        let decide = |maintenance| {
+            let mut temperature_accumulator = TemperatureAccumulator::new();
+            for state in states.iter() {
+                temperature_accumulator
+                    .add_chain_temp(state.control_temperature(&config, maintenance));
+            }
+            ControlDecision::decide_tick(
+                &config,
+                maintenance,
+                false,
+                2,
+                temperature_accumulator.calc_result(),
+            )
+            .decision
+        };
+
+        assert_eq!(decide(false), ControlDecision::Shutdown);
+        // broken hashchain is ignored during maintenance window
+        assert_eq!(
+            decide(true),
+            ControlDecision::UsePid {
+                target_temp: 75.0,
+                input_temp: 70.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_chain_events() {
+        let now = Instant::now();
+        let kind = |event: Option<(events::Kind, String)>| event.map(|(kind, _)| kind);
+        let mut chain = Chain::new(6, 650_000_000, Some(90.0));
+
+        // alert is emitted only when temperature crosses the threshold
+        assert_eq!(kind(chain.alert_event(ChainTemperature::Ok(85.0))), None);
+        assert_eq!(
+            kind(chain.alert_event(ChainTemperature::Ok(90.0))),
+            Some(events::Kind::ThermalWarn)
+        );
+        assert_eq!(kind(chain.alert_event(ChainTemperature::Ok(95.0))), None);
+        assert_eq!(kind(chain.alert_event(ChainTemperature::Unknown)), None);
+        assert_eq!(kind(chain.alert_event(ChainTemperature::Ok(89.0))), None);
+        assert_eq!(
+            kind(chain.alert_event(ChainTemperature::Ok(91.0))),
+            Some(events::Kind::ThermalWarn)
+        );
+        let mut chain_without_alert = Chain::new(7, 650_000_000, None);
+        assert_eq!(
+            kind(chain_without_alert.alert_event(ChainTemperature::Ok(120.0))),
+            None
+        );
+
+        // degraded hashchain that responds again recovers
+        let running_state = ChainState::Running {
+            started: now,
+            last_heartbeat: now,
+            temperature: sensor::INVALID_TEMPERATURE_READING,
+        };
+        chain.state = running_state.clone();
+        assert_eq!(kind(chain.watchdog_event()), None);
+        chain.state = ChainState::Degraded {
+            started: now,
+            last_heartbeat: now,
+        };
+        assert_eq!(kind(chain.watchdog_event()), None);
+        chain.state = running_state;
+        assert_eq!(
+            kind(chain.watchdog_event()),
+            Some(events::Kind::ChainRecovered)
+        );
+        assert_eq!(kind(chain.watchdog_event()), None);
+
+        // failure is reported only once
+        chain.state = ChainState::Broken("test");
+        assert_eq!(
+            kind(chain.watchdog_event()),
+            Some(events::Kind::ChainFailed)
+        );
+        assert_eq!(kind(chain.watchdog_event()), None);
+    }
+
     fn test_acc(temp1: ChainTemperature, temp2: ChainTemperature) -> ChainTemperature {
         let mut tacc = TemperatureAccumulator::new();
         tacc.add_chain_temp(temp1);
@@ -900,48 +2177,101 @@ mod test {
         let temp_config = TempControlConfig {
             dangerous_temp: 100.0,
             hot_temp: 80.0,
+            hot_temp_override: true,
         };
         let fan_speed = fan::Speed::new(50);
         let fan_config = FanControlConfig {
             mode: FanControlMode::FixedSpeed(fan_speed),
             min_fans: 2,
+            pwm_frequency: None,
+            on_fan_failure: FanFailureAction::Shutdown,
+            idle_speed: fan::Speed::FULL_SPEED,
         };
         let fans_off = fan::Speed::STOPPED;
         let fans_off_config = Config {
             fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
             fan_config: Some(FanControlConfig {
                 mode: FanControlMode::FixedSpeed(fans_off),
                 min_fans: 2,
+                pwm_frequency: None,
+                on_fan_failure: FanFailureAction::Shutdown,
+                idle_speed: fan::Speed::FULL_SPEED,
             }),
             temp_config: None,
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
         };
         let all_off_config = Config {
             fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
             fan_config: None,
             temp_config: None,
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
         };
         let fans_on_config = Config {
             fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
             fan_config: Some(fan_config.clone()),
             temp_config: None,
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
         };
         let temp_on_config = Config {
             fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
             fan_config: None,
             temp_config: Some(temp_config.clone()),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
         };
         let both_on_config = Config {
             fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
             fan_config: Some(fan_config.clone()),
             temp_config: Some(temp_config.clone()),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
+        };
+        let both_on_no_override_config = Config {
+            temp_config: Some(TempControlConfig {
+                hot_temp_override: false,
+                ..temp_config.clone()
+            }),
+            ..both_on_config.clone()
         };
         let both_on_pid_config = Config {
             fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
             fan_config: Some(FanControlConfig {
                 mode: FanControlMode::TargetTemperature(75.0),
                 min_fans: 2,
+                pwm_frequency: None,
+                on_fan_failure: FanFailureAction::Shutdown,
+                idle_speed: fan::Speed::FULL_SPEED,
             }),
             temp_config: Some(temp_config.clone()),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
         };
 
         assert_variant!(
@@ -1011,6 +2341,10 @@ mod test {
         );
         assert_eq!(
             ControlDecision::decide(&both_on_config, 2, hot_temp).decision,
+            ControlDecision::UseFixedSpeed(fan::Speed::FULL_SPEED)
+        );
+        assert_eq!(
+            ControlDecision::decide(&both_on_no_override_config, 2, hot_temp).decision,
             ControlDecision::UseFixedSpeed(fan_speed)
         );
         assert_eq!(
@@ -1046,4 +2380,412 @@ mod test {
             }
         );
     }
+
+    /// Test actions taken when not enough fans are running
+    #[test]
+    fn test_decide_fan_failure() {
+        let dang_temp = ChainTemperature::Ok(150.0);
+        let low_temp = ChainTemperature::Ok(50.0);
+        let fan_speed = fan::Speed::new(50);
+        let config = |on_fan_failure| Config {
+            fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
+            fan_config: Some(FanControlConfig {
+                mode: FanControlMode::FixedSpeed(fan_speed),
+                min_fans: 2,
+                pwm_frequency: None,
+                on_fan_failure,
+                idle_speed: fan::Speed::FULL_SPEED,
+            }),
+            temp_config: Some(TempControlConfig {
+                dangerous_temp: 100.0,
+                hot_temp: 80.0,
+                hot_temp_override: true,
+            }),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
+        };
+        let warn_config = config(FanFailureAction::Warn);
+        let throttle_config = config(FanFailureAction::Throttle(400_000_000));
+
+        for config in [&warn_config, &throttle_config].iter() {
+            let fan_config = config.fan_config.as_ref().unwrap();
+            assert_eq!(
+                ControlDecision::decide(config, 1, low_temp).decision,
+                ControlDecision::UseFixedSpeed(fan_speed)
+            );
+            assert!(ControlDecision::fans_failed(
+                fan_config,
+                1,
+                &ControlDecision::UseFixedSpeed(fan_speed)
+            ));
+            assert!(!ControlDecision::fans_failed(
+                fan_config,
+                1,
+                &ControlDecision::UseFixedSpeed(fan::Speed::STOPPED)
+            ));
+            // dangerous temperature is still checked
+            assert_eq!(
+                ControlDecision::decide(config, 1, dang_temp).decision,
+                ControlDecision::Shutdown
+            );
+        }
+    }
+
+    /// Test that maintenance window suppresses shutdown below dangerous temperature only
+    #[test]
+    fn test_decide_maintenance() {
+        let dang_temp = ChainTemperature::Ok(150.0);
+        let low_temp = ChainTemperature::Ok(50.0);
+        let config = Config {
+            fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
+            fan_config: Some(FanControlConfig {
+                mode: FanControlMode::FixedSpeed(fan::Speed::new(50)),
+                min_fans: 2,
+                pwm_frequency: None,
+                on_fan_failure: FanFailureAction::Shutdown,
+                idle_speed: fan::Speed::FULL_SPEED,
+            }),
+            temp_config: Some(TempControlConfig {
+                dangerous_temp: 100.0,
+                hot_temp: 80.0,
+                hot_temp_override: true,
+            }),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
+        };
+        let decide = |num_fans_running, temp| {
+            ControlDecision::decide_maintenance(
+                &config,
+                temp,
+                ControlDecision::decide(&config, num_fans_running, temp),
+            )
+            .decision
+        };
+
+        // not enough fans
+        assert_eq!(
+            decide(1, low_temp),
+            ControlDecision::UseFixedSpeed(fan::Speed::FULL_SPEED)
+        );
+        // failed sensors cannot guard dangerous temperature
+        assert_eq!(
+            decide(2, ChainTemperature::Failed),
+            ControlDecision::Shutdown
+        );
+        assert_eq!(
+            decide(1, ChainTemperature::Failed),
+            ControlDecision::Shutdown
+        );
+        // not enough fans with unknown temperature
+        assert_eq!(
+            decide(1, ChainTemperature::Unknown),
+            ControlDecision::Shutdown
+        );
+        // dangerous temperature is still honored
+        assert_eq!(decide(2, dang_temp), ControlDecision::Shutdown);
+        assert_eq!(decide(1, dang_temp), ControlDecision::Shutdown);
+        // other decisions are left intact
+        assert_eq!(
+            decide(2, low_temp),
+            ControlDecision::UseFixedSpeed(fan::Speed::new(50))
+        );
+    }
+
+    #[test]
+    fn test_decide_paused() {
+        let idle_speed = fan::Speed::new(20);
+        let config = Config {
+            fans_on_while_warming_up: true,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
+            fan_config: Some(FanControlConfig {
+                mode: FanControlMode::TargetTemperature(75.0),
+                min_fans: 2,
+                pwm_frequency: None,
+                on_fan_failure: FanFailureAction::Shutdown,
+                idle_speed,
+            }),
+            temp_config: Some(TempControlConfig {
+                dangerous_temp: 100.0,
+                hot_temp: 80.0,
+                hot_temp_override: true,
+            }),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
+        };
+        let decide = |num_fans_running, temp| {
+            ControlDecision::decide_paused(&config, num_fans_running, temp).decision
+        };
+
+        // idle fan speed is used instead of full speed when there's no temperature
+        assert_eq!(
+            decide(2, ChainTemperature::Unknown),
+            ControlDecision::UseFixedSpeed(idle_speed)
+        );
+        assert_eq!(
+            ControlDecision::decide(&config, 2, ChainTemperature::Unknown).decision,
+            ControlDecision::UseFixedSpeed(fan::Speed::FULL_SPEED)
+        );
+        // fans are still checked
+        assert_eq!(
+            decide(1, ChainTemperature::Unknown),
+            ControlDecision::Shutdown
+        );
+        // temperatures are still controlled while mining is paused
+        assert_eq!(
+            decide(2, ChainTemperature::Ok(150.0)),
+            ControlDecision::Shutdown
+        );
+        assert_eq!(
+            decide(2, ChainTemperature::Failed),
+            ControlDecision::Shutdown
+        );
+        assert_eq!(
+            decide(2, ChainTemperature::Ok(90.0)),
+            ControlDecision::UseFixedSpeed(fan::Speed::FULL_SPEED)
+        );
+    }
+
+    /// Test daily maintenance schedule
+    #[test]
+    fn test_maintenance_schedule() {
+        let time = |hour, min| NaiveTime::from_hms(hour, min, 0);
+        let minutes = |min: u64| Some(Duration::from_secs(min * 60));
+
+        let config = MaintenanceConfig {
+            duration: Duration::from_secs(30 * 60),
+            daily_start: None,
+        };
+        assert_eq!(config.scheduled_remaining(time(0, 0)), None);
+
+        let config = MaintenanceConfig {
+            daily_start: Some(time(2, 0)),
+            ..config
+        };
+        assert_eq!(config.scheduled_remaining(time(1, 59)), None);
+        assert_eq!(config.scheduled_remaining(time(2, 0)), minutes(30));
+        assert_eq!(config.scheduled_remaining(time(2, 20)), minutes(10));
+        assert_eq!(config.scheduled_remaining(time(2, 30)), None);
+
+        // window reaching over midnight
+        let config = MaintenanceConfig {
+            daily_start: Some(time(23, 50)),
+            ..config
+        };
+        assert_eq!(config.scheduled_remaining(time(23, 49)), None);
+        assert_eq!(config.scheduled_remaining(time(23, 55)), minutes(25));
+        assert_eq!(config.scheduled_remaining(time(0, 10)), minutes(10));
+        assert_eq!(config.scheduled_remaining(time(0, 20)), None);
+    }
+
+    /// Test health score composition and weighting
+    #[test]
+    fn test_chain_health() {
+        let mut config = Config {
+            fan_config: None,
+            temp_config: Some(TempControlConfig {
+                dangerous_temp: 100.0,
+                hot_temp: 80.0,
+                hot_temp_override: true,
+            }),
+            throttle_config: None,
+            peak_temp_window: None,
+            health_weights: Default::default(),
+            maintenance_config: Default::default(),
+            fans_on_while_warming_up: false,
+            failure_grace: Duration::from_secs(0),
+            continue_on_chain_failure: false,
+        };
+        let mhz = 1_000_000;
+
+        // Perfectly healthy hashchain
+        let health = ChainHealth::new(
+            6,
+            &config,
+            ChainTemperature::Ok(70.0),
+            Some(0.0),
+            Some(1.0),
+            600 * mhz,
+            0,
+        );
+        assert_eq!(health.score, Some(100.0));
+
+        // Halfway into temperature band, 2.5% HW errors, 90% acceptance, throttled by 1/4
+        let health = ChainHealth::new(
+            6,
+            &config,
+            ChainTemperature::Ok(90.0),
+            Some(0.025),
+            Some(0.9),
+            600 * mhz,
+            150 * mhz,
+        );
+        assert_relative_eq!(health.temperature.unwrap(), 0.5);
+        assert_relative_eq!(health.hw_errors.unwrap(), 0.5);
+        assert_relative_eq!(health.acceptance.unwrap(), 0.9);
+        assert_relative_eq!(health.frequency.unwrap(), 0.75);
+        assert_relative_eq!(health.score.unwrap(), 66.25, epsilon = 0.001);
+
+        // Unknown components are left out
+        let health = ChainHealth::new(
+            6,
+            &config,
+            ChainTemperature::Unknown,
+            None,
+            None,
+            600 * mhz,
+            150 * mhz,
+        );
+        assert_eq!(health.temperature, None);
+        assert_relative_eq!(health.score.unwrap(), 75.0, epsilon = 0.001);
+
+        // Only weighted components count
+        config.health_weights = HealthWeights {
+            hw_errors: 0.0,
+            temperature: 3.0,
+            acceptance: 0.0,
+            frequency: 1.0,
+        };
+        let health = ChainHealth::new(
+            6,
+            &config,
+            ChainTemperature::Ok(90.0),
+            Some(0.05),
+            Some(0.0),
+            600 * mhz,
+            0,
+        );
+        assert_relative_eq!(health.score.unwrap(), 62.5, epsilon = 0.001);
+    }
+
+    /// Test evaluation of fan test
+    #[test]
+    fn test_fan_test_report() {
+        let fan_feedback = fan::Feedback {
+            rpm: vec![FAN_TEST_MIN_RPM, FAN_TEST_MIN_RPM - 1, 0],
+        };
+        let report = FanTestReport::evaluate(&fan_feedback, FAN_TEST_MIN_RPM, 2, None);
+        assert_eq!(
+            report.fans,
+            vec![
+                FanTestResult {
+                    rpm: FAN_TEST_MIN_RPM,
+                    ok: true
+                },
+                FanTestResult {
+                    rpm: FAN_TEST_MIN_RPM - 1,
+                    ok: false
+                },
+                FanTestResult { rpm: 0, ok: false },
+            ]
+        );
+        assert!(!report.passed());
+
+        let fan_feedback = fan::Feedback {
+            rpm: vec![FAN_TEST_MIN_RPM, 2 * FAN_TEST_MIN_RPM, 0],
+        };
+        assert!(FanTestReport::evaluate(&fan_feedback, FAN_TEST_MIN_RPM, 2, None).passed());
+        // Aborted test never passes
+        assert!(!FanTestReport::evaluate(
+            &fan_feedback,
+            FAN_TEST_MIN_RPM,
+            2,
+            Some("temperature above HOT".to_string())
+        )
+        .passed());
+    }
+
+    /// Test tracking of peak temperature since start and within rolling window
+    #[test]
+    fn test_peak_temperature() {
+        let window = Some(Duration::from_secs(10));
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut peak = PeakTemperature::default();
+
+        peak.update(ChainTemperature::Ok(70.0), at(0), window);
+        peak.update(ChainTemperature::Ok(80.0), at(2), window);
+        peak.update(ChainTemperature::Ok(75.0), at(4), window);
+        peak.update(ChainTemperature::Unknown, at(6), window);
+        assert_eq!(peak.peak, Some(80.0));
+        assert_eq!(peak.window_peak(), Some(80.0));
+
+        // hottest measurement leaves the window
+        peak.update(ChainTemperature::Ok(72.0), at(13), window);
+        assert_eq!(peak.peak, Some(80.0));
+        assert_eq!(peak.window_peak(), Some(75.0));
+        peak.update(ChainTemperature::Ok(71.0), at(20), window);
+        assert_eq!(peak.window_peak(), Some(72.0));
+
+        // without window only peak since start is tracked
+        peak.update(ChainTemperature::Ok(90.0), at(21), None);
+        assert_eq!(peak.peak, Some(90.0));
+        assert_eq!(peak.window_peak(), None);
+    }
+
+    /// Test throttling down and recovery of hashchain frequency
+    #[test]
+    fn test_throttle() {
+        const MHZ: usize = 1_000_000;
+        let config = ThrottleConfig {
+            throttle_temp: 90.0,
+            hysteresis: 5.0,
+            step: 50 * MHZ,
+            min_frequency: 500 * MHZ,
+            recovery_temp: 88.0,
+            recovery_dwell: Duration::from_secs(60),
+            warmup_period: Duration::from_secs(0),
+        };
+        let now = Instant::now();
+        let secs = |secs| now + Duration::from_secs(secs);
+        let mut state = ThrottleState::new(650 * MHZ);
+
+        // cool chain is left alone
+        assert!(!state.update(&config, ChainTemperature::Ok(60.0), now));
+        assert_eq!(state.throttled_by, 0);
+
+        // hot chain is throttled down step by step until it reaches minimal frequency
+        assert!(state.update(&config, ChainTemperature::Ok(90.0), secs(5)));
+        assert_eq!(state.throttled_by, 50 * MHZ);
+        assert!(state.update(&config, ChainTemperature::Ok(95.0), secs(10)));
+        assert!(state.update(&config, ChainTemperature::Ok(95.0), secs(15)));
+        assert_eq!(state.throttled_by, 150 * MHZ);
+        assert!(!state.update(&config, ChainTemperature::Ok(95.0), secs(20)));
+        assert_eq!(state.throttled_by, 150 * MHZ);
+
+        // recovery threshold never reaches into hysteresis band (90 - 5 < 88)
+        assert!(!state.update(&config, ChainTemperature::Ok(86.0), secs(25)));
+        assert!(!state.update(&config, ChainTemperature::Ok(86.0), secs(200)));
+        assert_eq!(state.throttled_by, 150 * MHZ);
+
+        // temperature has to stay below threshold for the whole dwell period
+        assert!(!state.update(&config, ChainTemperature::Ok(80.0), secs(205)));
+        assert!(!state.update(&config, ChainTemperature::Ok(86.0), secs(240)));
+        assert!(!state.update(&config, ChainTemperature::Ok(80.0), secs(245)));
+        assert!(!state.update(&config, ChainTemperature::Ok(80.0), secs(300)));
+        assert!(state.update(&config, ChainTemperature::Ok(80.0), secs(305)));
+        assert_eq!(state.throttled_by, 100 * MHZ);
+
+        // each recovery step requires another dwell period
+        assert!(!state.update(&config, ChainTemperature::Ok(80.0), secs(310)));
+        assert!(state.update(&config, ChainTemperature::Ok(80.0), secs(365)));
+        assert_eq!(state.throttled_by, 50 * MHZ);
+
+        // unknown temperature interrupts recovery
+        assert!(!state.update(&config, ChainTemperature::Unknown, secs(370)));
+        assert!(!state.update(&config, ChainTemperature::Ok(80.0), secs(425)));
+        assert!(state.update(&config, ChainTemperature::Ok(80.0), secs(485)));
+        assert_eq!(state.throttled_by, 0);
+        assert!(!state.update(&config, ChainTemperature::Ok(80.0), secs(600)));
+    }
 }
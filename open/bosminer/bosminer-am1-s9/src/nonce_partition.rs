@@ -0,0 +1,250 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Nonce-range partitioning of work among hash chains and their chips. Each work is split by the
+//! work engine into `midstate_count` midstates (disjoint version ranges) and every chip on a hash
+//! chain explores its own slice of nonce space determined by its address. This module verifies
+//! that such partitioning is disjoint and complete and optionally detects work that has been
+//! dispatched twice at runtime.
+
+use ii_logging::macros::*;
+
+use crate::bm1387::{self, ChipAddress};
+
+use bosminer::work;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex as StdMutex;
+
+/// Number of nonce slices addressable by chips on one hash chain
+pub const CHIP_SLICE_COUNT: usize = crate::MAX_CHIPS_ON_CHAIN;
+
+/// Number of recently dispatched midstates remembered by `OverlapDetector`
+pub const OVERLAP_DETECTOR_CAPACITY: usize = 4096;
+
+/// Nonce partition of one hash chain
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainPartition {
+    pub hashboard_idx: usize,
+    /// Number of midstates (version ranges) in each work sent to the hash chain
+    pub midstate_count: usize,
+    /// Nonce slices explored by the chips (in order of chip addresses)
+    pub chip_slices: Vec<usize>,
+}
+
+impl ChainPartition {
+    pub fn new(hashboard_idx: usize, midstate_count: usize, chip_count: usize) -> Self {
+        Self {
+            hashboard_idx,
+            midstate_count,
+            chip_slices: (0..chip_count)
+                .filter_map(|chip| ChipAddress::One(chip).nonce_slice())
+                .collect(),
+        }
+    }
+
+    /// Fraction of nonce space explored by the hash chain
+    pub fn coverage(&self) -> f64 {
+        self.chip_slices.len() as f64 / CHIP_SLICE_COUNT as f64
+    }
+
+    /// Nonce slices that are not explored by any chip
+    pub fn missing_slices(&self) -> Vec<usize> {
+        (0..CHIP_SLICE_COUNT)
+            .filter(|slice| !self.chip_slices.contains(slice))
+            .collect()
+    }
+
+    /// Check that each nonce slice is explored by one chip at most
+    pub fn check(&self) -> Result<(), String> {
+        let mut explored = [false; CHIP_SLICE_COUNT];
+        for (chip, &slice) in self.chip_slices.iter().enumerate() {
+            if slice >= CHIP_SLICE_COUNT {
+                Err(format!(
+                    "chain {}: chip {} explores nonce slice {} out of range",
+                    self.hashboard_idx, chip, slice
+                ))?
+            }
+            if explored[slice] {
+                Err(format!(
+                    "chain {}: nonce slice {} is explored by more chips",
+                    self.hashboard_idx, slice
+                ))?
+            }
+            explored[slice] = true;
+        }
+        Ok(())
+    }
+}
+
+/// Nonce partition of the whole miner
+#[derive(Clone, Debug, PartialEq)]
+pub struct Partition {
+    /// Number of midstates generated by the work engine for each work
+    pub work_midstate_count: usize,
+    pub chains: Vec<ChainPartition>,
+}
+
+impl Partition {
+    pub fn new(work_midstate_count: usize, chains: Vec<ChainPartition>) -> Self {
+        Self {
+            work_midstate_count,
+            chains,
+        }
+    }
+
+    /// Check that the partition is disjoint: hash chains consume all midstates of work they get
+    /// (the work engine never hands out the same version range twice) and chips of each hash
+    /// chain do not share nonce slices.
+    pub fn check(&self) -> Result<(), String> {
+        for chain in self.chains.iter() {
+            if chain.midstate_count != self.work_midstate_count {
+                Err(format!(
+                    "chain {}: midstate count {} differs from work engine midstate count {}",
+                    chain.hashboard_idx, chain.midstate_count, self.work_midstate_count
+                ))?
+            }
+            chain.check()?;
+        }
+        Ok(())
+    }
+}
+
+/// Key identifying search space of one midstate of a work
+type SearchSpace = (u32, ii_bitcoin::Midstate, u32, u32);
+
+/// Runtime check that no search space is dispatched to hardware twice. It is meant for debugging
+/// only as it serializes work dispatch of all hash chains.
+#[derive(Debug)]
+pub struct OverlapDetector {
+    midstate_count: usize,
+    capacity: usize,
+    inner: StdMutex<OverlapDetectorInner>,
+}
+
+#[derive(Debug, Default)]
+struct OverlapDetectorInner {
+    dispatched: BTreeMap<SearchSpace, usize>,
+    order: VecDeque<SearchSpace>,
+}
+
+impl OverlapDetector {
+    pub fn new(midstate_count: bm1387::MidstateCount) -> Self {
+        Self::with_capacity(midstate_count.to_count(), OVERLAP_DETECTOR_CAPACITY)
+    }
+
+    fn with_capacity(midstate_count: usize, capacity: usize) -> Self {
+        Self {
+            midstate_count,
+            capacity,
+            inner: Default::default(),
+        }
+    }
+
+    /// Record work dispatched to hash chain `hashboard_idx`. Returns `false` when the work does
+    /// not fit the partition (an error is logged).
+    pub fn check_work(&self, hashboard_idx: usize, work: &work::Assignment) -> bool {
+        let mut valid = true;
+        if work.midstates.len() != self.midstate_count {
+            error!(
+                "Nonce partition: chain {} got work with {} midstates instead of {}",
+                hashboard_idx,
+                work.midstates.len(),
+                self.midstate_count
+            );
+            valid = false;
+        }
+        let mut inner = self.inner.lock().expect("BUG: failed to lock mutex");
+        for midstate in work.midstates.iter() {
+            let search_space = (
+                midstate.version,
+                midstate.state,
+                work.merkle_root_tail(),
+                work.ntime,
+            );
+            if let Some(&previous_idx) = inner.dispatched.get(&search_space) {
+                error!(
+                    "Nonce partition: chain {} got work with version {:#010x} already \
+                     dispatched to chain {}",
+                    hashboard_idx, midstate.version, previous_idx
+                );
+                valid = false;
+                continue;
+            }
+            inner.dispatched.insert(search_space, hashboard_idx);
+            inner.order.push_back(search_space);
+            if inner.order.len() > self.capacity {
+                let oldest = inner.order.pop_front().expect("BUG: empty queue");
+                inner.dispatched.remove(&oldest);
+            }
+        }
+        valid
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::null_work;
+
+    #[test]
+    fn test_chain_partition() {
+        let full = ChainPartition::new(0, 4, crate::MAX_CHIPS_ON_CHAIN);
+        assert!(full.check().is_ok());
+        assert!(full.missing_slices().is_empty());
+        assert_eq!(full.coverage(), 1.0);
+
+        let partial = ChainPartition::new(1, 4, crate::EXPECTED_CHIPS_ON_CHAIN);
+        assert!(partial.check().is_ok());
+        assert_eq!(
+            partial.missing_slices(),
+            vec![crate::EXPECTED_CHIPS_ON_CHAIN]
+        );
+
+        let mut overlapping = partial.clone();
+        overlapping.chip_slices[1] = 0;
+        assert!(overlapping.check().is_err());
+    }
+
+    #[test]
+    fn test_partition() {
+        let chains = vec![ChainPartition::new(6, 4, 63), ChainPartition::new(7, 4, 60)];
+        assert!(Partition::new(4, chains.clone()).check().is_ok());
+        assert!(Partition::new(2, chains).check().is_err());
+    }
+
+    #[test]
+    fn test_overlap_detector() {
+        let detector = OverlapDetector::with_capacity(1, 2);
+        assert!(detector.check_work(6, &null_work::prepare(0)));
+        assert!(detector.check_work(7, &null_work::prepare(1)));
+        // same search space dispatched twice
+        assert!(!detector.check_work(8, &null_work::prepare(1)));
+        // the oldest work is forgotten when capacity is exceeded
+        assert!(detector.check_work(6, &null_work::prepare(2)));
+        assert!(detector.check_work(6, &null_work::prepare(0)));
+
+        // unexpected number of midstates
+        let detector = OverlapDetector::with_capacity(4, 2);
+        assert!(!detector.check_work(6, &null_work::prepare(0)));
+    }
+}
@@ -92,40 +92,84 @@ pub const EXPECTED_VOLTAGE_CTRL_VERSION: u8 = 0x03;
 /// Path to voltage controller PIC program
 pub const PIC_PROGRAM_PATH: &'static str = "/lib/antminer/hash_s8_app.txt";
 
-/// Bundle voltage value with methods to convert it to/from various representations
-#[derive(Clone, Copy, PartialEq)]
-pub struct Voltage(u8);
+/// Mapping between hashboard voltage and the value sent to the voltage controller (PIC). It may
+/// differ among power supply units and voltage regulators of various hardware revisions.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VoltageMapping {
+    coef_1: f32,
+    coef_2: f32,
+}
 
-impl Voltage {
+impl VoltageMapping {
     /// These PIC conversion functions and coefficients are taken from
     /// bmminer source: getPICvoltageFromValue, getVolValueFromPICvoltage
-    const VOLT_CONV_COEF_1: f32 = 1608.420446;
-    const VOLT_CONV_COEF_2: f32 = 170.423497;
+    pub const DEFAULT: Self = Self {
+        coef_1: 1608.420446,
+        coef_2: 170.423497,
+    };
 
-    fn pic_to_volts(pic_val: f32) -> f32 {
-        (Self::VOLT_CONV_COEF_1 - pic_val) / Self::VOLT_CONV_COEF_2
+    fn pic_to_volts(&self, pic_val: f32) -> f32 {
+        (self.coef_1 - pic_val) / self.coef_2
     }
 
     /// This function returns f32 so that we can do range checking later
-    fn volts_to_pic(voltage: f32) -> f32 {
-        (Self::VOLT_CONV_COEF_1 - Self::VOLT_CONV_COEF_2 * voltage).round()
+    fn volts_to_pic(&self, voltage: f32) -> f32 {
+        (self.coef_1 - self.coef_2 * voltage).round()
     }
+}
+
+/// Default PSU model
+pub const DEFAULT_PSU_MODEL: &'static str = "apw3";
+
+/// Table of supported PSU models with their voltage mappings
+pub const PSU_MODELS: &[(&'static str, VoltageMapping)] =
+    &[(DEFAULT_PSU_MODEL, VoltageMapping::DEFAULT)];
+
+/// Find voltage mapping for `psu_model`
+pub fn voltage_mapping(psu_model: &str) -> Option<VoltageMapping> {
+    PSU_MODELS
+        .iter()
+        .find(|(name, _)| *name == psu_model)
+        .map(|(_, mapping)| *mapping)
+}
 
+/// Bundle voltage value with methods to convert it to/from various representations
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Voltage {
+    pic_value: u8,
+    mapping: VoltageMapping,
+}
+
+impl Voltage {
     /// Note: the higher the PIC value, the lower the voltage
-    pub const MIN_VOLTAGE: Self = Self(255);
-    pub const MAX_VOLTAGE: Self = Self(0);
+    pub const MIN_VOLTAGE: Self = Self {
+        pic_value: 255,
+        mapping: VoltageMapping::DEFAULT,
+    };
+    pub const MAX_VOLTAGE: Self = Self {
+        pic_value: 0,
+        mapping: VoltageMapping::DEFAULT,
+    };
 
     /// Instantiate self from voltage
     pub fn from_volts(voltage: f32) -> error::Result<Self> {
-        let pic = Self::volts_to_pic(voltage) as isize;
+        Self::from_volts_with_mapping(voltage, VoltageMapping::DEFAULT)
+    }
+
+    /// Instantiate self from voltage using voltage controller specific `mapping`
+    pub fn from_volts_with_mapping(voltage: f32, mapping: VoltageMapping) -> error::Result<Self> {
+        let pic = mapping.volts_to_pic(voltage) as isize;
         if pic >= 0 && pic <= 255 {
-            Ok(Self(pic as u8))
+            Ok(Self {
+                pic_value: pic as u8,
+                mapping,
+            })
         } else {
             Err(ErrorKind::Power(format!(
                 "requested voltage {} out of range allowed range <{};{}>",
                 voltage,
-                Self::MIN_VOLTAGE.as_volts(),
-                Self::MAX_VOLTAGE.as_volts(),
+                mapping.pic_to_volts(255.0),
+                mapping.pic_to_volts(0.0),
             )))?
         }
     }
@@ -134,17 +178,33 @@ impl Voltage {
     /// This function cannot return error because the range checking is done by parameter type
     /// (all u8 are valid voltages).
     pub fn from_pic_value(pic_value: u8) -> error::Result<Self> {
-        Ok(Self(pic_value))
+        Ok(Self {
+            pic_value,
+            mapping: VoltageMapping::DEFAULT,
+        })
+    }
+
+    /// Create voltage from raw PIC value interpreted with the same mapping as `self`
+    pub fn with_pic_value(&self, pic_value: u8) -> Self {
+        Self {
+            pic_value,
+            mapping: self.mapping,
+        }
+    }
+
+    /// Create voltage that differs by `delta` volts (using the same mapping)
+    pub fn offset_volts(&self, delta: f32) -> error::Result<Self> {
+        Self::from_volts_with_mapping(self.as_volts() + delta, self.mapping)
     }
 
     #[inline]
     pub fn as_volts(&self) -> f32 {
-        Self::pic_to_volts(self.0 as f32)
+        self.mapping.pic_to_volts(self.pic_value as f32)
     }
 
     #[inline]
     pub fn as_pic_value(&self) -> u8 {
-        self.0
+        self.pic_value
     }
 }
 
@@ -154,6 +214,25 @@ impl std::fmt::Display for Voltage {
     }
 }
 
+/// Result of reading voltage back from the voltage controller after it has been applied
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VoltageReadback {
+    pub requested: Voltage,
+    pub measured: Voltage,
+    pub tolerance: f32,
+}
+
+impl VoltageReadback {
+    /// Absolute difference between requested and measured voltage in volts
+    pub fn difference(&self) -> f32 {
+        (self.requested.as_volts() - self.measured.as_volts()).abs()
+    }
+
+    pub fn is_mismatch(&self) -> bool {
+        self.difference() > self.tolerance
+    }
+}
+
 impl std::cmp::PartialOrd for Voltage {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         // We cannot compare voltages directly on PIC values, because they are inverted
@@ -161,6 +240,84 @@ impl std::cmp::PartialOrd for Voltage {
     }
 }
 
+/// Coefficient of the power estimation model in W/(MHz*V^2). It has been derived from a stock
+/// S9 hashboard that draws roughly 450 W with 63 chips running at 650 MHz and 8.8 V.
+const POWER_ESTIMATE_COEF: f64 = 450.0 / (63.0 * 650.0 * 8.8 * 8.8);
+
+/// Estimate power consumption (in Watts) of a hashboard running at `frequency` and `voltage`.
+///
+/// This is an *estimate* based on a simple CMOS dynamic power model `P = k * f * V^2` where `f`
+/// is the sum of frequencies of all chips (in MHz) and `V` is the hashboard voltage. Static
+/// leakage (that depends on temperature), fans and control board are not accounted for.
+pub fn estimate_power(frequency: &crate::FrequencySettings, voltage: Voltage) -> f64 {
+    let total_frequency_mhz = frequency.total() as f64 / 1_000_000.0;
+    let voltage = voltage.as_volts() as f64;
+    POWER_ESTIMATE_COEF * total_frequency_mhz * voltage * voltage
+}
+
+/// Operating point of one hash chain for the purpose of power limiting
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainOperatingPoint {
+    pub hashboard_idx: usize,
+    /// Frequency of individual chips
+    pub frequency: crate::FrequencySettings,
+    pub voltage: Voltage,
+    /// Frozen hash chain counts towards the limit but its frequency is never lowered
+    pub frozen: bool,
+}
+
+impl ChainOperatingPoint {
+    pub fn estimate_power(&self) -> f64 {
+        estimate_power(&self.frequency, self.voltage)
+    }
+}
+
+/// Lower frequency of the fastest hash chains by `step` (in Hz) one at a time until estimated
+/// total power consumption fits into `limit` (in Watts). All chips of the hash chain are lowered
+/// by the same amount to keep chip frequency offsets. Frequency is never lowered below
+/// `min_frequency` (frozen hash chains are never lowered) and when the limit cannot be met even
+/// then, the error lists estimates of all hash chains.
+pub fn fit_power_limit(
+    chains: &mut [ChainOperatingPoint],
+    limit: f64,
+    step: usize,
+    min_frequency: usize,
+) -> error::Result<()> {
+    loop {
+        let total: f64 = chains.iter().map(|chain| chain.estimate_power()).sum();
+        if total <= limit {
+            return Ok(());
+        }
+        match chains
+            .iter_mut()
+            .filter(|chain| !chain.frozen && chain.frequency.max() > min_frequency)
+            .max_by_key(|chain| chain.frequency.max())
+        {
+            Some(chain) => {
+                chain.frequency = chain.frequency.throttle(step, min_frequency);
+            }
+            None => {
+                let estimates: Vec<_> = chains
+                    .iter()
+                    .map(|chain| {
+                        format!(
+                            "chain {}: {:.0} W",
+                            chain.hashboard_idx,
+                            chain.estimate_power()
+                        )
+                    })
+                    .collect();
+                Err(ErrorKind::Power(format!(
+                    "estimated power {:.0} W at minimal frequencies exceeds limit {:.0} W ({})",
+                    total,
+                    limit,
+                    estimates.join(", ")
+                )))?;
+            }
+        }
+    }
+}
+
 /// Type that represents an I2C voltage controller communication backend
 /// S9 devices have a single I2C master that manages the voltage controllers on all hashboards.
 /// Therefore, this will be a single communication instance.
@@ -625,6 +782,24 @@ impl Control {
         Ok(self.read(GET_VOLTAGE, 1).await?[0])
     }
 
+    /// Read voltage back from PIC and compare it with the last voltage applied by
+    /// `set_voltage`. Returns `None` when no voltage has been set yet.
+    pub async fn read_back_voltage(
+        &self,
+        tolerance: f32,
+    ) -> error::Result<Option<VoltageReadback>> {
+        let requested = match self.get_current_voltage().await {
+            Some(voltage) => voltage,
+            None => return Ok(None),
+        };
+        let measured = requested.with_pic_value(self.get_voltage().await?);
+        Ok(Some(VoltageReadback {
+            requested,
+            measured,
+            tolerance,
+        }))
+    }
+
     pub async fn send_heart_beat(&self) -> error::Result<()> {
         self.write(SEND_HEART_BEAT, &[]).await
     }
@@ -780,6 +955,15 @@ mod test {
         assert!(Voltage::from_volts(10.0).is_err());
     }
 
+    #[test]
+    fn test_voltage_mapping() {
+        let mapping = voltage_mapping(DEFAULT_PSU_MODEL).expect("BUG: missing default mapping");
+        assert_eq!(mapping, VoltageMapping::DEFAULT);
+        assert!(voltage_mapping("unknown").is_none());
+        let voltage = Voltage::from_volts_with_mapping(8.9, mapping).unwrap();
+        assert_eq!(voltage.as_pic_value(), 92);
+    }
+
     #[test]
     fn test_pic_to_voltage() {
         let epsilon = 0.01f32;
@@ -789,6 +973,91 @@ mod test {
         assert!(difference.abs() <= epsilon);
     }
 
+    #[test]
+    fn test_voltage_readback() {
+        let requested = Voltage::from_volts(8.9).unwrap();
+        let readback = VoltageReadback {
+            requested,
+            measured: requested.with_pic_value(requested.as_pic_value()),
+            tolerance: 0.1,
+        };
+        assert!(!readback.is_mismatch());
+        let readback = VoltageReadback {
+            measured: Voltage::from_volts(8.7).unwrap(),
+            ..readback
+        };
+        assert!((readback.difference() - 0.2).abs() <= 0.01);
+        assert!(readback.is_mismatch());
+    }
+
+    #[test]
+    fn test_estimate_power() {
+        let voltage = Voltage::from_volts(8.8).unwrap();
+        let mut frequency = crate::FrequencySettings::from_frequency(650_000_000);
+        frequency.set_chip_count(63);
+        let power = estimate_power(&frequency, voltage);
+        assert!((power - 450.0).abs() <= 5.0);
+        // power scales linearly with frequency
+        let mut frequency = crate::FrequencySettings::from_frequency(325_000_000);
+        frequency.set_chip_count(63);
+        assert!((estimate_power(&frequency, voltage) - power / 2.0).abs() <= 0.01);
+    }
+
+    #[test]
+    fn test_fit_power_limit() {
+        let voltage = Voltage::from_volts(8.8).unwrap();
+        let chain = |hashboard_idx, frequency| ChainOperatingPoint {
+            hashboard_idx,
+            frequency: crate::FrequencySettings::from_frequency(frequency),
+            voltage,
+            frozen: false,
+        };
+        let mut chains = vec![chain(6, 650_000_000), chain(7, 700_000_000)];
+        let total: f64 = chains.iter().map(|chain| chain.estimate_power()).sum();
+
+        // limit that is met is left untouched
+        fit_power_limit(&mut chains, total, 25_000_000, 400_000_000).unwrap();
+        assert_eq!(chains, vec![chain(6, 650_000_000), chain(7, 700_000_000)]);
+
+        // the fastest chain is trimmed first
+        fit_power_limit(&mut chains, total - 30.0, 25_000_000, 400_000_000).unwrap();
+        assert_eq!(chains, vec![chain(6, 650_000_000), chain(7, 650_000_000)]);
+
+        // chains cannot go below minimal frequency
+        let error = fit_power_limit(&mut chains, 100.0, 25_000_000, 400_000_000)
+            .expect_err("BUG: unreachable power limit met");
+        assert!(error.to_string().contains("chain 6"));
+        assert_eq!(chains, vec![chain(6, 400_000_000), chain(7, 400_000_000)]);
+
+        // frozen chain is never trimmed
+        let frozen = ChainOperatingPoint {
+            frozen: true,
+            ..chain(7, 700_000_000)
+        };
+        let mut chains = vec![chain(6, 650_000_000), frozen.clone()];
+        fit_power_limit(&mut chains, total - 30.0, 25_000_000, 400_000_000).unwrap();
+        assert_eq!(chains, vec![chain(6, 600_000_000), frozen]);
+
+        // chips slower than the fastest one are estimated at their own frequency
+        let mut offset = chain(6, 650_000_000);
+        for frequency in offset.frequency.chip.iter_mut().take(10) {
+            *frequency = 600_000_000;
+        }
+        let mut chains = vec![offset.clone()];
+        fit_power_limit(
+            &mut chains,
+            chain(6, 650_000_000).estimate_power() - 1.0,
+            25_000_000,
+            400_000_000,
+        )
+        .unwrap();
+        assert_eq!(chains, vec![offset.clone()]);
+        // all chips are lowered by the same amount
+        fit_power_limit(&mut chains, 400.0, 25_000_000, 400_000_000).unwrap();
+        assert_eq!(chains[0].frequency.max(), 575_000_000);
+        assert_eq!(chains[0].frequency.min(), 525_000_000);
+    }
+
     #[test]
     fn test_pic_boundary() {
         // pic=255
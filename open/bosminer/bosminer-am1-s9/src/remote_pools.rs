@@ -0,0 +1,251 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Pool groups served by provisioning server. The server returns pool section of configuration
+//! file (`[[group]]` tables with their `[[group.pool]]` entries) which replaces pool groups from
+//! the local configuration file. The last successfully fetched content is cached so that the
+//! miner can start with it when the server is unreachable.
+//!
+//! Fetching requires HTTPS client which is built only with the `remote-pools` feature. Otherwise
+//! the cached content is used.
+
+use ii_logging::macros::*;
+
+use crate::config::{self, WarningId};
+
+use bosminer_config::GroupConfig;
+
+use serde::Deserialize;
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait for response of provisioning server
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pool section of configuration file
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct PoolSection {
+    #[serde(rename = "group")]
+    #[serde(default)]
+    groups: Vec<GroupConfig>,
+}
+
+/// Parse pool groups from fetched (or cached) content
+pub fn parse(content: &str) -> Result<Vec<GroupConfig>, String> {
+    toml::from_str::<PoolSection>(content)
+        .map(|section| section.groups)
+        .map_err(|e| format!("invalid pool section: {}", e))
+}
+
+/// Provisioning server with pool section and local cache of its content
+#[derive(Clone, Debug)]
+pub struct Source {
+    pub url: String,
+    pub cache_path: PathBuf,
+}
+
+impl Source {
+    /// Fetch content of pool section from provisioning server
+    #[cfg(feature = "remote-pools")]
+    pub async fn fetch(&self) -> Result<String, String> {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        client
+            .get(self.url.as_str())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "remote-pools"))]
+    pub async fn fetch(&self) -> Result<String, String> {
+        Err("fetching pools is not supported by this build".to_string())
+    }
+
+    fn read_cache(&self) -> Result<String, String> {
+        fs::read_to_string(&self.cache_path).map_err(|e| e.to_string())
+    }
+
+    pub fn store_cache(&self, content: &str) {
+        if let Err(e) = fs::write(&self.cache_path, content) {
+            warn!(
+                "Cannot cache pools in '{}': {}",
+                self.cache_path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Replace pool groups of `backend_config` with the ones served by provisioning server. Fetched
+/// pools are validated the same way as pools from configuration file and the content is cached
+/// only when it is accepted.
+pub async fn fetch_groups(
+    backend_config: &mut config::Backend,
+    source: &Source,
+) -> Result<(), String> {
+    let content = source.fetch().await?;
+    backend_config.replace_groups(parse(&content)?)?;
+    source.store_cache(&content);
+    Ok(())
+}
+
+/// Replace pool groups of `backend_config` with the ones served by provisioning server (see
+/// `fetch_groups`). When fetching fails (or the content is invalid), cached pools are used and
+/// when even those are not usable, pools from the local configuration file are kept.
+pub async fn load(backend_config: &mut config::Backend) {
+    let source = match backend_config.remote_pools() {
+        Some(source) => source,
+        None => return,
+    };
+    match fetch_groups(backend_config, &source).await {
+        Ok(()) => info!("Loaded pools from '{}'", source.url),
+        Err(e) => {
+            backend_config.warnings.push_field(
+                WarningId::RemotePools,
+                "miner.pools_url",
+                format!(
+                    "Cannot load pools from '{}': {}, using cached copy '{}'",
+                    source.url,
+                    e,
+                    source.cache_path.display()
+                ),
+            );
+            let cached = source
+                .read_cache()
+                .and_then(|content| backend_config.replace_groups(parse(&content)?));
+            if let Err(e) = cached {
+                backend_config.warnings.push_field(
+                    WarningId::RemotePools,
+                    "miner.pools_url",
+                    format!(
+                        "Cannot use cached pools '{}': {}, using pools from configuration file",
+                        source.cache_path.display(),
+                        e
+                    ),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ii_async_compat::tokio;
+
+    #[test]
+    fn test_parse() {
+        let groups = parse(
+            r#"
+            [[group]]
+            name = 'Fleet'
+
+            [[group.pool]]
+            url = 'stratum+tcp://stratum.slushpool.com:3333'
+            user = 'fleet.worker'
+            "#,
+        )
+        .expect("BUG: cannot parse pool section");
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].descriptor.name, "Fleet");
+        assert_eq!(groups[0].pools.as_ref().map(|v| v.len()), Some(1));
+
+        assert!(parse("")
+            .expect("BUG: cannot parse pool section")
+            .is_empty());
+
+        // only pool section is accepted
+        assert!(parse(
+            r#"
+            [hash_chain_global]
+            frequency = 650.0
+            "#
+        )
+        .is_err());
+    }
+
+    /// Test that cached pools are used when provisioning server is unreachable
+    #[tokio::test]
+    async fn test_load_cached() {
+        let cache_path =
+            std::env::temp_dir().join(format!("bosminer-pools-{}.toml", std::process::id()));
+        let backend_config = || -> config::Backend {
+            toml::from_str(&format!(
+                r#"
+                [miner]
+                pools_url = 'https://127.0.0.1:1/pools.toml'
+                pools_cache = '{}'
+
+                [[group]]
+                name = 'Local'
+
+                [[group.pool]]
+                url = 'stratum+tcp://stratum.slushpool.com:3333'
+                user = 'local.worker'
+                "#,
+                cache_path.display()
+            ))
+            .expect("BUG: cannot parse test configuration")
+        };
+        let group_name = |backend_config: &config::Backend| {
+            backend_config.groups.as_ref().expect("BUG: missing groups")[0]
+                .descriptor
+                .name
+                .clone()
+        };
+
+        // pools from configuration file are kept when nothing is cached
+        let _ = fs::remove_file(&cache_path);
+        let mut backend = backend_config();
+        load(&mut backend).await;
+        assert_eq!(group_name(&backend), "Local");
+        assert_eq!(backend.warnings.list().len(), 2);
+
+        let source = backend.remote_pools().expect("BUG: missing pools URL");
+        source.store_cache(
+            r#"
+            [[group]]
+            name = 'Fleet'
+
+            [[group.pool]]
+            url = 'stratum+tcp://stratum.slushpool.com:3333'
+            user = 'fleet.worker'
+            "#,
+        );
+        let mut backend = backend_config();
+        load(&mut backend).await;
+        assert_eq!(group_name(&backend), "Fleet");
+        assert_eq!(backend.warnings.list().len(), 1);
+
+        fs::remove_file(&cache_path).expect("BUG: cannot remove cached pools");
+    }
+}
@@ -0,0 +1,448 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Uptime and restart telemetry. Number of restarts and the reason of the last termination
+//! survive across restarts in a small state file.
+//!
+//! The state file also keeps history of recent thermal shutdowns which decides how the miner
+//! recovers from them (see `Recovery`).
+
+use ii_logging::macros::*;
+
+use crate::error;
+
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::OnceCell;
+
+/// Reason of miner termination
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    /// Miner was shut down on request (signal, API...)
+    CleanShutdown,
+    /// Hashchain stopped responding within watchdog timeout
+    Watchdog,
+    /// Dangerous temperature, failed temperature sensors or fans
+    Thermal,
+    /// Hashchain failed to initialize
+    ChainFailure,
+    /// Miner terminated without recording any reason
+    Crash,
+}
+
+impl std::string::ToString for Reason {
+    fn to_string(&self) -> String {
+        match self {
+            Self::CleanShutdown => "clean_shutdown".to_string(),
+            Self::Watchdog => "watchdog".to_string(),
+            Self::Thermal => "thermal".to_string(),
+            Self::ChainFailure => "chain_failure".to_string(),
+            Self::Crash => "crash".to_string(),
+        }
+    }
+}
+
+/// What to do after thermal shutdown
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum RecoveryMode {
+    /// Start hash chains again after cooldown
+    Retry,
+    /// Keep hash chains off until thermal lockout is cleared
+    StayOff,
+}
+
+/// Thermal shutdown recovery settings
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecoveryConfig {
+    pub mode: RecoveryMode,
+    /// How long hash chains stay off after thermal shutdown
+    pub cooldown: Duration,
+    /// Number of thermal shutdowns within `window` after which hash chains stay off
+    pub max_attempts: usize,
+    /// Thermal shutdowns older than this are forgotten
+    pub window: Duration,
+}
+
+/// How the miner recovers from thermal shutdown of the previous run
+#[derive(Clone, PartialEq, Debug)]
+pub enum Recovery {
+    /// Previous run didn't end with thermal shutdown
+    None,
+    /// Start hash chains after remaining `cooldown`
+    Retry {
+        attempt: usize,
+        max_attempts: usize,
+        cooldown: Duration,
+    },
+    /// Hash chains are kept off until thermal lockout is cleared
+    StayOff,
+}
+
+impl std::string::ToString for Recovery {
+    fn to_string(&self) -> String {
+        match self {
+            Self::None => "none".to_string(),
+            Self::Retry { .. } => "retry".to_string(),
+            Self::StayOff => "stay_off".to_string(),
+        }
+    }
+}
+
+/// Content of the state file
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+struct State {
+    restart_count: u64,
+    /// Reason of the last termination
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_reason: Option<Reason>,
+    /// Set while the miner is running. When it is found set on start, the previous run
+    /// terminated without recording its reason.
+    running: bool,
+    /// Unix timestamps (in seconds) of recent thermal shutdowns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    thermal_shutdowns: Vec<u64>,
+    /// Hash chains are kept off after thermal shutdowns until the lockout is cleared
+    #[serde(default)]
+    thermal_lockout: bool,
+}
+
+impl State {
+    /// Account new start of the miner. Missing state means the first start.
+    fn restarted(previous: Option<Self>) -> Self {
+        match previous {
+            None => Self {
+                running: true,
+                ..Default::default()
+            },
+            Some(previous) => Self {
+                restart_count: previous.restart_count + 1,
+                last_reason: if previous.running {
+                    Some(Reason::Crash)
+                } else {
+                    previous.last_reason
+                },
+                running: true,
+                thermal_shutdowns: previous.thermal_shutdowns,
+                thermal_lockout: previous.thermal_lockout,
+            },
+        }
+    }
+
+    /// Decide how to recover from thermal shutdown of the previous run. Repeated thermal
+    /// shutdowns within recovery window escalate to thermal lockout.
+    fn recover(&mut self, config: &RecoveryConfig, now: u64) -> Recovery {
+        self.thermal_shutdowns
+            .retain(|time| now.saturating_sub(*time) < config.window.as_secs());
+        if self.thermal_lockout {
+            return Recovery::StayOff;
+        }
+        if self.last_reason != Some(Reason::Thermal) {
+            return Recovery::None;
+        }
+        let attempt = self.thermal_shutdowns.len().max(1);
+        if config.mode == RecoveryMode::StayOff || attempt > config.max_attempts {
+            self.thermal_lockout = true;
+            return Recovery::StayOff;
+        }
+        let since_shutdown = self
+            .thermal_shutdowns
+            .last()
+            .map(|time| Duration::from_secs(now.saturating_sub(*time)))
+            .unwrap_or(config.cooldown);
+        Recovery::Retry {
+            attempt,
+            max_attempts: config.max_attempts,
+            cooldown: config
+                .cooldown
+                .checked_sub(since_shutdown)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Current time as Unix timestamp in seconds
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0)
+}
+
+/// Uptime and restart telemetry of running miner
+#[derive(Debug)]
+pub struct Tracker {
+    path: PathBuf,
+    started: Instant,
+    state: Mutex<State>,
+    /// Termination reason recorded during this run (the first one wins)
+    exit_reason: Mutex<Option<Reason>>,
+    /// Recovery from thermal shutdown decided on start
+    recovery: Mutex<Recovery>,
+}
+
+impl Tracker {
+    fn read(path: &Path) -> Option<State> {
+        match fs::read_to_string(path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(state) => Some(state),
+                Err(e) => {
+                    warn!("Ignoring corrupted state file '{}': {}", path.display(), e);
+                    None
+                }
+            },
+            Err(_) => None,
+        }
+    }
+
+    fn load(path: &Path, recovery_config: &RecoveryConfig) -> Self {
+        let mut state = State::restarted(Self::read(path));
+        let recovery = state.recover(recovery_config, unix_time());
+        match &recovery {
+            Recovery::None => {}
+            Recovery::Retry {
+                attempt,
+                max_attempts,
+                cooldown,
+            } => warn!(
+                "Thermal recovery attempt {}/{}: hash chains start after cooldown of {}s",
+                attempt,
+                max_attempts,
+                cooldown.as_secs()
+            ),
+            Recovery::StayOff => error!(
+                "Thermal lockout: hash chains are kept off after thermal shutdown until the \
+                 lockout is cleared"
+            ),
+        }
+        let tracker = Self {
+            path: path.to_path_buf(),
+            started: Instant::now(),
+            state: Mutex::new(state),
+            exit_reason: Mutex::new(None),
+            recovery: Mutex::new(recovery),
+        };
+        tracker.store();
+        tracker
+    }
+
+    fn store(&self) {
+        let state = self.state.lock().expect("BUG: cannot lock state").clone();
+        if let Err(e) = Self::write(&self.path, &state) {
+            warn!("Cannot write state file '{}': {}", self.path.display(), e);
+        }
+    }
+
+    fn write(path: &Path, state: &State) -> error::Result<()> {
+        let content =
+            serde_json::to_string(state).map_err(|e| format!("serialization failed: {}", e))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn restart_count(&self) -> u64 {
+        self.state
+            .lock()
+            .expect("BUG: cannot lock state")
+            .restart_count
+    }
+
+    /// Reason of the last termination (before current start)
+    pub fn last_reason(&self) -> Option<Reason> {
+        self.state
+            .lock()
+            .expect("BUG: cannot lock state")
+            .last_reason
+    }
+
+    /// Recovery from thermal shutdown of the previous run
+    pub fn recovery(&self) -> Recovery {
+        self.recovery
+            .lock()
+            .expect("BUG: cannot lock recovery")
+            .clone()
+    }
+
+    /// Number of thermal shutdowns within recovery window
+    pub fn thermal_shutdowns(&self) -> usize {
+        self.state
+            .lock()
+            .expect("BUG: cannot lock state")
+            .thermal_shutdowns
+            .len()
+    }
+
+    /// Clear thermal lockout and history of thermal shutdowns. Hash chains are started on the
+    /// next start of the miner.
+    pub fn clear_thermal_lockout(&self) {
+        {
+            let mut state = self.state.lock().expect("BUG: cannot lock state");
+            state.thermal_lockout = false;
+            state.thermal_shutdowns.clear();
+        }
+        info!("Thermal lockout cleared");
+        self.store();
+    }
+
+    /// Record reason of current termination. Only the first recorded reason is kept because
+    /// it is the cause of the following ones (e.g. thermal shutdown halts the miner cleanly).
+    pub fn record_exit(&self, reason: Reason) {
+        {
+            let mut exit_reason = self.exit_reason.lock().expect("BUG: cannot lock reason");
+            if exit_reason.is_some() {
+                return;
+            }
+            exit_reason.replace(reason);
+        }
+        info!("Recording termination reason: {}", reason.to_string());
+        let mut state = {
+            let mut state = self.state.lock().expect("BUG: cannot lock state");
+            if reason == Reason::Thermal {
+                state.thermal_shutdowns.push(unix_time());
+            }
+            state.clone()
+        };
+        state.last_reason = Some(reason);
+        state.running = false;
+        if let Err(e) = Self::write(&self.path, &state) {
+            warn!("Cannot write state file '{}': {}", self.path.display(), e);
+        }
+    }
+}
+
+static TRACKER: OnceCell<Tracker> = OnceCell::new();
+
+/// Load restart state from `path`, account current start and decide how to recover from
+/// thermal shutdown
+pub fn init(path: &Path, recovery_config: &RecoveryConfig) -> &'static Tracker {
+    TRACKER.get_or_init(|| Tracker::load(path, recovery_config))
+}
+
+/// Reason of the last termination stored in `path` without accounting current start
+pub fn previous_reason(path: &Path) -> Option<Reason> {
+    State::restarted(Tracker::read(path)).last_reason
+}
+
+/// Tracker of current run (missing when `init` hasn't been called yet)
+pub fn tracker() -> Option<&'static Tracker> {
+    TRACKER.get()
+}
+
+/// Record reason of current termination
+pub fn record_exit(reason: Reason) {
+    if let Some(tracker) = tracker() {
+        tracker.record_exit(reason);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_restarted_state() {
+        let first = State::restarted(None);
+        assert_eq!(first.restart_count, 0);
+        assert_eq!(first.last_reason, None);
+        assert!(first.running);
+
+        // previous run didn't record its termination
+        let crashed = State::restarted(Some(first));
+        assert_eq!(crashed.restart_count, 1);
+        assert_eq!(crashed.last_reason, Some(Reason::Crash));
+
+        let clean = State::restarted(Some(State {
+            restart_count: 1,
+            last_reason: Some(Reason::Thermal),
+            running: false,
+            ..Default::default()
+        }));
+        assert_eq!(clean.restart_count, 2);
+        assert_eq!(clean.last_reason, Some(Reason::Thermal));
+    }
+
+    #[test]
+    fn test_thermal_recovery() {
+        let config = RecoveryConfig {
+            mode: RecoveryMode::Retry,
+            cooldown: Duration::from_secs(300),
+            max_attempts: 2,
+            window: Duration::from_secs(3600),
+        };
+        let thermal = |thermal_shutdowns: Vec<u64>| State {
+            last_reason: Some(Reason::Thermal),
+            thermal_shutdowns,
+            ..Default::default()
+        };
+
+        let mut state = State::default();
+        assert_eq!(state.recover(&config, 10_000), Recovery::None);
+
+        // the rest of cooldown is waited out
+        let mut state = thermal(vec![9_900]);
+        assert_eq!(
+            state.recover(&config, 10_000),
+            Recovery::Retry {
+                attempt: 1,
+                max_attempts: 2,
+                cooldown: Duration::from_secs(200)
+            }
+        );
+        assert!(!state.thermal_lockout);
+
+        // old shutdowns are forgotten
+        let mut state = thermal(vec![1_000, 9_000, 9_900]);
+        match state.recover(&config, 10_000) {
+            Recovery::Retry { attempt, .. } => assert_eq!(attempt, 2),
+            recovery => panic!("unexpected recovery {:?}", recovery),
+        }
+        assert_eq!(state.thermal_shutdowns, vec![9_000, 9_900]);
+
+        // repeated shutdowns within window escalate to lockout which survives restarts
+        let mut state = thermal(vec![8_000, 9_000, 9_900]);
+        assert_eq!(state.recover(&config, 10_000), Recovery::StayOff);
+        assert!(state.thermal_lockout);
+        let mut state = State::restarted(Some(State {
+            last_reason: Some(Reason::CleanShutdown),
+            ..state
+        }));
+        assert_eq!(state.recover(&config, 20_000), Recovery::StayOff);
+
+        let config = RecoveryConfig {
+            mode: RecoveryMode::StayOff,
+            ..config
+        };
+        assert_eq!(
+            thermal(vec![9_900]).recover(&config, 10_000),
+            Recovery::StayOff
+        );
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright (C) 2019  Braiins Systems s.r.o.
+//
+// This file is part of Braiins Open-Source Initiative (BOSI).
+//
+// BOSI is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+//
+// Please, keep in mind that we may also license BOSI or any part thereof
+// under a proprietary license. For more information on the terms and conditions
+// of such proprietary license or if you have any other questions, please
+// contact us at opensource@braiins.com.
+
+//! Startup self-check that consolidates everything verified during miner initialization
+//! (hash chains, fans, temperature sensors and pools) into a single report.
+
+use ii_logging::macros::*;
+
+use crate::fan;
+use crate::monitor;
+use crate::sensor;
+use crate::Manager;
+
+use bosminer::client;
+
+use serde::Serialize;
+
+use std::sync::Arc;
+
+/// Result of hash chain initialization
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub enum ChainState {
+    /// Hash chain is initialized and mining
+    Ok,
+    /// Hash chain failed to initialize (with reason)
+    Failed(String),
+    /// Hash chain has not been started (it is disabled or not present)
+    Stopped,
+}
+
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct ChainReport {
+    #[serde(rename = "ID")]
+    pub id: usize,
+    #[serde(rename = "State")]
+    pub state: ChainState,
+    #[serde(rename = "Chips")]
+    pub chip_count: usize,
+    /// Resolved frequency in MHz
+    #[serde(rename = "Frequency")]
+    pub frequency: f64,
+    /// Resolved voltage in V
+    #[serde(rename = "Voltage")]
+    pub voltage: f64,
+    #[serde(rename = "Midstate Count")]
+    pub midstate_count: usize,
+    #[serde(rename = "Board Temperature")]
+    pub board_temperature: Option<f32>,
+    #[serde(rename = "Chip Temperature")]
+    pub chip_temperature: Option<f32>,
+    /// Missing when PLL lock status has not been read
+    #[serde(rename = "PLL Locked")]
+    pub pll_locked: Option<bool>,
+    /// Chips whose PLL failed to lock
+    #[serde(rename = "PLL Unlocked Chips")]
+    pub pll_unlocked_chips: Vec<usize>,
+}
+
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct FanReport {
+    #[serde(rename = "RPM")]
+    pub rpm: Vec<usize>,
+    #[serde(rename = "Running")]
+    pub running: usize,
+    #[serde(rename = "Min Fans")]
+    pub min_fans: usize,
+    #[serde(rename = "Ok")]
+    pub ok: bool,
+}
+
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct PoolReport {
+    #[serde(rename = "URL")]
+    pub url: String,
+    #[serde(rename = "Status")]
+    pub status: String,
+    #[serde(rename = "Reachable")]
+    pub reachable: bool,
+}
+
+/// Consolidated startup self-check report
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct Report {
+    #[serde(rename = "Chains")]
+    pub chains: Vec<ChainReport>,
+    #[serde(rename = "Failed Chains")]
+    pub failed_chains: Vec<usize>,
+    /// Missing when fan control is disabled or monitor hasn't reported yet
+    #[serde(rename = "Fans")]
+    pub fans: Option<FanReport>,
+    #[serde(rename = "Pools")]
+    pub pools: Vec<PoolReport>,
+}
+
+impl FanReport {
+    pub fn new(feedback: &fan::Feedback, min_fans: usize) -> Self {
+        let running = feedback.num_fans_running();
+        Self {
+            rpm: feedback.rpm.clone(),
+            running,
+            min_fans,
+            ok: running >= min_fans,
+        }
+    }
+}
+
+impl Report {
+    pub fn new(chains: Vec<ChainReport>, fans: Option<FanReport>, pools: Vec<PoolReport>) -> Self {
+        let failed_chains = chains
+            .iter()
+            .filter(|chain| match chain.state {
+                ChainState::Failed(_) => true,
+                _ => false,
+            })
+            .map(|chain| chain.id)
+            .collect();
+        Self {
+            chains,
+            failed_chains,
+            fans,
+            pools,
+        }
+    }
+
+    /// Collect report from current state of hash chain managers, monitor and pool clients
+    pub async fn collect(
+        managers: &[Arc<Manager>],
+        monitor: &monitor::Monitor,
+        client_manager: &client::Manager,
+    ) -> Self {
+        let mut chains = vec![];
+        for manager in managers.iter() {
+            let inner = manager.inner.lock().await;
+            let (state, chip_count, temperature, pll_unlocked_chips) =
+                match inner.hash_chain.as_ref() {
+                    Some(hash_chain) => (
+                        ChainState::Ok,
+                        hash_chain.chip_count,
+                        hash_chain.current_temperature(),
+                        hash_chain.get_pll_unlocked_chips().await,
+                    ),
+                    None => match inner.start_failure.as_ref() {
+                        Some(reason) => (ChainState::Failed(reason.clone()), 0, None, None),
+                        None => (ChainState::Stopped, 0, None, None),
+                    },
+                };
+            let (board_temperature, chip_temperature) = match temperature {
+                Some(sensor::Temperature { local, remote }) => (local.into(), remote.into()),
+                None => (None, None),
+            };
+            chains.push(ChainReport {
+                id: manager.hashboard_idx,
+                state,
+                chip_count,
+                frequency: manager.chain_config.frequency.avg() as f64 / 1_000_000.0,
+                voltage: manager.chain_config.voltage.as_volts() as f64,
+                midstate_count: manager.chain_config.midstate_count.to_count(),
+                board_temperature,
+                chip_temperature,
+                pll_locked: pll_unlocked_chips
+                    .as_ref()
+                    .map(|unlocked_chips| unlocked_chips.is_empty()),
+                pll_unlocked_chips: pll_unlocked_chips.unwrap_or_default(),
+            });
+        }
+
+        let fans = monitor.status_receiver.borrow().clone().and_then(|status| {
+            let min_fans = status.config.fan_config.as_ref()?.min_fans;
+            Some(FanReport::new(&status.fan_feedback, min_fans))
+        });
+
+        let mut pools = vec![];
+        for group in client_manager.get_groups().await {
+            for client in group.get_clients().await {
+                let status = client.status();
+                pools.push(PoolReport {
+                    url: client.descriptor().await.get_url(true, true, false),
+                    status: status.to_string(),
+                    reachable: client.is_running(),
+                });
+            }
+        }
+
+        Self::new(chains, fans, pools)
+    }
+
+    /// All hash chains that were started are mining (at least one has to be started)
+    pub fn all_chains_started(&self) -> bool {
+        self.failed_chains.is_empty()
+            && self
+                .chains
+                .iter()
+                .any(|chain| chain.state == ChainState::Ok)
+    }
+
+    /// Emit the whole report as a single structured log entry
+    pub fn log(&self) {
+        let report = serde_json::to_string(self).expect("BUG: cannot serialize self-check report");
+        if self.failed_chains.is_empty() {
+            info!("Self-check report: {}", report);
+        } else {
+            warn!(
+                "Self-check report (failed chains {:?}): {}",
+                self.failed_chains, report
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chain_report(id: usize, state: ChainState) -> ChainReport {
+        ChainReport {
+            id,
+            state,
+            chip_count: 63,
+            frequency: 650.0,
+            voltage: 8.8,
+            midstate_count: 4,
+            board_temperature: Some(50.0),
+            chip_temperature: None,
+            pll_locked: Some(true),
+            pll_unlocked_chips: vec![],
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let fans = FanReport::new(
+            &fan::Feedback {
+                rpm: vec![6000, 0, 5900, 0],
+            },
+            2,
+        );
+        assert_eq!(fans.running, 2);
+        assert!(fans.ok);
+        assert!(!FanReport::new(&fan::Feedback { rpm: vec![6000, 0] }, 2).ok);
+
+        let report = Report::new(
+            vec![
+                chain_report(6, ChainState::Ok),
+                chain_report(7, ChainState::Failed("no chips found".to_string())),
+                chain_report(8, ChainState::Stopped),
+            ],
+            Some(fans),
+            vec![],
+        );
+        assert_eq!(report.failed_chains, vec![7]);
+
+        let json = serde_json::to_value(&report).expect("BUG: cannot serialize report");
+        assert_eq!(json["Failed Chains"], serde_json::json!([7]));
+        assert_eq!(
+            json["Chains"][1]["State"],
+            serde_json::json!({ "Failed": "no chips found" })
+        );
+        assert_eq!(json["Fans"]["Ok"], serde_json::json!(true));
+    }
+}
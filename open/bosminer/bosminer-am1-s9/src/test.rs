@@ -107,3 +107,448 @@ fn test_work_time_computation() {
         36296
     );
 }
+
+/// Test rate limiting of runtime frequency and voltage changes
+#[test]
+fn test_remaining_change_cooldown() {
+    let interval = Duration::from_secs(60);
+    let last_change = Instant::now();
+    assert_eq!(
+        remaining_change_cooldown(interval, last_change, last_change + Duration::from_secs(20)),
+        Some(Duration::from_secs(40))
+    );
+    assert_eq!(
+        remaining_change_cooldown(interval, last_change, last_change + interval),
+        None
+    );
+    assert_eq!(
+        remaining_change_cooldown(interval, last_change, last_change + Duration::from_secs(90)),
+        None
+    );
+    // changes are not limited at all
+    assert_eq!(
+        remaining_change_cooldown(Duration::from_secs(0), last_change, last_change),
+        None
+    );
+}
+
+/// Test that action is taken only when hashrate stays low for the whole period
+#[test]
+fn test_detect_hashrate_drop() {
+    let hashrate_check = config::HashrateCheckConfig {
+        tolerance: 0.9,
+        period: Duration::from_secs(600),
+        action: config::HashrateDropAction::Warn,
+        voltage_step: 0.1,
+        max_voltage: 9.4,
+    };
+    let now = Instant::now();
+    let later = |secs| now + Duration::from_secs(secs);
+    let mut low_since = None;
+
+    assert_eq!(
+        detect_hashrate_drop(&mut low_since, None, &hashrate_check, now),
+        None
+    );
+    assert_eq!(
+        detect_hashrate_drop(&mut low_since, Some(0.8), &hashrate_check, now),
+        None
+    );
+    // recovered hashrate starts the period over
+    assert_eq!(
+        detect_hashrate_drop(&mut low_since, Some(0.95), &hashrate_check, later(300)),
+        None
+    );
+    assert_eq!(
+        detect_hashrate_drop(&mut low_since, Some(0.8), &hashrate_check, later(600)),
+        None
+    );
+    assert_eq!(
+        detect_hashrate_drop(&mut low_since, Some(0.7), &hashrate_check, later(1200)),
+        Some(0.7)
+    );
+    assert_eq!(low_since, None);
+}
+
+/// Test readiness criteria reported by health-check API command
+#[test]
+fn test_health_check() {
+    let readiness = config::ReadinessConfig {
+        min_chains: None,
+        min_pools: 1,
+    };
+    // all enabled hash chains have to mine by default
+    assert_eq!(
+        cgminer::HealthCheck::evaluate(&readiness, 3, 2, 1),
+        Err("2 of 3 hash chain(s) mining, 1 of 1 pool(s) connected".to_string())
+    );
+    assert!(cgminer::HealthCheck::evaluate(&readiness, 3, 2, 0).is_err());
+    let health_check =
+        cgminer::HealthCheck::evaluate(&readiness, 3, 3, 2).expect("BUG: miner is not ready");
+    assert!(health_check.ready);
+    assert_eq!(health_check.min_chains, 3);
+
+    let readiness = config::ReadinessConfig {
+        min_chains: Some(2),
+        min_pools: 0,
+    };
+    assert!(cgminer::HealthCheck::evaluate(&readiness, 3, 2, 0).is_ok());
+}
+
+/// Test minimal voltage required by voltage guard for runtime frequency changes
+#[test]
+fn test_required_voltage() {
+    let raise = config::VoltageGuardConfig {
+        action: config::VoltageGuardAction::RaiseVoltage,
+    };
+    let reject = config::VoltageGuardConfig {
+        action: config::VoltageGuardAction::Reject,
+    };
+    let frequency = FrequencySettings::from_frequency(650_000_000);
+    let volts = |volts| power::Voltage::from_volts(volts).expect("BUG: invalid voltage");
+
+    let raised_voltage = required_voltage(&raise, &frequency, volts(8.5))
+        .expect("BUG: voltage not raised")
+        .expect("BUG: missing raised voltage");
+    assert!((raised_voltage.as_volts() - 8.7).abs() <= 0.01);
+    assert!(required_voltage(&reject, &frequency, volts(8.5)).is_err());
+
+    // sufficient voltage is kept
+    assert!(required_voltage(&reject, &frequency, volts(8.8))
+        .expect("BUG: sufficient voltage rejected")
+        .is_none());
+    let low_frequency = FrequencySettings::from_frequency(450_000_000);
+    assert!(required_voltage(&reject, &low_frequency, volts(8.0))
+        .expect("BUG: sufficient voltage rejected")
+        .is_none());
+}
+
+/// Test alignment of runtime frequency adjustments to frequency step
+#[test]
+fn test_frequency_step_alignment() {
+    let step = 25_000_000;
+    assert_eq!(FrequencyControl::align(0, step), 0);
+    assert_eq!(FrequencyControl::align(50_000_000, step), 50_000_000);
+    // tiny adjustments are not lost
+    assert_eq!(FrequencyControl::align(1_000_000, step), 25_000_000);
+    assert_eq!(FrequencyControl::align(60_000_000, step), 50_000_000);
+    assert_eq!(FrequencyControl::align(65_000_000, step), 75_000_000);
+    assert_eq!(FrequencyControl::align(10_000_000, 0), 10_000_000);
+}
+
+/// Test grouping of chips by frequency and lookup of their PLL dividers
+#[test]
+fn test_pll_settings() {
+    let mut frequency = FrequencySettings::from_frequency(650_000_000);
+    frequency.set_chip_count(3);
+    frequency.chip[2] = 1_300_000_000;
+    let pll_settings = frequency.pll_settings();
+    assert_eq!(pll_settings.len(), 2);
+
+    assert_eq!(pll_settings[0].frequency, 650_000_000);
+    assert_eq!(pll_settings[0].chip_count, 2);
+    let pll = pll_settings[0].pll.as_ref().expect("BUG: missing PLL");
+    assert_eq!(pll.frequency, 650_000_000);
+    assert_eq!(pll.reg.to_reg(), 0x340211);
+
+    // out of PLL range
+    assert_eq!(pll_settings[1].chip_count, 1);
+    assert!(pll_settings[1].pll.is_none());
+}
+
+/// Test that non-uniform frequency is loaded chip by chip with target frequency of each chip
+#[test]
+fn test_pll_updates() {
+    let mut frequency = FrequencySettings::from_frequency(650_000_000);
+    frequency.set_chip_count(3);
+
+    // uniform frequency is loaded to all chips at once
+    let mut current = FrequencySettings::from_frequency(0);
+    current.set_chip_count(3);
+    assert_eq!(
+        frequency.pll_updates(&current, 3),
+        vec![(ChipAddress::All, 650_000_000)]
+    );
+
+    // chips have no frequency set after initialization
+    frequency.chip[1] = 600_000_000;
+    assert_eq!(
+        frequency.pll_updates(&current, 3),
+        vec![
+            (ChipAddress::One(0), 650_000_000),
+            (ChipAddress::One(1), 600_000_000),
+            (ChipAddress::One(2), 650_000_000),
+        ]
+    );
+
+    // only chips with changed frequency are updated
+    current = frequency.clone();
+    frequency.chip[2] = 700_000_000;
+    assert_eq!(
+        frequency.pll_updates(&current, 3),
+        vec![(ChipAddress::One(2), 700_000_000)]
+    );
+    assert!(current.pll_updates(&current, 3).is_empty());
+}
+
+/// Test ramping frequency from initialization frequency up to the target frequency
+#[test]
+fn test_frequency_ramp_up() {
+    let mut target = FrequencySettings::from_frequency(650_000_000);
+    target.set_chip_count(3);
+    target.chip[2] = 580_000_000;
+    let mut frequency = target.cap(550_000_000);
+    assert_eq!(frequency.chip, vec![550_000_000; 3]);
+
+    frequency = frequency.ramp_up(&target, 25_000_000);
+    assert_eq!(frequency.chip, vec![575_000_000, 575_000_000, 575_000_000]);
+    frequency = frequency.ramp_up(&target, 25_000_000);
+    assert_eq!(frequency.chip, vec![600_000_000, 600_000_000, 580_000_000]);
+    frequency = frequency.ramp_up(&target, 25_000_000);
+    frequency = frequency.ramp_up(&target, 25_000_000);
+    assert_eq!(frequency.chip, target.chip);
+    // target has been reached
+    assert_eq!(frequency.ramp_up(&target, 25_000_000).chip, target.chip);
+}
+
+/// Test that frequency ramp doesn't undo lowering of base frequency by corrective actions
+#[test]
+fn test_frequency_control_ramp() {
+    let mut target = FrequencySettings::from_frequency(650_000_000);
+    target.set_chip_count(3);
+    let mut frequency_control = FrequencyControl::new(target.cap(550_000_000));
+    // no ramp in progress
+    assert!(!frequency_control.ramp_up(25_000_000));
+
+    frequency_control.ramp_target = Some(target.clone());
+    assert!(frequency_control.ramp_up(25_000_000));
+    assert_eq!(frequency_control.base.chip, vec![575_000_000; 3]);
+
+    // e.g. PLL fallback of one chip caps its ramp target
+    let mut base = frequency_control.base.clone();
+    base.chip[1] = 550_000_000;
+    frequency_control.lower_base(base);
+    assert!(frequency_control.ramp_up(25_000_000));
+    assert_eq!(
+        frequency_control.base.chip,
+        vec![600_000_000, 550_000_000, 600_000_000]
+    );
+
+    // e.g. power limit lowers all chips which finishes the ramp
+    frequency_control.lower_base(frequency_control.base.throttle(25_000_000, 0));
+    assert!(!frequency_control.ramp_up(25_000_000));
+    assert_eq!(
+        frequency_control.base.chip,
+        vec![575_000_000, 525_000_000, 575_000_000]
+    );
+    assert!(frequency_control.ramp_target.is_none());
+}
+
+/// Hash chain settings kept in memory. Applying settings to a failing chain changes voltage and
+/// then fails.
+struct FakeChain {
+    hashboard_idx: usize,
+    fail: bool,
+    settings: Mutex<(FrequencySettings, power::Voltage)>,
+}
+
+impl FakeChain {
+    fn new(hashboard_idx: usize, fail: bool) -> Arc<Self> {
+        let mut frequency = FrequencySettings::from_frequency(650_000_000);
+        frequency.set_chip_count(3);
+        Arc::new(Self {
+            hashboard_idx,
+            fail,
+            settings: Mutex::new((
+                frequency,
+                power::Voltage::from_volts(8.9).expect("BUG: invalid voltage"),
+            )),
+        })
+    }
+
+    async fn get_settings(&self) -> (Vec<usize>, f32) {
+        let settings = self.settings.lock().await;
+        (settings.0.chip.clone(), settings.1.as_volts())
+    }
+}
+
+#[async_trait]
+impl ChainSettings for FakeChain {
+    fn hashboard_idx(&self) -> usize {
+        self.hashboard_idx
+    }
+
+    fn chip_count(&self) -> usize {
+        3
+    }
+
+    fn guard_voltage(
+        &self,
+        _frequency: &FrequencySettings,
+        _voltage: power::Voltage,
+    ) -> error::Result<Option<power::Voltage>> {
+        Ok(None)
+    }
+
+    async fn get_base_frequency(&self) -> FrequencySettings {
+        self.settings.lock().await.0.clone()
+    }
+
+    async fn get_voltage(&self) -> power::Voltage {
+        self.settings.lock().await.1
+    }
+
+    async fn apply_settings(
+        &self,
+        frequency: &FrequencySettings,
+        voltage: power::Voltage,
+        _source: audit::Source,
+    ) -> error::Result<()> {
+        let mut settings = self.settings.lock().await;
+        settings.1 = voltage;
+        // rolling back to the original frequency always succeeds
+        if self.fail && frequency.max() != 650_000_000 {
+            Err(ErrorKind::General("cannot set frequency".to_string()))?;
+        }
+        settings.0 = frequency.clone();
+        Ok(())
+    }
+}
+
+/// Test applying configuration to all hash chains and rolling it back when some chain fails
+#[tokio::test]
+async fn test_apply_chain_settings() {
+    let original_settings = (vec![650_000_000; 3], 8.9);
+    let frequency = FrequencySettings::from_frequency(600_000_000);
+    let voltage = power::Voltage::from_volts(8.5).expect("BUG: invalid voltage");
+
+    let chains = vec![FakeChain::new(6, false), FakeChain::new(7, false)];
+    let chain_settings: Vec<_> = chains
+        .iter()
+        .map(|chain| (chain.clone(), frequency.clone(), voltage))
+        .collect();
+    assert_eq!(
+        Backend::apply_chain_settings(&chain_settings).await,
+        Ok(vec![6, 7])
+    );
+    for chain in chains.iter() {
+        let (chip_frequency, volts) = chain.get_settings().await;
+        assert_eq!(chip_frequency, vec![600_000_000; 3]);
+        assert_eq!(volts, voltage.as_volts());
+    }
+
+    // chains changed before the failing one and the failing one itself are rolled back, the
+    // remaining chains are not touched at all
+    let chains = vec![
+        FakeChain::new(6, false),
+        FakeChain::new(7, true),
+        FakeChain::new(8, false),
+    ];
+    let chain_settings: Vec<_> = chains
+        .iter()
+        .map(|chain| (chain.clone(), frequency.clone(), voltage))
+        .collect();
+    let error = Backend::apply_chain_settings(&chain_settings)
+        .await
+        .expect_err("BUG: failure not reported");
+    assert!(error.starts_with("chain 7:"));
+    for chain in chains.iter() {
+        let (chip_frequency, volts) = chain.get_settings().await;
+        assert_eq!(chip_frequency, original_settings.0);
+        assert!((volts - original_settings.1).abs() <= 0.01);
+    }
+}
+
+/// Test hardware error rate measured between two snapshots of hash chain counters
+#[test]
+fn test_hw_error_rate() {
+    let core = bm1387::CoreAddress { chip: 1, core: 2 };
+    let mut counter = counters::HashChain::new(3, 4);
+    let previous = counter.snapshot();
+    assert_eq!(counter.snapshot().error_rate_since(&previous), None);
+
+    // valid nonces are counted with ASIC difficulty
+    for _ in 0..3 {
+        counter.add_valid(core);
+    }
+    counter.add_error(core);
+    assert_eq!(counter.snapshot().error_rate_since(&previous), Some(0.25));
+
+    // counters reset in the meantime
+    let previous = counter.snapshot();
+    counter.reset();
+    counter.add_error(core);
+    assert_eq!(counter.snapshot().error_rate_since(&previous), None);
+}
+
+/// Test that valid nonces are accounted with ASIC difficulty of the hash chain
+#[test]
+fn test_asic_difficulty_accounting() {
+    let core = bm1387::CoreAddress { chip: 0, core: 1 };
+    for &asic_difficulty in &[1, 128] {
+        let mut counter = counters::HashChain::new(1, asic_difficulty);
+        let previous = counter.snapshot();
+        counter.add_valid(core);
+        counter.add_error(core);
+        assert_eq!(counter.valid, asic_difficulty);
+        assert_eq!(counter.chip[0].core[1].valid, asic_difficulty);
+        // hardware error rate does not depend on ASIC difficulty
+        assert_eq!(counter.snapshot().error_rate_since(&previous), Some(0.5));
+    }
+}
+
+/// Test detection of inserted and removed hashboards by periodic hotplug scans
+#[test]
+fn test_hotplug_event() {
+    let mut present = false;
+    assert_eq!(HotplugEvent::detect(&mut present, false), None);
+    assert_eq!(
+        HotplugEvent::detect(&mut present, true),
+        Some(HotplugEvent::Inserted)
+    );
+    assert!(present);
+    // board that stays inserted is not started again
+    assert_eq!(HotplugEvent::detect(&mut present, true), None);
+    assert_eq!(
+        HotplugEvent::detect(&mut present, false),
+        Some(HotplugEvent::Removed)
+    );
+    assert_eq!(HotplugEvent::detect(&mut present, false), None);
+}
+
+/// Test that no more than configured number of hash chains is initialized at once
+#[tokio::test]
+async fn test_init_slots() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn max_running(limit: Option<usize>, chain_count: usize) -> usize {
+        let init_slots = InitSlots::new(limit, chain_count);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let chain_starts: Vec<_> = (0..chain_count)
+            .map(|hashboard_idx| {
+                let init_slots = init_slots.clone();
+                let running = running.clone();
+                let max_running = max_running.clone();
+                tokio::spawn(async move {
+                    init_slots
+                        .run(hashboard_idx, async {
+                            let count = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            max_running.fetch_max(count, Ordering::SeqCst);
+                            delay_for(Duration::from_millis(20)).await;
+                            running.fetch_sub(1, Ordering::SeqCst);
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for chain_start in chain_starts {
+            chain_start.await.expect("BUG: chain start failed");
+        }
+        max_running.load(Ordering::SeqCst)
+    }
+
+    assert_eq!(max_running(Some(1), 3).await, 1);
+    assert_eq!(max_running(Some(2), 5).await, 2);
+    assert_eq!(max_running(None, 3).await, 3);
+}
@@ -28,6 +28,7 @@ use url::Url;
 
 use std::convert::TryFrom;
 use std::fmt;
+use std::time;
 
 use failure::ResultExt;
 
@@ -143,6 +144,36 @@ impl<'a> UserInfo<'a> {
     }
 }
 
+/// Alternative address of the same pool used when connection to the primary address fails
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupAddress {
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+/// Handling of the `clean_jobs` flag sent by the pool with new work
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CleanJobs {
+    /// Drop current work immediately
+    Honor,
+    /// Keep submitting solutions of current work until it becomes stale
+    Ignore,
+    /// Keep submitting solutions of current work for the specified grace period
+    Delay(time::Duration),
+}
+
+impl CleanJobs {
+    pub const DEFAULT_DELAY: time::Duration = time::Duration::from_secs(5);
+    /// Maximal time a cancelled job is kept for submitting its solutions
+    pub const MAX_DELAY: time::Duration = time::Duration::from_secs(120);
+}
+
+impl Default for CleanJobs {
+    fn default() -> Self {
+        Self::Honor
+    }
+}
+
 /// Contains basic information about client used for obtaining jobs for solving.
 #[derive(Clone, Debug)]
 pub struct Descriptor {
@@ -154,9 +185,21 @@ pub struct Descriptor {
     pub port: Option<u16>,
     // Currently used only for `#xnsub`: `stratum+tcp://equihash.eu.nicehash.com:3357#xnsub`
     pub fragment: Option<String>,
+    /// Addresses tried in order when connection to `host` fails
+    pub backup_addresses: Vec<BackupAddress>,
+    pub clean_jobs: CleanJobs,
+    /// Connection is considered dead when nothing is received from the pool for this long
+    pub keepalive_interval: Option<time::Duration>,
+    /// Solutions found within this window are submitted together (zero disables batching)
+    pub submit_batch_window: time::Duration,
 }
 
 impl Descriptor {
+    /// Shorter keepalive interval would cause reconnects on healthy connections
+    pub const MIN_KEEPALIVE_INTERVAL: time::Duration = time::Duration::from_secs(5);
+    /// Longer batching would delay submission of shares noticeably
+    pub const MAX_SUBMIT_BATCH_WINDOW: time::Duration = time::Duration::from_millis(500);
+
     pub fn port(&self) -> u16 {
         match self.port {
             Some(value) => value,
@@ -164,6 +207,16 @@ impl Descriptor {
         }
     }
 
+    /// All addresses of the pool (the primary address is the first one) in form `host:port`
+    pub fn get_hosts_and_ports(&self) -> Vec<String> {
+        let default_port = self.protocol.default_port();
+        std::iter::once(format!("{}:{}", self.host, self.port()))
+            .chain(self.backup_addresses.iter().map(|address| {
+                format!("{}:{}", address.host, address.port.unwrap_or(default_port))
+            }))
+            .collect()
+    }
+
     pub fn get_url(&self, protocol: bool, port: bool, user: bool) -> String {
         let mut result = if protocol {
             self.protocol.scheme().to_string() + "://"
@@ -209,6 +262,38 @@ impl Descriptor {
             host,
             port,
             fragment,
+            backup_addresses: vec![],
+            clean_jobs: Default::default(),
+            keepalive_interval: None,
+            submit_batch_window: time::Duration::from_millis(0),
         })
     }
+
+    /// Create client `Descriptor` from a list of URLs of the same pool. The first URL is the
+    /// primary address and the others are tried in order when connection fails.
+    pub fn create_with_backups(
+        urls: &[&str],
+        user_info: &UserInfo,
+        enabled: bool,
+    ) -> error::Result<Self> {
+        let (url, backup_urls) = urls
+            .split_first()
+            .ok_or(error::ErrorKind::Client("missing URL".to_string()))?;
+        let mut descriptor = Self::create(url, user_info, enabled)?;
+        for backup_url in backup_urls {
+            let backup = Self::create(backup_url, user_info, enabled)?;
+            if backup.protocol.scheme() != descriptor.protocol.scheme() {
+                Err(error::ErrorKind::Client(format!(
+                    "protocol of backup URL '{}' differs from '{}'",
+                    backup_url,
+                    descriptor.protocol.scheme()
+                )))?;
+            }
+            descriptor.backup_addresses.push(BackupAddress {
+                host: backup.host,
+                port: backup.port,
+            });
+        }
+        Ok(descriptor)
+    }
 }
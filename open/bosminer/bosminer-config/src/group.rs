@@ -22,6 +22,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use std::time;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub enum LoadBalanceStrategy {
@@ -49,6 +51,25 @@ impl LoadBalanceStrategy {
     }
 }
 
+/// Selection of pool from pools within one group
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolStrategy {
+    /// The first running pool is used, next pools are connected only when it fails
+    Failover,
+    /// All pools are connected and work is spread evenly among running pools
+    Balance,
+}
+
+impl std::string::ToString for PoolStrategy {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Failover => "failover".to_string(),
+            Self::Balance => "balance".to_string(),
+        }
+    }
+}
+
 /// Contains basic information about group
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
@@ -59,12 +80,37 @@ pub struct Descriptor {
     #[serde(flatten)]
     #[serde(skip_serializing_if = "Option::is_none")]
     strategy: Option<LoadBalanceStrategy>,
+    /// Groups with lower priority value are preferred. Group is used only when no pool of all
+    /// groups with lower value is running.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool_strategy: Option<PoolStrategy>,
+    /// Pool is failed over when ratio of its accepted shares (in percent) falls below this value
+    /// and some alternative pool is available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_accept_rate: Option<f64>,
+    /// Length of window (in seconds) over which accept rate of pool is evaluated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accept_rate_window: Option<u64>,
 }
 
 impl Descriptor {
     pub const DEFAULT_NAME: &'static str = "Default";
     pub const DEFAULT_INDEX: usize = 0;
     pub const DEFAULT_QUOTA: usize = 1;
+    pub const DEFAULT_PRIORITY: usize = 0;
+    pub const DEFAULT_POOL_STRATEGY: PoolStrategy = PoolStrategy::Failover;
+    pub const DEFAULT_ACCEPT_RATE_WINDOW: time::Duration = time::Duration::from_secs(600);
+    pub const MIN_ACCEPT_RATE_WINDOW: time::Duration = time::Duration::from_secs(60);
+    /// Pool failed over due to low accept rate is used again only when its accept rate exceeds
+    /// `min_accept_rate` by this many percentage points
+    pub const ACCEPT_RATE_HYSTERESIS: f64 = 5.0;
+    /// Minimal number of shares submitted within the window to evaluate accept rate
+    pub const MIN_ACCEPT_RATE_SHARES: u64 = 10;
+    /// Pool failed over due to low accept rate is retried after this many windows when it has
+    /// not been used (and its accept rate could not recover)
+    pub const ACCEPT_RATE_RETRY_WINDOWS: u32 = 6;
 
     pub fn new<T>(name: String, private: bool, strategy: T) -> Self
     where
@@ -74,6 +120,10 @@ impl Descriptor {
             name,
             private,
             strategy: strategy.into(),
+            priority: None,
+            pool_strategy: None,
+            min_accept_rate: None,
+            accept_rate_window: None,
         }
     }
 
@@ -95,6 +145,24 @@ impl Descriptor {
             .as_ref()
             .and_then(|strategy| strategy.get_fixed_share_ratio())
     }
+
+    pub fn priority(&self) -> usize {
+        self.priority.unwrap_or(Self::DEFAULT_PRIORITY)
+    }
+
+    pub fn pool_strategy(&self) -> PoolStrategy {
+        self.pool_strategy.unwrap_or(Self::DEFAULT_POOL_STRATEGY)
+    }
+
+    pub fn min_accept_rate(&self) -> Option<f64> {
+        self.min_accept_rate
+    }
+
+    pub fn accept_rate_window(&self) -> time::Duration {
+        self.accept_rate_window
+            .map(time::Duration::from_secs)
+            .unwrap_or(Self::DEFAULT_ACCEPT_RATE_WINDOW)
+    }
 }
 
 impl Default for Descriptor {
@@ -103,6 +171,10 @@ impl Default for Descriptor {
             name: Self::DEFAULT_NAME.to_string(),
             private: false,
             strategy: None,
+            priority: None,
+            pool_strategy: None,
+            min_accept_rate: None,
+            accept_rate_window: None,
         }
     }
 }
@@ -25,6 +25,8 @@ mod error;
 mod group;
 
 // Reexport inner structures
+pub use client::BackupAddress as ClientBackupAddress;
+pub use client::CleanJobs as ClientCleanJobs;
 pub use client::Descriptor as ClientDescriptor;
 pub use client::Protocol as ClientProtocol;
 pub use client::UserInfo as ClientUserInfo;
@@ -32,6 +34,7 @@ pub use client::URL_JAVA_SCRIPT_REGEX as CLIENT_URL_JAVA_SCRIPT_REGEX;
 
 pub use group::Descriptor as GroupDescriptor;
 pub use group::LoadBalanceStrategy;
+pub use group::PoolStrategy as GroupPoolStrategy;
 
 // reexport common crates
 pub use clap;
@@ -39,15 +42,133 @@ pub use config;
 
 use serde::{Deserialize, Serialize};
 
+use std::time::Duration;
+
+/// Configured handling of the `clean_jobs` flag of new work notifications
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum CleanJobsMode {
+    #[serde(rename = "honor")]
+    Honor,
+    #[serde(rename = "ignore")]
+    Ignore,
+    #[serde(rename = "delay")]
+    Delay,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct PoolConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enabled: Option<bool>,
-    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Addresses of the same pool tried in order on connection failure (appended to `url`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urls: Option<Vec<String>>,
     pub user: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clean_jobs: Option<CleanJobsMode>,
+    /// Grace period in seconds used with `clean_jobs = "delay"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clean_jobs_delay: Option<u64>,
+    /// Reconnect when nothing is received from the pool for this many seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keepalive_interval: Option<u64>,
+    /// Submit solutions found within this many milliseconds together
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submit_batch_window: Option<u64>,
+}
+
+impl PoolConfig {
+    /// All configured URLs of the pool (the primary one is the first)
+    pub fn urls(&self) -> Vec<&str> {
+        self.url
+            .iter()
+            .chain(self.urls.iter().flatten())
+            .map(|url| url.as_str())
+            .collect()
+    }
+
+    /// Create client descriptor from all configured URLs of the pool
+    pub fn create_descriptor(&self, default_enabled: bool) -> error::Result<ClientDescriptor> {
+        let mut descriptor = ClientDescriptor::create_with_backups(
+            &self.urls(),
+            &ClientUserInfo::new(self.user.as_str(), self.password.as_deref()),
+            self.enabled.unwrap_or(default_enabled),
+        )?;
+        descriptor.clean_jobs = self.clean_jobs()?;
+        // Only jobs of Stratum V1 pools (translated to Stratum V2) carry the `clean_jobs` flag
+        match descriptor.protocol {
+            ClientProtocol::StratumV1 => {}
+            _ if descriptor.clean_jobs != ClientCleanJobs::Honor => {
+                Err(error::ErrorKind::Client(format!(
+                    "'clean_jobs' is supported only by '{}' pools",
+                    ClientProtocol::SCHEME_STRATUM_V1
+                )))?;
+            }
+            _ => {}
+        }
+        descriptor.keepalive_interval = self.keepalive_interval()?;
+        descriptor.submit_batch_window = self.submit_batch_window()?;
+        Ok(descriptor)
+    }
+
+    /// Resolve share submission batching window (batching is disabled when it is missing)
+    pub fn submit_batch_window(&self) -> error::Result<Duration> {
+        let window = Duration::from_millis(self.submit_batch_window.unwrap_or(0));
+        if window > ClientDescriptor::MAX_SUBMIT_BATCH_WINDOW {
+            Err(error::ErrorKind::Client(format!(
+                "'submit_batch_window' exceeds {} milliseconds",
+                ClientDescriptor::MAX_SUBMIT_BATCH_WINDOW.as_millis()
+            )))?;
+        }
+        Ok(window)
+    }
+
+    /// Resolve keepalive interval (keepalive is disabled when it is missing)
+    pub fn keepalive_interval(&self) -> error::Result<Option<Duration>> {
+        let keepalive_interval = match self.keepalive_interval {
+            Some(keepalive_interval) => Duration::from_secs(keepalive_interval),
+            None => return Ok(None),
+        };
+        if keepalive_interval < ClientDescriptor::MIN_KEEPALIVE_INTERVAL {
+            Err(error::ErrorKind::Client(format!(
+                "'keepalive_interval' is shorter than {} seconds",
+                ClientDescriptor::MIN_KEEPALIVE_INTERVAL.as_secs()
+            )))?;
+        }
+        Ok(Some(keepalive_interval))
+    }
+
+    /// Resolve handling of the `clean_jobs` flag. The grace period is bounded so that solutions
+    /// of a cancelled job cannot be submitted indefinitely.
+    pub fn clean_jobs(&self) -> error::Result<ClientCleanJobs> {
+        let mode = self.clean_jobs.unwrap_or(CleanJobsMode::Honor);
+        if self.clean_jobs_delay.is_some() && mode != CleanJobsMode::Delay {
+            Err(error::ErrorKind::Client(
+                "'clean_jobs_delay' requires 'clean_jobs = \"delay\"'".to_string(),
+            ))?;
+        }
+        Ok(match mode {
+            CleanJobsMode::Honor => ClientCleanJobs::Honor,
+            CleanJobsMode::Ignore => ClientCleanJobs::Ignore,
+            CleanJobsMode::Delay => {
+                let delay = self
+                    .clean_jobs_delay
+                    .map(Duration::from_secs)
+                    .unwrap_or(ClientCleanJobs::DEFAULT_DELAY);
+                if delay > ClientCleanJobs::MAX_DELAY {
+                    Err(error::ErrorKind::Client(format!(
+                        "'clean_jobs_delay' exceeds {} seconds",
+                        ClientCleanJobs::MAX_DELAY.as_secs()
+                    )))?;
+                }
+                ClientCleanJobs::Delay(delay)
+            }
+        })
+    }
 }
 
 // NOTE: `#[serde(deny_unknown_fields)]` cannot be used due to flatten descriptor but the error is
@@ -74,3 +195,85 @@ where
     // Parse it into structure
     settings.try_into::<T>().map_err(|e| format!("{}", e))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pool_config(url: &str) -> PoolConfig {
+        PoolConfig {
+            enabled: None,
+            url: Some(url.to_string()),
+            urls: None,
+            user: "user.worker".to_string(),
+            password: None,
+            clean_jobs: None,
+            clean_jobs_delay: None,
+            keepalive_interval: None,
+            submit_batch_window: None,
+        }
+    }
+
+    #[test]
+    fn test_clean_jobs() {
+        let mut pool = pool_config("stratum+tcp://pool.example.com:3333");
+        assert_eq!(pool.clean_jobs().unwrap(), ClientCleanJobs::Honor);
+
+        pool.clean_jobs = Some(CleanJobsMode::Delay);
+        assert_eq!(
+            pool.clean_jobs().unwrap(),
+            ClientCleanJobs::Delay(ClientCleanJobs::DEFAULT_DELAY)
+        );
+        pool.clean_jobs_delay = Some(10);
+        assert_eq!(
+            pool.clean_jobs().unwrap(),
+            ClientCleanJobs::Delay(Duration::from_secs(10))
+        );
+        // The grace period is bounded by the stale job window
+        pool.clean_jobs_delay = Some(ClientCleanJobs::MAX_DELAY.as_secs() + 1);
+        assert!(pool.clean_jobs().is_err());
+
+        // The grace period makes sense only with delayed handling
+        pool.clean_jobs = Some(CleanJobsMode::Ignore);
+        pool.clean_jobs_delay = Some(10);
+        assert!(pool.clean_jobs().is_err());
+        pool.clean_jobs_delay = None;
+        assert_eq!(pool.clean_jobs().unwrap(), ClientCleanJobs::Ignore);
+        assert!(pool.create_descriptor(true).is_ok());
+
+        // Stratum V2 pools ignore the `clean_jobs` flag
+        let mut pool = pool_config("stratum2+tcp+insecure://pool.example.com:3336");
+        assert!(pool.create_descriptor(true).is_ok());
+        pool.clean_jobs = Some(CleanJobsMode::Ignore);
+        assert!(pool.create_descriptor(true).is_err());
+    }
+
+    #[test]
+    fn test_keepalive_interval() {
+        let mut pool = pool_config("stratum+tcp://pool.example.com:3333");
+        assert_eq!(pool.keepalive_interval().unwrap(), None);
+
+        pool.keepalive_interval = Some(30);
+        assert_eq!(
+            pool.keepalive_interval().unwrap(),
+            Some(Duration::from_secs(30))
+        );
+        pool.keepalive_interval = Some(ClientDescriptor::MIN_KEEPALIVE_INTERVAL.as_secs() - 1);
+        assert!(pool.keepalive_interval().is_err());
+    }
+
+    #[test]
+    fn test_submit_batch_window() {
+        let mut pool = pool_config("stratum+tcp://pool.example.com:3333");
+        assert_eq!(pool.submit_batch_window().unwrap(), Duration::from_secs(0));
+
+        pool.submit_batch_window = Some(20);
+        assert_eq!(
+            pool.submit_batch_window().unwrap(),
+            Duration::from_millis(20)
+        );
+        pool.submit_batch_window =
+            Some(ClientDescriptor::MAX_SUBMIT_BATCH_WINDOW.as_millis() as u64 + 1);
+        assert!(pool.submit_batch_window().is_err());
+    }
+}
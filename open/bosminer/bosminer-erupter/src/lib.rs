@@ -190,6 +190,7 @@ impl hal::Backend for Backend {
 
         Ok(hal::FrontendConfig {
             cgminer_custom_commands: None,
+            api_access: Default::default(),
         })
     }
 }
@@ -25,14 +25,16 @@
 
 use crate::client;
 use crate::error;
+use crate::hal;
 use crate::hub;
 use crate::node::{self, Stats as _, WorkSolver, WorkSolverStats as _};
 use crate::stats::{self, UnixTime as _};
 use crate::sync;
 use crate::version;
 
+use ii_cgminer_api::command::POOLSTATES;
 use ii_cgminer_api::support::ValueExt as _;
-use ii_cgminer_api::{command, json, response};
+use ii_cgminer_api::{command, commands, json, response};
 
 use bosminer_config::{ClientDescriptor, ClientUserInfo};
 
@@ -349,6 +351,73 @@ impl Handler {
         .await
     }
 
+    fn get_pool_connection_state(
+        enabled: bool,
+        status: sync::Status,
+    ) -> response::ext::PoolConnectionState {
+        if !enabled {
+            return response::ext::PoolConnectionState::Disabled;
+        }
+        match status {
+            sync::Status::Running => response::ext::PoolConnectionState::Connected,
+            sync::Status::Created
+            | sync::Status::Starting
+            | sync::Status::Retrying
+            | sync::Status::Restarting
+            | sync::Status::Recovering => response::ext::PoolConnectionState::Connecting,
+            sync::Status::Stopping | sync::Status::Stopped => {
+                response::ext::PoolConnectionState::Idle
+            }
+            sync::Status::Failing | sync::Status::Declining | sync::Status::Failed => {
+                response::ext::PoolConnectionState::Failed
+            }
+        }
+    }
+
+    async fn get_pool_state(
+        idx: usize,
+        group: &client::Group,
+        priority: usize,
+        client: Arc<client::Handle>,
+        active: bool,
+    ) -> response::ext::PoolState {
+        let client_descriptor = client.descriptor().await;
+        let last_job = client.get_last_job().await;
+        let accepted = client.stats().accepted().take_snapshot().await;
+
+        let state = Self::get_pool_connection_state(client.is_enabled(), client.status());
+
+        response::ext::PoolState {
+            idx: idx as i32,
+            url: client_descriptor.get_url(true, true, false),
+            group: group.descriptor.name.clone(),
+            state,
+            active: active.into(),
+            priority: priority as i32,
+            quota: group.descriptor.get_quota().unwrap_or_default() as i32,
+            difficulty: last_job
+                .map(|job| job.target().get_difficulty() as f64)
+                .unwrap_or(0.0),
+            last_accepted_share_time: accepted
+                .last_time
+                .map_or(0, |time| time.get_unix_time().unwrap_or_default()),
+        }
+    }
+
+    /// Handles extended command with connection state of all pools
+    async fn handle_pool_states(&self) -> command::Result<response::ext::PoolStates> {
+        let active_client = self.core.get_active_client().await;
+        let mut list = vec![];
+        for group in self.core.get_client_manager().get_groups().await {
+            for (priority, client) in group.get_clients().await.into_iter().enumerate() {
+                let idx = list.len();
+                let active = active_client.as_ref() == Some(&client);
+                list.push(Self::get_pool_state(idx, &group, priority, client, active).await);
+            }
+        }
+        Ok(response::ext::PoolStates { list })
+    }
+
     /// Collects all clients from all groups into a single `Vec`
     async fn get_clients(&self) -> Vec<Arc<client::Handle>> {
         let mut clients = vec![];
@@ -782,17 +851,62 @@ pub async fn run(
     core: Arc<hub::Core>,
     listen_addr: SocketAddr,
     custom_commands: Option<command::Map>,
+    access: hal::ApiAccess,
     signature: String,
 ) {
+    // Extended commands implemented by BOSminer itself can be extended by backend
+    let extended_handler = Arc::new(Handler::new(core.clone()));
+    let mut extended_commands = commands![
+        (POOLSTATES: ParameterLess -> extended_handler.handle_pool_states)
+    ];
+    if let Some(custom_commands) = custom_commands {
+        extended_commands.extend(custom_commands.into_iter());
+    }
+
     let handler = Handler::new(core);
     let command_receiver = command::Receiver::new(
         handler,
         signature,
         version::STRING.to_string(),
-        custom_commands,
-    );
+        extended_commands,
+    )
+    .read_only(access.read_only)
+    .token(access.token, access.protect_queries);
 
     ii_cgminer_api::run(command_receiver, listen_addr)
         .await
         .unwrap();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pool_connection_state() {
+        use response::ext::PoolConnectionState;
+
+        let state = Handler::get_pool_connection_state;
+        assert_eq!(
+            state(true, sync::Status::Running),
+            PoolConnectionState::Connected
+        );
+        assert_eq!(
+            state(true, sync::Status::Retrying),
+            PoolConnectionState::Connecting
+        );
+        // backup pool is stopped by the scheduler while other pool provides work
+        assert_eq!(
+            state(true, sync::Status::Stopped),
+            PoolConnectionState::Idle
+        );
+        assert_eq!(
+            state(true, sync::Status::Failed),
+            PoolConnectionState::Failed
+        );
+        assert_eq!(
+            state(false, sync::Status::Running),
+            PoolConnectionState::Disabled
+        );
+    }
+}
@@ -30,6 +30,8 @@ pub mod drain;
 pub mod stratum_v2;
 pub mod stratum_v2_channels;
 
+use ii_logging::macros::*;
+
 use crate::error;
 use crate::hal;
 use crate::job;
@@ -42,17 +44,20 @@ use crate::work;
 pub use scheduler::JobExecutor;
 
 use bosminer_config::{
-    ClientDescriptor, ClientProtocol, ClientUserInfo, GroupConfig, GroupDescriptor,
-    LoadBalanceStrategy,
+    ClientDescriptor, ClientProtocol, GroupConfig, GroupDescriptor, LoadBalanceStrategy,
 };
 
 use futures::channel::mpsc;
 use futures::lock::Mutex;
-use ii_async_compat::futures;
+use ii_async_compat::prelude::*;
+use ii_async_compat::{futures, tokio};
+use tokio::time::delay_for;
 
+use std::future::Future;
 use std::slice;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time;
 
 #[derive(Debug)]
 pub struct Handle {
@@ -242,7 +247,7 @@ impl Handle {
     }
 
     #[inline]
-    pub(crate) fn stats(&self) -> &dyn stats::Client {
+    pub fn stats(&self) -> &dyn stats::Client {
         self.node.client_stats()
     }
 
@@ -310,7 +315,9 @@ impl Group {
             .collect()
     }
 
-    pub async fn push_client(&self, client_handle: Handle) -> Arc<Handle> {
+    /// Prepare client for addition to the group. The client stays disabled until
+    /// `enable_added_client` is called.
+    fn prepare_client(&self, client_handle: Handle) -> Arc<Handle> {
         let midstate_count = self.midstate_count;
         let _ = client_handle.replace_engine_generator(Box::new(move |job| {
             Arc::new(work::engine::VersionRolling::new(job, midstate_count))
@@ -318,7 +325,22 @@ impl Group {
         let _ = client_handle.try_disable();
         client_handle.set_event_sender(self.event_sender.clone());
 
-        let client_handle = Arc::new(client_handle);
+        Arc::new(client_handle)
+    }
+
+    async fn enable_added_client(client_handle: &Handle) {
+        // NOTE: Keep descriptor locked to synchronize descriptor changes
+        let client_descriptor = client_handle.descriptor.lock().await;
+
+        if client_descriptor.enabled {
+            client_handle
+                .try_enable()
+                .expect("BUG: client is already enabled");
+        }
+    }
+
+    pub async fn push_client(&self, client_handle: Handle) -> Arc<Handle> {
+        let client_handle = self.prepare_client(client_handle);
         let scheduler_client_handle = scheduler::ClientHandle::new(client_handle.clone());
         self.scheduler_client_handles
             .lock()
@@ -327,18 +349,42 @@ impl Group {
         // Immediately notify about client addition to the group
         self.event_sender.notify();
 
-        {
-            // NOTE: Keep descriptor locked to synchronize descriptor changes
-            let client_descriptor = client_handle.descriptor.lock().await;
+        Self::enable_added_client(&client_handle).await;
 
-            if client_descriptor.enabled {
-                client_handle
-                    .try_enable()
-                    .expect("BUG: client is already enabled");
-            }
+        client_handle
+    }
+
+    /// Replace all clients of the group at once so that the scheduler never sees partially
+    /// updated group. Removed clients are disabled and returned.
+    pub async fn replace_clients(&self, client_handles: Vec<Handle>) -> Vec<Arc<Handle>> {
+        let client_handles: Vec<_> = client_handles
+            .into_iter()
+            .map(|client_handle| self.prepare_client(client_handle))
+            .collect();
+        let removed_client_handles: Vec<_> = {
+            let mut scheduler_client_handles = self.scheduler_client_handles.lock().await;
+            let replacement = client_handles
+                .iter()
+                .map(|client_handle| scheduler::ClientHandle::new(client_handle.clone()))
+                .collect();
+            std::mem::replace(&mut *scheduler_client_handles, replacement)
+                .into_iter()
+                .map(|scheduler_client_handle| scheduler_client_handle.client_handle)
+                .collect()
+        };
+        // Immediately notify about client replacement in the group
+        self.event_sender.notify();
+
+        for client_handle in removed_client_handles.iter() {
+            // Remove event sender not to notify about removed client status changes
+            client_handle.take_event_sender();
+            let _ = client_handle.try_disable();
+        }
+        for client_handle in client_handles.iter() {
+            Self::enable_added_client(client_handle).await;
         }
 
-        client_handle
+        removed_client_handles
     }
 
     pub async fn remove_client_at(&self, index: usize) -> Result<Arc<Handle>, error::Client> {
@@ -408,6 +454,9 @@ impl Group {
     }
 }
 
+/// Created and removed public groups after replacing registry content
+type ReplacedGroups = (Vec<Arc<Group>>, Vec<Arc<Group>>);
+
 /// Keeps track of all active clients
 pub struct GroupRegistry {
     list: Vec<scheduler::GroupHandle>,
@@ -457,7 +506,44 @@ impl GroupRegistry {
         descriptor: GroupDescriptor,
         midstate_count: usize,
     ) -> Result<Arc<Group>, error::Client> {
-        match descriptor.strategy() {
+        let group_handle = Arc::new(Group::new(
+            descriptor,
+            self.event_monitor.publish(),
+            midstate_count,
+        ));
+        self.add_group(group_handle.clone())?;
+
+        Ok(group_handle)
+    }
+
+    /// Replace all public groups at once so that the scheduler never sees partially updated
+    /// registry. Private groups are kept after the new ones. Returns created and removed groups.
+    fn replace_groups(
+        &mut self,
+        descriptors: Vec<GroupDescriptor>,
+        midstate_count: usize,
+    ) -> Result<ReplacedGroups, error::Client> {
+        let mut registry = Self::new(self.event_monitor.clone());
+        let mut created_groups = Vec::with_capacity(descriptors.len());
+        for descriptor in descriptors {
+            created_groups.push(registry.create_group(descriptor, midstate_count)?);
+        }
+        let (private_groups, removed_groups): (Vec<_>, Vec<_>) = self
+            .list
+            .iter()
+            .map(|scheduler_group_handle| scheduler_group_handle.group_handle.clone())
+            .partition(|group_handle| group_handle.descriptor.private);
+        for group_handle in private_groups {
+            registry.add_group(group_handle)?;
+        }
+        *self = registry;
+
+        Ok((created_groups, removed_groups))
+    }
+
+    /// Register group and recalculate share ratios of all groups
+    fn add_group(&mut self, group_handle: Arc<Group>) -> Result<(), error::Client> {
+        match group_handle.descriptor.strategy() {
             LoadBalanceStrategy::Quota(quota) => {
                 self.total_quota += quota;
             }
@@ -471,17 +557,10 @@ impl GroupRegistry {
                 self.total_fixed_share_ratio += fixed_share_ratio;
             }
         }
-
-        let group_handle = Arc::new(Group::new(
-            descriptor,
-            self.event_monitor.publish(),
-            midstate_count,
-        ));
-        let scheduler_group_handle = scheduler::GroupHandle::new(group_handle.clone());
-        self.list.push(scheduler_group_handle);
+        self.list.push(scheduler::GroupHandle::new(group_handle));
         self.recalculate_quotas(true);
 
-        Ok(group_handle)
+        Ok(())
     }
 
     pub fn get_groups(&self) -> Vec<Arc<Group>> {
@@ -543,6 +622,17 @@ impl GroupRegistry {
     }
 }
 
+/// Client that supplies work exclusively to particular work solvers instead of being scheduled
+/// with other clients (see `Manager::create_dedicated_client`)
+#[derive(Debug, Clone)]
+pub struct DedicatedClient {
+    pub handle: Arc<Handle>,
+    /// Work solvers have to generate work from this receiver instead of the shared one
+    pub engine_receiver: work::EngineReceiver,
+    /// Solutions sent here are delivered directly to the client
+    pub solution_sender: work::SolutionSender,
+}
+
 #[derive(Debug, Clone)]
 pub struct Manager {
     group_registry: Arc<Mutex<GroupRegistry>>,
@@ -574,15 +664,9 @@ impl Manager {
                 let group = self.create_group(group_config.descriptor).await?;
                 if let Some(pool_configs) = group_config.pools {
                     for pool_config in pool_configs {
-                        let descriptor = ClientDescriptor::create(
-                            pool_config.url.as_str(),
-                            &ClientUserInfo::new(
-                                pool_config.user.as_str(),
-                                pool_config.password.as_deref(),
-                            ),
-                            pool_config.enabled.unwrap_or(default_pool_enabled),
-                        )
-                        .map_err(|e| e.to_string())?;
+                        let descriptor = pool_config
+                            .create_descriptor(default_pool_enabled)
+                            .map_err(|e| e.to_string())?;
                         let client_handle = Handle::new(descriptor, backend_info.cloned(), None);
                         group.push_client(client_handle).await;
                     }
@@ -592,6 +676,86 @@ impl Manager {
         Ok(())
     }
 
+    /// Replace all public groups with the ones from `group_configs` (e.g. when pool groups are
+    /// reloaded). Clients of removed groups are disabled. Nothing is replaced when the new groups
+    /// are not valid.
+    pub async fn replace_groups(
+        &self,
+        group_configs: Vec<GroupConfig>,
+        backend_info: Option<&hal::BackendInfo>,
+        default_pool_enabled: bool,
+    ) -> error::Result<()> {
+        let mut descriptors = Vec::with_capacity(group_configs.len());
+        let mut group_client_handles = Vec::with_capacity(group_configs.len());
+        for group_config in group_configs {
+            let mut client_handles = vec![];
+            for pool_config in group_config.pools.unwrap_or_default() {
+                let descriptor = pool_config
+                    .create_descriptor(default_pool_enabled)
+                    .map_err(|e| e.to_string())?;
+                client_handles.push(Handle::new(descriptor, backend_info.cloned(), None));
+            }
+            descriptors.push(group_config.descriptor);
+            group_client_handles.push(client_handles);
+        }
+
+        let (groups, removed_groups) = self
+            .group_registry
+            .lock()
+            .await
+            .replace_groups(descriptors, self.midstate_count)?;
+        for group in removed_groups {
+            group.replace_clients(vec![]).await;
+        }
+        for (group, client_handles) in groups.into_iter().zip(group_client_handles) {
+            group.replace_clients(client_handles).await;
+        }
+        Ok(())
+    }
+
+    /// How often is a failed dedicated client restarted (scheduled clients are restarted by the
+    /// scheduler)
+    const DEDICATED_CLIENT_RESTART_INTERVAL: time::Duration = time::Duration::from_secs(1);
+
+    /// Create client bound to its own work engine channel. The scheduler never switches it, so
+    /// work solvers using returned engine receiver mine for this client only.
+    pub fn create_dedicated_client(
+        &self,
+        descriptor: ClientDescriptor,
+        backend_info: Option<&hal::BackendInfo>,
+    ) -> DedicatedClient {
+        let client_handle = Handle::new(descriptor, backend_info.cloned(), None);
+        let midstate_count = self.midstate_count;
+        let _ = client_handle.replace_engine_generator(Box::new(move |job| {
+            Arc::new(work::engine::VersionRolling::new(job, midstate_count))
+        }));
+        let (engine_sender, engine_receiver) = work::engine_channel(work::IgnoreEvents);
+        client_handle.engine_sender.swap_sender(&engine_sender);
+        let solution_sender = work::SolutionSender::new(client_handle.solution_sender.clone());
+
+        let client_handle = Arc::new(client_handle);
+        let _ = client_handle.try_enable();
+        tokio::spawn(Self::dedicated_client_task(Arc::downgrade(&client_handle)));
+
+        DedicatedClient {
+            handle: client_handle,
+            engine_receiver,
+            solution_sender,
+        }
+    }
+
+    /// Restart failed dedicated client until it is dropped
+    async fn dedicated_client_task(client_handle: Weak<Handle>) {
+        loop {
+            delay_for(Self::DEDICATED_CLIENT_RESTART_INTERVAL).await;
+            match client_handle.upgrade() {
+                Some(client_handle) if client_handle.is_enabled() => client_handle.start(),
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+
     #[inline]
     pub fn subscribe_to_clients_status_changes(&self) -> event::Receiver {
         self.event_monitor.subscribe()
@@ -631,3 +795,212 @@ impl Manager {
         self.group_registry.lock().await.get_groups()
     }
 }
+
+/// Connect to the first reachable address of a pool. Addresses are tried in order (the primary
+/// one is the first) and `None` is returned when none of them is reachable.
+pub async fn connect_any<'a, T, F, R>(
+    hosts_and_ports: &'a [String],
+    user: &str,
+    timeout: time::Duration,
+    mut connect: F,
+) -> Option<(&'a str, T)>
+where
+    F: FnMut(&'a str) -> R,
+    R: Future<Output = error::Result<T>>,
+{
+    for host_and_port in hosts_and_ports.iter() {
+        match connect(host_and_port).timeout(timeout).await {
+            Ok(Ok(connection)) => return Some((host_and_port, connection)),
+            Ok(Err(e)) => info!(
+                "Failed to connect to {}, user={} {:?}",
+                host_and_port, user, e
+            ),
+            Err(_) => info!(
+                "Failed to connect to {}, user={} (timeout)",
+                host_and_port, user
+            ),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use bosminer_config::{ClientUserInfo, PoolConfig};
+
+    use std::sync::Mutex as StdMutex;
+
+    fn build_descriptor(name: &str) -> GroupDescriptor {
+        let mut descriptor = GroupDescriptor::default();
+        descriptor.name = name.to_string();
+        descriptor
+    }
+
+    fn build_group_config(name: &str) -> GroupConfig {
+        GroupConfig {
+            descriptor: build_descriptor(name),
+            pools: Some(vec![PoolConfig {
+                // disabled pool is never connected
+                enabled: Some(false),
+                url: Some("stratum+tcp://stratum.slushpool.com:3333".to_string()),
+                urls: None,
+                user: "user".to_string(),
+                password: None,
+                clean_jobs: None,
+                clean_jobs_delay: None,
+                keepalive_interval: None,
+                submit_batch_window: None,
+            }]),
+        }
+    }
+
+    async fn group_names(manager: &Manager) -> Vec<String> {
+        manager
+            .get_groups()
+            .await
+            .iter()
+            .map(|group| group.descriptor.name.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_replace_groups() {
+        let manager = Manager::new(1);
+        manager
+            .load_config(
+                vec![build_group_config("A"), build_group_config("B")],
+                None,
+                true,
+            )
+            .await
+            .expect("BUG: cannot load groups");
+        let mut private_descriptor = build_descriptor("Private");
+        private_descriptor.private = true;
+        manager
+            .create_group(private_descriptor)
+            .await
+            .expect("BUG: cannot create private group");
+        let removed_group = manager.get_groups().await[0].clone();
+
+        // groups missing in the new configuration are removed
+        manager
+            .replace_groups(
+                vec![build_group_config("B"), build_group_config("C")],
+                None,
+                true,
+            )
+            .await
+            .expect("BUG: cannot replace groups");
+        assert_eq!(group_names(&manager).await, vec!["B", "C"]);
+        for group in manager.get_groups().await {
+            assert_eq!(group.len().await, 1);
+        }
+        assert!(removed_group.is_empty().await);
+        // private groups are kept
+        assert_eq!(manager.group_registry.lock().await.count(), 3);
+
+        manager
+            .replace_groups(vec![], None, true)
+            .await
+            .expect("BUG: cannot remove all groups");
+        assert!(group_names(&manager).await.is_empty());
+        assert_eq!(manager.group_registry.lock().await.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replace_clients() {
+        let client_handle = |url: &str| {
+            let descriptor =
+                ClientDescriptor::create(url, &ClientUserInfo::new("user", None), true)
+                    .expect("BUG: cannot create descriptor");
+            Handle::new(descriptor, None, None)
+        };
+
+        let manager = Manager::new(1);
+        let group = manager.create_or_get_default_group().await;
+        let old_client_handle = group.push_client(client_handle("drain://old")).await;
+        assert!(old_client_handle.is_enabled());
+
+        // All clients are replaced at once and the removed ones are disabled
+        let removed_client_handles = group
+            .replace_clients(vec![client_handle("drain://a"), client_handle("drain://b")])
+            .await;
+        assert_eq!(removed_client_handles.len(), 1);
+        assert!(Arc::ptr_eq(&removed_client_handles[0], &old_client_handle));
+        assert!(!old_client_handle.is_enabled());
+
+        let client_handles = group.get_clients().await;
+        assert_eq!(client_handles.len(), 2);
+        assert!(client_handles
+            .iter()
+            .all(|client_handle| client_handle.is_enabled()));
+    }
+
+    #[tokio::test]
+    async fn test_connect_any() {
+        let hosts_and_ports: Vec<_> =
+            vec!["unreachable:3333", "stuck:3333", "backup:3333", "next:3333"]
+                .into_iter()
+                .map(String::from)
+                .collect();
+        let attempts = StdMutex::new(vec![]);
+        let connect = |host_and_port: &str| {
+            attempts.lock().unwrap().push(host_and_port.to_string());
+            let host_and_port = host_and_port.to_string();
+            async move {
+                match host_and_port.as_str() {
+                    "unreachable:3333" => Err(error::ErrorKind::General("refused".to_string()))?,
+                    "stuck:3333" => futures::future::pending().await,
+                    _ => Ok(host_and_port),
+                }
+            }
+        };
+
+        // backup address is used when the primary one fails and the rest is not tried at all
+        let connection = connect_any(
+            &hosts_and_ports,
+            "user",
+            time::Duration::from_millis(10),
+            connect,
+        )
+        .await;
+        assert_eq!(connection, Some(("backup:3333", "backup:3333".to_string())));
+        assert_eq!(*attempts.lock().unwrap(), &hosts_and_ports[..3]);
+
+        let connection = connect_any(
+            &hosts_and_ports[..2],
+            "user",
+            time::Duration::from_millis(10),
+            connect,
+        )
+        .await;
+        assert_eq!(connection, None);
+    }
+
+    #[tokio::test]
+    async fn test_dedicated_client() {
+        let manager = Manager::new(1);
+        let descriptor = ClientDescriptor::create(
+            "drain://localhost",
+            &ClientUserInfo::new("user", None),
+            true,
+        )
+        .expect("BUG: cannot create descriptor");
+        let mut dedicated_client = manager.create_dedicated_client(descriptor, None);
+        assert!(dedicated_client.handle.is_enabled());
+
+        // Client is started immediately and its jobs are delivered to the dedicated receiver
+        let engine = dedicated_client
+            .engine_receiver
+            .get_engine()
+            .timeout(time::Duration::from_secs(1))
+            .await
+            .expect("BUG: no work from dedicated client");
+        assert!(engine.is_some());
+
+        // Dedicated client is not scheduled with clients of pool groups
+        assert!(manager.get_groups().await.is_empty());
+    }
+}
@@ -24,6 +24,10 @@ use crate::client;
 use crate::sync::event;
 use crate::work;
 
+use ii_logging::macros::*;
+
+use bosminer_config::{GroupDescriptor, GroupPoolStrategy};
+
 use futures::channel::mpsc;
 use futures::lock::{Mutex, MutexGuard};
 use ii_async_compat::{futures, FutureExt};
@@ -38,6 +42,10 @@ use std::time;
 pub struct ClientHandle {
     pub client_handle: Arc<client::Handle>,
     last_generated_work: u64,
+    /// Start of current accept rate window with accepted and all submitted shares at that time
+    accept_rate_window: Option<(time::Instant, u64, u64)>,
+    /// Time when the client has been failed over due to low accept rate
+    failed_over: Option<time::Instant>,
 }
 
 impl ClientHandle {
@@ -45,6 +53,8 @@ impl ClientHandle {
         Self {
             last_generated_work: Self::get_generated_work(&client_handle),
             client_handle,
+            accept_rate_window: None,
+            failed_over: None,
         }
     }
 
@@ -53,6 +63,88 @@ impl ClientHandle {
         self.client_handle.is_running()
     }
 
+    #[inline]
+    fn is_failed_over(&self) -> bool {
+        self.failed_over.is_some()
+    }
+
+    /// Evaluate ratio of shares accepted by pool at the end of each window and fail over the
+    /// client when it falls below `min_accept_rate`. Failed over client is used again when its
+    /// accept rate exceeds `min_accept_rate` with some hysteresis or it is retried after a few
+    /// windows when it has not been used at all.
+    async fn update_accept_rate(
+        &mut self,
+        min_accept_rate: Option<f64>,
+        window: time::Duration,
+        now: time::Instant,
+    ) {
+        let min_accept_rate = match min_accept_rate {
+            Some(min_accept_rate) => min_accept_rate,
+            None => {
+                self.failed_over = None;
+                return;
+            }
+        };
+        let client_stats = self.client_handle.node.client_stats();
+        let accepted = client_stats.accepted().take_snapshot().await.solutions;
+        let total = accepted
+            + client_stats.rejected().take_snapshot().await.solutions
+            + client_stats.stale().take_snapshot().await.solutions;
+
+        let (window_start, window_accepted, window_total) = match self.accept_rate_window {
+            Some(accept_rate_window) => accept_rate_window,
+            None => {
+                self.accept_rate_window = Some((now, accepted, total));
+                return;
+            }
+        };
+        if now.duration_since(window_start) < window {
+            return;
+        }
+        self.accept_rate_window = Some((now, accepted, total));
+
+        let url = self
+            .client_handle
+            .descriptor()
+            .await
+            .get_url(true, true, false);
+        let shares = total - window_total;
+        if shares < GroupDescriptor::MIN_ACCEPT_RATE_SHARES {
+            if let Some(failed_over) = self.failed_over {
+                if now.duration_since(failed_over)
+                    >= window * GroupDescriptor::ACCEPT_RATE_RETRY_WINDOWS
+                {
+                    info!("Retrying pool {} failed over due to low accept rate", url);
+                    self.failed_over = None;
+                }
+            }
+            return;
+        }
+        let accept_rate = 100.0 * (accepted - window_accepted) as f64 / shares as f64;
+        match self.failed_over {
+            None if accept_rate < min_accept_rate => {
+                warn!(
+                    "Failing over pool {}: accept rate {:.1}% over last {} s is below {}%",
+                    url,
+                    accept_rate,
+                    window.as_secs(),
+                    min_accept_rate
+                );
+                self.failed_over = Some(now);
+            }
+            Some(_) if accept_rate >= min_accept_rate + GroupDescriptor::ACCEPT_RATE_HYSTERESIS => {
+                info!(
+                    "Pool {} recovered: accept rate {:.1}% over last {} s",
+                    url,
+                    accept_rate,
+                    window.as_secs()
+                );
+                self.failed_over = None;
+            }
+            _ => {}
+        }
+    }
+
     #[inline]
     fn try_start(&self) -> Result<(), ()> {
         if self.client_handle.is_enabled() {
@@ -106,6 +198,8 @@ impl PartialEq for ClientHandle {
 pub struct GroupHandle {
     pub group_handle: Arc<client::Group>,
     active_client: Option<Arc<client::Handle>>,
+    /// All running pools of the group have been failed over due to low accept rate
+    failed_over: bool,
     generated_work: u64,
     /// Current ratio of hashrate that this group has been allocated to. This number
     /// changes based on newly added/removed groups.
@@ -116,6 +210,7 @@ impl GroupHandle {
     pub fn new(group_handle: Arc<client::Group>) -> Self {
         Self {
             active_client: None,
+            failed_over: false,
             generated_work: 0,
             share_ratio: group_handle
                 .descriptor
@@ -143,24 +238,81 @@ impl GroupHandle {
         self.group_handle.descriptor.get_quota()
     }
 
-    async fn update_status(&mut self) {
+    #[inline]
+    pub fn priority(&self) -> usize {
+        self.group_handle.descriptor.priority()
+    }
+
+    async fn update_status(&mut self, now: time::Instant) {
         let mut scheduler_client_handles = self.group_handle.scheduler_client_handles.lock().await;
         let mut generated_work_delta = 0;
-
-        self.active_client = None;
-        for scheduler_client_handle in scheduler_client_handles.iter_mut() {
-            generated_work_delta += scheduler_client_handle.get_delta_and_update_generated_work();
-            match self.active_client {
-                None => {
-                    if scheduler_client_handle.is_running() {
-                        self.active_client = Some(scheduler_client_handle.client_handle.clone());
-                    } else {
+        let min_accept_rate = self.group_handle.descriptor.min_accept_rate();
+        let accept_rate_window = self.group_handle.descriptor.accept_rate_window();
+
+        match self.group_handle.descriptor.pool_strategy() {
+            GroupPoolStrategy::Failover => {
+                // Failed over pool is used only when no following pool is running
+                let mut failed_over_client = None;
+                self.active_client = None;
+                for scheduler_client_handle in scheduler_client_handles.iter_mut() {
+                    generated_work_delta +=
+                        scheduler_client_handle.get_delta_and_update_generated_work();
+                    scheduler_client_handle
+                        .update_accept_rate(min_accept_rate, accept_rate_window, now)
+                        .await;
+                    match self.active_client {
+                        None => {
+                            if !scheduler_client_handle.is_running() {
+                                let _ = scheduler_client_handle.try_start();
+                            } else if scheduler_client_handle.is_failed_over() {
+                                failed_over_client.get_or_insert_with(|| {
+                                    scheduler_client_handle.client_handle.clone()
+                                });
+                            } else {
+                                self.active_client =
+                                    Some(scheduler_client_handle.client_handle.clone());
+                            }
+                        }
+                        Some(_) => {
+                            let _ = scheduler_client_handle.try_delayed_stop();
+                        }
+                    }
+                }
+                self.failed_over = self.active_client.is_none() && failed_over_client.is_some();
+                self.active_client = self.active_client.take().or(failed_over_client);
+            }
+            GroupPoolStrategy::Balance => {
+                // Keep all pools connected and let running pools take turns
+                let previous_client = self.active_client.take();
+                let mut running_clients = vec![];
+                let mut failed_over_clients = vec![];
+                for scheduler_client_handle in scheduler_client_handles.iter_mut() {
+                    generated_work_delta +=
+                        scheduler_client_handle.get_delta_and_update_generated_work();
+                    scheduler_client_handle
+                        .update_accept_rate(min_accept_rate, accept_rate_window, now)
+                        .await;
+                    if !scheduler_client_handle.is_running() {
                         let _ = scheduler_client_handle.try_start();
+                    } else if scheduler_client_handle.is_failed_over() {
+                        failed_over_clients.push(scheduler_client_handle.client_handle.clone());
+                    } else {
+                        running_clients.push(scheduler_client_handle.client_handle.clone());
                     }
                 }
-                Some(_) => {
-                    let _ = scheduler_client_handle.try_delayed_stop();
+                // Failed over pools take turns only when there's no other running pool
+                self.failed_over = running_clients.is_empty() && !failed_over_clients.is_empty();
+                if running_clients.is_empty() {
+                    running_clients = failed_over_clients;
                 }
+                let next_index = previous_client
+                    .and_then(|previous_client| {
+                        running_clients
+                            .iter()
+                            .position(|client| *client == previous_client)
+                    })
+                    .map_or(0, |index| (index + 1) % running_clients.len());
+                self.active_client = running_clients.get(next_index).cloned();
             }
         }
 
@@ -259,7 +411,11 @@ impl JobDispatcher {
         }
     }
 
-    async fn select_client(&self, generated_work_delta: u64) -> Option<Arc<client::Handle>> {
+    async fn select_client(
+        &self,
+        generated_work_delta: u64,
+        now: time::Instant,
+    ) -> Option<Arc<client::Handle>> {
         let mut group_registry = self.group_registry.lock().await;
         if group_registry.is_empty() {
             return None;
@@ -267,12 +423,31 @@ impl JobDispatcher {
 
         let mut total_generated_work = 0;
         for scheduler_group_handle in group_registry.iter_mut() {
-            scheduler_group_handle.update_status().await;
+            scheduler_group_handle.update_status(now).await;
             total_generated_work += scheduler_group_handle.generated_work;
         }
 
+        // Groups with lower priority value take precedence as long as some of their pools run.
+        // Groups with all pools failed over due to low accept rate are used only when no other
+        // group is available.
+        let priority = group_registry
+            .iter()
+            .filter(|scheduler_group_handle| scheduler_group_handle.active_client.is_some())
+            .map(|scheduler_group_handle| {
+                (
+                    scheduler_group_handle.failed_over,
+                    scheduler_group_handle.priority(),
+                )
+            })
+            .min();
+
         let mut next_client = None;
-        for scheduler_group_handle in group_registry.iter() {
+        for scheduler_group_handle in group_registry.iter().filter(|scheduler_group_handle| {
+            Some((
+                scheduler_group_handle.failed_over,
+                scheduler_group_handle.priority(),
+            )) == priority
+        }) {
             let group_generated_work = scheduler_group_handle.generated_work;
             let next_group_share_ratio = (group_generated_work + generated_work_delta) as f64
                 / (total_generated_work + generated_work_delta) as f64;
@@ -301,7 +476,10 @@ impl JobDispatcher {
             }
             _ => {}
         }
-        if let Some(next_client) = self.select_client(generated_work_delta).await {
+        if let Some(next_client) = self
+            .select_client(generated_work_delta, time::Instant::now())
+            .await
+        {
             self.switch_client(next_client);
         }
     }
@@ -338,7 +516,8 @@ impl JobExecutor {
         self.dispatcher.lock().await
     }
 
-    async fn active_client(&self) -> Option<Arc<client::Handle>> {
+    /// Client which currently provides jobs to the backend
+    pub async fn active_client(&self) -> Option<Arc<client::Handle>> {
         self.lock_dispatcher().await.active_client.get_client()
     }
 
@@ -396,3 +575,160 @@ impl JobExecutor {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use bosminer_config::{ClientDescriptor, ClientUserInfo};
+    use ii_async_compat::tokio;
+
+    use std::sync::atomic::Ordering;
+
+    const MIN_ACCEPT_RATE: f64 = 90.0;
+    const ACCEPT_RATE_WINDOW: time::Duration = time::Duration::from_secs(60);
+
+    /// Add running client to the group (the client is not connected to any server)
+    async fn push_running_client(group: &client::Group, host: &str) -> Arc<client::Handle> {
+        let client_descriptor = ClientDescriptor::create(
+            &format!("stratum+tcp://{}:3333", host),
+            &ClientUserInfo::new("user", None),
+            false,
+        )
+        .expect("BUG: invalid client descriptor");
+        let client_handle = group
+            .push_client(client::Handle::new(client_descriptor, None, None))
+            .await;
+        client_handle.enabled.store(true, Ordering::Relaxed);
+        assert!(client_handle.node.status().initiate_starting());
+        assert!(client_handle.node.status().initiate_running());
+        client_handle
+    }
+
+    /// Create group with one running client
+    async fn create_group(manager: &client::Manager, priority: usize) -> Arc<client::Handle> {
+        let descriptor: GroupDescriptor = serde_json::from_value(serde_json::json!({
+            "name": format!("Group {}", priority),
+            "priority": priority,
+            "min_accept_rate": MIN_ACCEPT_RATE,
+            "accept_rate_window": ACCEPT_RATE_WINDOW.as_secs(),
+        }))
+        .expect("BUG: invalid group descriptor");
+        let group = manager
+            .create_group(descriptor)
+            .await
+            .expect("BUG: cannot create group");
+        push_running_client(&group, &format!("pool{}", priority)).await
+    }
+
+    /// Dispatcher with simulated time advancing by one accept rate window
+    struct AcceptRateTest {
+        dispatcher: JobDispatcher,
+        now: time::Instant,
+    }
+
+    impl AcceptRateTest {
+        /// Account shares submitted to `client_handle` during the next accept rate window and
+        /// select client at its end
+        async fn select_client(
+            &mut self,
+            client_handle: &client::Handle,
+            accepted: usize,
+            rejected: usize,
+        ) -> Arc<client::Handle> {
+            let target = ii_bitcoin::Target::default();
+            for _ in 0..accepted {
+                client_handle
+                    .stats()
+                    .accepted()
+                    .account_solution(&target, self.now)
+                    .await;
+            }
+            for _ in 0..rejected {
+                client_handle
+                    .stats()
+                    .rejected()
+                    .account_solution(&target, self.now)
+                    .await;
+            }
+            self.now += ACCEPT_RATE_WINDOW;
+            self.dispatcher
+                .select_client(0, self.now)
+                .await
+                .expect("BUG: no client selected")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_accept_rate_failover() {
+        let manager = client::Manager::new(1);
+        let primary = create_group(&manager, 0).await;
+        let backup = create_group(&manager, 1).await;
+        let mut test = AcceptRateTest {
+            dispatcher: JobDispatcher::new(
+                work::EngineSender::new(None),
+                manager.group_registry.clone(),
+            ),
+            now: time::Instant::now(),
+        };
+
+        // accept rate window starts
+        assert_eq!(test.select_client(&primary, 0, 0).await, primary);
+        assert_eq!(test.select_client(&primary, 10, 0).await, primary);
+        // accept rate falls below minimum and the backup pool is used
+        assert_eq!(test.select_client(&primary, 5, 5).await, backup);
+        // accept rate is above minimum but within hysteresis
+        assert_eq!(test.select_client(&primary, 92, 8).await, backup);
+        // recovered
+        assert_eq!(test.select_client(&primary, 100, 0).await, primary);
+
+        // failed over pool with too few shares to evaluate accept rate is retried after a while
+        assert_eq!(test.select_client(&primary, 0, 10).await, backup);
+        for _ in 1..GroupDescriptor::ACCEPT_RATE_RETRY_WINDOWS {
+            assert_eq!(test.select_client(&primary, 0, 0).await, backup);
+        }
+        assert_eq!(test.select_client(&primary, 0, 0).await, primary);
+    }
+
+    #[tokio::test]
+    async fn test_pool_group_failover() {
+        let manager = client::Manager::new(1);
+        let dispatcher = JobDispatcher::new(
+            work::EngineSender::new(None),
+            manager.group_registry.clone(),
+        );
+        let now = time::Instant::now();
+        let select_client = || async {
+            dispatcher
+                .select_client(0, now)
+                .await
+                .expect("BUG: no client selected")
+        };
+
+        let descriptor: GroupDescriptor = serde_json::from_value(serde_json::json!({
+            "name": "Balanced",
+            "pool_strategy": "balance",
+        }))
+        .expect("BUG: invalid group descriptor");
+        let group = manager
+            .create_group(descriptor)
+            .await
+            .expect("BUG: cannot create group");
+        let first = push_running_client(&group, "first").await;
+        let second = push_running_client(&group, "second").await;
+        let backup = create_group(&manager, 1).await;
+
+        // running pools of balanced group take turns
+        assert_eq!(select_client().await, first);
+        assert_eq!(select_client().await, second);
+        assert_eq!(select_client().await, first);
+
+        // group with lower priority is used only when no pool of preferred group runs
+        for client_handle in &[&first, &second] {
+            client_handle.enabled.store(false, Ordering::Relaxed);
+        }
+        assert_eq!(select_client().await, backup);
+        second.enabled.store(true, Ordering::Relaxed);
+        assert_eq!(select_client().await, second);
+    }
+}
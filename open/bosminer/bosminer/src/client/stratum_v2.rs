@@ -79,6 +79,12 @@ pub struct ConnectionDetails {
     pub user: String,
     pub host: String,
     pub port: u16,
+    /// Primary address followed by backup addresses of the pool
+    pub hosts_and_ports: Vec<String>,
+    /// Reconnect when nothing is received from the pool for this long
+    pub keepalive_interval: Option<time::Duration>,
+    /// Solutions found within this window are submitted together
+    pub submit_batch_window: time::Duration,
 }
 
 impl ConnectionDetails {
@@ -88,11 +94,18 @@ impl ConnectionDetails {
             user: descriptor.user.clone(),
             host: descriptor.host.clone(),
             port: descriptor.port(),
+            hosts_and_ports: descriptor.get_hosts_and_ports(),
+            keepalive_interval: descriptor.keepalive_interval,
+            submit_batch_window: descriptor.submit_batch_window,
         }
     }
 
-    fn get_host_and_port(&self) -> String {
-        format!("{}:{}", self.host, self.port)
+    /// Maximal time without any message from the pool before the connection is considered dead
+    fn event_timeout(&self) -> time::Duration {
+        self.keepalive_interval
+            .map_or(StratumClient::EVENT_TIMEOUT, |keepalive_interval| {
+                keepalive_interval.min(StratumClient::EVENT_TIMEOUT)
+            })
     }
 }
 
@@ -423,7 +436,8 @@ where
         }
     }
 
-    async fn process_solution(&mut self, solution: work::Solution) -> error::Result<()> {
+    /// Assign sequence number to the solution and store it for future server acknowledge
+    async fn register_solution(&mut self, solution: work::Solution) -> SubmitSharesStandard {
         let job: &StratumJob = solution.job();
 
         let seq_num = self.seq_num;
@@ -443,8 +457,16 @@ where
             .lock()
             .await
             .push_back((solution, seq_num));
+        share_msg
+    }
+
+    async fn process_solutions(&mut self, solutions: Vec<work::Solution>) -> error::Result<()> {
+        let mut share_msgs = Vec::with_capacity(solutions.len());
+        for solution in solutions {
+            share_msgs.push(self.register_solution(solution).await);
+        }
         // send solutions back to the stratum server
-        StratumClient::send_msg(&self.connection_tx, share_msg)
+        StratumClient::send_msgs(&self.connection_tx, share_msgs)
             .await
             .context("Cannot send submit to stratum server")?;
         // the response is handled in a separate task
@@ -541,9 +563,9 @@ impl StratumConnectionHandler {
             .unwrap_or(Err("Unexpected response for stratum open channel".into()))
     }
 
-    async fn connect(&self) -> error::Result<v2::Framed> {
+    async fn connect(&self, host_and_port: &str) -> error::Result<v2::Framed> {
         let connection_details = self.client.connection_details();
-        let addr = ii_wire::Address::from_str(connection_details.get_host_and_port().as_str())?;
+        let addr = ii_wire::Address::from_str(host_and_port)?;
         let mut client = ii_wire::Client::new(addr);
         // Attempt only once to connect (as the stratum client is being managed externally)
         let connection = client.next().await?;
@@ -791,6 +813,38 @@ impl StratumClient {
         }
     }
 
+    /// Send all messages and flush the connection only once
+    async fn send_msgs<M, S, E>(
+        connection_tx: &Arc<Mutex<S>>,
+        messages: Vec<M>,
+    ) -> error::Result<()>
+    where
+        M: TryInto<<Framing as ii_wire::Framing>::Tx, Error = <Framing as ii_wire::Framing>::Error>,
+        E: Into<error::Error>,
+        // TODO use S: FrameSink once the trait is adjusted to deal with payload specific error
+        S: Sink<<Framing as ii_wire::Framing>::Tx, Error = E>
+            + std::marker::Unpin
+            + std::fmt::Debug
+            + 'static,
+    {
+        let frames = messages
+            .into_iter()
+            .map(|message| message.try_into())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut frames = futures::stream::iter(frames.into_iter().map(Ok));
+        match connection_tx
+            .lock()
+            .await
+            .send_all(&mut frames)
+            .timeout(Self::SEND_TIMEOUT)
+            .await
+        {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(e.into()),
+            Err(_) => Err("Cannot send message due to timeout")?,
+        }
+    }
+
     async fn handle_frame(
         &self,
         frame: <Framing as ii_wire::Framing>::Rx,
@@ -836,8 +890,10 @@ impl StratumClient {
         S: FrameSink,
     {
         let mut solution_receiver = self.solution_receiver.lock().await;
+        solution_receiver.set_batch_window(self.connection_details().submit_batch_window);
         let mut extension_channel_rx = self.extension_channel_receiver.lock().await;
         let mut solution_handler = StratumSolutionHandler::new(self.clone(), connection_tx.clone());
+        let event_timeout = self.connection_details().event_timeout();
 
         // Notify the extension user that we are ready to start forwarding its protocol, use a
         // separate block, so that the lock is dropped immediately after the start notification
@@ -854,12 +910,18 @@ impl StratumClient {
         }
         while !self.status.is_shutting_down() {
             select! {
-                frame = connection_rx.next().timeout(Self::EVENT_TIMEOUT).fuse() => {
+                frame = connection_rx.next().timeout(event_timeout).fuse() => {
                     match frame {
                         Ok(Some(frame)) => self.handle_frame(frame?, &mut event_handler).await?,
-                        Ok(None) | Err(_) => {
+                        Ok(None) => {
                             Err("The remote stratum server was disconnected prematurely")?;
                         }
+                        Err(_) => {
+                            Err(format!(
+                                "The remote stratum server has been idle for {}s",
+                                event_timeout.as_secs()
+                            ))?;
+                        }
                     }
                 }
                 // Forward extension protocol frames onto the network
@@ -868,9 +930,9 @@ impl StratumClient {
                         .send(frame.expect("BUG: extension channel must not shutdown!"))
                         .await?;
                 }
-                solution = solution_receiver.receive().fuse() => {
-                    match solution {
-                        Some(solution) => solution_handler.process_solution(solution).await?,
+                solutions = solution_receiver.receive_batch().fuse() => {
+                    match solutions {
+                        Some(solutions) => solution_handler.process_solutions(solutions).await?,
                         None => {
                             // TODO: initiate Destroying and remove error
                             Err("Standard application shutdown")?;
@@ -906,16 +968,19 @@ impl StratumClient {
     async fn run(self: Arc<Self>) {
         let connection_handler = StratumConnectionHandler::new(self.clone());
         let connection_details = connection_handler.client.connection_details();
-        let host_and_port = connection_details.get_host_and_port();
         let user = connection_details.user.clone();
 
-        match connection_handler
-            .connect()
-            .timeout(Self::CONNECTION_TIMEOUT)
-            .await
-            .map_err(|_| error::ErrorKind::General("Connection timeout".to_string()).into())
-        {
-            Ok(Ok(framed_connection)) => {
+        // Try all addresses of the pool in order and fail only when none of them is reachable
+        let connection = super::connect_any(
+            &connection_details.hosts_and_ports,
+            &user,
+            Self::CONNECTION_TIMEOUT,
+            |host_and_port| connection_handler.connect(host_and_port),
+        )
+        .await;
+
+        match connection {
+            Some((host_and_port, framed_connection)) => {
                 let (framed_sink, mut framed_stream) = framed_connection.split();
                 let framed_sink = Arc::new(Mutex::new(framed_sink));
                 match connection_handler
@@ -943,13 +1008,7 @@ impl StratumClient {
                     }
                 }
             }
-            Ok(Err(e)) | Err(e) => {
-                info!(
-                    "Failed to connect to {}, user={} {:?}",
-                    host_and_port, user, e
-                );
-                self.status.initiate_failing()
-            }
+            None => self.status.initiate_failing(),
         }
     }
 
@@ -1043,3 +1102,42 @@ impl fmt::Display for StratumClient {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use bosminer_config::ClientUserInfo;
+
+    #[test]
+    fn test_event_timeout() {
+        let mut descriptor = ClientDescriptor::create(
+            "stratum+tcp://stratum.slushpool.com:3333",
+            &ClientUserInfo::new("user", None),
+            true,
+        )
+        .expect("BUG: cannot create descriptor");
+
+        // Keepalive is disabled so the default protocol timeout is used
+        let connection_details = ConnectionDetails::from_descriptor(&descriptor);
+        assert_eq!(
+            connection_details.event_timeout(),
+            StratumClient::EVENT_TIMEOUT
+        );
+
+        descriptor.keepalive_interval = Some(time::Duration::from_secs(30));
+        let connection_details = ConnectionDetails::from_descriptor(&descriptor);
+        assert_eq!(
+            connection_details.event_timeout(),
+            time::Duration::from_secs(30)
+        );
+
+        // Keepalive never prolongs the default protocol timeout
+        descriptor.keepalive_interval = Some(time::Duration::from_secs(3600));
+        let connection_details = ConnectionDetails::from_descriptor(&descriptor);
+        assert_eq!(
+            connection_details.event_timeout(),
+            StratumClient::EVENT_TIMEOUT
+        );
+    }
+}
@@ -35,7 +35,7 @@ use failure::ResultExt;
 
 use ii_bitcoin::HashTrait;
 
-use bosminer_config::{ClientDescriptor, ClientProtocol};
+use bosminer_config::{ClientCleanJobs, ClientDescriptor, ClientProtocol};
 use bosminer_macros::ClientNode;
 
 use async_trait::async_trait;
@@ -61,7 +61,7 @@ use ii_stratum::v2::types::DeviceInfo;
 use ii_stratum::v2::types::*;
 use ii_stratum::v2::{build_message_from_frame, Handler};
 use ii_stratum::{v1, v2};
-use ii_stratum_proxy::translation::{V2ToV1Translation, V2ToV1TranslationOptions};
+use ii_stratum_proxy::translation::{V1CleanJobs, V2ToV1Translation, V2ToV1TranslationOptions};
 use ii_wire::Connection;
 
 use std::collections::HashMap;
@@ -75,6 +75,14 @@ pub struct ConnectionDetails {
     pub host: String,
     pub port: u16,
     pub fragment: Option<String>,
+    /// Primary address followed by backup addresses of the pool
+    pub hosts_and_ports: Vec<String>,
+    /// Handling of jobs cancelled by the pool
+    pub clean_jobs: ClientCleanJobs,
+    /// Reconnect when nothing is received from the pool for this long
+    pub keepalive_interval: Option<time::Duration>,
+    /// Solutions found within this window are submitted together
+    pub submit_batch_window: time::Duration,
 }
 
 impl ConnectionDetails {
@@ -84,11 +92,27 @@ impl ConnectionDetails {
             host: descriptor.host.clone(),
             port: descriptor.port(),
             fragment: descriptor.fragment.clone(),
+            hosts_and_ports: descriptor.get_hosts_and_ports(),
+            clean_jobs: descriptor.clean_jobs,
+            keepalive_interval: descriptor.keepalive_interval,
+            submit_batch_window: descriptor.submit_batch_window,
         }
     }
 
-    fn get_host_and_port(&self) -> String {
-        format!("{}:{}", self.host, self.port)
+    /// Maximal time without any message from the pool before the connection is considered dead
+    fn event_timeout(&self) -> time::Duration {
+        self.keepalive_interval
+            .map_or(StratumClient::EVENT_TIMEOUT, |keepalive_interval| {
+                keepalive_interval.min(StratumClient::EVENT_TIMEOUT)
+            })
+    }
+
+    fn v1_clean_jobs(&self) -> V1CleanJobs {
+        match self.clean_jobs {
+            ClientCleanJobs::Honor => V1CleanJobs::Honor,
+            ClientCleanJobs::Ignore => V1CleanJobs::Ignore,
+            ClientCleanJobs::Delay(delay) => V1CleanJobs::Delay(delay),
+        }
     }
 
     fn try_enable_xnsub(&self) -> bool {
@@ -417,7 +441,8 @@ where
         }
     }
 
-    async fn process_solution(&mut self, solution: work::Solution) -> error::Result<()> {
+    /// Assign sequence number to the solution and store it for future server acknowledge
+    async fn register_solution(&mut self, solution: work::Solution) -> SubmitSharesStandard {
         let job: &StratumJob = solution.job();
 
         let seq_num = self.seq_num;
@@ -437,8 +462,16 @@ where
             .lock()
             .await
             .push_back((solution, seq_num));
+        share_msg
+    }
+
+    async fn process_solutions(&mut self, solutions: Vec<work::Solution>) -> error::Result<()> {
+        let mut share_msgs = Vec::with_capacity(solutions.len());
+        for solution in solutions {
+            share_msgs.push(self.register_solution(solution).await);
+        }
         // send solutions back to the stratum server
-        StratumClient::send_msg(&mut self.connection_tx, share_msg)
+        StratumClient::send_msgs(&mut self.connection_tx, share_msgs)
             .await
             .context("Cannot send submit to stratum server")?;
         // the response is handled in a separate task
@@ -549,11 +582,8 @@ impl StratumConnectionHandler {
             .unwrap_or(Err("Unexpected response for stratum open channel".into()))
     }
 
-    async fn connect(self) -> error::Result<v1::Framed> {
-        let socket_addr = self
-            .client
-            .connection_details
-            .get_host_and_port()
+    async fn connect(self, host_and_port: &str) -> error::Result<v1::Framed> {
+        let socket_addr = host_and_port
             .to_socket_addrs()
             .context("Invalid server address")?
             // TODO: this is not correct as it always only attempts to ever connect to the first
@@ -682,6 +712,28 @@ impl StratumClient {
         }
     }
 
+    /// Send all messages and flush the connection only once
+    async fn send_msgs<M, S>(connection_tx: &mut S, messages: Vec<M>) -> error::Result<()>
+    where
+        M: TryInto<<Framing as ii_wire::Framing>::Tx, Error = <Framing as ii_wire::Framing>::Error>,
+        S: FrameSink,
+    {
+        let frames = messages
+            .into_iter()
+            .map(|message| message.try_into())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let mut frames = futures::stream::iter(frames.into_iter().map(Ok));
+        match connection_tx
+            .send_all(&mut frames)
+            .timeout(Self::SEND_TIMEOUT)
+            .await
+        {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(e.into()),
+            Err(_) => Err("Cannot send message due to timeout")?,
+        }
+    }
+
     async fn main_loop<R, S>(
         &self,
         mut connection_rx: R,
@@ -693,6 +745,7 @@ impl StratumClient {
         S: FrameSink,
     {
         let mut solution_receiver = self.solution_receiver.lock().await;
+        solution_receiver.set_batch_window(self.connection_details.submit_batch_window);
 
         while !self.status.is_shutting_down() {
             select! {
@@ -707,9 +760,9 @@ impl StratumClient {
                         }
                     }
                 },
-                solution = solution_receiver.receive().fuse() => {
-                    match solution {
-                        Some(solution) => solution_handler.process_solution(solution).await?,
+                solutions = solution_receiver.receive_batch().fuse() => {
+                    match solutions {
+                        Some(solutions) => solution_handler.process_solutions(solutions).await?,
                         None => {
                             // TODO: initiate Destroying and remove error
                             Err("Standard application shutdown")?;
@@ -747,18 +800,28 @@ impl StratumClient {
     }
 
     async fn run(self: Arc<Self>) {
-        match StratumConnectionHandler::new(self.clone())
-            .connect()
-            .timeout(Self::CONNECTION_TIMEOUT)
-            .await
-        {
-            Ok(Ok(v1_framed_connection)) => {
+        // Try all addresses of the pool in order and fail only when none of them is reachable
+        let connection = super::connect_any(
+            &self.connection_details.hosts_and_ports,
+            &self.connection_details.user,
+            Self::CONNECTION_TIMEOUT,
+            |host_and_port| StratumConnectionHandler::new(self.clone()).connect(host_and_port),
+        )
+        .await;
+
+        match connection {
+            Some((_, v1_framed_connection)) => {
                 if self.status.initiate_running() {
                     let options = V2ToV1TranslationOptions {
                         try_enable_xnsub: self.connection_details.try_enable_xnsub(),
+                        clean_jobs: self.connection_details.v1_clean_jobs(),
                     };
                     let (translation_handler, v2_translation_rx, v2_translation_tx) =
-                        TranslationHandler::new(v1_framed_connection, options);
+                        TranslationHandler::new(
+                            v1_framed_connection,
+                            options,
+                            self.connection_details.event_timeout(),
+                        );
                     tokio::spawn(async move {
                         let status = translation_handler.run().await;
                         info!("V2->V1 translation terminated: {:?}", status);
@@ -768,7 +831,7 @@ impl StratumClient {
                         .await;
                 }
             }
-            Ok(Err(_)) | Err(_) => self.status.initiate_failing(),
+            None => self.status.initiate_failing(),
         }
     }
 
@@ -814,6 +877,8 @@ struct TranslationHandler {
     v1_translation_rx: mpsc::Receiver<v1::Frame>,
     /// V2 Frames from the client that we use for feeding the translator
     v2_client_rx: mpsc::Receiver<v2::Frame>,
+    /// Upstream connection is dropped when nothing is received for this long
+    event_timeout: time::Duration,
 }
 
 impl TranslationHandler {
@@ -823,6 +888,7 @@ impl TranslationHandler {
     fn new(
         v1_conn: v1::Framed,
         options: V2ToV1TranslationOptions,
+        event_timeout: time::Duration,
     ) -> (Self, mpsc::Receiver<v2::Frame>, mpsc::Sender<v2::Frame>) {
         let (v1_translation_tx, v1_translation_rx) =
             mpsc::channel(Self::MAX_TRANSLATION_CHANNEL_SIZE);
@@ -838,6 +904,7 @@ impl TranslationHandler {
                 v1_conn,
                 v1_translation_rx,
                 v2_client_rx,
+                event_timeout,
             },
             v2_translation_rx,
             v2_client_tx,
@@ -858,15 +925,21 @@ impl TranslationHandler {
         loop {
             select! {
                 // Receive V1 frame and translate it to V2 message
-                v1_frame = self.v1_conn.next().timeout(StratumClient::EVENT_TIMEOUT).fuse() => {
+                v1_frame = self.v1_conn.next().timeout(self.event_timeout).fuse() => {
                     match v1_frame {
                         Ok(Some(v1_frame)) => {
                             let v1_msg = v1::build_message_from_frame(v1_frame?)?;
                             v1_msg.accept(&mut self.translation).await;
                         }
-                        Ok(None) | Err(_) => {
+                        Ok(None) => {
                             Err("Upstream V1 stratum connection dropped terminating translation")?;
                         }
+                        Err(_) => {
+                            Err(format!(
+                                "Upstream V1 stratum connection idle for {}s terminating translation",
+                                self.event_timeout.as_secs()
+                            ))?;
+                        }
                     }
                 },
                 // Receive V2 frame from our client (no timeout needed) and pass it to V1
@@ -112,8 +112,20 @@ pub trait BackendConfig: Debug + Send + Sync {
     }
 }
 
+/// Access restrictions of miner API
+#[derive(Clone, Debug, Default)]
+pub struct ApiAccess {
+    /// Refuse API commands changing miner state
+    pub read_only: bool,
+    /// Token required for API commands changing miner state
+    pub token: Option<String>,
+    /// Require the token also for query commands
+    pub protect_queries: bool,
+}
+
 pub struct FrontendConfig {
     pub cgminer_custom_commands: Option<command::Map>,
+    pub api_access: ApiAccess,
 }
 
 /// Minimal interface for running compatible backend with BOSminer crate
@@ -201,6 +201,12 @@ impl Core {
         &self.client_manager
     }
 
+    /// Client which currently provides jobs to the backend
+    #[inline]
+    pub async fn get_active_client(&self) -> Option<Arc<client::Handle>> {
+        self.job_executor.active_client().await
+    }
+
     pub async fn run(self: Arc<Self>) {
         let solution_router = self
             .solution_router
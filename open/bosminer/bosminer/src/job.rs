@@ -32,11 +32,13 @@ use crate::work;
 use futures::channel::mpsc;
 use futures::stream::StreamExt;
 use ii_async_compat::futures;
+use ii_async_compat::prelude::*;
 
 use std::convert::TryInto;
 use std::fmt::Debug;
 use std::mem;
 use std::sync::{Arc, Weak};
+use std::time;
 
 use downcast_rs::{impl_downcast, Downcast};
 
@@ -156,16 +158,38 @@ impl Sender {
     }
 }
 
+/// Maximal time for which the batch of solutions is held without checking validity of their jobs
+const BATCH_VALIDITY_CHECK_INTERVAL: time::Duration = time::Duration::from_millis(100);
+
 /// Receives `work::Solution` via a channel and filters only solutions that meet the client/pool
 /// specified target
 #[derive(Debug)]
 pub struct SolutionReceiver {
     solution_channel: mpsc::UnboundedReceiver<work::Solution>,
+    /// Solutions found within this window are received together by `receive_batch`
+    batch_window: time::Duration,
+    /// Batch being collected. It is kept here so that no solution is lost when receiving of the
+    /// batch is interrupted by another event.
+    batch: Vec<work::Solution>,
+    batch_deadline: time::Instant,
+    /// Solution channel has been closed while collecting the batch
+    closed: bool,
 }
 
 impl SolutionReceiver {
     pub fn new(solution_channel: mpsc::UnboundedReceiver<work::Solution>) -> Self {
-        Self { solution_channel }
+        Self {
+            solution_channel,
+            batch_window: time::Duration::from_millis(0),
+            batch: vec![],
+            batch_deadline: time::Instant::now(),
+            closed: false,
+        }
+    }
+
+    /// Set window for collecting solutions submitted together (zero disables batching)
+    pub fn set_batch_window(&mut self, batch_window: time::Duration) {
+        self.batch_window = batch_window;
     }
 
     fn trace_share(solution: &work::Solution, target: &ii_bitcoin::Target) {
@@ -186,46 +210,217 @@ impl SolutionReceiver {
         );
     }
 
+    /// Account the solution and check if it meets the client/pool target and its job is still
+    /// valid
+    async fn check_solution(solution: work::Solution) -> Option<work::Solution> {
+        let path = solution.path();
+        let time = solution.timestamp();
+        let hash = solution.hash();
+        let job_target = solution.job_target();
+
+        // compare block hash for given solution with all targets
+        // TODO: create tests for solution validation with all difficulty variants
+        assert!(&solution.network_target() <= job_target);
+        if hash.meets(&solution.network_target()) {
+            stats::account_valid_solution(&path, &solution, time, DiffTargetType::Network).await;
+        } else if hash.meets(&job_target) {
+            stats::account_valid_solution(&path, &solution, time, DiffTargetType::Job).await;
+        } else if hash.meets(solution.backend_target()) {
+            stats::account_valid_solution(&path, &solution, time, DiffTargetType::Backend).await;
+            // skip submitting the solution as we've only met backend difficulty
+            return None;
+        } else {
+            stats::account_error_backend_diff(&path, &solution.backend_target(), time).await;
+            // skip submitting the solution as this is a backend error
+            return None;
+        }
+
+        if solution.has_valid_job() {
+            // TODO: Account solution to Discard meter
+            Self::trace_share(&solution, &job_target);
+            return Some(solution);
+        }
+        None
+    }
+
     pub async fn receive(&mut self) -> Option<work::Solution> {
         while let Some(solution) = self.solution_channel.next().await {
-            let path = solution.path();
-            let time = solution.timestamp();
-            let hash = solution.hash();
-            let job_target = solution.job_target();
-
-            // compare block hash for given solution with all targets
-            // TODO: create tests for solution validation with all difficulty variants
-            assert!(&solution.network_target() <= job_target);
-            if hash.meets(&solution.network_target()) {
-                stats::account_valid_solution(&path, &solution, time, DiffTargetType::Network)
-                    .await;
-            } else if hash.meets(&job_target) {
-                stats::account_valid_solution(&path, &solution, time, DiffTargetType::Job).await;
-            } else if hash.meets(solution.backend_target()) {
-                stats::account_valid_solution(&path, &solution, time, DiffTargetType::Backend)
-                    .await;
-                // skip submitting the solution as we've only met backend difficulty
-                continue;
-            } else {
-                stats::account_error_backend_diff(&path, &solution.backend_target(), time).await;
-                // skip submitting the solution as this is a backend error
-                continue;
-            }
-
-            if solution.has_valid_job() {
-                // TODO: Account solution to Discard meter
-                Self::trace_share(&solution, &job_target);
+            if let Some(solution) = Self::check_solution(solution).await {
                 return Some(solution);
             }
         }
         None
     }
 
+    /// Receive all solutions found within the batch window starting with the first solution.
+    /// The batch is finished early when a job of any collected solution is no longer valid so
+    /// that batching does not hold back solutions which are about to become stale. Validity of
+    /// the jobs is checked at least every `BATCH_VALIDITY_CHECK_INTERVAL` while waiting.
+    pub async fn receive_batch(&mut self) -> Option<Vec<work::Solution>> {
+        if self.batch.is_empty() {
+            if self.closed {
+                return None;
+            }
+            let solution = self.receive().await?;
+            self.batch.push(solution);
+            self.batch_deadline = time::Instant::now() + self.batch_window;
+        }
+        while self.batch.iter().all(|solution| solution.has_valid_job()) {
+            let now = time::Instant::now();
+            if now >= self.batch_deadline {
+                break;
+            }
+            let wait = (self.batch_deadline - now).min(BATCH_VALIDITY_CHECK_INTERVAL);
+            // Only waiting for the channel can be interrupted so that no received solution is lost
+            match self.solution_channel.next().timeout(wait).await {
+                Ok(Some(solution)) => {
+                    if let Some(solution) = Self::check_solution(solution).await {
+                        self.batch.push(solution);
+                    }
+                }
+                // Submit what has been collected and report closed channel with next call
+                Ok(None) => {
+                    self.closed = true;
+                    break;
+                }
+                // Check validity of the jobs again
+                Err(_) => {}
+            }
+        }
+        Some(mem::take(&mut self.batch))
+    }
+
     /// Empty all buffered solutions without blocking. This is to prevent the client from submitting
     /// already stale solutions
     /// TODO: We should review this regularly as there may be extensions in the mining protocol that
     /// may allow resume a mining session
     pub fn flush(&mut self) {
+        self.batch.clear();
         while let Ok(Some(_)) = self.solution_channel.try_next() {}
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils;
+
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Test job which can be invalidated during the test
+    #[derive(Debug)]
+    struct TestJob {
+        block: test_utils::TestBlock,
+        valid: AtomicBool,
+    }
+
+    impl Bitcoin for TestJob {
+        fn origin(&self) -> Weak<dyn node::Client> {
+            self.block.origin()
+        }
+
+        fn version(&self) -> u32 {
+            self.block.version()
+        }
+
+        fn version_mask(&self) -> u32 {
+            self.block.version_mask()
+        }
+
+        fn previous_hash(&self) -> &ii_bitcoin::DHash {
+            self.block.previous_hash()
+        }
+
+        fn merkle_root(&self) -> &ii_bitcoin::DHash {
+            self.block.merkle_root()
+        }
+
+        fn time(&self) -> u32 {
+            self.block.time()
+        }
+
+        fn bits(&self) -> u32 {
+            self.block.bits()
+        }
+
+        fn target(&self) -> ii_bitcoin::Target {
+            self.block.target()
+        }
+
+        fn is_valid(&self) -> bool {
+            self.valid.load(Ordering::Relaxed)
+        }
+    }
+
+    fn build_solution(job: Arc<TestJob>) -> work::Solution {
+        let block = job.block;
+        let mid = work::Midstate {
+            version: block.version(),
+            state: block.midstate,
+        };
+        work::Solution::new(
+            work::Assignment::new(job, vec![mid], block.time()),
+            test_utils::TestSolution::new(&block),
+            None,
+        )
+    }
+
+    fn build_job(block: &test_utils::TestBlock) -> Arc<TestJob> {
+        Arc::new(TestJob {
+            block: *block,
+            valid: AtomicBool::new(true),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_receive_batch() {
+        let (solution_sender, solution_receiver) = mpsc::unbounded();
+        let mut solution_receiver = SolutionReceiver::new(solution_receiver);
+        solution_receiver.set_batch_window(time::Duration::from_millis(200));
+
+        // all solutions found within the window are received together
+        for block in test_utils::TEST_BLOCKS.iter().take(2) {
+            solution_sender
+                .unbounded_send(build_solution(build_job(block)))
+                .unwrap();
+        }
+        let start = time::Instant::now();
+        let solutions = solution_receiver.receive_batch().await.unwrap();
+        assert_eq!(solutions.len(), 2);
+        assert!(start.elapsed() >= time::Duration::from_millis(200));
+
+        // closed channel is reported only after the collected batch is received
+        solution_sender
+            .unbounded_send(build_solution(build_job(&test_utils::TEST_BLOCKS[2])))
+            .unwrap();
+        drop(solution_sender);
+        assert_eq!(solution_receiver.receive_batch().await.unwrap().len(), 1);
+        assert!(solution_receiver.receive_batch().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_receive_batch_job_invalidation() {
+        let (solution_sender, solution_receiver) = mpsc::unbounded();
+        let mut solution_receiver = SolutionReceiver::new(solution_receiver);
+        solution_receiver.set_batch_window(time::Duration::from_secs(10));
+
+        let job = build_job(&test_utils::TEST_BLOCKS[0]);
+        solution_sender
+            .unbounded_send(build_solution(job.clone()))
+            .unwrap();
+        tokio::spawn(async move {
+            tokio::time::delay_for(time::Duration::from_millis(50)).await;
+            job.valid.store(false, Ordering::Relaxed);
+        });
+
+        // the batch has to be released shortly after the job is invalidated even when no other
+        // solution arrives
+        let solutions = solution_receiver
+            .receive_batch()
+            .timeout(time::Duration::from_secs(1))
+            .await
+            .expect("batch held after job invalidation")
+            .unwrap();
+        assert_eq!(solutions.len(), 1);
+    }
+}
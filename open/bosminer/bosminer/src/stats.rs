@@ -90,6 +90,8 @@ pub struct MeterSnapshot {
     pub solutions: u64,
     /// All shares measured from the beginning of the mining
     pub shares: ii_bitcoin::Shares,
+    /// Time when the last solution has been measured
+    pub last_time: Option<time::SystemTime>,
     /// Approximate arithmetic mean of hashes within given time intervals (in kH/time)
     time_means: Vec<WindowedTimeMean>,
 }
@@ -159,6 +161,7 @@ impl Meter {
             inner: Mutex::new(MeterSnapshot {
                 solutions: 0,
                 shares: Default::default(),
+                last_time: None,
                 time_means: intervals
                     .iter()
                     .map(|&interval| WindowedTimeMean::new(interval))
@@ -180,6 +183,7 @@ impl Meter {
         // TODO: what to do when number overflows
         meter.solutions += 1;
         meter.shares.account_solution(target);
+        meter.last_time = Some(time::SystemTime::now());
         for time_mean in &mut meter.time_means {
             time_mean.insert(kilo_hashes, time);
         }
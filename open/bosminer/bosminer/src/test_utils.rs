@@ -179,7 +179,7 @@ impl TestBlockBuilder for TestBlock {
 }
 
 #[derive(Debug)]
-struct TestSolution {
+pub struct TestSolution {
     test_block: TestBlock,
     target: ii_bitcoin::Target,
 }
@@ -187,6 +187,14 @@ impl Generator {
         }
     }
 
+    /// Draw work from another source of work engines (e.g. a client dedicated to this solver)
+    pub fn with_engine_receiver(self, engine_receiver: EngineReceiver) -> Self {
+        Self {
+            engine_receiver,
+            ..self
+        }
+    }
+
     /// Loops until new work is available or no more `WorkEngines` are supplied (signals
     /// Generator shutdown)
     pub async fn generate(&mut self) -> Option<Assignment> {
@@ -255,6 +263,10 @@ impl Generator {
 pub struct SolutionSender(mpsc::UnboundedSender<Solution>);
 
 impl SolutionSender {
+    pub fn new(solution_sender: mpsc::UnboundedSender<Solution>) -> Self {
+        Self(solution_sender)
+    }
+
     pub fn send(&self, solution: Solution) {
         self.0
             .unbounded_send(solution)
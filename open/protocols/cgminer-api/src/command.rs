@@ -24,7 +24,7 @@
 
 use crate::response;
 use crate::support::ValueExt as _;
-use crate::support::{MultiResponse, ResponseType, UnixTime, When};
+use crate::support::{self, MultiResponse, ResponseType, UnixTime, When};
 
 use serde_json as json;
 
@@ -55,6 +55,18 @@ const ASC_COUNT: &str = "asccount";
 const ASC: &str = "asc";
 const LCD: &str = "lcd";
 
+/// Request field carrying API token
+const TOKEN: &str = "token";
+
+/// List of standard commands that change miner state
+const MUTATING_COMMANDS: &[&str] = &[
+    SWITCH_POOL,
+    ENABLE_POOL,
+    DISABLE_POOL,
+    ADD_POOL,
+    REMOVE_POOL,
+];
+
 // List of all standard commands which can be optionally implemented.
 pub const DEVDETAILS: &str = "devdetails";
 
@@ -62,6 +74,7 @@ pub const DEVDETAILS: &str = "devdetails";
 pub const TEMPCTRL: &str = "tempctrl";
 pub const TEMPS: &str = "temps";
 pub const FANS: &str = "fans";
+pub const POOLSTATES: &str = "poolstates";
 
 pub type Result<T> = std::result::Result<T, response::Error>;
 /// Type describing command table
@@ -142,6 +155,8 @@ impl HandlerType {
 pub struct Descriptor {
     handler: HandlerType,
     parameter_check: Option<ParameterCheckHandler>,
+    /// Command changes miner state (it is refused by read-only API)
+    mutating: bool,
 }
 
 impl Descriptor {
@@ -152,6 +167,7 @@ impl Descriptor {
         Self {
             handler,
             parameter_check: parameter_check.into(),
+            mutating: false,
         }
     }
 
@@ -159,6 +175,16 @@ impl Descriptor {
     pub fn has_parameters(&self) -> bool {
         self.handler.has_parameters()
     }
+
+    /// Mark command as changing miner state
+    pub fn set_mutating(&mut self) {
+        self.mutating = true;
+    }
+
+    #[inline]
+    pub fn is_mutating(&self) -> bool {
+        self.mutating
+    }
 }
 
 /// Generates a descriptor for a specified command type (`ParameterLess` or `Parameter`) that also
@@ -219,6 +245,12 @@ pub struct Receiver<T = UnixTime> {
     miner_signature: String,
     miner_version: String,
     description: String,
+    /// Refuse all commands changing miner state
+    read_only: bool,
+    /// Token required for commands changing miner state
+    token: Option<String>,
+    /// Require the token also for query commands
+    protect_queries: bool,
     _marker: marker::PhantomData<T>,
 }
 
@@ -277,6 +309,12 @@ where
             (CHECK: BuiltIn(Check))
         ];
 
+        for name in MUTATING_COMMANDS {
+            commands
+                .get_mut(name)
+                .expect("BUG: missing standard command")
+                .set_mutating();
+        }
         if let Some(custom_commands) = custom_commands.into() {
             commands.extend(custom_commands.into_iter());
         }
@@ -287,10 +325,45 @@ where
             miner_signature,
             miner_version,
             description,
+            read_only: false,
+            token: None,
+            protect_queries: false,
             _marker: marker::PhantomData,
         }
     }
 
+    /// Refuse all commands changing miner state while keeping query commands available
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Require matching `token` in requests with commands changing miner state. When
+    /// `protect_queries` is set then the token is required for all commands.
+    pub fn token(mut self, token: Option<String>, protect_queries: bool) -> Self {
+        self.token = token;
+        self.protect_queries = protect_queries;
+        self
+    }
+
+    fn is_authorized(&self, request_token: Option<&str>) -> bool {
+        match &self.token {
+            None => true,
+            Some(token) => request_token.map_or(false, |request_token| {
+                support::constant_time_eq(token.as_bytes(), request_token.as_bytes())
+            }),
+        }
+    }
+
+    #[inline]
+    fn is_denied(&self, descriptor: &Descriptor, authorized: bool) -> bool {
+        if descriptor.is_mutating() {
+            self.read_only || !authorized
+        } else {
+            self.protect_queries && !authorized
+        }
+    }
+
     fn check_add_pool(_command: &str, parameter: &Option<&json::Value>) -> Result<()> {
         const ARG_COUNT: usize = 3;
         match parameter {
@@ -333,31 +406,41 @@ where
         })
     }
 
-    fn handle_check(&self, parameter: Option<&json::Value>) -> Result<response::Check> {
+    fn handle_check(
+        &self,
+        parameter: Option<&json::Value>,
+        authorized: bool,
+    ) -> Result<response::Check> {
         let command =
             parameter.ok_or_else(|| response::Error::from(response::ErrorCode::MissingCheckCmd))?;
-        let result = match command {
-            json::Value::String(command) => self.commands.get(command.as_str()).into(),
-            _ => response::Bool::N,
+        let descriptor = match command {
+            json::Value::String(command) => self.commands.get(command.as_str()),
+            _ => None,
         };
 
         Ok(response::Check {
-            exists: result,
-            access: result,
+            exists: descriptor.into(),
+            access: descriptor
+                .map_or(false, |descriptor| !self.is_denied(descriptor, authorized))
+                .into(),
         })
     }
 
     /// Handles a single `command` with optional `parameter`. `multi_command` flag ensures that no
-    /// command with parameters can be processed in batched mode.
+    /// command with parameters can be processed in batched mode. `authorized` flag tells whether
+    /// the request presented valid API token.
     async fn handle_single(
         &self,
         command: &str,
         parameter: Option<&json::Value>,
         multi_command: bool,
+        authorized: bool,
     ) -> response::Dispatch {
         let dispatch = match self.commands.get(command) {
             Some(descriptor) => {
-                if multi_command && descriptor.has_parameters() {
+                if (multi_command && descriptor.has_parameters())
+                    || self.is_denied(descriptor, authorized)
+                {
                     Err(response::ErrorCode::AccessDeniedCmd(command.to_string()).into())
                 } else {
                     let check_result = descriptor
@@ -371,9 +454,9 @@ where
                             HandlerType::Version => {
                                 self.handle_version().map(|response| response.into())
                             }
-                            HandlerType::Check => {
-                                self.handle_check(parameter).map(|response| response.into())
-                            }
+                            HandlerType::Check => self
+                                .handle_check(parameter, authorized)
+                                .map(|response| response.into()),
                         },
                         Err(response) => Err(response),
                     }
@@ -413,17 +496,27 @@ where
             .filter(|command| command.len() > 0)
             .collect();
         let parameter = command_request.value.get("parameter");
+        let authorized = self.is_authorized(
+            command_request
+                .value
+                .get(TOKEN)
+                .and_then(json::Value::as_str),
+        );
 
         if commands.len() == 0 {
             self.get_single_response(response::ErrorCode::InvalidCommand.into())
         } else if commands.len() == 1 {
-            self.get_single_response(self.handle_single(command, parameter, false).await)
+            self.get_single_response(
+                self.handle_single(command, parameter, false, authorized)
+                    .await,
+            )
         } else {
             let mut responses = MultiResponse::new();
             for command in commands {
-                if let ResponseType::Single(response) =
-                    self.get_single_response(self.handle_single(command, parameter, true).await)
-                {
+                if let ResponseType::Single(response) = self.get_single_response(
+                    self.handle_single(command, parameter, true, authorized)
+                        .await,
+                ) {
                     responses.add_response(command, response);
                 }
             }
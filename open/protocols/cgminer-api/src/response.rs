@@ -58,6 +58,16 @@ pub enum Bool {
     Y,
 }
 
+impl From<bool> for Bool {
+    fn from(value: bool) -> Self {
+        if value {
+            Bool::Y
+        } else {
+            Bool::N
+        }
+    }
+}
+
 impl<T> From<Option<T>> for Bool {
     fn from(value: Option<T>) -> Self {
         match value {
@@ -129,6 +139,7 @@ pub enum StatusCode {
     TempCtrl = 200,
     Temps = 201,
     Fans = 202,
+    PoolStates = 203,
 
     // info status codes
     PoolAlreadyEnabled = 49,
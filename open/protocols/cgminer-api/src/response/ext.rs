@@ -50,6 +50,10 @@ pub struct TempCtrl {
     #[serde(rename = "Dangerous")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dangerous: Option<f32>,
+    /// Mining is paused while temperature monitoring and fan control are still active
+    #[serde(rename = "Paused Monitoring")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused_monitoring: Option<bool>,
 }
 
 impl From<TempCtrl> for Dispatch {
@@ -125,3 +129,59 @@ impl From<Fans> for Dispatch {
         )
     }
 }
+
+/// Connection state of pool client
+#[derive(Serialize, PartialEq, Clone, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub enum PoolConnectionState {
+    Connected,
+    Connecting,
+    /// Enabled pool which is not connected because other pool provides work (failover)
+    Idle,
+    Failed,
+    Disabled,
+}
+
+#[derive(Serialize, PartialEq, Clone, Debug)]
+pub struct PoolState {
+    #[serde(rename = "POOL")]
+    pub idx: i32,
+    #[serde(rename = "URL")]
+    pub url: String,
+    #[serde(rename = "Group")]
+    pub group: String,
+    #[serde(rename = "State")]
+    pub state: PoolConnectionState,
+    /// The pool currently provides work to the miner
+    #[serde(rename = "Active")]
+    pub active: Bool,
+    /// Failover order of the pool within its group
+    #[serde(rename = "Priority")]
+    pub priority: i32,
+    /// Quota of the pool group (zero for group with fixed share ratio)
+    #[serde(rename = "Quota")]
+    pub quota: i32,
+    /// Difficulty of the last job received from the pool
+    #[serde(rename = "Difficulty")]
+    pub difficulty: Difficulty,
+    #[serde(rename = "Last Accepted Share Time")]
+    pub last_accepted_share_time: Time,
+}
+
+pub struct PoolStates {
+    pub list: Vec<PoolState>,
+}
+
+impl From<PoolStates> for Dispatch {
+    fn from(pool_states: PoolStates) -> Self {
+        let pool_count = pool_states.list.len();
+        Dispatch::from_success(
+            StatusCode::PoolStates.into(),
+            format!("{} Pool(s)", pool_count),
+            Some(Body {
+                name: "POOLSTATES",
+                list: pool_states.list,
+            }),
+        )
+    }
+}
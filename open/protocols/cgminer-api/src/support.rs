@@ -45,6 +45,14 @@ impl When for UnixTime {
     }
 }
 
+/// Compare secrets in time that does not depend on their content (only the length can leak)
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 pub trait ValueExt {
     fn to_i32(&self) -> Option<i32>;
 
@@ -29,7 +29,7 @@ use crate::command;
 use crate::commands;
 use crate::response;
 
-use utils::{assert_json_eq, codec_roundtrip};
+use utils::{assert_json_eq, codec_roundtrip, codec_roundtrip_with, ZeroTime};
 
 use ii_async_compat::tokio;
 
@@ -133,6 +133,35 @@ impl TestCustomHandler {
                 CustomCommandTwo { value }
             })
     }
+
+    async fn handle_pool_states(&self) -> command::Result<response::ext::PoolStates> {
+        Ok(response::ext::PoolStates {
+            list: vec![
+                response::ext::PoolState {
+                    idx: 0,
+                    url: "stratum+tcp://main.pool:3333".to_string(),
+                    group: "Default".to_string(),
+                    state: response::ext::PoolConnectionState::Connected,
+                    active: true.into(),
+                    priority: 0,
+                    quota: 1,
+                    difficulty: 8192.0,
+                    last_accepted_share_time: 1_600_000_000,
+                },
+                response::ext::PoolState {
+                    idx: 1,
+                    url: "stratum+tcp://backup.pool:3333".to_string(),
+                    group: "Default".to_string(),
+                    state: response::ext::PoolConnectionState::Idle,
+                    active: false.into(),
+                    priority: 1,
+                    quota: 1,
+                    difficulty: 0.0,
+                    last_accepted_share_time: 0,
+                },
+            ],
+        })
+    }
 }
 
 #[tokio::test]
@@ -338,3 +367,218 @@ async fn test_multiple_custom_commands() {
 
     assert_json_eq(&response, &expected);
 }
+
+#[tokio::test]
+async fn test_read_only() {
+    let handler = Arc::new(TestCustomHandler);
+
+    const CUSTOM_COMMAND_ONE: &str = "custom_command_one";
+    const CUSTOM_COMMAND_TWO: &str = "custom_command_two";
+
+    let custom_commands = || {
+        let mut custom_commands = commands![
+            (CUSTOM_COMMAND_ONE: ParameterLess -> handler.handle_command_one),
+            (CUSTOM_COMMAND_TWO: Parameter(None) -> handler.handle_command_two)
+        ];
+        custom_commands
+            .get_mut(CUSTOM_COMMAND_TWO)
+            .unwrap()
+            .set_mutating();
+        custom_commands
+    };
+    let read_only = |receiver: command::Receiver<ZeroTime>| receiver.read_only(true);
+    let access_denied = |name: &str| {
+        json::json!({
+            "STATUS": [{
+                "STATUS": "E",
+                "When": 0,
+                "Code": 45,
+                "Msg": format!("Access denied to '{}' command", name),
+                "Description": "TestMiner v1.0",
+            }],
+            "id": 1
+        })
+    };
+
+    // Query commands remain available
+    let command: json::Value = json::json!({ "command": CUSTOM_COMMAND_ONE });
+    let response = codec_roundtrip_with(command, custom_commands(), read_only).await;
+    let expected = json::json!({
+        "STATUS": [{
+            "STATUS": "S",
+            "When": 0,
+            "Code": 301,
+            "Msg": "TestMiner custom command 1",
+            "Description": "TestMiner v1.0",
+        }],
+        "CUSTOM_COMMAND_ONE": [{
+            "Attribute": "value",
+        }],
+        "id": 1
+    });
+    assert_json_eq(&response, &expected);
+
+    // Standard and custom commands changing miner state are refused
+    let command: json::Value = json::json!({
+        "command": "removepool",
+        "parameter": 0
+    });
+    let response = codec_roundtrip_with(command, custom_commands(), read_only).await;
+    assert_json_eq(&response, &access_denied("removepool"));
+
+    let command: json::Value = json::json!({
+        "command": CUSTOM_COMMAND_TWO,
+        "parameter": 42
+    });
+    let response = codec_roundtrip_with(command, custom_commands(), read_only).await;
+    assert_json_eq(&response, &access_denied(CUSTOM_COMMAND_TWO));
+
+    // Denied access is reported by `check` command
+    let command: json::Value = json::json!({
+        "command": "check",
+        "parameter": CUSTOM_COMMAND_TWO
+    });
+    let response = codec_roundtrip_with(command, custom_commands(), read_only).await;
+    let expected = json::json!({
+        "STATUS": [{
+            "STATUS": "S",
+            "When": 0,
+            "Code": 72,
+            "Msg": "Check command",
+            "Description": "TestMiner v1.0",
+        }],
+        "CHECK": [{
+            "Exists": "Y",
+            "Access": "N",
+        }],
+        "id": 1
+    });
+    assert_json_eq(&response, &expected);
+}
+
+#[tokio::test]
+async fn test_token() {
+    let handler = Arc::new(TestCustomHandler);
+
+    const CUSTOM_COMMAND_ONE: &str = "custom_command_one";
+
+    let custom_commands = || {
+        commands![
+            (CUSTOM_COMMAND_ONE: ParameterLess -> handler.handle_command_one)
+        ]
+    };
+    let with_token =
+        |receiver: command::Receiver<ZeroTime>| receiver.token(Some("secret".to_string()), false);
+    let with_token_for_queries =
+        |receiver: command::Receiver<ZeroTime>| receiver.token(Some("secret".to_string()), true);
+    let access_denied = |name: &str| {
+        json::json!({
+            "STATUS": [{
+                "STATUS": "E",
+                "When": 0,
+                "Code": 45,
+                "Msg": format!("Access denied to '{}' command", name),
+                "Description": "TestMiner v1.0",
+            }],
+            "id": 1
+        })
+    };
+    let custom_command_one = json::json!({
+        "STATUS": [{
+            "STATUS": "S",
+            "When": 0,
+            "Code": 301,
+            "Msg": "TestMiner custom command 1",
+            "Description": "TestMiner v1.0",
+        }],
+        "CUSTOM_COMMAND_ONE": [{
+            "Attribute": "value",
+        }],
+        "id": 1
+    });
+
+    // Query commands do not need the token by default
+    let command: json::Value = json::json!({ "command": CUSTOM_COMMAND_ONE });
+    let response = codec_roundtrip_with(command, custom_commands(), with_token).await;
+    assert_json_eq(&response, &custom_command_one);
+
+    // Commands changing miner state require matching token
+    for token in &[None, Some("secre"), Some("secreT")] {
+        let mut command: json::Value = json::json!({
+            "command": "removepool",
+            "parameter": 0
+        });
+        if let Some(token) = token {
+            command["token"] = json::Value::String(token.to_string());
+        }
+        let response = codec_roundtrip_with(command, custom_commands(), with_token).await;
+        assert_json_eq(&response, &access_denied("removepool"));
+    }
+
+    let command: json::Value = json::json!({
+        "command": "removepool",
+        "parameter": 0,
+        "token": "secret"
+    });
+    let response = codec_roundtrip_with(command, custom_commands(), with_token).await;
+    assert_eq!(response["STATUS"][0]["STATUS"], "S");
+
+    // Query commands can be protected as well
+    let command: json::Value = json::json!({ "command": CUSTOM_COMMAND_ONE });
+    let response = codec_roundtrip_with(command, custom_commands(), with_token_for_queries).await;
+    assert_json_eq(&response, &access_denied(CUSTOM_COMMAND_ONE));
+
+    let command: json::Value = json::json!({
+        "command": CUSTOM_COMMAND_ONE,
+        "token": "secret"
+    });
+    let response = codec_roundtrip_with(command, custom_commands(), with_token_for_queries).await;
+    assert_json_eq(&response, &custom_command_one);
+}
+
+#[tokio::test]
+async fn test_pool_states() {
+    let handler = Arc::new(TestCustomHandler);
+
+    use command::POOLSTATES;
+    let custom_commands = commands![
+        (POOLSTATES: ParameterLess -> handler.handle_pool_states)
+    ];
+
+    let command: json::Value = json::json!({ "command": POOLSTATES });
+
+    let response = codec_roundtrip(command, custom_commands).await;
+    let expected = json::json!({
+        "STATUS": [{
+            "STATUS": "S",
+            "When": 0,
+            "Code": 203,
+            "Msg": "2 Pool(s)",
+            "Description": "TestMiner v1.0",
+        }],
+        "POOLSTATES": [{
+            "POOL": 0,
+            "URL": "stratum+tcp://main.pool:3333",
+            "Group": "Default",
+            "State": "Connected",
+            "Active": "Y",
+            "Priority": 0,
+            "Quota": 1,
+            "Difficulty": 8192.0,
+            "Last Accepted Share Time": 1_600_000_000,
+        }, {
+            "POOL": 1,
+            "URL": "stratum+tcp://backup.pool:3333",
+            "Group": "Default",
+            "State": "Idle",
+            "Active": "N",
+            "Priority": 1,
+            "Quota": 1,
+            "Difficulty": 0.0,
+            "Last Accepted Share Time": 0,
+        }],
+        "id": 1
+    });
+
+    assert_json_eq(&response, &expected);
+}
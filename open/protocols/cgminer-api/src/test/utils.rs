@@ -33,7 +33,7 @@ use bytes::BytesMut;
 use json::Value;
 use serde_json as json;
 
-struct ZeroTime;
+pub struct ZeroTime;
 
 impl support::When for ZeroTime {
     fn when() -> response::Time {
@@ -44,6 +44,19 @@ impl support::When for ZeroTime {
 pub async fn codec_roundtrip<T>(command: json::Value, custom_commands: T) -> Value
 where
     T: Into<Option<command::Map>>,
+{
+    codec_roundtrip_with(command, custom_commands, |receiver| receiver).await
+}
+
+/// Roundtrip with command receiver modified by `configure` (e.g. to restrict access)
+pub async fn codec_roundtrip_with<T, F>(
+    command: json::Value,
+    custom_commands: T,
+    configure: F,
+) -> Value
+where
+    T: Into<Option<command::Map>>,
+    F: FnOnce(command::Receiver<ZeroTime>) -> command::Receiver<ZeroTime>,
 {
     let command_receiver = command::Receiver::<ZeroTime>::new(
         super::handler::BasicTest,
@@ -51,6 +64,7 @@ where
         "v1.0".to_string(),
         custom_commands,
     );
+    let command_receiver = configure(command_receiver);
     let mut codec = Codec::default();
 
     let mut command_buf = BytesMut::with_capacity(256);
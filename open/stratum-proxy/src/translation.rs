@@ -26,6 +26,7 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt;
 use std::mem::size_of;
+use std::time::{Duration, Instant};
 
 use ii_async_compat::{bytes, futures};
 
@@ -67,15 +68,29 @@ impl SeqId {
     }
 }
 
+/// Handling of V1 jobs cancelled by `clean_jobs` flag of `mining.notify`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum V1CleanJobs {
+    /// Cancelled jobs are dropped immediately
+    Honor,
+    /// Cancelled jobs are kept until they leave the stale job window
+    Ignore,
+    /// Cancelled jobs are kept for the grace period (bounded by the stale job window)
+    Delay(Duration),
+}
+
 pub struct V2ToV1TranslationOptions {
     /// Try to send `extranonce.subscribe` during handshake
     pub try_enable_xnsub: bool,
+    /// How to treat jobs cancelled by the upstream V1 server
+    pub clean_jobs: V1CleanJobs,
 }
 
 impl Default for V2ToV1TranslationOptions {
     fn default() -> Self {
         Self {
             try_enable_xnsub: false,
+            clean_jobs: V1CleanJobs::Honor,
         }
     }
 }
@@ -127,6 +142,9 @@ enum V1ResultOrError<'a> {
 /// Maps V2 job ID to V1 job ID so that we can submit mining results upstream to V1 server
 type JobMap = HashMap<u32, V1SubmitTemplate>;
 
+/// Deadlines of superseded jobs after which their solutions are no longer submitted
+type StaleJobMap = HashMap<u32, Instant>;
+
 //type V2ReqMap = HashMap<u32, FnMut(&mut V2ToV1Translation, &ii_stratum::Message<Protocol>, &v1::rpc::StratumResult)>;
 
 /// Object capable of translating stratm V2 header-only mining protocol that uses standard mining
@@ -151,6 +169,10 @@ pub struct V2ToV1Translation {
     /// `ntime` less than specified on jobs they are solving (but greater than ntime on prevhash).
     v1_force_future_jobs: bool,
 
+    /// Previous block hash of the latest future job. Its change invalidates all jobs regardless
+    /// of `clean_jobs` handling.
+    v1_prev_hash: Option<Vec<u8>>,
+
     /// Latest mining.notify payload that arrived before V1 authorize has completed.
     /// This allows immediate completion of channel open on V2.
     v1_deferred_notify: Option<v1::messages::Notify>,
@@ -170,6 +192,8 @@ pub struct V2ToV1Translation {
     v2_job_id: SeqId,
     /// Translates V2 job ID to V1 job ID
     v2_to_v1_job_map: JobMap,
+    /// Superseded jobs from `v2_to_v1_job_map` that are still accepted for a limited time
+    v2_stale_jobs: StaleJobMap,
     /// Options for translation
     options: V2ToV1TranslationOptions,
 }
@@ -189,6 +213,9 @@ impl V2ToV1Translation {
     /// TODO: DIFF1 const target is broken, the last U64 word gets actually initialized to 0xffffffff, not sure why
     const DIFF1_TARGET: uint::U256 = uint::U256([0, 0, 0, 0xffff0000u64]);
 
+    /// Maximal time solutions of a superseded job are submitted upstream
+    pub const MAX_STALE_JOB_WINDOW: Duration = Duration::from_secs(120);
+
     pub fn new(
         v1_tx: mpsc::Sender<v1::Frame>,
         v2_tx: mpsc::Sender<v2::Frame>,
@@ -206,12 +233,14 @@ impl V2ToV1Translation {
             v1_extra_nonce2_size: 0,
             v1_authorized: false,
             v1_force_future_jobs: true,
+            v1_prev_hash: None,
             v1_xnsub_enabled: false,
             v1_deferred_notify: None,
             v2_tx,
             v2_req_id: SeqId::new(),
             v2_job_id: SeqId::new(),
             v2_to_v1_job_map: JobMap::default(),
+            v2_stale_jobs: StaleJobMap::default(),
             options,
         }
     }
@@ -754,6 +783,47 @@ impl V2ToV1Translation {
         }
     }
 
+    /// Marks all registered jobs as superseded by a future job. Jobs are dropped immediately
+    /// unless configured to override `clean_jobs` handling - then jobs on the same previous block
+    /// hash stay valid for the grace period (bounded by the stale job window).
+    fn retire_jobs(&mut self, prev_hash_changed: bool, now: Instant) {
+        let window = match self.options.clean_jobs {
+            // Jobs on the old block are worthless regardless of configuration
+            _ if prev_hash_changed => None,
+            V1CleanJobs::Honor => None,
+            V1CleanJobs::Ignore => Some(Self::MAX_STALE_JOB_WINDOW),
+            V1CleanJobs::Delay(delay) => Some(delay.min(Self::MAX_STALE_JOB_WINDOW)),
+        };
+
+        match window {
+            None => {
+                self.v2_to_v1_job_map.clear();
+                self.v2_stale_jobs.clear();
+            }
+            Some(window) => {
+                let deadline = now + window;
+                for job_id in self.v2_to_v1_job_map.keys() {
+                    let job_deadline = self.v2_stale_jobs.entry(*job_id).or_insert(deadline);
+                    *job_deadline = (*job_deadline).min(deadline);
+                }
+                self.prune_stale_jobs(now);
+            }
+        }
+    }
+
+    /// Drops all superseded jobs whose deadline has passed
+    fn prune_stale_jobs(&mut self, now: Instant) {
+        let job_map = &mut self.v2_to_v1_job_map;
+        self.v2_stale_jobs.retain(|job_id, deadline| {
+            if *deadline <= now {
+                job_map.remove(job_id);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     fn perform_notify(&mut self, payload: &v1::messages::Notify) -> Result<()> {
         let merkle_root = self.calculate_merkle_root(payload)?;
 
@@ -771,7 +841,9 @@ impl V2ToV1Translation {
         // message and we also have to issue NewPrevHash. In addition to that, we also check the
         // clean jobs flag that indicates a must for new prev hash, too.
         let maybe_set_new_prev_hash = if v2_job.future_job {
-            self.v2_to_v1_job_map.clear();
+            let prev_hash_changed = self.v1_prev_hash.as_deref() != Some(payload.prev_hash());
+            self.v1_prev_hash = Some(payload.prev_hash().to_vec());
+            self.retire_jobs(prev_hash_changed, Instant::now());
             // Any error means immediate termination
             // TODO write a unit test for such scenario, too
             Some(self.build_set_new_prev_hash(v2_job.job_id, payload)?)
@@ -1176,7 +1248,8 @@ impl v2::Handler for V2ToV1Translation {
         // and causing borrow checker complains
         let v1_extra_nonce2_size = self.v1_extra_nonce2_size;
 
-        // Check job ID validity
+        // Check job ID validity (stale jobs are no longer valid)
+        self.prune_stale_jobs(Instant::now());
         let v1_submit_template = self
             .v2_to_v1_job_map
             .get(&payload.job_id)
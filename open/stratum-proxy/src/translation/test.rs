@@ -184,3 +184,55 @@ fn test_diff_1_bitcoin_target() {
         V2ToV1Translation::DIFF1_TARGET
     );
 }
+
+#[test]
+fn test_retire_jobs() {
+    let submit_template = V1SubmitTemplate {
+        job_id: v1::messages::JobId::from_str(&test_utils::v1::MINING_NOTIFY_JOB_ID),
+        time: test_utils::common::MINING_WORK_NTIME,
+        version: test_utils::common::MINING_WORK_VERSION,
+    };
+    let build_translation = |clean_jobs| {
+        let (v1_tx, _) = mpsc::channel(1);
+        let (v2_tx, _) = mpsc::channel(1);
+        let mut translation = V2ToV1Translation::new(
+            v1_tx,
+            v2_tx,
+            V2ToV1TranslationOptions {
+                try_enable_xnsub: false,
+                clean_jobs,
+            },
+        );
+        translation
+            .v2_to_v1_job_map
+            .insert(0, submit_template.clone());
+        translation
+    };
+    let now = Instant::now();
+    let delay = Duration::from_secs(5);
+
+    // Superseded jobs are dropped immediately
+    let mut translation = build_translation(V1CleanJobs::Honor);
+    translation.retire_jobs(false, now);
+    assert!(translation.v2_to_v1_job_map.is_empty());
+
+    // Cancelled jobs are kept for the grace period
+    let mut translation = build_translation(V1CleanJobs::Delay(delay));
+    translation.retire_jobs(false, now);
+    assert!(translation.v2_to_v1_job_map.contains_key(&0));
+    translation.prune_stale_jobs(now + delay);
+    assert!(translation.v2_to_v1_job_map.is_empty());
+
+    // Ignoring `clean_jobs` never keeps a cancelled job beyond the stale job window
+    let mut translation = build_translation(V1CleanJobs::Ignore);
+    translation.retire_jobs(false, now);
+    translation.prune_stale_jobs(now + delay);
+    assert!(translation.v2_to_v1_job_map.contains_key(&0));
+    translation.prune_stale_jobs(now + V2ToV1Translation::MAX_STALE_JOB_WINDOW);
+    assert!(translation.v2_to_v1_job_map.is_empty());
+
+    // Jobs on the old block are dropped even when `clean_jobs` is ignored
+    let mut translation = build_translation(V1CleanJobs::Ignore);
+    translation.retire_jobs(true, now);
+    assert!(translation.v2_to_v1_job_map.is_empty());
+}